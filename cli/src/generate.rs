@@ -211,7 +211,8 @@ fn ty_to_rust(column: &elephantry::inspect::Column) -> crate::Result<String> {
         }
     };
 
-    if !column.is_notnull {
+    // Columns with a default value are omitted at insertion when `None`
+    if !column.is_required() {
         rty = format!("Option<{}>", rty);
     }
 
@@ -0,0 +1,126 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+#[derive(elephantry::Entity)]
+#[elephantry(model = "Model", structure = "Structure", relation = "bench")]
+struct Row {
+    #[elephantry(pk)]
+    id: i32,
+    name: String,
+    description: Option<String>,
+    score: f64,
+    count: i64,
+    active: bool,
+    tags: Vec<String>,
+    a: i32,
+    b: i32,
+    c: i32,
+    d: String,
+    e: String,
+}
+
+impl Row {
+    fn new(id: i32) -> Self {
+        Self {
+            id,
+            name: format!("row {}", id),
+            description: None,
+            score: id as f64 / 3.,
+            count: id as i64 * 1_000,
+            active: id % 2 == 0,
+            tags: vec!["a".to_string(), "b".to_string()],
+            a: id,
+            b: id * 2,
+            c: id * 3,
+            d: "d".repeat(32),
+            e: "e".repeat(64),
+        }
+    }
+}
+
+fn connection() -> elephantry::Connection {
+    let database_url = std::env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "postgres://localhost/elephantry".to_string());
+
+    let connection = elephantry::Connection::new(&database_url).unwrap();
+    connection
+        .execute(
+            "create temporary table bench (
+                id int primary key, name text, description text, score float8,
+                count int8, active bool, tags text[], a int, b int, c int, d text, e text
+            )",
+        )
+        .unwrap();
+
+    connection
+}
+
+fn decode(c: &mut Criterion) {
+    let connection = connection();
+    connection
+        .copy::<Model<'_>, _>((0..1_000).map(Row::new))
+        .unwrap();
+
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(1_000));
+
+    group.bench_function("wide rows", |b| {
+        b.iter(|| connection.find_all::<Model<'_>>(None).unwrap().into_vec())
+    });
+
+    group.bench_function("array", |b| {
+        b.iter(|| {
+            connection
+                .query_one::<Vec<i32>>("select array_agg(x) from generate_series(1, 1000) x", &[])
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+fn write(c: &mut Criterion) {
+    let connection = connection();
+
+    let mut group = c.benchmark_group("write");
+
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("insert_one", |b| {
+        b.iter_batched(
+            || connection.execute("truncate bench").unwrap(),
+            |_| connection.insert_one::<Model<'_>>(&Row::new(1)).unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+
+    let rows = (0..1_000).map(Row::new).collect::<Vec<_>>();
+
+    group.throughput(Throughput::Elements(rows.len() as u64));
+    group.bench_function("upsert_many", |b| {
+        b.iter_batched(
+            || connection.execute("truncate bench").unwrap(),
+            |_| {
+                connection
+                    .upsert_many::<Model<'_>>(&rows, elephantry::OnConflict::DoNothing)
+                    .unwrap()
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.bench_function("copy", |b| {
+        b.iter_batched(
+            || connection.execute("truncate bench").unwrap(),
+            |_| {
+                connection
+                    .copy::<Model<'_>, _>((0..1_000).map(Row::new))
+                    .unwrap()
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, decode, write);
+criterion_main!(benches);
@@ -11,6 +11,16 @@ mod employee {
         pub day_salary: bigdecimal::BigDecimal,
         pub department_id: i32,
     }
+
+    impl<'a> Model<'a> {
+        pub fn managers() -> elephantry::Where<'static> {
+            elephantry::Where::from("is_manager = $*", vec![&true])
+        }
+
+        pub fn by_department(department_id: &'a i32) -> elephantry::Where<'a> {
+            elephantry::Where::from("department_id = $*", vec![department_id])
+        }
+    }
 }
 
 fn main() -> elephantry::Result {
@@ -26,6 +36,7 @@ fn main() -> elephantry::Result {
     find_where(&elephantry)?;
     count_where(&elephantry)?;
     exist_where(&elephantry)?;
+    scope(&elephantry)?;
 
     Ok(())
 }
@@ -81,3 +92,18 @@ fn exist_where(elephantry: &elephantry::Pool) -> elephantry::Result {
 
     Ok(())
 }
+
+fn scope(elephantry: &elephantry::Pool) -> elephantry::Result {
+    println!("# Scope\n");
+
+    let managers = elephantry.scope::<employee::Model>(
+        employee::Model::managers().and(employee::Model::by_department(&5)),
+    )?;
+
+    for manager in managers {
+        println!("{} {}", manager.first_name, manager.last_name);
+    }
+    println!();
+
+    Ok(())
+}
@@ -42,9 +42,12 @@ mod employee {
                 )?
                 .unwrap();
 
-            transaction.commit()?;
-
-            Ok(employee)
+            match transaction.commit()? {
+                elephantry::transaction::Outcome::Committed => Ok(employee),
+                elephantry::transaction::Outcome::RolledBack => {
+                    Err(elephantry::Error::TransactionAborted)
+                }
+            }
         }
     }
 }
@@ -73,7 +76,7 @@ fn main() -> elephantry::Result {
             employee_id: None,
             first_name: "First name".to_string(),
             last_name: "Last name".to_string(),
-            birth_date: chrono::NaiveDate::from_ymd(1990, 1, 1),
+            birth_date: chrono::NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
             is_manager: true,
             day_salary: 1_000.into(),
             department_id: -1,
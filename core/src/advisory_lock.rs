@@ -0,0 +1,87 @@
+/**
+ * Session level advisory lock, created by [`Connection::advisory_lock`] and
+ * released when dropped.
+ *
+ * Takes a lock before applying migrations, so application instances deployed
+ * at the same time apply them one after the other:
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * let _lock = connection.advisory_lock(elephantry::AdvisoryLock::key("migrations"))?;
+ *
+ * // Applies the pending migrations
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * Use [`Transaction::advisory_lock`] to release the lock with the
+ * transaction.
+ *
+ * [`Connection::advisory_lock`]: crate::Connection::advisory_lock
+ * [`Transaction::advisory_lock`]: crate::transaction::Transaction::advisory_lock
+ */
+#[derive(Debug)]
+pub struct AdvisoryLock<'c> {
+    connection: &'c crate::Connection,
+    key: i64,
+}
+
+impl<'c> AdvisoryLock<'c> {
+    pub(crate) fn new(connection: &'c crate::Connection, key: i64) -> Self {
+        Self { connection, key }
+    }
+
+    /**
+     * Computes a lock key from a name, with the 64 bits FNV-1a hash.
+     */
+    pub fn key(name: &str) -> i64 {
+        let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, x| {
+            (hash ^ u64::from(x)).wrapping_mul(0x0100_0000_01b3)
+        });
+
+        hash as i64
+    }
+
+    pub fn lock_key(&self) -> i64 {
+        self.key
+    }
+}
+
+impl<'c> Drop for AdvisoryLock<'c> {
+    fn drop(&mut self) {
+        if let Err(err) = self
+            .connection
+            .query_one::<bool>("select pg_advisory_unlock($1)", &[&self.key])
+        {
+            log::error!("Unable to release advisory lock {}: {}", self.key, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn advisory_lock() -> crate::Result {
+        let key = crate::AdvisoryLock::key("elephantry_advisory_lock");
+        let connection = crate::test::new_conn()?;
+        let other = crate::test::new_conn()?;
+
+        let lock = connection.advisory_lock(key)?;
+        assert_eq!(lock.lock_key(), key);
+        assert!(other.try_advisory_lock(key)?.is_none());
+
+        drop(lock);
+        assert!(other.try_advisory_lock(key)?.is_some());
+
+        let transaction = other.transaction();
+        transaction.start()?;
+        transaction.advisory_lock(key)?;
+        assert!(connection.try_advisory_lock(key)?.is_none());
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+        assert!(connection.try_advisory_lock(key)?.is_some());
+
+        Ok(())
+    }
+}
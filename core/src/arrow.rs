@@ -0,0 +1,336 @@
+use std::sync::Arc;
+
+/** Days between 1970-01-01 and 2000-01-01, the postgresql epoch. */
+const EPOCH_DAYS: i32 = 10_957;
+/** Microseconds between 1970-01-01 and 2000-01-01. */
+const EPOCH_MICROS: i64 = 946_684_800_000_000;
+
+/**
+ * Arrow type of a column.
+ *
+ * Date and time columns need the binary format, numeric columns the text
+ * format or the `numeric` feature. Other types without a text
+ * representation, like arrays or composites, are exported as raw binary
+ * values.
+ */
+fn data_type(ty: &crate::pq::Type, format: crate::pq::Format) -> arrow_schema::DataType {
+    use crate::pq::types;
+    use arrow_schema::{DataType, TimeUnit};
+
+    let binary = format == crate::pq::Format::Binary;
+
+    match ty.oid {
+        oid if oid == types::BOOL.oid => DataType::Boolean,
+        oid if oid == types::INT2.oid => DataType::Int16,
+        oid if oid == types::INT4.oid => DataType::Int32,
+        oid if oid == types::INT8.oid => DataType::Int64,
+        oid if oid == types::OID.oid => DataType::UInt32,
+        oid if oid == types::FLOAT4.oid => DataType::Float32,
+        oid if oid == types::FLOAT8.oid => DataType::Float64,
+        oid if oid == types::DATE.oid && binary => DataType::Date32,
+        oid if oid == types::TIME.oid && binary => DataType::Time64(TimeUnit::Microsecond),
+        oid if oid == types::TIMESTAMP.oid && binary => {
+            DataType::Timestamp(TimeUnit::Microsecond, None)
+        }
+        oid if oid == types::TIMESTAMPTZ.oid && binary => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        }
+        oid if oid == types::UUID.oid && binary => DataType::FixedSizeBinary(16),
+        #[cfg(feature = "numeric")]
+        oid if oid == types::NUMERIC.oid => DataType::Utf8,
+        oid if oid == types::JSON.oid || oid == types::JSONB.oid => DataType::Utf8,
+        _ if !binary || ty.kind == libpq::types::Kind::String => DataType::Utf8,
+        _ => DataType::Binary,
+    }
+}
+
+/**
+ * Text representation of a value of a `Utf8` column.
+ */
+fn text(
+    ty: &crate::pq::Type,
+    format: crate::pq::Format,
+    raw: Option<&[u8]>,
+) -> crate::Result<Option<String>> {
+    use crate::FromSql;
+
+    let raw = match raw {
+        Some(raw) => raw,
+        None => return Ok(None),
+    };
+
+    if format == crate::pq::Format::Binary {
+        #[cfg(feature = "numeric")]
+        if ty.oid == crate::pq::types::NUMERIC.oid {
+            return bigdecimal::BigDecimal::from_binary(ty, Some(raw)).map(|x| Some(x.to_string()));
+        }
+
+        // Binary jsonb is prefixed by its version
+        if ty.oid == crate::pq::types::JSONB.oid && raw.first() == Some(&1) {
+            return String::from_binary(ty, Some(&raw[1..])).map(Some);
+        }
+    }
+
+    String::from_sql(ty, format, Some(raw)).map(Some)
+}
+
+fn decode<T: crate::FromSql>(
+    result: &crate::pq::Result,
+    column: usize,
+) -> crate::Result<Vec<Option<T>>> {
+    use crate::FromSql;
+
+    (0..result.len())
+        .map(|row| {
+            let (ty, format, raw) = result.get(row).raw(column);
+
+            Option::<T>::from_sql(&ty, format, raw)
+        })
+        .collect()
+}
+
+fn shift<T: std::fmt::Display + Copy>(
+    ty: &crate::pq::Type,
+    values: Vec<Option<T>>,
+    f: impl Fn(T) -> Option<T>,
+) -> crate::Result<Vec<Option<T>>> {
+    values
+        .into_iter()
+        .map(|value| match value {
+            Some(value) => f(value).map(Some).ok_or_else(|| crate::Error::FromSql {
+                pg_type: ty.clone(),
+                rust_type: "arrow".to_string(),
+                value: value.to_string(),
+            }),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+fn array(
+    result: &crate::pq::Result,
+    column: usize,
+    ty: &crate::pq::Type,
+    data_type: &arrow_schema::DataType,
+) -> crate::Result<arrow_array::ArrayRef> {
+    use arrow_array::*;
+    use arrow_schema::DataType;
+
+    let array: ArrayRef = match data_type {
+        DataType::Boolean => Arc::new(BooleanArray::from(decode::<bool>(result, column)?)),
+        DataType::Int16 => Arc::new(Int16Array::from(decode::<i16>(result, column)?)),
+        DataType::Int32 => Arc::new(Int32Array::from(decode::<i32>(result, column)?)),
+        DataType::Int64 => Arc::new(Int64Array::from(decode::<i64>(result, column)?)),
+        DataType::UInt32 => Arc::new(UInt32Array::from(decode::<u32>(result, column)?)),
+        DataType::Float32 => Arc::new(Float32Array::from(decode::<f32>(result, column)?)),
+        DataType::Float64 => Arc::new(Float64Array::from(decode::<f64>(result, column)?)),
+        DataType::Date32 => {
+            let values = decode::<i32>(result, column)?;
+
+            Arc::new(Date32Array::from(shift(ty, values, |x| {
+                x.checked_add(EPOCH_DAYS)
+            })?))
+        }
+        DataType::Time64(_) => {
+            Arc::new(Time64MicrosecondArray::from(decode::<i64>(result, column)?))
+        }
+        DataType::Timestamp(_, tz) => {
+            let values = decode::<i64>(result, column)?;
+            let array = TimestampMicrosecondArray::from(shift(ty, values, |x| {
+                x.checked_add(EPOCH_MICROS)
+            })?);
+
+            Arc::new(array.with_timezone_opt(tz.clone()))
+        }
+        DataType::FixedSizeBinary(size) => {
+            let values = (0..result.len()).map(|row| result.get(row).raw(column).2);
+
+            Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                values, *size,
+            )?)
+        }
+        DataType::Utf8 => {
+            let values = (0..result.len())
+                .map(|row| {
+                    let (ty, format, raw) = result.get(row).raw(column);
+
+                    text(&ty, format, raw)
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+
+            Arc::new(StringArray::from(values))
+        }
+        _ => {
+            let values = (0..result.len())
+                .map(|row| result.get(row).raw(column).2)
+                .collect::<Vec<_>>();
+
+            Arc::new(BinaryArray::from(values))
+        }
+    };
+
+    Ok(array)
+}
+
+impl crate::pq::Result {
+    fn field_type(&self, n: usize) -> crate::pq::Type {
+        crate::Tuple::from(&self.inner, self.types(), 0).field_type(n)
+    }
+
+    /**
+     * Arrow schema of the result, see [`to_record_batch`] for the type
+     * mapping.
+     *
+     * [`to_record_batch`]: Self::to_record_batch
+     */
+    pub fn arrow_schema(&self) -> arrow_schema::Schema {
+        let fields = (0..self.inner.nfields())
+            .map(|n| {
+                let ty = self.field_type(n);
+                let format = self.inner.field_format(n);
+
+                arrow_schema::Field::new(
+                    self.inner.field_name(n).unwrap_or_default(),
+                    data_type(&ty, format),
+                    true,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        arrow_schema::Schema::new(fields)
+    }
+
+    /**
+     * Converts the result in an arrow record batch.
+     *
+     * | SQL type                    | Arrow type                |
+     * |-----------------------------|---------------------------|
+     * | `bool`                      | `Boolean`                 |
+     * | `int2`/`int4`/`int8`        | `Int16`/`Int32`/`Int64`   |
+     * | `oid`                       | `UInt32`                  |
+     * | `float4`/`float8`           | `Float32`/`Float64`       |
+     * | `bytea`                     | `Binary`                  |
+     * | `date`                      | `Date32`                  |
+     * | `time`                      | `Time64(Microsecond)`     |
+     * | `timestamp`/`timestamptz`   | `Timestamp(Microsecond)`  |
+     * | `uuid`                      | `FixedSizeBinary(16)`     |
+     * | `numeric`, `json`, text     | `Utf8`                    |
+     *
+     * Results of text queries (see [`Connection::execute`]) only have
+     * number, boolean and `Utf8` columns. Other types are exported as their
+     * raw binary value.
+     *
+     * [`Connection::execute`]: crate::Connection::execute
+     */
+    pub fn to_record_batch(&self) -> crate::Result<arrow_array::RecordBatch> {
+        let schema = Arc::new(self.arrow_schema());
+
+        let columns = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(n, field)| array(self, n, &self.field_type(n), field.data_type()))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let options = arrow_array::RecordBatchOptions::new().with_row_count(Some(self.len()));
+
+        arrow_array::RecordBatch::try_new_with_options(schema, columns, &options)
+            .map_err(Into::into)
+    }
+
+    /**
+     * Writes the result as a parquet file.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * let employees = connection.query::<elephantry::Row>("select * from employee", &[])?;
+     * let file = std::fs::File::create("employee.parquet")?;
+     *
+     * employees.write_parquet(file)?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     */
+    pub fn write_parquet<W: std::io::Write + Send>(&self, writer: W) -> crate::Result {
+        let batch = self.to_record_batch()?;
+
+        let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn to_record_batch() -> crate::Result {
+        use arrow_array::Array;
+        use arrow_schema::DataType;
+
+        let connection = crate::test::new_conn()?;
+        let rows = connection.query::<crate::Row>(
+            "select x::int4 as id, 'name ' || x as name, x % 2 = 0 as even,
+                '2000-01-02'::date + x as day, '1970-01-01 00:00:01'::timestamp as ts,
+                nullif(x, 2)::float8 as score, array[x] as list
+            from generate_series(1, 3) x",
+            &[],
+        )?;
+        let batch = rows.to_record_batch()?;
+
+        assert_eq!(batch.num_rows(), 3);
+        let types = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|x| x.data_type().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types[..4],
+            [
+                DataType::Int32,
+                DataType::Utf8,
+                DataType::Boolean,
+                DataType::Date32
+            ]
+        );
+        assert_eq!(types[6], DataType::Binary);
+
+        let id = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<arrow_array::Int32Array>()
+            .unwrap();
+        assert_eq!(id.values(), &[1, 2, 3]);
+        let name = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<arrow_array::StringArray>()
+            .unwrap();
+        assert_eq!(name.value(2), "name 3");
+        let day = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<arrow_array::Date32Array>()
+            .unwrap();
+        assert_eq!(day.value(0), 10_959);
+        let ts = batch
+            .column(4)
+            .as_any()
+            .downcast_ref::<arrow_array::TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(ts.value(0), 1_000_000);
+        assert!(batch.column(5).is_null(1));
+
+        let text = connection
+            .execute("select 1 as id, current_date as day")?
+            .to_record_batch()?;
+        assert_eq!(text.schema().field(1).data_type(), &DataType::Utf8);
+
+        let mut parquet = Vec::new();
+        rows.write_parquet(&mut parquet)?;
+        assert_eq!(&parquet[..4], b"PAR1");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+pub(crate) type Key = Vec<Option<String>>;
+
+/* Maximum number of parameters of a query */
+pub(crate) const MAX_PARAMS: usize = u16::MAX as usize;
+
+/**
+ * One-to-many association: the `foreign_key` columns of the model `C`
+ * reference the `key` columns of this model.
+ *
+ * Usually declared with the `has_many` attribute of the [`Entity`] or
+ * [`Model`] derive, which also generates a `{name}_for` helper:
+ *
+ * ```ignore
+ * #[derive(elephantry::Entity, elephantry::Model)]
+ * #[elephantry(
+ *     relation = "visitor",
+ *     pk = "visitor_id",
+ *     has_many(name = "events", model = "EventModel", fk = "visitor_id")
+ * )]
+ * struct Visitor {
+ *     visitor_id: i32,
+ *     name: String,
+ * }
+ *
+ * let events = connection.model::<VisitorModel>().events_for(&visitor)?;
+ * ```
+ *
+ * See [`Connection::find_has_many`] and [`Connection::load_has_many`].
+ *
+ * [`Connection::find_has_many`]: crate::Connection::find_has_many
+ * [`Connection::load_has_many`]: crate::Connection::load_has_many
+ * [`Entity`]: derive.Entity.html
+ * [`Model`]: derive.Model.html
+ */
+pub trait HasMany<'a, C: crate::Model<'a>>: crate::Model<'a> {
+    /** Columns of `C` referencing this model. */
+    fn foreign_key() -> &'static [&'static str];
+
+    /** Referenced columns of this model, its primary key by default. */
+    fn key() -> &'static [&'static str] {
+        use crate::Structure;
+
+        Self::Structure::primary_key()
+    }
+}
+
+/**
+ * Many-to-one association: the `foreign_key` columns of this model
+ * reference the `key` columns of the model `P`.
+ *
+ * Usually declared with the `belongs_to` attribute of the [`Entity`] or
+ * [`Model`] derive, which also generates a `{name}_for` helper:
+ *
+ * ```ignore
+ * #[derive(elephantry::Entity, elephantry::Model)]
+ * #[elephantry(
+ *     relation = "event",
+ *     belongs_to(name = "visitor", model = "VisitorModel", fk = "visitor_id")
+ * )]
+ * struct Event {
+ *     name: String,
+ *     visitor_id: i32,
+ * }
+ *
+ * let visitor = connection.model::<EventModel>().visitor_for(&event)?;
+ * ```
+ *
+ * See [`Connection::find_belongs_to`] and [`Connection::load_belongs_to`].
+ *
+ * [`Connection::find_belongs_to`]: crate::Connection::find_belongs_to
+ * [`Connection::load_belongs_to`]: crate::Connection::load_belongs_to
+ * [`Entity`]: derive.Entity.html
+ * [`Model`]: derive.Model.html
+ */
+pub trait BelongsTo<'a, P: crate::Model<'a>>: crate::Model<'a> {
+    /** Columns of this model referencing `P`. */
+    fn foreign_key() -> &'static [&'static str];
+
+    /** Referenced columns of `P`, its primary key by default. */
+    fn key() -> &'static [&'static str] {
+        use crate::Structure;
+
+        P::Structure::primary_key()
+    }
+}
+
+/**
+ * Values of the `columns` of `entity`, `None` if one of them is null.
+ */
+pub(crate) fn values<'e, E: crate::Entity>(
+    entity: &'e E,
+    columns: &[&str],
+) -> Option<Vec<&'e dyn crate::ToSql>> {
+    columns.iter().map(|x| entity.get(x)).collect()
+}
+
+/**
+ * Comparable key of `values`, with their text representation: the columns
+ * of the two sides of an association may have different types.
+ */
+pub(crate) fn key(values: &[&dyn crate::ToSql]) -> crate::Result<Key> {
+    values.iter().map(|x| x.to_text()).collect()
+}
+
+/**
+ * `"a" = $1 and "b" = $2` clause.
+ */
+pub(crate) fn clause(connection: &crate::Connection, columns: &[&str]) -> crate::Result<String> {
+    let clause = columns
+        .iter()
+        .enumerate()
+        .map(|(n, column)| {
+            Ok(format!(
+                "{} = ${}",
+                connection.escape_identifier(column)?,
+                n + 1
+            ))
+        })
+        .collect::<crate::Result<Vec<_>>>()?
+        .join(" and ");
+
+    Ok(clause)
+}
+
+/**
+ * Fetches the entities of `M` whose `columns` match one of `keys`, grouped
+ * by key. The keys are sent by batches of `(a, b) in (($1, $2), …)` clauses.
+ */
+pub(crate) fn load<'a, M: crate::Model<'a>>(
+    connection: &crate::Connection,
+    columns: &[&str],
+    keys: &[Vec<&dyn crate::ToSql>],
+) -> crate::Result<HashMap<Key, Vec<M::Entity>>> {
+    let mut entities = HashMap::<Key, Vec<M::Entity>>::new();
+
+    if columns.is_empty() {
+        return Err(crate::Error::PrimaryKey);
+    }
+
+    let quoted = columns
+        .iter()
+        .map(|x| connection.escape_identifier(x))
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    for chunk in keys.chunks(MAX_PARAMS / columns.len()) {
+        let mut params = Vec::new();
+        let mut tuples = Vec::new();
+
+        for values in chunk {
+            let placeholders = values
+                .iter()
+                .map(|x| {
+                    params.push(*x);
+                    format!("${}", params.len())
+                })
+                .collect::<Vec<_>>();
+
+            tuples.push(format!("({})", placeholders.join(", ")));
+        }
+
+        let clause = format!("({}) in ({})", quoted.join(", "), tuples.join(", "));
+
+        for entity in connection
+            .find_where::<M>(&clause, &params, None)?
+            .try_into_vec()?
+        {
+            let key = match values(&entity, columns) {
+                Some(values) => key(&values)?,
+                None => continue,
+            };
+
+            entities.entry(key).or_default().push(entity);
+        }
+    }
+
+    Ok(entities)
+}
+
+/**
+ * Distinct non null keys of `entities`.
+ */
+pub(crate) fn distinct_keys<'e, E: crate::Entity>(
+    entities: &'e [E],
+    columns: &[&str],
+) -> crate::Result<Vec<Vec<&'e dyn crate::ToSql>>> {
+    let mut seen = std::collections::HashSet::new();
+    let mut keys = Vec::new();
+
+    for entity in entities {
+        if let Some(values) = values(entity, columns) {
+            if seen.insert(key(&values)?) {
+                keys.push(values);
+            }
+        }
+    }
+
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{BelongsTo, HasMany};
+
+    #[derive(Clone, Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(
+        internal,
+        relation = "pg_temp.visitor",
+        pk = "visitor_id",
+        has_many(name = "events", model = "EventModel", fk = "visitor_id")
+    )]
+    struct Visitor {
+        visitor_id: i32,
+        name: String,
+    }
+
+    #[derive(Clone, Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(
+        internal,
+        relation = "pg_temp.event",
+        pk = "event_id",
+        belongs_to(name = "visitor", model = "VisitorModel", fk = "visitor_id")
+    )]
+    struct Event {
+        event_id: i32,
+        visitor_id: Option<i64>,
+    }
+
+    fn conn() -> crate::Result<crate::Pool> {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table visitor (visitor_id int primary key, name text);
+            create temporary table event (event_id int primary key, visitor_id int8);
+            insert into visitor values (1, 'alice'), (2, 'bob'), (3, 'carol');
+            insert into event values (1, 1), (2, 1), (3, 2), (4, null);",
+        )?;
+
+        Ok(conn)
+    }
+
+    #[test]
+    fn declaration() {
+        assert_eq!(
+            <VisitorModel<'_> as HasMany<'_, EventModel<'_>>>::foreign_key(),
+            &["visitor_id"]
+        );
+        assert_eq!(
+            <VisitorModel<'_> as HasMany<'_, EventModel<'_>>>::key(),
+            &["visitor_id"]
+        );
+        assert_eq!(
+            <EventModel<'_> as BelongsTo<'_, VisitorModel<'_>>>::key(),
+            &["visitor_id"]
+        );
+    }
+
+    #[test]
+    fn find() -> crate::Result {
+        let conn = conn()?;
+        let alice = conn.find_by_primary_key::<VisitorModel<'_>>(&1)?.unwrap();
+
+        let events = conn.model::<VisitorModel<'_>>().events_for(&alice)?;
+        let mut ids = events.map(|x| x.event_id).collect::<Vec<_>>();
+        ids.sort_unstable();
+        assert_eq!(ids, [1, 2]);
+
+        let event = conn.find_by_primary_key::<EventModel<'_>>(&3)?.unwrap();
+        let visitor = conn.model::<EventModel<'_>>().visitor_for(&event)?;
+        assert_eq!(visitor.map(|x| x.name), Some("bob".to_string()));
+
+        let orphan = conn.find_by_primary_key::<EventModel<'_>>(&4)?.unwrap();
+        assert!(conn
+            .find_belongs_to::<EventModel<'_>, VisitorModel<'_>>(&orphan)?
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn load() -> crate::Result {
+        let conn = conn()?;
+
+        let visitors = conn
+            .find_all::<VisitorModel<'_>>(Some("order by visitor_id"))?
+            .into_vec();
+        let events = conn.load_has_many::<VisitorModel<'_>, EventModel<'_>>(&visitors)?;
+        assert_eq!(
+            events.iter().map(|x| x.len()).collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+
+        // Parents sharing a key get the same children
+        let alice = visitors[0].clone();
+        let events =
+            conn.load_has_many::<VisitorModel<'_>, EventModel<'_>>(&[alice.clone(), alice])?;
+        assert_eq!(events.iter().map(|x| x.len()).collect::<Vec<_>>(), [2, 2]);
+
+        let events = conn
+            .find_all::<EventModel<'_>>(Some("order by event_id"))?
+            .into_vec();
+        let visitors = conn.load_belongs_to::<EventModel<'_>, VisitorModel<'_>>(&events)?;
+        assert_eq!(
+            visitors
+                .into_iter()
+                .map(|x| x.map(|x| x.name))
+                .collect::<Vec<_>>(),
+            [
+                Some("alice".to_string()),
+                Some("alice".to_string()),
+                Some("bob".to_string()),
+                None,
+            ]
+        );
+
+        Ok(())
+    }
+}
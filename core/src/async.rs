@@ -6,7 +6,7 @@
 #[derive(Debug)]
 pub struct Async<'c> {
     last_result: Option<crate::Result<crate::pq::Result>>,
-    connection: &'c std::sync::Mutex<libpq::Connection>,
+    connection: &'c crate::Connection,
 }
 
 impl<'c> std::future::Future for Async<'c> {
@@ -38,7 +38,7 @@ impl<'c> std::future::Future for Async<'c> {
 }
 
 impl<'c> Async<'c> {
-    pub(crate) fn new(connection: &'c std::sync::Mutex<libpq::Connection>) -> Self {
+    pub(crate) fn new(connection: &'c crate::Connection) -> Self {
         Self {
             last_result: None,
             connection,
@@ -52,9 +52,8 @@ impl<'c> Async<'c> {
      */
     pub async fn execute(self, query: &str) -> crate::Result<crate::pq::Result> {
         self.connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .send_query(query)
+            .lock()?
+            .send_query(&self.connection.tag(query))
             .map_err(crate::Error::Async)?;
 
         self.await
@@ -105,10 +104,9 @@ impl<'c> Async<'c> {
         }
 
         self.connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .lock()?
             .send_query_params(
-                query,
+                &self.connection.tag(query),
                 &param_types,
                 &param_values,
                 &param_formats,
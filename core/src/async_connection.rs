@@ -0,0 +1,612 @@
+use crate::Structure;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+/**
+ * Borrowed libpq socket, registered in the async-io reactor.
+ */
+struct Socket(std::os::unix::io::RawFd);
+
+impl std::os::unix::io::AsFd for Socket {
+    fn as_fd(&self) -> std::os::unix::io::BorrowedFd<'_> {
+        // The socket is owned and closed by libpq
+        unsafe { std::os::unix::io::BorrowedFd::borrow_raw(self.0) }
+    }
+}
+
+/**
+ * A connection whose queries don’t block the executor thread: queries are
+ * sent with the libpq non-blocking API and results are awaited with socket
+ * readiness notifications.
+ *
+ * It relies on the [async-io](https://docs.rs/async-io) reactor and works
+ * with any executor (tokio, async-std, smol…).
+ *
+ * A connection runs one query at a time, use one connection per concurrent
+ * task.
+ */
+#[derive(Clone, Debug)]
+pub struct AsyncConnection {
+    connection: crate::Connection,
+}
+
+impl AsyncConnection {
+    /**
+     * Connects to the database without blocking.
+     */
+    pub async fn new(dsn: &str) -> crate::Result<Self> {
+        crate::config::validate_dsn(dsn)?;
+
+        let error = |message| crate::Error::Connect {
+            dsn: dsn.to_string(),
+            message,
+        };
+
+        let connection = libpq::Connection::start(dsn).map_err(error)?;
+        let mut status = libpq::poll::Status::Writing;
+
+        loop {
+            match status {
+                libpq::poll::Status::Reading => Self::readable(&connection).await?,
+                libpq::poll::Status::Writing => Self::writable(&connection).await?,
+                libpq::poll::Status::Ok => break,
+                libpq::poll::Status::Failed => {
+                    return Err(error(connection.error_message().unwrap_or_default()))
+                }
+                libpq::poll::Status::Active => (),
+            }
+
+            status = connection.poll();
+        }
+
+        Ok(Self {
+            connection: crate::Connection::from_inner(connection),
+        })
+    }
+
+    /**
+     * The underlying blocking connection.
+     */
+    pub fn connection(&self) -> &crate::Connection {
+        &self.connection
+    }
+
+    /**
+     * Async version of [`Connection::execute`].
+     *
+     * [`Connection::execute`]: crate::Connection::execute
+     */
+    pub async fn execute(&self, query: &str) -> crate::Result<crate::pq::Result> {
+        let tagged = self.connection.tag(query);
+
+        self.send(query, |connection| connection.send_query(&tagged))
+            .await
+    }
+
+    /**
+     * Async version of [`Connection::query`].
+     *
+     * [`Connection::query`]: crate::Connection::query
+     */
+    pub async fn query<E: crate::Entity>(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::Rows<E>> {
+        Ok(self.send_query(query, params).await?.into())
+    }
+
+    /**
+     * Async version of [`Connection::query_one`].
+     *
+     * [`Connection::query_one`]: crate::Connection::query_one
+     */
+    pub async fn query_one<E: crate::Entity>(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<E> {
+        match self.query(query, params).await?.try_get(0) {
+            Some(e) => Ok(e),
+            None => Err(crate::Error::MissingField("0".to_string())),
+        }
+    }
+
+    /**
+     * Async version of [`Connection::find_by_pk`].
+     *
+     * [`Connection::find_by_pk`]: crate::Connection::find_by_pk
+     */
+    pub async fn find_by_pk<'a, M>(
+        &self,
+        pk: &HashMap<&str, &dyn crate::ToSql>,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let (clause, params) = self.connection.pk_clause::<M>(pk)?;
+        let mut tuples = self.find_where::<M>(&clause, &params, None).await?;
+
+        Ok(tuples.next())
+    }
+
+    /**
+     * Async version of [`Connection::find_all`].
+     *
+     * [`Connection::find_all`]: crate::Connection::find_all
+     */
+    pub async fn find_all<'a, M>(
+        &self,
+        suffix: Option<&str>,
+    ) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT {} FROM {} {};",
+            self.connection.projection::<M>(),
+            self.connection.relation::<M::Structure>(),
+            suffix.unwrap_or_default(),
+        );
+
+        self.query(&query, &[]).await
+    }
+
+    /**
+     * Async version of [`Connection::find_where`].
+     *
+     * [`Connection::find_where`]: crate::Connection::find_where
+     */
+    pub async fn find_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+        suffix: Option<&str>,
+    ) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = self.connection.find_where_query::<M>(clause, suffix);
+
+        self.query(&query, params).await
+    }
+
+    /**
+     * Async version of [`Connection::count_where`].
+     *
+     * [`Connection::count_where`]: crate::Connection::count_where
+     */
+    pub async fn count_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<usize>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT COUNT(*) FROM {} WHERE {};",
+            self.connection.relation::<M::Structure>(),
+            clause,
+        );
+
+        let results = self.send_query(&query, params).await?;
+
+        results.get(0).try_get("count")
+    }
+
+    /**
+     * Async version of [`Connection::exist_where`].
+     *
+     * [`Connection::exist_where`]: crate::Connection::exist_where
+     */
+    pub async fn exist_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<bool>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT EXISTS (SELECT true FROM {} WHERE {}) AS result;",
+            self.connection.relation::<M::Structure>(),
+            clause,
+        );
+
+        let results = self.send_query(&query, params).await?;
+
+        results.get(0).try_get("result")
+    }
+
+    /**
+     * Async version of [`Connection::insert_one`].
+     *
+     * [`Connection::insert_one`]: crate::Connection::insert_one
+     */
+    pub async fn insert_one<'a, M>(&self, entity: &M::Entity) -> crate::Result<M::Entity>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        use crate::Entity;
+
+        let mut tuple = Vec::new();
+        let mut fields = Vec::new();
+
+        for field in M::Structure::columns() {
+            if let Some(value) = entity.get(field) {
+                tuple.push(value);
+                fields.push(*field);
+            }
+        }
+
+        let query = self.connection.insert_query::<M>(&fields, None);
+
+        let results = self.send_query(&query, &tuple).await?;
+        self.connection
+            .emit::<M, _>(&results, |_| crate::events::Kind::Created)?;
+
+        Ok(M::create_entity(&results.get(0)))
+    }
+
+    /**
+     * Async version of [`Connection::update_by_pk`].
+     *
+     * [`Connection::update_by_pk`]: crate::Connection::update_by_pk
+     */
+    pub async fn update_by_pk<'a, M>(
+        &self,
+        pk: &HashMap<&str, &dyn crate::ToSql>,
+        data: &HashMap<String, &dyn crate::ToSql>,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let (query, params) = match self.connection.update_by_pk_query::<M>(pk, data)? {
+            Some(update) => update,
+            None => return Ok(None),
+        };
+
+        let results = self.send_query(&query, &params).await?;
+        self.connection
+            .emit::<M, _>(&results, |_| crate::events::Kind::Updated)?;
+
+        Ok(results.try_get(0).map(|x| M::create_entity(&x)))
+    }
+
+    /**
+     * Async version of [`Connection::delete_one`].
+     *
+     * [`Connection::delete_one`]: crate::Connection::delete_one
+     */
+    pub async fn delete_one<'a, M>(&self, entity: &M::Entity) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let pk = M::primary_key(entity)?;
+        let (clause, params) = self.connection.pk_clause::<M>(&pk)?;
+
+        Ok(self.delete_where::<M>(&clause, &params).await?.next())
+    }
+
+    /**
+     * Async version of [`Connection::delete_where`].
+     *
+     * [`Connection::delete_where`]: crate::Connection::delete_where
+     */
+    pub async fn delete_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let query = self.connection.delete_query::<M>(clause);
+
+        let results = self.send_query(&query, params).await?;
+        self.connection
+            .emit::<M, _>(&results, |_| crate::events::Kind::Deleted)?;
+
+        Ok(results.into())
+    }
+
+    async fn send_query(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::pq::Result> {
+        self.resolve_param_types(params).await?;
+
+        let (param_types, param_values, param_formats) = self.connection.encode_params(params)?;
+        let tagged = self
+            .connection
+            .tag(&self.connection.order_parameters(query));
+
+        self.send(query, |connection| {
+            connection.send_query_params(
+                &tagged,
+                &param_types,
+                &param_values,
+                &param_formats,
+                crate::pq::Format::Binary,
+            )
+        })
+        .await
+        .map_err(|err| crate::Connection::param_type_error(query, params, err))
+    }
+
+    /**
+     * Sends a query with `send`, then waits for its last result.
+     */
+    async fn send<F>(&self, query: &str, send: F) -> crate::Result<crate::pq::Result>
+    where
+        F: FnOnce(&libpq::Connection) -> std::result::Result<(), String>,
+    {
+        self.connection.watch(&*self.connection.lock()?, query)?;
+
+        let result = self.exec(send).await;
+
+        let status = self.connection.lock().map(|x| self.connection.touch(&x));
+
+        if let Ok(status) = status {
+            self.connection.settle_events(status, [&result])?;
+        }
+        let result = self.connection.count_error(result)?;
+
+        // Resolves the unknown types before `resolve_types` does it with a
+        // blocking query
+        let oids = self.connection.unknown_types(&result);
+
+        if !oids.is_empty() {
+            let filter = crate::Connection::nested_types_filter(&oids);
+
+            match self.load_types(&filter).await {
+                Ok(()) => self.connection.types().set_missing(&oids),
+                Err(err) => log::warn!("Unable to resolve types: {}", err),
+            }
+        }
+
+        self.connection.resolve_types(Ok(result))
+    }
+
+    /**
+     * Looks up the OID of the parameters types created by extensions, before
+     * `encode_params` does it with a blocking query.
+     */
+    async fn resolve_param_types(&self, params: &[&dyn crate::ToSql]) -> crate::Result {
+        let names = self.connection.unresolved_type_names(params);
+
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let filter = names
+            .iter()
+            .map(|x| self.connection.type_name_filter(x))
+            .collect::<crate::Result<Vec<_>>>()?
+            .join(" or ");
+
+        self.load_types(&filter).await?;
+
+        for name in &names {
+            self.connection.types().set_missing_name(name);
+        }
+
+        Ok(())
+    }
+
+    async fn load_types(&self, filter: &str) -> crate::Result {
+        let query = crate::Connection::types_query(filter);
+        let result = self
+            .exec(|connection| connection.send_query(&query))
+            .await?;
+
+        self.connection.register_types(&result);
+
+        Ok(())
+    }
+
+    /**
+     * Dispatches a query with `send` and waits for its last result.
+     */
+    async fn exec<F>(&self, send: F) -> crate::Result<crate::pq::Result>
+    where
+        F: FnOnce(&libpq::Connection) -> std::result::Result<(), String>,
+    {
+        let result = self.dispatch(send).await;
+
+        // Sync methods of the connection expect a blocking connection
+        if let Err(()) = self.connection.lock()?.set_non_blocking(false) {
+            log::warn!("Unable to restore the blocking mode");
+        }
+
+        match result {
+            Ok(()) => self.last_result().await,
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn dispatch<F>(&self, send: F) -> crate::Result
+    where
+        F: FnOnce(&libpq::Connection) -> std::result::Result<(), String>,
+    {
+        {
+            let connection = self.connection.lock()?;
+
+            if connection.set_non_blocking(true).is_err() {
+                return Err(crate::Error::Async(
+                    connection.error_message().unwrap_or_default(),
+                ));
+            }
+
+            send(&connection).map_err(crate::Error::Async)?;
+        }
+
+        loop {
+            let socket = {
+                let connection = self.connection.lock()?;
+
+                if connection.flush().is_ok() {
+                    return Ok(());
+                }
+
+                if connection.status() == libpq::connection::Status::Bad {
+                    return Err(crate::Error::Async(
+                        connection.error_message().unwrap_or_default(),
+                    ));
+                }
+
+                Self::socket(&connection)?
+            };
+
+            socket.writable().await?;
+        }
+    }
+
+    async fn last_result(&self) -> crate::Result<crate::pq::Result> {
+        let mut last_result = None;
+
+        loop {
+            let socket = {
+                let connection = self.connection.lock()?;
+                connection.consume_input().map_err(crate::Error::Async)?;
+
+                while !connection.is_busy() {
+                    match connection.result() {
+                        Some(result) => last_result = Some(result.try_into()),
+                        None => {
+                            return last_result.unwrap_or_else(|| {
+                                Err(crate::Error::Async("No result".to_string()))
+                            })
+                        }
+                    }
+                }
+
+                Self::socket(&connection)?
+            };
+
+            socket.readable().await?;
+        }
+    }
+
+    async fn readable(connection: &libpq::Connection) -> crate::Result {
+        Self::socket(connection)?.readable().await?;
+
+        Ok(())
+    }
+
+    async fn writable(connection: &libpq::Connection) -> crate::Result {
+        Self::socket(connection)?.writable().await?;
+
+        Ok(())
+    }
+
+    fn socket(connection: &libpq::Connection) -> crate::Result<async_io::Async<Socket>> {
+        let fd = connection.socket().map_err(|_| {
+            crate::Error::Async(
+                connection
+                    .error_message()
+                    .unwrap_or_else(|| "Invalid socket".to_string()),
+            )
+        })?;
+
+        Ok(async_io::Async::new(Socket(fd))?)
+    }
+}
+
+impl From<crate::Connection> for AsyncConnection {
+    fn from(connection: crate::Connection) -> Self {
+        Self { connection }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[async_std::test]
+    async fn query() -> crate::Result {
+        let conn = crate::AsyncConnection::new(&crate::test::dsn()).await?;
+
+        let sum = conn
+            .query_one::<i64>("select sum(x) from generate_series(1, $*) x", &[&100])
+            .await?;
+        assert_eq!(sum, 5050);
+
+        assert!(conn.execute("select 1 / 0").await.is_err());
+        assert_eq!(conn.query_one::<i32>("select 1", &[]).await?, 1);
+
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn model() -> crate::Result {
+        let conn = crate::AsyncConnection::new(&crate::test::dsn()).await?;
+        crate::test::create_entity_table(conn.connection(), true)?;
+
+        let entity = crate::test::Entity {
+            id: 1,
+            first_name: "Alice".to_string(),
+            last_name: "Liddell".to_string(),
+        };
+        conn.insert_one::<crate::test::Model<'_>>(&entity).await?;
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("first_name".to_string(), &"Bob" as &dyn crate::ToSql);
+        conn.update_by_pk::<crate::test::Model<'_>>(&crate::pk!(employee_id => 1), &data)
+            .await?;
+
+        let entity = conn
+            .find_by_pk::<crate::test::Model<'_>>(&crate::pk!(employee_id => 1))
+            .await?
+            .unwrap();
+        assert_eq!(entity.first_name, "Bob");
+        assert_eq!(
+            conn.count_where::<crate::test::Model<'_>>("true", &[])
+                .await?,
+            1
+        );
+
+        conn.delete_one::<crate::test::Model<'_>>(&entity).await?;
+        assert!(
+            !conn
+                .exist_where::<crate::test::Model<'_>>("true", &[])
+                .await?
+        );
+
+        // The blocking connection is still usable
+        assert_eq!(
+            conn.connection()
+                .count_where::<crate::test::Model<'_>>("true", &[])?,
+            0
+        );
+
+        Ok(())
+    }
+    #[async_std::test]
+    async fn types() -> crate::Result {
+        let conn = crate::AsyncConnection::new(&crate::test::dsn()).await?;
+        conn.execute("create type pg_temp.async_mood as enum ('sad', 'ok', 'happy')")
+            .await?;
+
+        let result = conn
+            .execute("select array['ok'::async_mood, 'happy'] as moods")
+            .await?;
+        let oid = conn.connection().types().oid("async_mood").unwrap();
+        assert_eq!(
+            result.get(0).field_type(0).kind,
+            libpq::types::Kind::Array(oid)
+        );
+
+        assert_eq!(
+            conn.query_one::<Vec<String>>("select array['sad'::async_mood]", &[])
+                .await?,
+            vec!["sad".to_string()]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+/**
+ * Timings of a benchmarked operation.
+ */
+#[derive(Clone, Debug)]
+pub struct Measure {
+    /** Name of the benchmark. */
+    pub name: String,
+    /** Number of timed iterations. */
+    pub iterations: u32,
+    /** Total time spent. */
+    pub total: std::time::Duration,
+    /** Fastest iteration. */
+    pub min: std::time::Duration,
+    /** Slowest iteration. */
+    pub max: std::time::Duration,
+}
+
+impl Measure {
+    /**
+     * Average time of an iteration.
+     */
+    pub fn mean(&self) -> std::time::Duration {
+        if self.iterations == 0 {
+            return std::time::Duration::default();
+        }
+
+        self.total / self.iterations
+    }
+
+    /**
+     * Number of iterations per second, 0 if no time was measured.
+     */
+    pub fn per_second(&self) -> f64 {
+        if self.total.is_zero() {
+            return 0.;
+        }
+
+        self.iterations as f64 / self.total.as_secs_f64()
+    }
+}
+
+impl std::fmt::Display for Measure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} iterations, mean {:?} (min {:?}, max {:?}), {:.1}/s",
+            self.name,
+            self.iterations,
+            self.mean(),
+            self.min,
+            self.max,
+            self.per_second()
+        )
+    }
+}
+
+/**
+ * Runs `f` once to warm up, then `iterations` times.
+ */
+pub fn run<F>(name: &str, iterations: u32, mut f: F) -> crate::Result<Measure>
+where
+    F: FnMut() -> crate::Result,
+{
+    f()?;
+
+    let mut measure = Measure {
+        name: name.to_string(),
+        iterations,
+        total: std::time::Duration::default(),
+        min: std::time::Duration::MAX,
+        max: std::time::Duration::default(),
+    };
+
+    for _ in 0..iterations {
+        let start = std::time::Instant::now();
+        f()?;
+        let elapsed = start.elapsed();
+
+        measure.total += elapsed;
+        measure.min = measure.min.min(elapsed);
+        measure.max = measure.max.max(elapsed);
+    }
+
+    if iterations == 0 {
+        measure.min = std::time::Duration::default();
+    }
+
+    Ok(measure)
+}
+
+/**
+ * Runs `f` on a connection of each configuration, to compare them.
+ *
+ * ```no_run
+ * let mut binary = elephantry::Config::new();
+ * binary.host = Some("localhost".to_string());
+ *
+ * let mut ssl = binary.clone();
+ * ssl.sslmode = Some(elephantry::SslMode::Require);
+ *
+ * let measures = elephantry::bench::compare(&[("plain", &binary), ("ssl", &ssl)], 100, |conn| {
+ *     conn.execute("select generate_series(1, 1000)").map(|_| ())
+ * })?;
+ *
+ * for measure in measures {
+ *     println!("{}", measure);
+ * }
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+pub fn compare<F>(
+    configs: &[(&str, &crate::Config)],
+    iterations: u32,
+    mut f: F,
+) -> crate::Result<Vec<Measure>>
+where
+    F: FnMut(&crate::Connection) -> crate::Result,
+{
+    let mut measures = Vec::new();
+
+    for (name, config) in configs {
+        let connection = crate::Connection::from_config(config)?;
+
+        measures.push(run(name, iterations, || f(&connection))?);
+    }
+
+    Ok(measures)
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn run() -> crate::Result {
+        let mut calls = 0;
+
+        let measure = super::run("calls", 10, || {
+            calls += 1;
+            Ok(())
+        })?;
+
+        assert_eq!(calls, 11);
+        assert_eq!(measure.iterations, 10);
+        assert!(measure.min <= measure.mean());
+        assert!(measure.mean() <= measure.max);
+
+        let measure = super::run("none", 0, || Ok(()))?;
+        assert_eq!(measure.mean(), std::time::Duration::default());
+        assert_eq!(measure.per_second(), 0.);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::VecDeque;
+
+/**
+ * Builder of [`BoundedPool`].
+ *
+ * ```no_run
+ * let pool = elephantry::PoolBuilder::new()
+ *     .max_size(10)
+ *     .min_idle(2)
+ *     .idle_timeout(Some(std::time::Duration::from_secs(300)))
+ *     .checkout_timeout(std::time::Duration::from_secs(5))
+ *     .build("postgres://localhost/elephantry")?;
+ *
+ * let connection = pool.get()?;
+ * connection.execute("select 1")?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct PoolBuilder {
+    max_size: usize,
+    min_idle: usize,
+    idle_timeout: Option<std::time::Duration>,
+    checkout_timeout: std::time::Duration,
+    test_on_checkout: bool,
+}
+
+impl PoolBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Maximum number of connections, idle or checked out. Defaults to 10.
+     */
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /**
+     * Number of connections opened by [`build`] and kept open when idle.
+     * Defaults to 0.
+     *
+     * [`build`]: Self::build
+     */
+    pub fn min_idle(mut self, min_idle: usize) -> Self {
+        self.min_idle = min_idle;
+        self
+    }
+
+    /**
+     * Idle connections above `min_idle` are closed after this delay.
+     * Defaults to 10 minutes, `None` keeps them forever.
+     */
+    pub fn idle_timeout(mut self, idle_timeout: Option<std::time::Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /**
+     * Maximum time [`BoundedPool::get`] waits for a connection. Defaults to
+     * 30 seconds.
+     */
+    pub fn checkout_timeout(mut self, checkout_timeout: std::time::Duration) -> Self {
+        self.checkout_timeout = checkout_timeout;
+        self
+    }
+
+    /**
+     * Checks idle connections with a `select 1` before handing them out,
+     * broken ones are replaced. Defaults to true.
+     */
+    pub fn test_on_checkout(mut self, test_on_checkout: bool) -> Self {
+        self.test_on_checkout = test_on_checkout;
+        self
+    }
+
+    /**
+     * Creates the pool and opens `min_idle` connections to `url`.
+     */
+    pub fn build(self, url: &str) -> crate::Result<BoundedPool> {
+        crate::config::validate_dsn(url)?;
+
+        if self.max_size == 0 || self.min_idle > self.max_size {
+            return Err(crate::Error::Connect {
+                dsn: url.to_string(),
+                message: format!(
+                    "Invalid pool size: min_idle ({}) must be lower than max_size ({}), which can't be 0",
+                    self.min_idle, self.max_size
+                ),
+            });
+        }
+
+        let pool = BoundedPool {
+            inner: std::sync::Arc::new(Inner {
+                url: url.to_string(),
+                builder: self,
+                state: std::sync::Mutex::new(State::default()),
+                available: std::sync::Condvar::new(),
+            }),
+        };
+
+        for _ in 0..pool.inner.builder.min_idle {
+            let connection = crate::Connection::new(url)?;
+
+            let mut state = pool.inner.lock();
+            state.size += 1;
+            state.idle.push_back(Idle::new(connection));
+        }
+
+        Ok(pool)
+    }
+}
+
+impl Default for PoolBuilder {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            min_idle: 0,
+            idle_timeout: Some(std::time::Duration::from_secs(600)),
+            checkout_timeout: std::time::Duration::from_secs(30),
+            test_on_checkout: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Idle {
+    connection: crate::Connection,
+    since: std::time::Instant,
+}
+
+impl Idle {
+    fn new(connection: crate::Connection) -> Self {
+        Self {
+            connection,
+            since: std::time::Instant::now(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    idle: VecDeque<Idle>,
+    /** Number of opened connections, idle or checked out. */
+    size: usize,
+}
+
+#[derive(Debug)]
+struct Inner {
+    url: String,
+    builder: PoolBuilder,
+    state: std::sync::Mutex<State>,
+    available: std::sync::Condvar,
+}
+
+impl Inner {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state stays consistent even if a thread panicked while holding
+        // the lock.
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /**
+     * Closes connections idle for more than `idle_timeout`, while keeping
+     * `min_idle` of them.
+     */
+    fn reap(&self, state: &mut State) {
+        let idle_timeout = match self.builder.idle_timeout {
+            Some(idle_timeout) => idle_timeout,
+            None => return,
+        };
+
+        // The oldest connections are at the front of the queue.
+        while state.idle.len() > self.builder.min_idle {
+            match state.idle.front() {
+                Some(idle) if idle.since.elapsed() >= idle_timeout => {
+                    state.idle.pop_front();
+                    state.size -= 1;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn release(&self, connection: crate::Connection) {
+        let reusable = !connection.has_broken().unwrap_or(true) && Self::rollback(&connection);
+
+        let mut state = self.lock();
+
+        if !reusable {
+            state.size -= 1;
+        } else {
+            state.idle.push_back(Idle::new(connection));
+        }
+
+        self.reap(&mut state);
+        self.available.notify_one();
+    }
+
+    /**
+     * Rollbacks the transaction left open by the previous user, returns
+     * `false` if the connection isn’t idle after that.
+     */
+    fn rollback(connection: &crate::Connection) -> bool {
+        use libpq::transaction::Status;
+
+        match connection.transaction_status() {
+            Ok(Status::Idle) => true,
+            Ok(Status::InTrans) | Ok(Status::InError) => {
+                connection.execute("rollback").is_ok()
+                    && matches!(connection.transaction_status(), Ok(Status::Idle))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_valid(&self, connection: &crate::Connection) -> bool {
+        if connection.has_broken().unwrap_or(true) {
+            return false;
+        }
+
+        !self.builder.test_on_checkout || connection.execute("select 1").is_ok()
+    }
+}
+
+/**
+ * Pool of interchangeable connections to the same database, to share them
+ * between threads.
+ *
+ * Unlike [`Pool`], each connection is used by only one thread at a time:
+ * [`get`] hands out a connection, returned to the pool when the
+ * [`PooledConnection`] is dropped.
+ *
+ * Idle connections are reaped when connections are checked out and returned.
+ *
+ * [`Pool`]: crate::Pool
+ * [`get`]: Self::get
+ */
+#[derive(Clone, Debug)]
+pub struct BoundedPool {
+    inner: std::sync::Arc<Inner>,
+}
+
+impl BoundedPool {
+    /**
+     * Retreives a connection, waits up to the checkout timeout if all
+     * connections are in use.
+     */
+    pub fn get(&self) -> crate::Result<PooledConnection> {
+        self.get_timeout(self.inner.builder.checkout_timeout)
+    }
+
+    /**
+     * Retreives a connection, waits up to `timeout` if all connections are in
+     * use.
+     */
+    pub fn get_timeout(&self, timeout: std::time::Duration) -> crate::Result<PooledConnection> {
+        let deadline = std::time::Instant::now() + timeout;
+
+        loop {
+            match self.checkout()? {
+                Some(connection) => return Ok(connection),
+                None => {
+                    let state = self.inner.lock();
+                    let now = std::time::Instant::now();
+
+                    if now >= deadline {
+                        return Err(crate::Error::Timeout(timeout));
+                    }
+
+                    if state.idle.is_empty() && state.size >= self.inner.builder.max_size {
+                        let _ = self.inner.available.wait_timeout(state, deadline - now);
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Retreives a connection if one is available without waiting.
+     */
+    pub fn try_get(&self) -> crate::Result<Option<PooledConnection>> {
+        self.checkout()
+    }
+
+    /**
+     * Number of opened connections, idle or checked out.
+     */
+    pub fn size(&self) -> usize {
+        self.inner.lock().size
+    }
+
+    /**
+     * Number of idle connections.
+     */
+    pub fn idle(&self) -> usize {
+        self.inner.lock().idle.len()
+    }
+
+    fn checkout(&self) -> crate::Result<Option<PooledConnection>> {
+        loop {
+            let mut state = self.inner.lock();
+            self.inner.reap(&mut state);
+
+            if let Some(idle) = state.idle.pop_back() {
+                drop(state);
+
+                if self.inner.is_valid(&idle.connection) {
+                    return Ok(Some(self.pooled(idle.connection)));
+                }
+
+                log::warn!("Dropping broken connection from pool");
+                self.inner.lock().size -= 1;
+                continue;
+            }
+
+            if state.size >= self.inner.builder.max_size {
+                return Ok(None);
+            }
+
+            // Reserves the slot while connecting, without holding the lock.
+            state.size += 1;
+            drop(state);
+
+            return match crate::Connection::new(&self.inner.url) {
+                Ok(connection) => Ok(Some(self.pooled(connection))),
+                Err(err) => {
+                    self.inner.lock().size -= 1;
+                    self.inner.available.notify_one();
+                    Err(err)
+                }
+            };
+        }
+    }
+
+    fn pooled(&self, connection: crate::Connection) -> PooledConnection {
+        PooledConnection {
+            connection: Some(connection),
+            pool: self.inner.clone(),
+        }
+    }
+}
+
+/**
+ * Connection checked out from a [`BoundedPool`], returned to the pool on drop.
+ */
+#[derive(Debug)]
+pub struct PooledConnection {
+    connection: Option<crate::Connection>,
+    pool: std::sync::Arc<Inner>,
+}
+
+impl std::ops::Deref for PooledConnection {
+    type Target = crate::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection.as_ref().unwrap()
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.release(connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn checkout() -> crate::Result {
+        let pool = crate::PoolBuilder::new()
+            .max_size(2)
+            .min_idle(1)
+            .checkout_timeout(std::time::Duration::from_millis(50))
+            .build(&crate::test::dsn())?;
+        assert_eq!(pool.size(), 1);
+
+        let first = pool.get()?;
+        let second = pool.get()?;
+        assert_eq!(pool.size(), 2);
+        assert_eq!(pool.idle(), 0);
+
+        assert!(pool.try_get()?.is_none());
+        assert!(matches!(pool.get(), Err(crate::Error::Timeout(_))));
+
+        let thread = {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                pool.get_timeout(std::time::Duration::from_secs(1))
+                    .map(|x| x.query_one::<i32>("select 1", &[]))
+            })
+        };
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        drop(first);
+        assert_eq!(thread.join().unwrap()??, 1);
+
+        drop(second);
+        assert_eq!(pool.idle(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn idle_timeout() -> crate::Result {
+        let pool = crate::PoolBuilder::new()
+            .max_size(3)
+            .min_idle(1)
+            .idle_timeout(Some(std::time::Duration::from_millis(20)))
+            .build(&crate::test::dsn())?;
+
+        let connections = (0..3)
+            .map(|_| pool.get())
+            .collect::<crate::Result<Vec<_>>>()?;
+        drop(connections);
+        assert_eq!(pool.idle(), 3);
+
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        let _connection = pool.get()?;
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.idle(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn release_transaction() -> crate::Result {
+        let pool = crate::PoolBuilder::new()
+            .max_size(1)
+            .build(&crate::test::dsn())?;
+
+        let connection = pool.get()?;
+        connection.execute("begin")?;
+        assert!(connection.execute("select 1 / 0").is_err());
+        drop(connection);
+
+        let connection = pool.get()?;
+        assert_eq!(
+            connection.transaction_status()?,
+            libpq::transaction::Status::Idle
+        );
+        assert_eq!(connection.query_one::<i32>("select 1", &[])?, 1);
+
+        connection.execute("begin")?;
+        drop(connection);
+        assert_eq!(
+            pool.get()?.transaction_status()?,
+            libpq::transaction::Status::Idle
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_size() {
+        assert!(crate::PoolBuilder::new()
+            .max_size(1)
+            .min_idle(2)
+            .build(&crate::test::dsn())
+            .is_err());
+    }
+}
@@ -0,0 +1,233 @@
+lazy_static::lazy_static! {
+    static ref CIPHER: std::sync::RwLock<Option<std::sync::Arc<dyn FieldCipher>>> =
+        std::sync::RwLock::new(None);
+}
+
+/**
+ * Encrypts and decrypts the fields marked with `#[elephantry(encrypted)]`.
+ *
+ * ```no_run
+ * struct Cipher;
+ *
+ * impl elephantry::FieldCipher for Cipher {
+ *     fn encrypt(&self, plaintext: &[u8]) -> elephantry::Result<Vec<u8>> {
+ *         todo!()
+ *     }
+ *
+ *     fn decrypt(&self, ciphertext: &[u8]) -> elephantry::Result<Vec<u8>> {
+ *         todo!()
+ *     }
+ * }
+ *
+ * elephantry::set_field_cipher(Cipher);
+ *
+ * #[derive(elephantry::Entity)]
+ * struct Customer {
+ *     #[elephantry(pk)]
+ *     id: i32,
+ *     #[elephantry(encrypted)]
+ *     email: String,
+ * }
+ * ```
+ */
+pub trait FieldCipher: Send + Sync {
+    fn encrypt(&self, plaintext: &[u8]) -> crate::Result<Vec<u8>>;
+    fn decrypt(&self, ciphertext: &[u8]) -> crate::Result<Vec<u8>>;
+}
+
+/**
+ * Sets the cipher used by [`Encrypted`] values.
+ */
+pub fn set_field_cipher<C: FieldCipher + 'static>(cipher: C) {
+    *CIPHER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(std::sync::Arc::new(cipher));
+}
+
+fn cipher() -> crate::Result<std::sync::Arc<dyn FieldCipher>> {
+    CIPHER
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone()
+        .ok_or_else(|| crate::Error::Encryption("no field cipher set".to_string()))
+}
+
+/**
+ * Value encrypted with the cipher set by [`set_field_cipher`], stored as
+ * `bytea`.
+ *
+ * The plaintext is the SQL representation of `T`, prefixed by its format and
+ * type oid to decode it back. `NULL` isn’t encrypted.
+ */
+#[derive(Clone, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct Encrypted<T>(T);
+
+impl<T> Encrypted<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /**
+     * Wraps a reference, to encrypt a borrowed value.
+     */
+    pub fn from_ref(value: &T) -> &Self {
+        // Safe thanks to `repr(transparent)`
+        unsafe { &*(value as *const T as *const Self) }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Encrypted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: crate::ToSql> crate::ToSql for Encrypted<T> {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::BYTEA
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let mut raw = match self.0.to_sql()? {
+            Some(raw) => raw,
+            None => return Ok(None),
+        };
+
+        let format = self.0.format();
+
+        // Text values are sent as nul terminated strings
+        if format == crate::pq::Format::Text && raw.last() == Some(&0) {
+            raw.pop();
+        }
+
+        let mut plaintext = Vec::with_capacity(raw.len() + 5);
+        plaintext.push(match format {
+            crate::pq::Format::Binary => 1,
+            crate::pq::Format::Text => 0,
+        });
+        plaintext.extend_from_slice(&self.0.ty().oid.to_be_bytes());
+        plaintext.extend_from_slice(&raw);
+
+        cipher()?.encrypt(&plaintext).map(Some)
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        match self.to_sql()? {
+            Some(ciphertext) => crate::Bytea::from(ciphertext).to_text(),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: crate::FromSql> crate::FromSql for Encrypted<T> {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let ciphertext = raw
+            .map(|x| crate::Bytea::from_text(ty, Some(x)))
+            .transpose()?;
+
+        Self::from_binary(ty, ciphertext.as_deref().map(Vec::as_slice))
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        use std::convert::TryFrom;
+
+        let ciphertext = match raw {
+            Some(ciphertext) => ciphertext,
+            None => return T::from_binary(ty, None).map(Self),
+        };
+
+        let plaintext = cipher()?.decrypt(ciphertext)?;
+
+        if plaintext.len() < 5 {
+            return Err(Self::error(ty, "Encrypted", raw));
+        }
+
+        let format = match plaintext[0] {
+            1 => crate::pq::Format::Binary,
+            _ => crate::pq::Format::Text,
+        };
+        let oid = u32::from_be_bytes([plaintext[1], plaintext[2], plaintext[3], plaintext[4]]);
+        let ty = crate::pq::Type::try_from(oid).unwrap_or_else(|_| ty.clone());
+
+        T::from_sql(&ty, format, Some(&plaintext[5..])).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    struct Xor;
+
+    impl crate::FieldCipher for Xor {
+        fn encrypt(&self, plaintext: &[u8]) -> crate::Result<Vec<u8>> {
+            Ok(plaintext.iter().map(|x| x ^ 0x42).collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> crate::Result<Vec<u8>> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    #[derive(Debug, PartialEq, elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "Model",
+        structure = "Structure",
+        relation = "customer"
+    )]
+    struct Customer {
+        #[elephantry(pk)]
+        id: i32,
+        #[elephantry(encrypted)]
+        email: String,
+        #[elephantry(encrypted)]
+        age: Option<i32>,
+    }
+
+    #[test]
+    fn encrypted() -> crate::Result {
+        crate::set_field_cipher(Xor);
+
+        let connection = crate::test::new_conn()?;
+        connection.execute(
+            "create temporary table customer (id int primary key, email bytea, age bytea)",
+        )?;
+
+        let customer = Customer {
+            id: 1,
+            email: "alice@example.org".to_string(),
+            age: None,
+        };
+        connection.insert_one::<Model<'_>>(&customer)?;
+
+        let stored = connection.query_one::<crate::Bytea>("select email from customer", &[])?;
+        assert!(!String::from_utf8_lossy(&stored).contains("alice"));
+        assert!(connection
+            .query_one::<Option<crate::Bytea>>("select age from customer", &[])?
+            .is_none());
+
+        assert_eq!(
+            connection.find_by_pk::<Model<'_>>(&crate::pk!(id => 1))?,
+            Some(customer)
+        );
+
+        let age = crate::Encrypted::new(Some(42));
+        connection.update_by_pk::<Model<'_>>(&crate::pk!(id => 1), &crate::values!(age))?;
+        let customer = connection
+            .find_by_pk::<Model<'_>>(&crate::pk!(id => 1))?
+            .unwrap();
+        assert_eq!(customer.age, Some(42));
+
+        Ok(())
+    }
+}
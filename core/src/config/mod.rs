@@ -1,12 +1,19 @@
 mod channel_binding;
+mod dsn;
 mod gssencmode;
+mod password_provider;
 mod sslmode;
 mod target_session_attrs;
+mod tls;
 
 pub use channel_binding::*;
 pub use gssencmode::*;
+pub use password_provider::*;
 pub use sslmode::*;
 pub use target_session_attrs::*;
+pub use tls::*;
+
+pub(crate) use dsn::validate as validate_dsn;
 
 /**
  * Connection configuration.
@@ -34,6 +41,11 @@ pub struct Config {
     pub options: Option<String>,
     pub passfile: Option<String>,
     pub password: Option<String>,
+    /**
+     * Overrides `password` when connecting.
+     */
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub password_provider: Option<PasswordProvider>,
     pub port: Option<String>,
     pub replication: Option<String>,
     pub requirepeer: Option<String>,
@@ -49,60 +61,265 @@ pub struct Config {
     pub sslrootcert: Option<String>,
     pub target_session_attrs: Option<TargetSessionAttrs>,
     pub tcp_user_timeout: Option<i32>,
+    /**
+     * Applied once connected, see [`TimezonePolicy`].
+     *
+     * [`TimezonePolicy`]: crate::TimezonePolicy
+     */
+    pub timezone_policy: Option<crate::TimezonePolicy>,
+    /**
+     * Overrides `sslcert`, `sslkey` and `sslrootcert` with in-memory
+     * material when connecting.
+     */
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub tls: Option<TlsMaterial>,
     pub user: Option<String>,
 }
 
+macro_rules! param {
+    ($params:ident, $config:ident . $name:ident) => {
+        if let Some($name) = &$config.$name {
+            $params.push((stringify!($name), $name.to_string()));
+        }
+    };
+}
+
 impl Config {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /**
+     * Checks the parameters with the libpq connection string parser, and the
+     * consistency of encryption parameters: `channel_binding=require` needs
+     * SSL, it can’t be used with `sslmode=disable`.
+     *
+     * The other combinations are left to libpq, like `gssencmode=require`
+     * with `sslmode=require`: GSSAPI encryption is used in preference to SSL
+     * whatever `sslmode`.
+     */
+    pub fn validate(&self) -> crate::Result {
+        if self.channel_binding == Some(ChannelBinding::Require)
+            && self.sslmode == Some(SslMode::Disable)
+        {
+            return Err(crate::Error::InvalidConfig(
+                "channel_binding=require needs SSL but sslmode=disable".to_string(),
+            ));
+        }
+
+        libpq::connection::Info::from(&self.to_string()).map_err(crate::Error::InvalidConfig)?;
+
+        Ok(())
+    }
+
+    /**
+     * Returns a copy of this configuration with the password retreived from
+     * the password provider, if any.
+     */
+    pub(crate) fn resolve(&self) -> crate::Result<Self> {
+        let mut config = self.clone();
+
+        if let Some(provider) = &self.password_provider {
+            config.password = Some(provider.password()?);
+        }
+
+        Ok(config)
+    }
+
+    /**
+     * Creates a configuration to connect via the Unix-domain socket in the
+     * `dir` directory. Starts `dir` with `@` to use the abstract namespace.
+     */
+    pub fn unix(dir: &str, port: u16) -> Self {
+        Self {
+            host: Some(dir.to_string()),
+            port: Some(port.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /**
+     * Returns the connection URI, with correctly percent-encoded values.
+     *
+     * See <https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-CONNSTRING>.
+     */
+    pub fn to_url(&self) -> String {
+        let mut url = "postgresql://".to_string();
+
+        if let Some(user) = &self.user {
+            url.push_str(&percent_encode(user));
+
+            if let Some(password) = &self.password {
+                url.push(':');
+                url.push_str(&percent_encode(password));
+            }
+
+            url.push('@');
+        }
+
+        if let Some(host) = &self.host {
+            url.push_str(&percent_encode(host));
+        }
+
+        if let Some(port) = &self.port {
+            url.push(':');
+            url.push_str(&percent_encode(port));
+        }
+
+        url.push('/');
+
+        if let Some(dbname) = &self.dbname {
+            url.push_str(&percent_encode(dbname));
+        }
+
+        let query = self
+            .params()
+            .iter()
+            .filter(|(name, _)| !["user", "password", "host", "port", "dbname"].contains(name))
+            .map(|(name, value)| format!("{}={}", name, percent_encode(value)))
+            .collect::<Vec<_>>();
+
+        if !query.is_empty() {
+            url.push('?');
+            url.push_str(&query.join("&"));
+        }
+
+        url
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        param!(params, self.application_name);
+        param!(params, self.channel_binding);
+        param!(params, self.client_encoding);
+        param!(params, self.connect_timeout);
+        param!(params, self.dbname);
+        param!(params, self.fallback_application_name);
+        param!(params, self.gssencmode);
+        param!(params, self.gsslib);
+        param!(params, self.hostaddr);
+        param!(params, self.host);
+        param!(params, self.keepalives_count);
+        param!(params, self.keepalives_idle);
+        param!(params, self.keepalives_interval);
+        param!(params, self.keepalives);
+        param!(params, self.krbsrvname);
+        param!(params, self.options);
+        param!(params, self.passfile);
+        param!(params, self.password);
+        param!(params, self.port);
+        param!(params, self.replication);
+        param!(params, self.requirepeer);
+        param!(params, self.service);
+        param!(params, self.sslcert);
+        param!(params, self.sslcompression);
+        param!(params, self.sslcrl);
+        param!(params, self.sslkey);
+        param!(params, self.ssl_max_protocol_version);
+        param!(params, self.ssl_min_protocol_version);
+        param!(params, self.sslmode);
+        param!(params, self.sslpassword);
+        param!(params, self.sslrootcert);
+        param!(params, self.target_session_attrs);
+        param!(params, self.tcp_user_timeout);
+        param!(params, self.user);
+
+        params
+    }
 }
 
-macro_rules! display {
-    ($f:ident, $config:ident . $name:ident) => {
-        if let Some($name) = &$config.$name {
-            write!($f, "{}={} ", stringify!($name), $name)?;
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
         }
-    };
+    }
+
+    encoded
+}
+
+fn quote(s: &str) -> std::borrow::Cow<'_, str> {
+    if !s.is_empty() && !s.contains(|c: char| c.is_whitespace() || c == '\'' || c == '\\') {
+        return s.into();
+    }
+
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")).into()
 }
 
 impl std::fmt::Display for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        display!(f, self.application_name);
-        display!(f, self.channel_binding);
-        display!(f, self.client_encoding);
-        display!(f, self.connect_timeout);
-        display!(f, self.dbname);
-        display!(f, self.fallback_application_name);
-        display!(f, self.gssencmode);
-        display!(f, self.gsslib);
-        display!(f, self.hostaddr);
-        display!(f, self.host);
-        display!(f, self.keepalives_count);
-        display!(f, self.keepalives_idle);
-        display!(f, self.keepalives_interval);
-        display!(f, self.keepalives);
-        display!(f, self.krbsrvname);
-        display!(f, self.options);
-        display!(f, self.passfile);
-        display!(f, self.password);
-        display!(f, self.port);
-        display!(f, self.replication);
-        display!(f, self.requirepeer);
-        display!(f, self.service);
-        display!(f, self.sslcert);
-        display!(f, self.sslcompression);
-        display!(f, self.sslcrl);
-        display!(f, self.sslkey);
-        display!(f, self.ssl_max_protocol_version);
-        display!(f, self.ssl_min_protocol_version);
-        display!(f, self.sslmode);
-        display!(f, self.sslpassword);
-        display!(f, self.sslrootcert);
-        display!(f, self.target_session_attrs);
-        display!(f, self.tcp_user_timeout);
-        display!(f, self.user);
+        for (name, value) in self.params() {
+            write!(f, "{}={} ", name, quote(&value))?;
+        }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn validate() {
+        let mut config = crate::Config::new();
+        assert!(config.validate().is_ok());
+
+        config.channel_binding = Some(crate::ChannelBinding::Require);
+        config.sslmode = Some(crate::SslMode::Require);
+        assert!(config.validate().is_ok());
+
+        config.sslmode = Some(crate::SslMode::Disable);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_gssencmode() {
+        let mut config = crate::Config::new();
+
+        config.gssencmode = Some(crate::GssEncMode::Require);
+        config.sslmode = Some(crate::SslMode::Prefer);
+        assert!(config.validate().is_ok());
+
+        // Accepted by libpq, GSSAPI encryption is preferred to SSL
+        config.sslmode = Some(crate::SslMode::VerifyFull);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn unix() {
+        let config = crate::Config::unix("/var/run/postgresql", 5432);
+
+        assert_eq!(config.to_string(), "host=/var/run/postgresql port=5432 ");
+        assert_eq!(
+            config.to_url(),
+            "postgresql://%2Fvar%2Frun%2Fpostgresql:5432/"
+        );
+    }
+
+    #[test]
+    fn to_url() {
+        let mut config = crate::Config::unix("/tmp", 5432);
+        config.user = Some("user".to_string());
+        config.password = Some("p@ss:word".to_string());
+        config.dbname = Some("db".to_string());
+        config.application_name = Some("my app".to_string());
+
+        assert_eq!(
+            config.to_url(),
+            "postgresql://user:p%40ss%3Aword@%2Ftmp:5432/db?application_name=my%20app"
+        );
+    }
+
+    #[test]
+    fn quote() {
+        let mut config = crate::Config::new();
+        config.password = Some("it's a secret".to_string());
+
+        assert_eq!(config.to_string(), "password='it\\'s a secret' ");
+    }
+}
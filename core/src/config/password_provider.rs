@@ -0,0 +1,28 @@
+/**
+ * Password evaluated at connection time, and again when the connection is
+ * reset, for short-lived credentials (IAM token, vault…).
+ */
+#[derive(Clone)]
+pub struct PasswordProvider(std::sync::Arc<dyn Fn() -> crate::Result<String> + Send + Sync>);
+
+impl PasswordProvider {
+    pub fn new<F>(provider: F) -> Self
+    where
+        F: Fn() -> crate::Result<String> + Send + Sync + 'static,
+    {
+        Self(std::sync::Arc::new(provider))
+    }
+
+    /**
+     * Retreives a fresh password.
+     */
+    pub fn password(&self) -> crate::Result<String> {
+        (self.0)()
+    }
+}
+
+impl std::fmt::Debug for PasswordProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PasswordProvider")
+    }
+}
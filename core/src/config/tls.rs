@@ -0,0 +1,132 @@
+/**
+ * TLS certificates and key held in memory, for environments where secrets
+ * aren’t available as files.
+ *
+ * libpq only reads them from files: they are written in a private temporary
+ * directory, removed when the connection is dropped.
+ */
+#[derive(Clone, Default)]
+pub struct TlsMaterial {
+    /** Client certificate, PEM encoded. Replaces `sslcert`. */
+    pub client_cert: Option<Vec<u8>>,
+    /** Client private key, PEM encoded. Replaces `sslkey`. */
+    pub client_key: Option<Vec<u8>>,
+    /** Certificate authorities, PEM encoded. Replaces `sslrootcert`. */
+    pub root_cert: Option<Vec<u8>>,
+}
+
+impl TlsMaterial {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Writes the material in a temporary directory and sets the `sslcert`,
+     * `sslkey` and `sslrootcert` parameters of `config`.
+     */
+    pub(crate) fn write(&self, config: &mut crate::Config) -> crate::Result<TlsFiles> {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "elephantry-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(&dir)?;
+
+        let files = TlsFiles { dir };
+
+        if let Some(cert) = &self.client_cert {
+            config.sslcert = Some(files.write("client.crt", cert)?);
+        }
+
+        if let Some(key) = &self.client_key {
+            config.sslkey = Some(files.write("client.key", key)?);
+        }
+
+        if let Some(cert) = &self.root_cert {
+            config.sslrootcert = Some(files.write("root.crt", cert)?);
+        }
+
+        Ok(files)
+    }
+}
+
+impl std::fmt::Debug for TlsMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsMaterial")
+            .field("client_cert", &self.client_cert.is_some())
+            .field("client_key", &self.client_key.is_some())
+            .field("root_cert", &self.root_cert.is_some())
+            .finish()
+    }
+}
+
+/**
+ * Temporary directory containing the TLS material, removed on drop.
+ */
+#[derive(Debug)]
+pub(crate) struct TlsFiles {
+    dir: std::path::PathBuf,
+}
+
+impl TlsFiles {
+    fn write(&self, name: &str, contents: &[u8]) -> crate::Result<String> {
+        use std::io::Write;
+
+        let path = self.dir.join(name);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(&path)?.write_all(contents)?;
+
+        Ok(path.to_string_lossy().to_string())
+    }
+}
+
+impl Drop for TlsFiles {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_dir_all(&self.dir) {
+            log::warn!("Unable to remove {}: {}", self.dir.display(), err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn write() -> crate::Result {
+        let material = crate::TlsMaterial {
+            client_key: Some(b"key".to_vec()),
+            root_cert: Some(b"root".to_vec()),
+            ..Default::default()
+        };
+
+        let mut config = crate::Config::new();
+        let files = material.write(&mut config)?;
+
+        assert!(config.sslcert.is_none());
+        let key = config.sslkey.clone().unwrap();
+        assert_eq!(std::fs::read(&key)?, b"key");
+        assert_eq!(std::fs::read(config.sslrootcert.unwrap())?, b"root");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = std::fs::metadata(&key)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        drop(files);
+        assert!(!std::path::Path::new(&key).exists());
+
+        Ok(())
+    }
+}
@@ -9,12 +9,111 @@ use std::convert::TryInto;
  */
 pub type PingStatus = libpq::ping::Status;
 
+/**
+ * Result type of [`auth_method`] function.
+ *
+ * [`auth_method`]: crate::Connection::auth_method
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuthMethod {
+    /**
+     * GSSAPI authentication, `encrypted` is true if GSSAPI encryption is used.
+     */
+    Gss { encrypted: bool },
+    /**
+     * Password authentication, `channel_binding` is true if SCRAM channel
+     * binding is in effect.
+     *
+     * Channel binding is only ensured with `channel_binding=require`: with
+     * `prefer`, the server can still choose an authentication without channel
+     * binding and `channel_binding` is false.
+     */
+    Password { channel_binding: bool },
+    /**
+     * Authentication without password (trust, peer, ident, certificate…).
+     */
+    Other,
+}
+
+/**
+ * Result of a statement executed by [`execute_multi`].
+ *
+ * [`execute_multi`]: crate::Connection::execute_multi
+ */
+#[derive(Debug)]
+pub struct ResultSummary {
+    /** Command tag of the statement, `None` on error. */
+    pub command_tag: Option<crate::pq::CommandTag>,
+    /** Number of rows returned or affected by the statement. */
+    pub rows: u64,
+    /** Returned rows or error. */
+    pub result: crate::Result<crate::pq::Result>,
+}
+
 /**
  * A connection to a database.
  */
 #[derive(Clone, Debug)]
 pub struct Connection {
-    connection: std::sync::Arc<std::sync::Mutex<libpq::Connection>>,
+    /* `None` once closed */
+    connection: std::sync::Arc<std::sync::Mutex<Option<libpq::Connection>>>,
+    /* Configuration with a password provider, to reconnect with a fresh password */
+    config: Option<std::sync::Arc<crate::Config>>,
+    pub(crate) events: std::sync::Arc<crate::events::Queue>,
+    pub(crate) stats: std::sync::Arc<crate::stats::Stats>,
+    detached: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    retry_policy: std::sync::Arc<std::sync::RwLock<Option<crate::RetryPolicy>>>,
+    #[allow(clippy::type_complexity)]
+    prepared:
+        std::sync::Arc<std::sync::RwLock<HashMap<String, Vec<(String, Vec<crate::pq::Oid>)>>>>,
+    statements: std::sync::Arc<std::sync::Mutex<crate::statement::Cache>>,
+    default_schema: std::sync::Arc<std::sync::RwLock<Option<String>>>,
+    masking_policy: std::sync::Arc<std::sync::RwLock<Option<crate::MaskingPolicy>>>,
+    query_tagger: std::sync::Arc<std::sync::RwLock<Option<crate::tag::QueryTagger>>>,
+    streaming: std::sync::Arc<std::sync::Mutex<Option<std::thread::ThreadId>>>,
+    timezone_policy: std::sync::Arc<std::sync::RwLock<crate::TimezonePolicy>>,
+    tls: Option<std::sync::Arc<crate::config::TlsFiles>>,
+    types: std::sync::Arc<crate::pq::Registry>,
+    watchdog: std::sync::Arc<std::sync::Mutex<crate::watchdog::Watch>>,
+}
+
+/**
+ * Locked connection, returned by [`Connection::lock`].
+ *
+ * The checkout timeout callback is called once the lock is released: it can
+ * use the connection.
+ */
+pub(crate) struct Guard<'c> {
+    connection: Option<std::sync::MutexGuard<'c, Option<libpq::Connection>>>,
+    timeout: Option<crate::stats::Timeout>,
+}
+
+impl std::ops::Deref for Guard<'_> {
+    type Target = libpq::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        // Only taken on drop, and a closed connection is never locked
+        self.connection.as_deref().and_then(Option::as_ref).unwrap()
+    }
+}
+
+impl std::ops::DerefMut for Guard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+            .as_deref_mut()
+            .and_then(Option::as_mut)
+            .unwrap()
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        drop(self.connection.take());
+
+        if let Some(timeout) = self.timeout.take() {
+            timeout.notify();
+        }
+    }
 }
 
 extern "C" fn notice_processor(_arg: *mut std::ffi::c_void, message: *const i8) {
@@ -25,6 +124,8 @@ extern "C" fn notice_processor(_arg: *mut std::ffi::c_void, message: *const i8)
 
 impl Connection {
     pub fn new(dsn: &str) -> crate::Result<Self> {
+        crate::config::validate_dsn(dsn)?;
+
         let connection = match libpq::Connection::new(dsn) {
             Ok(connection) => connection,
             Err(message) => {
@@ -35,732 +136,3694 @@ impl Connection {
             }
         };
 
-        connection.set_error_verbosity(libpq::Verbosity::Terse);
-        connection.set_client_encoding(libpq::Encoding::UTF8);
+        Ok(Self::from_inner(connection))
+    }
 
-        unsafe {
-            connection.set_notice_processor(Some(notice_processor), std::ptr::null_mut());
-        }
+    /**
+     * Waits until the server at `dsn` accepts connections, pinging it every
+     * `interval`, at least 10 milliseconds, doubled after each attempt up to
+     * 5 seconds.
+     *
+     * Returns [`Error::Timeout`] if the server isn’t ready after `timeout`,
+     * or [`Error::Ping`] if the parameters are invalid.
+     *
+     * ```no_run
+     * let dsn = "postgres://localhost/elephantry";
+     *
+     * elephantry::Connection::wait_until_ready(
+     *     dsn,
+     *     std::time::Duration::from_secs(30),
+     *     std::time::Duration::from_millis(100),
+     * )?;
+     * let connection = elephantry::Connection::new(dsn)?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     *
+     * [`Error::Timeout`]: crate::Error::Timeout
+     * [`Error::Ping`]: crate::Error::Ping
+     */
+    pub fn wait_until_ready(
+        dsn: &str,
+        timeout: std::time::Duration,
+        interval: std::time::Duration,
+    ) -> crate::Result {
+        const MIN_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+        const MAX_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
 
-        Ok(Self {
-            connection: std::sync::Arc::new(std::sync::Mutex::new(connection)),
-        })
-    }
+        crate::config::validate_dsn(dsn)?;
 
-    pub fn r#async(&self) -> crate::Async<'_> {
-        crate::Async::new(&self.connection)
-    }
+        let deadline = std::time::Instant::now() + timeout;
+        // A zero interval would ping the server in a busy loop
+        let mut interval = interval.max(MIN_INTERVAL);
 
-    pub fn transaction(&self) -> crate::Transaction<'_> {
-        crate::Transaction::new(self)
+        loop {
+            match libpq::Connection::ping(dsn) {
+                PingStatus::Ok => return Ok(()),
+                PingStatus::NoAttempt => return Err(crate::Error::Ping(PingStatus::NoAttempt)),
+                status => log::debug!("Server not ready: {:?}", status),
+            }
+
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                return Err(crate::Error::Timeout(timeout));
+            }
+
+            std::thread::sleep(interval.min(deadline - now));
+            interval = (interval * 2).min(MAX_INTERVAL.max(interval));
+        }
     }
 
-    pub(crate) fn transaction_status(&self) -> crate::Result<libpq::transaction::Status> {
-        let status = self
-            .connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .transaction_status();
+    /**
+     * Wraps an established libpq connection.
+     */
+    pub(crate) fn from_inner(connection: libpq::Connection) -> Self {
+        Self::setup(&connection);
 
-        Ok(status)
+        Self {
+            connection: std::sync::Arc::new(std::sync::Mutex::new(Some(connection))),
+            config: None,
+            events: Default::default(),
+            stats: Default::default(),
+            detached: Default::default(),
+            retry_policy: Default::default(),
+            prepared: Default::default(),
+            statements: Default::default(),
+            default_schema: Default::default(),
+            masking_policy: Default::default(),
+            query_tagger: Default::default(),
+            streaming: Default::default(),
+            timezone_policy: Default::default(),
+            tls: None,
+            types: Default::default(),
+            watchdog: Default::default(),
+        }
     }
 
-    pub(crate) fn escape_identifier(&self, str: &str) -> crate::Result<String> {
-        self.connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .escape_identifier(str)
-            .map_err(|e| crate::Error::Escape(str.to_string(), e))
+    fn setup(connection: &libpq::Connection) {
+        connection.set_error_verbosity(libpq::Verbosity::Terse);
+        connection.set_client_encoding(libpq::Encoding::UTF8);
+
+        unsafe {
+            connection.set_notice_processor(Some(notice_processor), std::ptr::null_mut());
+        }
     }
 
     /**
-     * Creates a new connection from [`Config`].
-     *
-     * [`Config`]: crate::Config
+     * Reconnects the broken or reset `connection`. With a password provider,
+     * a new connection is established with a fresh password, libpq reuses
+     * the initial one.
      */
-    pub fn from_config(config: &crate::Config) -> crate::Result<Self> {
-        Self::new(&config.to_string())
-    }
+    fn reconnect(&self, connection: &mut libpq::Connection) -> crate::Result {
+        let config = match &self.config {
+            Some(config) => config.resolve()?,
+            None => {
+                connection.reset();
+                return Ok(());
+            }
+        };
 
-    pub fn model<'a, M>(&'a self) -> M
-    where
-        M: crate::Model<'a>,
-    {
-        M::new(self)
+        let dsn = config.to_string();
+
+        *connection = libpq::Connection::new(&dsn)
+            .map_err(|message| crate::Error::Connect { dsn, message })?;
+        Self::setup(connection);
+
+        Ok(())
     }
 
     /**
-     * Executes a simple text query, without parameter.
+     * OID of the `hstore` type. It’s created by an extension, so its OID
+     * differs between databases: it’s looked up from `pg_type` on first use,
+     * then kept by the [`types`] registry.
+     *
+     * Returns `None` if the extension isn’t installed.
+     *
+     * [`types`]: Self::types
      */
-    pub fn execute(&self, query: &str) -> crate::Result<crate::pq::Result> {
-        self.connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .exec(query)
-            .try_into()
+    #[cfg(feature = "hstore")]
+    pub fn hstore_oid(&self) -> Option<crate::pq::Oid> {
+        self.type_oid("hstore").ok().flatten()
     }
 
     /**
-     * Executes a simple query, can have parameters.
+     * Registry of the types resolved by this connection.
      */
-    pub fn query<E: crate::Entity>(
-        &self,
-        query: &str,
-        params: &[&dyn crate::ToSql],
-    ) -> crate::Result<crate::Rows<E>> {
-        Ok(self.send_query(query, params)?.into())
+    pub fn types(&self) -> &crate::pq::Registry {
+        &self.types
     }
 
     /**
-     * Likes [`query`] but peaks only the first result.
+     * Retreives the type `oid`, looking it up from `pg_type` if it isn’t a
+     * built-in type nor an already resolved one.
      *
-     * [`query`]: #method.query
+     * Returns `None` if the type doesn’t exist.
      */
-    pub fn query_one<E: crate::Entity>(
-        &self,
-        query: &str,
-        params: &[&dyn crate::ToSql],
-    ) -> crate::Result<E> {
-        match self.query(query, params)?.try_get(0) {
-            Some(e) => Ok(e),
-            None => Err(crate::Error::MissingField("0".to_string())),
+    pub fn type_by_oid(&self, oid: crate::pq::Oid) -> crate::Result<Option<crate::pq::Type>> {
+        if let Some(ty) = self.types.get(oid) {
+            return Ok(Some(ty));
         }
-    }
-
-    fn send_query(
-        &self,
-        query: &str,
-        params: &[&dyn crate::ToSql],
-    ) -> crate::Result<crate::pq::Result> {
-        let mut param_types = Vec::new();
-        let mut param_values = Vec::new();
-        let mut param_formats = Vec::new();
 
-        for param in params.iter() {
-            param_types.push(param.ty().oid);
-            param_values.push(param.to_sql()?);
-            param_formats.push(param.format());
+        if self.types.is_missing(oid) {
+            return Ok(None);
         }
 
-        self.connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .exec_params(
-                &self.order_parameters(query),
-                &param_types,
-                &param_values,
-                &param_formats,
-                crate::pq::Format::Binary,
-            )
-            .try_into()
+        self.load_types(&format!("t.oid = {}", oid))?;
+        self.types.set_missing(&[oid]);
+
+        Ok(self.types.get(oid))
     }
 
-    fn order_parameters<'a>(&self, query: &'a str) -> std::borrow::Cow<'a, str> {
-        lazy_static::lazy_static! {
-            static ref REGEX: regex::Regex =
-                #[allow(clippy::trivial_regex)]
-                regex::Regex::new(r"\$\*").unwrap();
+    /**
+     * Retreives the OID of the type `name`, optionally schema qualified,
+     * looking it up from `pg_type` if it isn’t already resolved.
+     *
+     * Returns `None` if the type doesn’t exist.
+     */
+    pub fn type_oid(&self, name: &str) -> crate::Result<Option<crate::pq::Oid>> {
+        if let Some(oid) = self.types.oid(name) {
+            return Ok(Some(oid));
         }
 
-        let mut count = 0;
+        if self.types.is_missing_name(name) {
+            return Ok(None);
+        }
 
-        REGEX.replace_all(query, |captures: &regex::Captures<'_>| {
-            count += 1;
+        self.load_types(&self.type_name_filter(name)?)?;
+        self.types.set_missing_name(name);
 
-            captures[0].replace("$*", &format!("${}", count))
-        })
+        Ok(self.types.oid(name))
+    }
+
+    pub(crate) fn type_name_filter(&self, name: &str) -> crate::Result<String> {
+        let filter = format!(
+            "(n.nspname || '.' || t.typname = {name} or (t.typname = {name} and pg_catalog.pg_type_is_visible(t.oid)))",
+            name = self.escape_literal(name)?,
+        );
+
+        Ok(filter)
     }
 
     /**
-     * Return an entity upon its primary key. If no entities are found, `None`
-     * is returned.
+     * Resolves the unknown types of the `result` fields, and recursively of
+     * their nested types (array elements, range subtypes, domain base types
+     * and composite attributes), before attaching the registry to it.
      */
-    pub fn find_by_pk<'a, M>(
+    pub(crate) fn resolve_types(
         &self,
-        pk: &HashMap<&str, &dyn crate::ToSql>,
-    ) -> crate::Result<Option<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let (clause, params) = self.pk_clause::<M>(pk)?;
-        let mut tuples = self.find_where::<M>(&clause, &params, None)?;
+        result: crate::Result<crate::pq::Result>,
+    ) -> crate::Result<crate::pq::Result> {
+        let result = result?;
 
-        Ok(tuples.next())
+        let oids = self.unknown_types(&result);
+
+        if !oids.is_empty() {
+            match self.load_types(&Self::nested_types_filter(&oids)) {
+                Ok(()) => self.types.set_missing(&oids),
+                Err(err) => log::warn!("Unable to resolve types: {}", err),
+            }
+        }
+
+        Ok(result.with_types(self.types.clone()))
     }
 
     /**
-     * Return all elements from a relation. If a suffix is given, it is append
-     * to the query. This is mainly useful for "order by" statements.
-     *
-     * NOTE: suffix is inserted as is with NO ESCAPING. DO NOT use it to place
-     * "where" condition nor any untrusted params.
+     * Types of the `result` fields to look up. A command creating or
+     * altering objects makes the failed lookups to be tried again.
      */
-    pub fn find_all<'a, M>(&self, suffix: Option<&str>) -> crate::Result<crate::Rows<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let query = format!(
-            "SELECT {} FROM {} {};",
-            M::create_projection(),
-            M::Structure::relation(),
-            suffix.unwrap_or_default(),
-        );
+    pub(crate) fn unknown_types(&self, result: &crate::pq::Result) -> Vec<crate::pq::Oid> {
+        match result.status() {
+            libpq::Status::TupplesOk | libpq::Status::SingleTuble => (),
+            libpq::Status::CommandOk => {
+                if let Some(crate::pq::CommandTag::Other(tag)) = result.command_tag() {
+                    if tag.starts_with("CREATE") || tag.starts_with("ALTER") {
+                        self.types.forget_missing();
+                    }
+                }
 
-        self.query(&query, &[])
+                return Vec::new();
+            }
+            _ => return Vec::new(),
+        }
+
+        (0..result.nfields())
+            .map(|n| result.field_type(n))
+            .filter(|oid| !self.types.is_known(*oid) && !self.types.is_missing(*oid))
+            .collect()
     }
 
-    /**
-     * Perform a simple select on a given condition
-     *
-     * NOTE: suffix is inserted as is with NO ESCAPING. DO NOT use it to place
-     * "where" condition nor any untrusted params.
-     */
-    pub fn find_where<'a, M>(
-        &self,
-        clause: &str,
-        params: &[&dyn crate::ToSql],
-        suffix: Option<&str>,
-    ) -> crate::Result<crate::Rows<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let query = format!(
-            "SELECT {} FROM {} WHERE {} {};",
-            M::create_projection(),
-            M::Structure::relation(),
-            clause,
-            suffix.unwrap_or_default(),
-        );
+    pub(crate) fn nested_types_filter(oids: &[crate::pq::Oid]) -> String {
+        let oids = oids.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        format!(
+            r#"t.oid in (
+    with recursive nested(oid) as (
+        select pg_catalog.unnest('{{{}}}'::oid[])
+        union
+        select x.oid
+        from nested
+        join pg_catalog.pg_type p on p.oid = nested.oid
+        cross join lateral (
+            select p.typelem where p.typelem <> 0
+            union all
+            select p.typbasetype where p.typbasetype <> 0
+            union all
+            select r.rngsubtype from pg_catalog.pg_range r
+                where r.rngtypid = p.oid or pg_catalog.to_jsonb(r) ->> 'rngmultitypid' = p.oid::text
+            union all
+            select a.atttypid from pg_catalog.pg_attribute a
+                where a.attrelid = p.typrelid and a.attnum > 0 and not a.attisdropped
+        ) x(oid)
+    )
+    select oid from nested
+)"#,
+            oids.join(","),
+        )
+    }
+
+    fn load_types(&self, filter: &str) -> crate::Result {
+        let result = self.lock()?.exec(&Self::types_query(filter)).try_into()?;
 
-        self.query(&query, params)
+        self.register_types(&result);
+
+        Ok(())
     }
 
     /**
-     * Paginate a query.
+     * Query describing the types matching `filter`, for [`register_types`].
      *
-     * This is done with limit/offset, read why it’s probably not a good idea to
-     * use it: <https://use-the-index-luke.com/no-offset>.
+     * [`register_types`]: Self::register_types
      */
-    pub fn paginate_find_where<'a, M>(
-        &self,
-        clause: &str,
-        params: &[&dyn crate::ToSql],
-        max_per_page: usize,
-        page: usize,
-        suffix: Option<&str>,
-    ) -> crate::Result<crate::Pager<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let suffix = format!(
-            "{} offset {} fetch first {} rows only",
-            suffix.unwrap_or_default(),
-            max_per_page * (page - 1),
-            max_per_page
-        );
+    pub(crate) fn types_query(filter: &str) -> String {
+        format!(
+            r#"
+select t.oid::int8 as oid, n.nspname as schema, t.typname as name,
+    coalesce(pg_catalog.obj_description(t.oid, 'pg_type'), '') as descr,
+    b.typtype::text as typtype, b.typcategory::text as category,
+    b.typelem::int8 as elem, coalesce(r.rngsubtype, 0)::int8 as subtype,
+    pg_catalog.pg_type_is_visible(t.oid) as visible
+from pg_catalog.pg_type t
+join pg_catalog.pg_namespace n on n.oid = t.typnamespace
+-- a domain is decoded as its base type
+join pg_catalog.pg_type b
+    on b.oid = case t.typtype when 'd' then t.typbasetype else t.oid end
+left join pg_catalog.pg_range r
+    on r.rngtypid = b.oid or pg_catalog.to_jsonb(r) ->> 'rngmultitypid' = b.oid::text
+where {}
+"#,
+            filter
+        )
+    }
 
-        let rows = self.find_where::<M>(clause, params, Some(&suffix))?;
-        let count = self.count_where::<M>(clause, params)?;
+    pub(crate) fn register_types(&self, result: &crate::pq::Result) {
+        for tuple in result {
+            let elem = tuple.get::<i64>("elem") as crate::pq::Oid;
+            let subtype = tuple.get::<i64>("subtype") as crate::pq::Oid;
+            let typtype = tuple.get::<String>("typtype");
+            let category = tuple.get::<String>("category");
 
-        let pager = crate::Pager::new(rows, count, page, max_per_page);
+            let ty = crate::pq::Type {
+                oid: tuple.get::<i64>("oid") as crate::pq::Oid,
+                name: crate::pq::registry::intern(&tuple.get::<String>("name")),
+                descr: crate::pq::registry::intern(&tuple.get::<String>("descr")),
+                kind: crate::pq::registry::kind(&typtype, &category, elem, subtype),
+            };
 
-        Ok(pager)
+            self.types
+                .insert(&tuple.get::<String>("schema"), ty, tuple.get("visible"));
+        }
+    }
+
+    pub fn r#async(&self) -> crate::Async<'_> {
+        crate::Async::new(self)
     }
 
     /**
-     * Return the number of records matching a condition.
+     * Creates a transaction. Started inside another transaction, it’s a
+     * savepoint: code using transactions can be called with or without an
+     * open transaction.
      */
-    pub fn count_where<'a, M>(
-        &self,
-        clause: &str,
-        params: &[&dyn crate::ToSql],
-    ) -> crate::Result<usize>
-    where
-        M: crate::Model<'a>,
-    {
-        let query = format!(
-            "SELECT COUNT(*) FROM {} WHERE {};",
-            M::Structure::relation(),
-            clause,
-        );
-
-        let results = self.send_query(&query, params)?;
-
-        results.get(0).try_get("count")
+    pub fn transaction(&self) -> crate::Transaction<'_> {
+        crate::Transaction::new(self)
     }
 
     /**
-     * Check if rows matching the given condition do exist or not.
+     * Runs the current transaction with the snapshot `id` exported by
+     * [`Transaction::export_snapshot`] on another connection: both see the
+     * same data, for consistent parallel reads.
+     *
+     * It must be the first statement of a `REPEATABLE READ` or
+     * `SERIALIZABLE` transaction, while the exporting transaction is still
+     * open.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * # let id = "";
+     * use elephantry::transaction::IsolationLevel;
+     *
+     * let transaction = connection.transaction();
+     * transaction.start()?;
+     * transaction.set_isolation_level(IsolationLevel::RepeatableRead)?;
+     * connection.set_transaction_snapshot(id)?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     *
+     * See <https://www.postgresql.org/docs/current/sql-set-transaction.html>
+     *
+     * [`Transaction::export_snapshot`]: crate::Transaction::export_snapshot
      */
-    pub fn exist_where<'a, M>(
-        &self,
-        clause: &str,
-        params: &[&dyn crate::ToSql],
-    ) -> crate::Result<bool>
-    where
-        M: crate::Model<'a>,
-    {
-        let query = format!(
-            "SELECT EXISTS (SELECT true FROM {} WHERE {}) AS result;",
-            M::Structure::relation(),
-            clause,
-        );
-
-        let results = self.send_query(&query, params)?;
+    pub fn set_transaction_snapshot(&self, id: &str) -> crate::Result {
+        let query = format!("set transaction snapshot {}", self.escape_literal(id)?);
 
-        results.get(0).try_get("result")
+        self.execute(&query).map(|_| ())
     }
 
     /**
-     * Insert a new entity in the database.
-     *
-     * Returns the entity with values from database (ie: default values).
+     * Locks the connection. If a thread panicked while holding the lock, the
+     * connection is validated and the lock recovered.
      */
-    pub fn insert_one<'a, M>(&self, entity: &M::Entity) -> crate::Result<M::Entity>
-    where
-        M: crate::Model<'a>,
-    {
-        self.insert::<M>(entity, None).map(|x| x.unwrap())
-    }
+    pub(crate) fn lock(&self) -> crate::Result<Guard<'_>> {
+        // Locking again would deadlock
+        if self.streaming_thread() == Some(std::thread::current().id()) {
+            return Err(crate::Error::Busy);
+        }
 
-    /**
-     * Try to insert a new entity in the database. On constraint violation error
-     * on `target` you can do an alternative action `action`.
-     *
-     * See [ON CONFLICT clause](https://www.postgresql.org/docs/current/sql-insert.html#SQL-ON-CONFLICT).
-     *
-     * Returns the entity with values from database (ie: default values).
-     */
-    pub fn upsert_one<'a, M>(
-        &self,
-        entity: &M::Entity,
-        target: &str,
-        action: &str,
-    ) -> crate::Result<Option<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let suffix = format!("on conflict {} do {}", target, action);
-        self.insert::<M>(entity, Some(suffix.as_str()))
-    }
+        let start = std::time::Instant::now();
 
-    fn insert<'a, M>(
-        &self,
-        entity: &M::Entity,
-        suffix: Option<&str>,
-    ) -> crate::Result<Option<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        use crate::Entity;
+        let connection = match self.connection.lock() {
+            Ok(connection) => connection,
+            Err(err) => {
+                log::warn!("A thread panicked while using the connection, recovering it");
 
-        let mut tuple = Vec::new();
-        let mut params = Vec::new();
-        let mut fields = Vec::new();
-        let mut x = 1;
+                let mut connection = err.into_inner();
+                if let Some(connection) = connection.as_mut() {
+                    self.recover(connection)?;
+                }
+                self.forget_prepared();
+                self.connection.clear_poison();
 
-        for field in M::Structure::columns() {
-            if let Some(value) = entity.get(field) {
-                tuple.push(value);
-                params.push(format!("${}", x));
-                fields.push(*field);
-                x += 1;
+                connection
             }
-        }
+        };
 
-        let query = format!(
-            "INSERT INTO {} ({}) VALUES({}) {} RETURNING {};",
-            M::Structure::relation(),
-            fields.join(", "),
-            params.join(", "),
-            suffix.unwrap_or_default(),
-            M::create_projection(),
-        );
+        let inner = match connection.as_ref() {
+            Some(inner) => inner,
+            None => return Err(crate::Error::Closed),
+        };
 
-        let results = self.send_query(&query, tuple.as_slice())?;
-        let result = results.try_get(0).map(|x| M::create_entity(&x));
+        let timeout = self.stats.waited(start.elapsed());
 
-        Ok(result)
+        if self
+            .detached
+            .swap(false, std::sync::atomic::Ordering::SeqCst)
+        {
+            self.drain(inner);
+        }
+
+        Ok(Guard {
+            connection: Some(connection),
+            timeout,
+        })
+    }
+
+    fn streaming_thread(&self) -> Option<std::thread::ThreadId> {
+        *self
+            .streaming
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
 
     /**
-     * Update the entity.
-     *
-     * Returns the entity with values from database.
+     * Records the thread reading a [`crate::RowStream`], `None` when the
+     * stream ends.
      */
-    pub fn update_one<'a, M>(
-        &self,
-        pk: &HashMap<&str, &dyn crate::ToSql>,
-        entity: &M::Entity,
-    ) -> crate::Result<Option<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        use crate::Entity;
+    pub(crate) fn set_streaming_thread(&self, thread: Option<std::thread::ThreadId>) {
+        *self
+            .streaming
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = thread;
+    }
 
-        let mut data = HashMap::new();
+    /**
+     * Reads and discards the results of a detached query.
+     */
+    fn drain(&self, connection: &libpq::Connection) {
+        use std::convert::TryFrom;
 
-        for field in M::Structure::columns() {
-            let value = match entity.get(field) {
-                Some(value) => value,
-                None => &Option::<&str>::None,
-            };
-            data.insert(field.to_string(), value);
+        while let Some(result) = connection.result() {
+            if let Err(err) = crate::pq::Result::try_from(result) {
+                log::warn!("Detached query failed: {}", err);
+                self.stats.error();
+            }
         }
-
-        self.update_by_pk::<M>(pk, &data)
     }
 
     /**
-     * Update a record and fetch it with its new values. If no records match
-     * the given key, `None` is returned.
+     * Makes the connection usable again: reconnects if the connection is
+     * broken and rollbacks an aborted transaction.
      */
-    pub fn update_by_pk<'a, M>(
-        &self,
-        pk: &HashMap<&str, &dyn crate::ToSql>,
-        data: &HashMap<String, &dyn crate::ToSql>,
-    ) -> crate::Result<Option<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let (clause, mut params) = self.pk_clause::<M>(pk)?;
-        let mut x = params.len() + 1;
-        let mut set = Vec::new();
-        let projection = M::default_projection();
+    fn recover(&self, connection: &mut libpq::Connection) -> crate::Result {
+        if connection.status() != libpq::connection::Status::Ok {
+            self.reconnect(connection)?;
+        }
 
-        for (key, value) in data.iter() {
-            if projection.has_field(key) {
-                set.push(format!("{} = ${}", key, x));
-                params.push(*value);
-                x += 1;
-            }
+        if connection.status() != libpq::connection::Status::Ok {
+            return Err(crate::Error::Mutex(
+                connection
+                    .error_message()
+                    .unwrap_or_else(|| "Unable to recover the connection".to_string()),
+            ));
         }
 
-        if set.is_empty() {
-            log::warn!("No field to update");
-            return Ok(None);
+        if connection.transaction_status() == libpq::transaction::Status::InError {
+            let result: crate::Result<crate::pq::Result> =
+                connection.exec("rollback transaction").try_into();
+            result?;
         }
 
-        let query = format!(
-            "UPDATE {} SET {} WHERE {} RETURNING {};",
-            M::Structure::relation(),
-            set.join(", "),
-            clause,
-            M::create_projection(),
+        Ok(())
+    }
+
+    /**
+     * Closes the connection, waiting for its current user to release it. The
+     * connection clones then fail with [`crate::Error::Closed`].
+     */
+    pub(crate) fn close(&self) {
+        let connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+
+        // Finishes the libpq connection
+        drop(connection);
+    }
+
+    pub(crate) fn status(&self) -> crate::Status {
+        let in_use = matches!(
+            self.connection.try_lock(),
+            Err(std::sync::TryLockError::WouldBlock)
         );
 
-        let results = self.send_query(&query, &params)?;
+        self.stats.status(in_use)
+    }
 
-        let entity = results.try_get(0).map(|x| M::create_entity(&x));
+    pub(crate) fn transaction_status(&self) -> crate::Result<libpq::transaction::Status> {
+        let status = self.lock()?.transaction_status();
 
-        Ok(entity)
+        Ok(status)
+    }
+
+    pub(crate) fn escape_identifier(&self, str: &str) -> crate::Result<String> {
+        self.lock()?
+            .escape_identifier(str)
+            .map_err(|e| crate::Error::Escape(str.to_string(), e))
     }
 
     /**
-     * Delete an entity from a table.
+     * Creates a new connection from [`Config`].
      *
-     * Returns the entity fetched from the deleted record.
+     * [`Config`]: crate::Config
      */
-    pub fn delete_one<'a, M>(&self, entity: &M::Entity) -> crate::Result<Option<M::Entity>>
+    pub fn from_config(config: &crate::Config) -> crate::Result<Self> {
+        config.validate()?;
+
+        let mut config = config.resolve()?;
+
+        let tls = match config.tls.take() {
+            Some(tls) => Some(tls.write(&mut config)?),
+            None => None,
+        };
+
+        let mut connection = Self::new(&config.to_string())?;
+        connection.tls = tls.map(std::sync::Arc::new);
+
+        if config.password_provider.is_some() {
+            connection.config = Some(std::sync::Arc::new(config.clone()));
+        }
+
+        if let Some(policy) = config.timezone_policy {
+            connection.set_timezone_policy(policy)?;
+        }
+
+        Ok(connection)
+    }
+
+    pub fn model<'a, M>(&'a self) -> M
     where
         M: crate::Model<'a>,
     {
-        let pk = M::primary_key(entity)?;
-
-        self.delete_by_pk::<M>(&pk)
+        M::new(self)
     }
 
     /**
-     * Delete a record from its primary key. The deleted entity is returned or
-     * `None` if not found.
+     * Executes a simple text query, without parameter.
      */
-    pub fn delete_by_pk<'a, M>(
-        &self,
-        pk: &HashMap<&str, &dyn crate::ToSql>,
-    ) -> crate::Result<Option<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let (clause, params) = self.pk_clause::<M>(pk)?;
-        let mut results = self.delete_where::<M>(&clause, &params)?;
+    pub fn execute(&self, query: &str) -> crate::Result<crate::pq::Result> {
+        let connection = self.lock()?;
 
-        Ok(results.next())
+        self.watch(&connection, query)?;
+
+        let result = connection.exec(&self.tag(query)).try_into();
+        let status = self.touch(&connection);
+        drop(connection);
+
+        self.settle_events(status, [&result])?;
+
+        self.resolve_types(self.count_error(result))
     }
 
     /**
-     * Delete records by a given condition. A collection of all deleted entries
-     * is returned.
+     * Executes a text query containing several statements, without
+     * parameter, and returns the result of each statement in order.
+     *
+     * PostgreSQL stops at the first failing statement, which is the last
+     * result. The session is checked and the result types resolved like with
+     * [`execute`].
+     *
+     * [`execute`]: Self::execute
      */
-    pub fn delete_where<'a, M>(
-        &self,
-        clause: &str,
-        params: &[&dyn crate::ToSql],
-    ) -> crate::Result<crate::Rows<M::Entity>>
-    where
-        M: crate::Model<'a>,
-    {
-        let query = format!(
-            "DELETE FROM {} WHERE {} RETURNING {};",
-            M::Structure::relation(),
-            clause,
-            M::create_projection(),
-        );
+    pub fn execute_multi(&self, query: &str) -> crate::Result<Vec<ResultSummary>> {
+        use std::convert::TryInto;
 
-        self.query(&query, params)
-    }
+        let connection = self.lock()?;
 
-    fn pk_clause<'a, 'b, M>(
-        &self,
-        pk: &HashMap<&str, &'b dyn crate::ToSql>,
-    ) -> crate::Result<(String, Vec<&'b dyn crate::ToSql>)>
-    where
-        M: crate::Model<'a>,
-    {
-        let keys: Vec<_> = pk.keys().copied().collect();
+        self.watch(&connection, query)?;
 
-        if keys != M::Structure::primary_key() {
-            return Err(crate::Error::PrimaryKey);
+        connection
+            .send_query(&self.tag(query))
+            .map_err(crate::Error::Async)?;
+
+        let mut results = Vec::new();
+
+        while let Some(result) = connection.result() {
+            let result: crate::Result<crate::pq::Result> = result.try_into();
+            results.push(result);
         }
 
-        let clause = keys.iter().enumerate().fold(String::new(), |acc, (i, x)| {
-            let field = format!("\"{}\"", x.replace("\"", "\\\""));
+        let status = self.touch(&connection);
+        drop(connection);
 
-            if acc.is_empty() {
-                format!("{} = ${}", field, i + 1)
-            } else {
-                format!("{} AND {} = ${}", acc, field, i + 1)
-            }
-        });
+        self.settle_events(status, &results)?;
 
-        let params: Vec<_> = pk.values().copied().collect();
+        let summaries = results
+            .into_iter()
+            .map(
+                |result| match self.resolve_types(self.count_error(result)) {
+                    Ok(result) => ResultSummary {
+                        command_tag: result.command_tag(),
+                        rows: result.affected_rows(),
+                        result: Ok(result),
+                    },
+                    Err(err) => ResultSummary {
+                        command_tag: None,
+                        rows: 0,
+                        result: Err(err),
+                    },
+                },
+            )
+            .collect();
 
-        Ok((clause, params))
+        Ok(summaries)
     }
 
     /**
-     * Determines if the connection is no longer usable.
+     * Sends a simple text query without waiting for its result.
+     *
+     * The result is read and discarded at the next use of the connection,
+     * errors are only logged. Use it for low-value statements, like heartbeat
+     * updates.
      */
-    pub fn has_broken(&self) -> crate::Result<bool> {
-        let status = self
-            .connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .status();
+    pub fn execute_async_detach(&self, query: &str) -> crate::Result {
+        self.lock()?
+            .send_query(&self.tag(query))
+            .map_err(crate::Error::Async)?;
 
-        Ok(status == libpq::connection::Status::Bad)
+        self.detached
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+
+        Ok(())
     }
 
     /**
-     * Send a NOTIFY event to the database server. An optional data can be sent
-     * with the notification.
+     * Executes a simple text query, without parameter, and returns the
+     * number of affected rows.
      */
-    pub fn notify(&self, channel: &str, data: Option<&str>) -> crate::Result {
-        let data = self.escape_literal(data.unwrap_or_default())?;
-
-        let query = format!("notify {}, {}", channel, data);
-
-        self.execute(&query).map(|_| ())
+    pub fn execute_returning_count(&self, query: &str) -> crate::Result<u64> {
+        self.execute(query).map(|x| x.affected_rows())
     }
 
     /**
-     * Start to listen on the given channel.
-     *
-     * Note: when listen is issued in a transaction it is unlisten when the
-     * transaction is committed or rollback.
+     * Executes a simple query, can have parameters.
      */
-    pub fn listen(&self, channel: &str) -> crate::Result {
-        let query = format!("listen {}", channel);
+    pub fn query<E: crate::Entity>(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::Rows<E>> {
+        Ok(self.send_query(query, params)?.into())
+    }
 
-        self.execute(&query).map(|_| ())
+    /**
+     * Likes [`query`] but peaks only the first result.
+     *
+     * [`query`]: #method.query
+     */
+    pub fn query_one<E: crate::Entity>(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<E> {
+        match self.query(query, params)?.try_get(0) {
+            Some(e) => Ok(e),
+            None => Err(crate::Error::MissingField("0".to_string())),
+        }
     }
 
     /**
-     * Stop to listen on the given channel.
+     * Likes [`query`] but fetches the rows one by one with the single row
+     * mode, to iterate over large results without loading them in memory.
+     *
+     * The connection is reserved to the stream until it ends or is dropped:
+     * other threads wait for it, using the connection from the thread
+     * reading the stream returns [`Error::Busy`].
+     *
+     * Dropping the stream before its end cancels the query, except inside a
+     * transaction where the remaining rows are read and discarded: a
+     * canceled query aborts the transaction.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * for x in connection.query_stream::<i64>("select generate_series(1, 10000000)", &[])? {
+     *     let x = x?;
+     * }
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     *
+     * [`query`]: #method.query
+     * [`Error::Busy`]: crate::Error::Busy
      */
-    pub fn unlisten(&self, channel: &str) -> crate::Result {
-        let query = format!("unlisten {}", channel);
+    pub fn query_stream<E: crate::Entity>(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::RowStream<'_, E>> {
+        let in_transaction = match self.transaction_status()? {
+            libpq::transaction::Status::InError => return Err(crate::Error::TransactionAborted),
+            status => status == libpq::transaction::Status::InTrans,
+        };
 
-        self.execute(&query).map(|_| ())
+        let (param_types, param_values, param_formats) = self.encode_params(params)?;
+        let query = self.tag(&self.order_parameters(query));
+
+        let connection = self.lock()?;
+
+        connection
+            .send_query_params(
+                &query,
+                &param_types,
+                &param_values,
+                &param_formats,
+                crate::pq::Format::Binary,
+            )
+            .map_err(crate::Error::Async)?;
+
+        if connection.set_single_row_mode().is_err() {
+            log::warn!("Unable to activate the single row mode");
+        }
+
+        self.set_streaming_thread(Some(std::thread::current().id()));
+
+        Ok(crate::RowStream::new(self, connection, in_transaction))
+    }
+
+    fn send_query(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::pq::Result> {
+        // Encoded before locking the connection, looking up a parameter type
+        // is a query
+        let (param_types, param_values, param_formats) = self.encode_params(params)?;
+
+        let connection = self.lock()?;
+
+        if connection.transaction_status() == libpq::transaction::Status::InError {
+            return Err(crate::Error::TransactionAborted);
+        }
+
+        self.watch(&connection, query)?;
+
+        let query = self.order_parameters(query);
+        let tagged = self.tag(&query);
+
+        // The CRUD statements are prepared untagged, the tagged queries are
+        // cached by their text: a tag changing between executions is a new
+        // statement
+        let crud = if tagged == query {
+            self.crud_statement(&connection, &query, &param_types)?
+        } else {
+            None
+        };
+
+        let prepared = match crud {
+            Some(name) => Some(name),
+            None => self.cached_statement(&connection, &tagged, &param_types)?,
+        };
+
+        let query = tagged;
+        let result = match prepared {
+            Some(name) => self.exec_prepared(
+                &connection,
+                &query,
+                &name,
+                &param_types,
+                &param_values,
+                &param_formats,
+            ),
+            None => connection
+                .exec_params(
+                    &query,
+                    &param_types,
+                    &param_values,
+                    &param_formats,
+                    crate::pq::Format::Binary,
+                )
+                .try_into(),
+        };
+        let status = self.touch(&connection);
+        drop(connection);
+
+        self.settle_events(status, [&result])?;
+
+        self.resolve_types(self.count_error(result))
+            .map_err(|err| Self::param_type_error(&query, params, err))
     }
 
     /**
-     * Check if a notification is pending. If so, the payload is returned.
-     * Otherwise, `None` is returned.
+     * Converts `params` to the types, values and formats expected by libpq.
      */
-    pub fn notifies(&self) -> crate::Result<Option<crate::pq::Notify>> {
-        let connection = self
-            .connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn encode_params(
+        &self,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<(
+        Vec<crate::pq::Oid>,
+        Vec<Option<Vec<u8>>>,
+        Vec<crate::pq::Format>,
+    )> {
+        let mut param_types = Vec::new();
+        let mut param_values = Vec::new();
+        let mut param_formats = Vec::new();
 
-        connection.consume_input().ok();
-        Ok(connection.notifies())
+        for param in params.iter() {
+            param_types.push(self.param_type(*param));
+            param_values.push(param.to_sql()?);
+            param_formats.push(param.format());
+        }
+
+        Ok((param_types, param_values, param_formats))
     }
 
-    fn escape_literal(&self, str: &str) -> crate::Result<String> {
-        self.connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?
-            .escape_literal(str)
-            .map_err(|e| crate::Error::Escape(str.to_string(), e))
+    /**
+     * OID of a parameter, resolving the types created by extensions.
+     */
+    fn param_type(&self, param: &dyn crate::ToSql) -> crate::pq::Oid {
+        let ty = param.ty();
+
+        match Self::type_names(&ty) {
+            Some(names) => names
+                .iter()
+                .find_map(|name| self.type_oid(name).ok().flatten())
+                .unwrap_or_default(),
+            None => ty.oid,
+        }
     }
 
     /**
-     * Reports the status of the server.
+     * Names of the parameter types to look up, not resolved nor missing yet.
      */
-    pub fn ping(&self) -> crate::Result {
-        let connection = self
-            .connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+    #[cfg(feature = "async")]
+    pub(crate) fn unresolved_type_names(&self, params: &[&dyn crate::ToSql]) -> Vec<String> {
+        let mut names = Vec::new();
 
-        let mut params = HashMap::new();
-        params.insert("dbname".to_string(), connection.db());
-        params.insert("host".to_string(), connection.host());
-        params.insert("port".to_string(), connection.port());
-        params.insert("user".to_string(), connection.user());
-        if let Some(password) = connection.pass() {
-            params.insert("password".to_string(), password);
+        for param in params {
+            let candidates = match Self::type_names(&param.ty()) {
+                Some(candidates) => candidates,
+                None => continue,
+            };
+
+            let resolved = candidates.iter().any(|x| self.types.oid(x).is_some());
+
+            if resolved {
+                continue;
+            }
+
+            for name in candidates {
+                if !self.types.is_missing_name(&name) && !names.contains(&name) {
+                    names.push(name);
+                }
+            }
         }
 
-        match libpq::Connection::ping_params(&params, false) {
-            PingStatus::Ok => Ok(()),
-            status => Err(crate::Error::Ping(status)),
+        names
+    }
+
+    /**
+     * Names of a type without fixed OID, `None` for the others.
+     */
+    fn type_names(ty: &crate::pq::Type) -> Option<[String; 2]> {
+        let named = matches!(
+            ty.kind,
+            libpq::types::Kind::UserDefined
+                | libpq::types::Kind::Composite
+                | libpq::types::Kind::Enum
+                | libpq::types::Kind::Array(0)
+        );
+
+        if ty.oid == 0 && named {
+            // Unquoted identifiers are case folded, like a derived
+            // `Composite` name
+            Some([ty.name.to_string(), ty.name.to_lowercase()])
+        } else {
+            None
         }
     }
 
     /**
-     * Retreives connection configuration.
+     * Retreives the name of the statement automatically prepared for `query`,
+     * preparing it the second time it’s seen.
      */
-    pub fn config(&self) -> crate::Result<crate::Config> {
-        let connection = self
-            .connection
+    fn cached_statement(
+        &self,
+        connection: &libpq::Connection,
+        query: &str,
+        param_types: &[crate::pq::Oid],
+    ) -> crate::Result<Option<String>> {
+        let mut statements = self
+            .statements
             .lock()
             .map_err(|e| crate::Error::Mutex(e.to_string()))?;
-        let info = libpq::v2::connection::info(&connection);
 
-        let config = crate::Config {
-            application_name: info
-                .get("application_name")
-                .map(|x| x.val.clone())
-                .flatten(),
-            channel_binding: self.config_get(&info, "channel_binding")?,
-            client_encoding: info.get("client_encoding").map(|x| x.val.clone()).flatten(),
-            connect_timeout: self.config_get(&info, "connect_timeout")?,
-            dbname: info.get("dbname").map(|x| x.val.clone()).flatten(),
-            fallback_application_name: info
-                .get("fallback_application_name")
-                .map(|x| x.val.clone())
-                .flatten(),
-            gssencmode: self.config_get(&info, "gssencmode")?,
-            gsslib: info.get("gsslib").map(|x| x.val.clone()).flatten(),
-            hostaddr: info.get("hostaddr").map(|x| x.val.clone()).flatten(),
-            host: info.get("host").map(|x| x.val.clone()).flatten(),
-            keepalives_count: self.config_get(&info, "keepalives_count")?,
-            keepalives_idle: self.config_get(&info, "keepalives_idle")?,
-            keepalives_interval: self.config_get(&info, "keepalives_interval")?,
-            keepalives: self.config_get::<i32>(&info, "keepalives")?.map(|x| x == 1),
-            krbsrvname: info.get("krbsrvname").map(|x| x.val.clone()).flatten(),
-            options: info.get("options").map(|x| x.val.clone()).flatten(),
-            passfile: info.get("passfile").map(|x| x.val.clone()).flatten(),
-            password: info.get("password").map(|x| x.val.clone()).flatten(),
-            port: info.get("port").map(|x| x.val.clone()).flatten(),
-            replication: info.get("replication").map(|x| x.val.clone()).flatten(),
-            requirepeer: info.get("requirepeer").map(|x| x.val.clone()).flatten(),
-            service: info.get("service").map(|x| x.val.clone()).flatten(),
-            sslcert: info.get("sslcert").map(|x| x.val.clone()).flatten(),
-            sslcompression: self
-                .config_get::<i32>(&info, "sslcompression")?
-                .map(|x| x == 1),
-            sslcrl: info.get("sslcrl").map(|x| x.val.clone()).flatten(),
-            sslkey: info.get("sslkey").map(|x| x.val.clone()).flatten(),
-            ssl_max_protocol_version: info
-                .get("ssl_max_protocol_version")
-                .map(|x| x.val.clone())
-                .flatten(),
-            ssl_min_protocol_version: info
-                .get("ssl_min_protocol_version")
-                .map(|x| x.val.clone())
-                .flatten(),
-            sslmode: self.config_get(&info, "sslmode")?,
-            sslpassword: info.get("sslpassword").map(|x| x.val.clone()).flatten(),
-            sslrootcert: info.get("sslrootcert").map(|x| x.val.clone()).flatten(),
-            target_session_attrs: self.config_get(&info, "target_session_attrs")?,
-            tcp_user_timeout: self.config_get(&info, "tcp_user_timeout")?,
-            user: info.get("user").map(|x| x.val.clone()).flatten(),
+        let lookup = statements.lookup(query, param_types);
+        let evicted = statements.take_evicted();
+        drop(statements);
+
+        for name in evicted {
+            Self::deallocate_statement(connection, &name);
+        }
+
+        let name = match lookup {
+            crate::statement::Lookup::Prepared(name) => return Ok(Some(name)),
+            crate::statement::Lookup::Unknown => return Ok(None),
+            crate::statement::Lookup::Prepare(name) => name,
         };
 
-        Ok(config)
+        let result: crate::Result<crate::pq::Result> = connection
+            .prepare(Some(&name), query, param_types)
+            .try_into();
+        result?;
+
+        let evicted = self
+            .statements
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .prepared(query, param_types, name.clone());
+
+        for name in evicted {
+            Self::deallocate_statement(connection, &name);
+        }
+
+        Ok(Some(name))
     }
 
-    fn config_get<T>(
-        &self,
-        info: &HashMap<String, libpq::connection::Info>,
-        name: &str,
-    ) -> Result<Option<T>, <T as std::str::FromStr>::Err>
-    where
-        T: std::str::FromStr,
-    {
-        let r = match info.get(name).map(|x| x.val.clone()) {
-            Some(Some(val)) => Some(val.parse()?),
-            _ => None,
-        };
+    /**
+     * Server side prepares `query`, to execute it many times without parsing
+     * and planning it again.
+     *
+     * Queries executed with [`query`] can also be prepared automatically,
+     * see [`set_statement_cache_capacity`].
+     *
+     * [`query`]: Self::query
+     * [`set_statement_cache_capacity`]: Self::set_statement_cache_capacity
+     */
+    pub fn prepare(&self, query: &str) -> crate::Result<crate::Statement> {
+        let query = self.order_parameters(query).to_string();
+        let (name, param_types) = self.prepare_named(&*self.lock()?, &self.tag(&query), &[])?;
 
-        Ok(r)
+        Ok(crate::Statement::new(self, name, query, param_types))
     }
 
     /**
-     * Bulk insert entities via COPY mode.
+     * Prepares `query` under a new name, returns it with the parameter types
+     * used by the server: `param_types`, or inferred from the query if
+     * unspecified.
      */
-    pub fn copy<'m, M, I>(&self, entities: I) -> crate::Result
-    where
-        I: Iterator<Item = M::Entity>,
-        M: crate::Model<'m>,
-    {
-        use crate::Entity;
+    fn prepare_named(
+        &self,
+        connection: &libpq::Connection,
+        query: &str,
+        param_types: &[crate::pq::Oid],
+    ) -> crate::Result<(String, Vec<crate::pq::Oid>)> {
+        let name = self
+            .statements
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .next_name();
 
-        let projection = M::default_projection();
-        let field_names = projection.field_names();
+        let result: crate::Result<crate::pq::Result> = connection
+            .prepare(Some(&name), query, param_types)
+            .try_into();
+        self.count_error(result)?;
 
-        let query = format!(
-            "copy {} ({}) from stdin;",
-            M::Structure::relation(),
-            field_names.join(", "),
-        );
-        self.execute(&query)?;
+        let description: crate::pq::Result =
+            connection.describe_prepared(Some(&name)).try_into()?;
+        let param_types = (0..description.nparams())
+            .map(|x| description.param_type(x).unwrap_or_default())
+            .collect();
 
-        let connection = self
-            .connection
-            .lock()
-            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+        Ok((name, param_types))
+    }
 
-        let null = b"\\N\0".to_vec();
-        let mut data = Vec::new();
+    /**
+     * Executes the prepared statement `name`, see [`prepare`].
+     *
+     * [`prepare`]: Self::prepare
+     */
+    pub(crate) fn send_prepared(
+        &self,
+        query: &str,
+        name: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::pq::Result> {
+        let (param_types, param_values, param_formats) = self.encode_params(params)?;
 
-        for entity in entities {
-            for field in &field_names {
-                let value = match entity.get(field) {
-                    Some(value) => value.to_sql()?,
-                    None => None,
-                };
+        let connection = self.lock()?;
 
-                data.extend_from_slice(&value.unwrap_or_else(|| null.clone()));
-                data.pop();
-                data.push(b'\t');
-            }
-            data.pop();
-            data.push(b'\n');
+        if connection.transaction_status() == libpq::transaction::Status::InError {
+            return Err(crate::Error::TransactionAborted);
         }
 
-        connection
-            .put_copy_data(&String::from_utf8(data)?)
-            .map_err(crate::Error::Copy)?;
+        self.watch(&connection, query)?;
 
-        connection.put_copy_end(None).map_err(crate::Error::Copy)?;
+        let result = self.exec_prepared(
+            &connection,
+            query,
+            name,
+            &param_types,
+            &param_values,
+            &param_formats,
+        );
+        let status = self.touch(&connection);
+        drop(connection);
 
-        if let Some(result) = connection.result() {
-            if result.status() == libpq::Status::FatalError {
-                return Err(crate::Error::Copy(
+        self.settle_events(status, [&result])?;
+
+        self.resolve_types(self.count_error(result))
+            .map_err(|err| Self::param_type_error(query, params, err))
+    }
+
+    /**
+     * Deallocates the prepared statement `name`, errors are only logged: the
+     * statement is deallocated at the end of the session anyway.
+     */
+    pub(crate) fn deallocate(&self, name: &str) {
+        match self.lock() {
+            Ok(connection) => Self::deallocate_statement(&connection, name),
+            Err(err) => log::warn!("Unable to deallocate statement {}: {}", name, err),
+        }
+    }
+
+    fn deallocate_statement(connection: &libpq::Connection, name: &str) {
+        let result: crate::Result<crate::pq::Result> =
+            connection.exec(&format!("deallocate {}", name)).try_into();
+
+        if let Err(err) = result {
+            log::warn!("Unable to deallocate statement {}: {}", name, err);
+        }
+    }
+
+    /**
+     * Number of statements prepared automatically and kept by the
+     * connection.
+     */
+    pub fn statement_cache_capacity(&self) -> crate::Result<usize> {
+        let statements = self
+            .statements
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+
+        Ok(statements.capacity())
+    }
+
+    /**
+     * Sets the number of statements prepared automatically and kept by the
+     * connection: a query is prepared the second time it’s executed, the
+     * least recently used statement is deallocated once the cache is full.
+     *
+     * Disabled by default (0): server side prepared statements aren’t
+     * supported behind a connection pooler in transaction mode, like
+     * pgbouncer.
+     */
+    pub fn set_statement_cache_capacity(&self, capacity: usize) -> crate::Result {
+        let evicted = self
+            .statements
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .set_capacity(capacity);
+
+        if evicted.is_empty() {
+            return Ok(());
+        }
+
+        let connection = self.lock()?;
+
+        for name in evicted {
+            Self::deallocate_statement(&connection, &name);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Executes the prepared statement `name`. If the server doesn’t know it
+     * anymore, `query` is executed unprepared.
+     */
+    fn exec_prepared(
+        &self,
+        connection: &libpq::Connection,
+        query: &str,
+        name: &str,
+        param_types: &[crate::pq::Oid],
+        param_values: &[Option<Vec<u8>>],
+        param_formats: &[crate::pq::Format],
+    ) -> crate::Result<crate::pq::Result> {
+        let stale = self
+            .statements
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .take_stale(name);
+
+        if stale {
+            Self::prepare_again(connection, name, query, param_types)?;
+        }
+
+        let exec = |connection: &libpq::Connection| {
+            connection
+                .exec_prepared(
+                    Some(name),
+                    param_values,
+                    param_formats,
+                    crate::pq::Format::Binary,
+                )
+                .try_into()
+        };
+
+        let result: crate::Result<crate::pq::Result> = exec(connection);
+
+        match result {
+            Err(crate::Error::Sql(result)) if Self::is_result_type_changed(&result) => {
+                // The aborted transaction refuses any command
+                if connection.transaction_status() != libpq::transaction::Status::Idle {
+                    self.statements
+                        .lock()
+                        .map_err(|e| crate::Error::Mutex(e.to_string()))?
+                        .mark_stale(name);
+
+                    return Err(crate::Error::Sql(result));
+                }
+
+                log::warn!(
+                    "Result type of statement {} changed, preparing it again",
+                    name
+                );
+                Self::prepare_again(connection, name, query, param_types)?;
+
+                exec(connection)
+            }
+            Err(crate::Error::Sql(result))
+                if result.state() == Some(crate::pq::state::UNDEFINED_PSTATEMENT) =>
+            {
+                log::warn!("Prepared statement {} lost, executing the query", name);
+                self.forget_prepared();
+
+                connection
+                    .exec_params(
+                        query,
+                        param_types,
+                        param_values,
+                        param_formats,
+                        crate::pq::Format::Binary,
+                    )
+                    .try_into()
+            }
+            result => result,
+        }
+    }
+
+    /**
+     * Server side prepares the standard queries of `M` (find by primary key,
+     * insert, update and delete), the write helpers use them automatically.
+     *
+     * The prepared insert query is used when every column has a value and
+     * prepared statements are forgotten on [`reset`].
+     *
+     * [`reset`]: Self::reset
+     */
+    pub(crate) fn prepare_crud<'a, M>(&self) -> crate::Result
+    where
+        M: crate::Model<'a>,
+    {
+        let columns = M::Structure::columns();
+        let primary_key = M::Structure::primary_key();
+
+        let mut queries = vec![self.insert_query::<M>(columns, None)];
+
+        if !primary_key.is_empty() {
+            let clause = Self::pk_condition::<M>();
+            let set = columns
+                .iter()
+                .enumerate()
+                .map(|(n, column)| format!("{} = ${}", column, primary_key.len() + n + 1))
+                .collect::<Vec<_>>();
+
+            queries.push(self.find_where_query::<M>(&clause, None));
+            queries.push(self.update_query::<M>(&set, &clause));
+            queries.push(self.delete_query::<M>(&clause));
+        }
+
+        for query in queries {
+            let known = self
+                .prepared
+                .read()
+                .map_err(|e| crate::Error::Mutex(e.to_string()))?
+                .contains_key(&query);
+
+            if known {
+                continue;
+            }
+
+            let statement = self.prepare_named(&*self.lock()?, &query, &[])?;
+
+            self.prepared
+                .write()
+                .map_err(|e| crate::Error::Mutex(e.to_string()))?
+                .insert(query, vec![statement]);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Name of the statement prepared by [`prepare_crud`] for `query`. The
+     * parameters are sent with the binary format of their rust type: a
+     * variant is prepared with `param_types` if the server inferred other
+     * types, like an `int4` for an `i64` value.
+     *
+     * [`prepare_crud`]: Self::prepare_crud
+     */
+    fn crud_statement(
+        &self,
+        connection: &libpq::Connection,
+        query: &str,
+        param_types: &[crate::pq::Oid],
+    ) -> crate::Result<Option<String>> {
+        let statements = self
+            .prepared
+            .read()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .get(query)
+            .cloned();
+
+        let statements = match statements {
+            Some(statements) => statements,
+            None => return Ok(None),
+        };
+
+        let same_types = |types: &[crate::pq::Oid]| {
+            types.len() == param_types.len()
+                && types
+                    .iter()
+                    .zip(param_types)
+                    .all(|(ty, param)| *param == 0 || ty == param)
+        };
+
+        if let Some((name, _)) = statements.iter().find(|(_, types)| same_types(types)) {
+            return Ok(Some(name.clone()));
+        }
+
+        let statement = self.prepare_named(connection, query, param_types)?;
+        let name = statement.0.clone();
+
+        self.prepared
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .entry(query.to_string())
+            .or_default()
+            .push(statement);
+
+        Ok(Some(name))
+    }
+
+    /*
+     * The server refuses to execute a statement whose result columns changed
+     * since its preparation, like after an `alter table` of a `select *`.
+     */
+    fn is_result_type_changed(result: &crate::pq::Result) -> bool {
+        result.state() == Some(crate::pq::state::FEATURE_NOT_SUPPORTED)
+            && result
+                .error_message()
+                .is_some_and(|x| x.contains("cached plan must not change result type"))
+    }
+
+    fn prepare_again(
+        connection: &libpq::Connection,
+        name: &str,
+        query: &str,
+        param_types: &[crate::pq::Oid],
+    ) -> crate::Result {
+        connection.exec(&format!("deallocate {}", name));
+
+        let _: crate::pq::Result = connection
+            .prepare(Some(name), query, param_types)
+            .try_into()?;
+
+        Ok(())
+    }
+
+    fn forget_prepared(&self) {
+        if let Ok(mut prepared) = self.prepared.write() {
+            prepared.clear();
+        }
+
+        if let Ok(mut statements) = self.statements.lock() {
+            statements.clear();
+        }
+    }
+
+    /**
+     * Turns a type mismatch reported by the server into an
+     * [`crate::Error::ParamType`] pointing to the faulty parameter.
+     */
+    pub(crate) fn param_type_error(
+        query: &str,
+        params: &[&dyn crate::ToSql],
+        err: crate::Error,
+    ) -> crate::Error {
+        lazy_static::lazy_static! {
+            static ref OPERATOR: regex::Regex =
+                regex::Regex::new(r"^operator does not exist: (.+) [^\w\s]+ (.+)$").unwrap();
+            static ref COLUMN: regex::Regex =
+                regex::Regex::new(r#"^column ".+" is of type (.+) but expression is of type .+$"#).unwrap();
+        }
+
+        let result = match &err {
+            crate::Error::Sql(result) => result,
+            _ => return err,
+        };
+
+        let state = result.state();
+        if state != Some(crate::pq::state::UNDEFINED_FUNCTION)
+            && state != Some(crate::pq::state::DATATYPE_MISMATCH)
+        {
+            return err;
+        }
+
+        let message = result
+            .error_field(libpq::result::ErrorField::MessagePrimary)
+            .unwrap_or_default();
+        let position = result
+            .error_field(libpq::result::ErrorField::StatementPosition)
+            .and_then(|x| x.parse::<usize>().ok());
+
+        let (index, left) = match position.and_then(|x| Self::param_at(query, x)) {
+            Some(param) => param,
+            None => return err,
+        };
+
+        let expected = if let Some(captures) = COLUMN.captures(message) {
+            captures[1].to_string()
+        } else if let Some(captures) = OPERATOR.captures(message) {
+            // The expected type is the one of the other operand
+            if left {
+                captures[2].to_string()
+            } else {
+                captures[1].to_string()
+            }
+        } else {
+            return err;
+        };
+
+        let param = match index.checked_sub(1).and_then(|x| params.get(x)) {
+            Some(param) => param,
+            None => return err,
+        };
+
+        crate::Error::ParamType {
+            index,
+            rust_type: param.type_name().to_string(),
+            oid: param.ty().oid,
+            expected,
+            message: message.to_string(),
+        }
+    }
+
+    /**
+     * Finds the parameter at the 1-based character `position` of `query`: the
+     * parameter itself, or an operand of the operator at this position.
+     * Returns the parameter number and if it is on the left of the operator.
+     */
+    fn param_at(query: &str, position: usize) -> Option<(usize, bool)> {
+        lazy_static::lazy_static! {
+            static ref AFTER: regex::Regex = regex::Regex::new(r"^[^\w\s$()]*\s*\$(\d+)").unwrap();
+            static ref BEFORE: regex::Regex = regex::Regex::new(r"\$(\d+)\s*$").unwrap();
+        }
+
+        let offset = query
+            .char_indices()
+            .nth(position.checked_sub(1)?)
+            .map(|(offset, _)| offset)?;
+
+        if let Some(captures) = AFTER.captures(&query[offset..]) {
+            return Some((captures[1].parse().ok()?, false));
+        }
+
+        if let Some(captures) = BEFORE.captures(&query[..offset]) {
+            return Some((captures[1].parse().ok()?, true));
+        }
+
+        None
+    }
+
+    /**
+     * Likes `send_query` but retries on connection failure according to the
+     * retry policy.
+     */
+    fn send_read_query(
+        &self,
+        query: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::pq::Result> {
+        let policy = match self.retry_policy()? {
+            Some(policy) if self.transaction_status()? == libpq::transaction::Status::Idle => {
+                policy
+            }
+            _ => return self.send_query(query, params),
+        };
+
+        let mut attempt = 0;
+
+        loop {
+            match self.send_query(query, params) {
+                Err(err) if attempt < policy.max_retries && self.has_broken()? => {
+                    attempt += 1;
+                    log::warn!(
+                        "Connection lost, retrying ({}/{}): {}",
+                        attempt,
+                        policy.max_retries,
+                        err
+                    );
+
+                    std::thread::sleep(policy.delay(attempt));
+
+                    if let Err(err) = self.reset() {
+                        log::warn!("Unable to reconnect: {}", err);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /**
+     * Sets the policy used to retry read queries on connection failure. Read
+     * queries aren’t retried by default.
+     */
+    pub fn set_retry_policy(&self, policy: Option<crate::RetryPolicy>) -> crate::Result {
+        *self
+            .retry_policy
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))? = policy;
+
+        Ok(())
+    }
+
+    /**
+     * Sets the watchdog of the long or idle transactions. Transactions
+     * aren’t watched by default.
+     */
+    pub fn set_transaction_watchdog(
+        &self,
+        watchdog: Option<crate::TransactionWatchdog>,
+    ) -> crate::Result {
+        self.watchdog
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .set_policy(watchdog);
+
+        Ok(())
+    }
+
+    /**
+     * Sets the handling of the session time zone. With
+     * [`TimezonePolicy::Utc`], the session time zone is set to UTC
+     * immediately and after each [`reset`].
+     *
+     * [`TimezonePolicy::Utc`]: crate::TimezonePolicy::Utc
+     * [`reset`]: Self::reset
+     */
+    pub fn set_timezone_policy(&self, policy: crate::TimezonePolicy) -> crate::Result {
+        *self
+            .timezone_policy
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))? = policy;
+
+        self.apply_timezone_policy()
+    }
+
+    fn apply_timezone_policy(&self) -> crate::Result {
+        let policy = *self
+            .timezone_policy
+            .read()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+
+        if policy == crate::TimezonePolicy::Utc {
+            let _: crate::pq::Result = self.lock()?.exec("set time zone 'UTC'").try_into()?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Checks the session before `query`: its time zone with the timezone
+     * policy, then the current transaction with the watchdog, rollbacking it
+     * if the watchdog aborts it.
+     *
+     * The time zone isn’t checked for the queries able to restore it (ending
+     * the transaction, setting or resetting the time zone), otherwise a
+     * `set local time zone` would make the connection unusable.
+     *
+     * `connection` is the locked connection used to send `query`.
+     */
+    pub(crate) fn watch(&self, connection: &libpq::Connection, query: &str) -> crate::Result {
+        let policy = *self
+            .timezone_policy
+            .read()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+
+        if policy != crate::TimezonePolicy::Server && !crate::timezone::restores(query) {
+            policy.check(&connection.parameter_status("TimeZone"))?;
+        }
+
+        let error = self
+            .watchdog
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .check();
+
+        if let Some(error) = error {
+            let result: crate::Result<crate::pq::Result> =
+                connection.exec("rollback transaction").try_into();
+            self.events.clear()?;
+            self.touch(connection);
+            result?;
+
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Has the current transaction been rollbacked by the watchdog?
+     */
+    pub(crate) fn is_aborted(&self) -> crate::Result<bool> {
+        let watch = self
+            .watchdog
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+
+        Ok(watch.is_aborted())
+    }
+
+    /**
+     * Records the start of a transaction for the watchdog.
+     */
+    pub(crate) fn begin(&self) -> crate::Result {
+        self.watchdog
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .begin();
+
+        Ok(())
+    }
+
+    /**
+     * Records the end of a query for the watchdog, returns the transaction
+     * status observed after it.
+     */
+    pub(crate) fn touch(&self, connection: &libpq::Connection) -> libpq::transaction::Status {
+        let status = connection.transaction_status();
+
+        if let Ok(mut watch) = self.watchdog.lock() {
+            if watch.is_enabled() {
+                watch.touch(status != libpq::transaction::Status::Idle);
+            }
+        }
+
+        status
+    }
+
+    /**
+     * Dispatches or drops the queued events once their transaction ended, even
+     * without the [`crate::Transaction`] helpers: `status` is the transaction
+     * status observed after the query and `results` its results.
+     *
+     * The events belong to the transaction in progress before the query, the
+     * first `COMMIT` or `ROLLBACK` of the results ended it. It’s rollbacked
+     * if there is none, the query failed or the connection was lost.
+     *
+     * Must be called once the connection is released, the subscribers may use
+     * it.
+     */
+    pub(crate) fn settle_events<'r, I>(
+        &self,
+        status: libpq::transaction::Status,
+        results: I,
+    ) -> crate::Result
+    where
+        I: IntoIterator<Item = &'r crate::Result<crate::pq::Result>>,
+    {
+        if status != libpq::transaction::Status::Idle || self.events.len()? == 0 {
+            return Ok(());
+        }
+
+        let committed = results
+            .into_iter()
+            .find_map(|result| match result.as_ref().ok()?.command_tag()? {
+                crate::pq::CommandTag::Other(tag) if tag == "COMMIT" => Some(true),
+                crate::pq::CommandTag::Other(tag) if tag == "ROLLBACK" => Some(false),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        if committed {
+            self.events.flush()
+        } else {
+            self.events.clear()
+        }
+    }
+
+    /**
+     * Qualifies relations without schema with `schema` in generated queries,
+     * so they don’t depend on the session `search_path`. The schema name is
+     * quoted, so it’s case sensitive.
+     */
+    pub fn set_default_schema(&self, schema: Option<&str>) -> crate::Result {
+        *self
+            .default_schema
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))? = schema.map(ToString::to_string);
+
+        Ok(())
+    }
+
+    /**
+     * Relation name of `S` used in generated queries.
+     */
+    pub(crate) fn relation<S: crate::Structure>(&self) -> String {
+        let default_schema = self
+            .default_schema
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        match (S::schema(), default_schema.as_ref()) {
+            (None, Some(schema)) => {
+                format!("{}.{}", crate::identifier::quote(schema), S::relation())
+            }
+            _ => S::relation().to_string(),
+        }
+    }
+
+    /**
+     * Sets the policy used to mask columns of the queries generated from
+     * models. Nothing is masked by default.
+     */
+    pub fn set_masking_policy(&self, policy: Option<crate::MaskingPolicy>) -> crate::Result {
+        *self
+            .masking_policy
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))? = policy;
+
+        Ok(())
+    }
+
+    /**
+     * Projection of `M` used in generated queries, with the masking policy
+     * applied.
+     */
+    pub(crate) fn projection<'a, M: crate::Model<'a>>(&self) -> crate::Projection {
+        let projection = M::create_projection();
+
+        let policy = self
+            .masking_policy
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        match policy.as_ref() {
+            Some(policy) => policy.apply(M::Structure::relation(), projection),
+            None => projection,
+        }
+    }
+
+    /**
+     * Prepends the string returned by `tagger`, usually a SQL comment, to
+     * every statement sent, to correlate `pg_stat_activity` and server logs
+     * with application traces.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * # let request_id = 42;
+     * connection.tag_queries(move |_| format!("/* request_id={} */", request_id))?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     *
+     * A prepared statement keeps the tag of its preparation. The automatic
+     * statement cache is keyed by the tagged query, a tag changing on each
+     * execution prevents the queries to be prepared automatically.
+     */
+    pub fn tag_queries<F>(&self, tagger: F) -> crate::Result
+    where
+        F: Fn(&crate::QueryContext<'_>) -> String + Send + Sync + 'static,
+    {
+        *self
+            .query_tagger
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))? =
+            Some(crate::tag::QueryTagger::new(tagger));
+
+        Ok(())
+    }
+
+    /**
+     * Stops tagging statements, see [`tag_queries`].
+     *
+     * [`tag_queries`]: Self::tag_queries
+     */
+    pub fn untag_queries(&self) -> crate::Result {
+        *self
+            .query_tagger
+            .write()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))? = None;
+
+        Ok(())
+    }
+
+    /**
+     * Prepends the tag to `query`, if any.
+     */
+    pub(crate) fn tag(&self, query: &str) -> String {
+        let tagger = self
+            .query_tagger
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        match tagger.as_ref() {
+            Some(tagger) => tagger.tag(query),
+            None => query.to_string(),
+        }
+    }
+
+    fn retry_policy(&self) -> crate::Result<Option<crate::RetryPolicy>> {
+        let policy = self
+            .retry_policy
+            .read()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))?
+            .clone();
+
+        Ok(policy)
+    }
+
+    pub(crate) fn count_error<T>(&self, result: crate::Result<T>) -> crate::Result<T> {
+        if result.is_err() {
+            self.stats.error();
+        }
+
+        result
+    }
+
+    pub(crate) fn order_parameters<'a>(&self, query: &'a str) -> std::borrow::Cow<'a, str> {
+        lazy_static::lazy_static! {
+            static ref REGEX: regex::Regex =
+                #[allow(clippy::trivial_regex)]
+                regex::Regex::new(r"\$\*").unwrap();
+        }
+
+        let mut count = 0;
+
+        REGEX.replace_all(query, |captures: &regex::Captures<'_>| {
+            count += 1;
+
+            captures[0].replace("$*", &format!("${}", count))
+        })
+    }
+
+    /**
+     * Return an entity upon its primary key. If no entities are found, `None`
+     * is returned.
+     */
+    pub fn find_by_pk<'a, M>(
+        &self,
+        pk: &HashMap<&str, &dyn crate::ToSql>,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let (clause, params) = self.pk_clause::<M>(pk)?;
+        let mut tuples = self.find_where::<M>(&clause, &params, None)?;
+
+        Ok(tuples.next())
+    }
+
+    /**
+     * Same as [`find_by_pk`] with a typed primary key, checked at compile
+     * time.
+     *
+     * [`find_by_pk`]: Self::find_by_pk
+     */
+    pub fn find_by_primary_key<'a, M>(&self, pk: &M::PrimaryKey) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::HasPrimaryKey<'a>,
+    {
+        self.find_by_pk::<M>(&M::primary_key_values(pk)?)
+    }
+
+    /**
+     * Return entities upon their primary keys, in one query. Entities are
+     * indexed by their primary key value, `K` is the primary key type (a
+     * tuple for multi-columns primary keys).
+     */
+    pub fn find_by_pks<'a, M, K>(
+        &self,
+        pks: &[HashMap<&str, &dyn crate::ToSql>],
+    ) -> crate::Result<HashMap<K, M::Entity>>
+    where
+        M: crate::Model<'a>,
+        K: crate::FromSql + Eq + std::hash::Hash,
+    {
+        let primary_key = M::Structure::primary_key();
+
+        if pks.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut values = Vec::new();
+
+        for pk in pks {
+            if pk.len() != primary_key.len() {
+                return Err(crate::Error::PrimaryKey);
+            }
+
+            for field in primary_key {
+                let value = pk.get(field).ok_or(crate::Error::PrimaryKey)?;
+                values.push(*value);
+            }
+        }
+
+        let fields = primary_key
+            .iter()
+            .map(|x| crate::identifier::quote(x))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let results = if primary_key.len() == 1 {
+            let query = format!(
+                "SELECT {}, {} as \"__pk\" FROM {} WHERE {} = ANY($1);",
+                self.projection::<M>(),
+                fields,
+                self.relation::<M::Structure>(),
+                fields,
+            );
+
+            self.send_read_query(&query, &[&values])?
+        } else {
+            let rows = (0..pks.len())
+                .map(|i| {
+                    let params = (1..=primary_key.len())
+                        .map(|j| format!("${}", i * primary_key.len() + j))
+                        .collect::<Vec<_>>();
+
+                    format!("({})", params.join(", "))
+                })
+                .collect::<Vec<_>>();
+
+            let query = format!(
+                "SELECT {}, row({}) as \"__pk\" FROM {} WHERE ({}) IN ({});",
+                self.projection::<M>(),
+                fields,
+                self.relation::<M::Structure>(),
+                fields,
+                rows.join(", "),
+            );
+
+            self.send_read_query(&query, &values)?
+        };
+
+        let mut entities = HashMap::new();
+
+        for tuple in &results {
+            entities.insert(tuple.try_get("__pk")?, M::create_entity(&tuple));
+        }
+
+        Ok(entities)
+    }
+
+    /**
+     * Return all elements from a relation. If a suffix is given, it is append
+     * to the query. This is mainly useful for "order by" statements.
+     *
+     * NOTE: suffix is inserted as is with NO ESCAPING. DO NOT use it to place
+     * "where" condition nor any untrusted params.
+     */
+    pub fn find_all<'a, M>(&self, suffix: Option<&str>) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT {} FROM {} {};",
+            self.projection::<M>(),
+            self.relation::<M::Structure>(),
+            suffix.unwrap_or_default(),
+        );
+
+        Ok(self.send_read_query(&query, &[])?.into())
+    }
+
+    /**
+     * Perform a simple select on a given condition
+     *
+     * NOTE: suffix is inserted as is with NO ESCAPING. DO NOT use it to place
+     * "where" condition nor any untrusted params.
+     */
+    pub fn find_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+        suffix: Option<&str>,
+    ) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = self.find_where_query::<M>(clause, suffix);
+
+        Ok(self.send_read_query(&query, params)?.into())
+    }
+
+    pub(crate) fn find_where_query<'a, M>(&self, clause: &str, suffix: Option<&str>) -> String
+    where
+        M: crate::Model<'a>,
+    {
+        format!(
+            "SELECT {} FROM {} WHERE {} {};",
+            self.projection::<M>(),
+            self.relation::<M::Structure>(),
+            clause,
+            suffix.unwrap_or_default(),
+        )
+    }
+
+    /**
+     * Return elements matching a scope. Scopes are functions of models
+     * returning a [`Where`] clause, they can be combined with [`Where::and`]
+     * and [`Where::or`].
+     *
+     * ```ignore
+     * impl<'a> PostModel<'a> {
+     *     pub fn published() -> elephantry::Where<'static> {
+     *         elephantry::Where::from("published_at is not null", Vec::new())
+     *     }
+     *
+     *     pub fn by_author(id: &'a i32) -> elephantry::Where<'a> {
+     *         elephantry::Where::from("author_id = $*", vec![id])
+     *     }
+     * }
+     *
+     * connection.scope::<PostModel>(PostModel::published().and(PostModel::by_author(&id)))?;
+     * ```
+     *
+     * [`Where`]: crate::Where
+     * [`Where::and`]: crate::Where::and
+     * [`Where::or`]: crate::Where::or
+     */
+    pub fn scope<'a, M>(&self, scope: crate::Where<'_>) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        self.find_where::<M>(&scope.to_string(), &scope.params(), None)
+    }
+
+    /**
+     * Perform a select on a given condition, eliminating duplicate rows.
+     *
+     * The `ORDER BY` clause is generated from `order_by` and, for `DISTINCT
+     * ON`, the distinct expressions.
+     *
+     * NOTE: suffix is inserted as is with NO ESCAPING. DO NOT use it to place
+     * "where" condition nor any untrusted params.
+     */
+    pub fn find_distinct_where<'a, M>(
+        &self,
+        distinct: crate::Distinct<'_>,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+        order_by: &[&str],
+        suffix: Option<&str>,
+    ) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT {} {} FROM {} WHERE {} {} {};",
+            distinct,
+            self.projection::<M>(),
+            self.relation::<M::Structure>(),
+            clause,
+            distinct.order_by(order_by),
+            suffix.unwrap_or_default(),
+        );
+
+        Ok(self.send_read_query(&query, params)?.into())
+    }
+
+    /**
+     * Paginate a query.
+     *
+     * This is done with limit/offset, read why it’s probably not a good idea to
+     * use it: <https://use-the-index-luke.com/no-offset>.
+     */
+    pub fn paginate_find_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+        max_per_page: usize,
+        page: usize,
+        suffix: Option<&str>,
+    ) -> crate::Result<crate::Pager<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        let suffix = format!(
+            "{} offset {} fetch first {} rows only",
+            suffix.unwrap_or_default(),
+            max_per_page * (page - 1),
+            max_per_page
+        );
+
+        let rows = self.find_where::<M>(clause, params, Some(&suffix))?;
+        let count = self.count_where::<M>(clause, params)?;
+
+        let pager = crate::Pager::new(rows, count, page, max_per_page);
+
+        Ok(pager)
+    }
+
+    /**
+     * Return the number of records matching a condition.
+     */
+    pub fn count_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<usize>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT COUNT(*) FROM {} WHERE {};",
+            self.relation::<M::Structure>(),
+            clause,
+        );
+
+        let results = self.send_read_query(&query, params)?;
+
+        results.get(0).try_get("count")
+    }
+
+    /**
+     * Check if rows matching the given condition do exist or not.
+     */
+    pub fn exist_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<bool>
+    where
+        M: crate::Model<'a>,
+    {
+        let query = format!(
+            "SELECT EXISTS (SELECT true FROM {} WHERE {}) AS result;",
+            self.relation::<M::Structure>(),
+            clause,
+        );
+
+        let results = self.send_read_query(&query, params)?;
+
+        results.get(0).try_get("result")
+    }
+
+    /**
+     * Loads all the rows of a small lookup table into an in-memory snapshot,
+     * indexed by primary key and unique keys.
+     */
+    pub fn load_reference<'c, M>(&'c self) -> crate::Result<crate::Reference<'c, M>>
+    where
+        M: crate::Model<'c>,
+    {
+        crate::Reference::new(self)
+    }
+
+    /**
+     * Children of `parent` in the [`HasMany`] association of `P` and `C`.
+     *
+     * [`HasMany`]: crate::HasMany
+     */
+    pub fn find_has_many<'a, P, C>(
+        &self,
+        parent: &P::Entity,
+    ) -> crate::Result<crate::Rows<C::Entity>>
+    where
+        P: crate::HasMany<'a, C>,
+        C: crate::Model<'a>,
+    {
+        let values = crate::association::values(parent, P::key()).unwrap_or_default();
+
+        if values.is_empty() {
+            return self.find_where::<C>("false", &[], None);
+        }
+
+        let clause = crate::association::clause(self, <P as crate::HasMany<'a, C>>::foreign_key())?;
+
+        self.find_where::<C>(&clause, &values, None)
+    }
+
+    /**
+     * Parent of `child` in the [`BelongsTo`] association of `C` and `P`,
+     * `None` if its foreign key is null or references nothing.
+     *
+     * [`BelongsTo`]: crate::BelongsTo
+     */
+    pub fn find_belongs_to<'a, C, P>(&self, child: &C::Entity) -> crate::Result<Option<P::Entity>>
+    where
+        C: crate::BelongsTo<'a, P>,
+        P: crate::Model<'a>,
+    {
+        let values = match crate::association::values(
+            child,
+            <C as crate::BelongsTo<'a, P>>::foreign_key(),
+        ) {
+            Some(values) => values,
+            None => return Ok(None),
+        };
+
+        let clause = crate::association::clause(self, C::key())?;
+
+        Ok(self
+            .find_where::<P>(&clause, &values, Some("limit 1"))?
+            .try_get(0))
+    }
+
+    /**
+     * Eager loading of the [`HasMany`] association: the children of all
+     * `parents` are fetched with one query (by batches of `(fk) in (…)`
+     * clauses for the large collections), instead of one query by parent.
+     *
+     * Returns the children of each parent, in the `parents` order.
+     *
+     * [`HasMany`]: crate::HasMany
+     */
+    pub fn load_has_many<'a, P, C>(
+        &self,
+        parents: &[P::Entity],
+    ) -> crate::Result<Vec<Vec<C::Entity>>>
+    where
+        P: crate::HasMany<'a, C>,
+        C: crate::Model<'a>,
+        C::Entity: Clone,
+    {
+        let keys = crate::association::distinct_keys(parents, P::key())?;
+        let children = crate::association::load::<C>(
+            self,
+            <P as crate::HasMany<'a, C>>::foreign_key(),
+            &keys,
+        )?;
+
+        parents
+            .iter()
+            .map(
+                |parent| match crate::association::values(parent, P::key()) {
+                    Some(values) => Ok(children
+                        .get(&crate::association::key(&values)?)
+                        .cloned()
+                        .unwrap_or_default()),
+                    None => Ok(Vec::new()),
+                },
+            )
+            .collect()
+    }
+
+    /**
+     * Eager loading of the [`BelongsTo`] association: the parents of all
+     * `children` are fetched with one query (by batches of `(key) in (…)`
+     * clauses for the large collections), instead of one query by child.
+     *
+     * Returns the parent of each child, in the `children` order.
+     *
+     * [`BelongsTo`]: crate::BelongsTo
+     */
+    pub fn load_belongs_to<'a, C, P>(
+        &self,
+        children: &[C::Entity],
+    ) -> crate::Result<Vec<Option<P::Entity>>>
+    where
+        C: crate::BelongsTo<'a, P>,
+        P: crate::Model<'a>,
+        P::Entity: Clone,
+    {
+        let foreign_key = <C as crate::BelongsTo<'a, P>>::foreign_key();
+        let keys = crate::association::distinct_keys(children, foreign_key)?;
+        let parents = crate::association::load::<P>(self, C::key(), &keys)?;
+
+        children
+            .iter()
+            .map(
+                |child| match crate::association::values(child, foreign_key) {
+                    Some(values) => Ok(parents
+                        .get(&crate::association::key(&values)?)
+                        .and_then(|x| x.first().cloned())),
+                    None => Ok(None),
+                },
+            )
+            .collect()
+    }
+
+    /**
+     * Insert a new entity in the database.
+     *
+     * Returns the entity with values from database (ie: default values).
+     */
+    pub fn insert_one<'a, M>(&self, entity: &M::Entity) -> crate::Result<M::Entity>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        self.insert::<M>(entity, None).map(|x| x.unwrap())
+    }
+
+    /**
+     * Insert a serializable value, only its fields matching a column of
+     * the model are inserted.
+     *
+     * Returns the entity with values from database.
+     *
+     * See [`to_params`].
+     *
+     * [`to_params`]: crate::to_params
+     */
+    #[cfg(feature = "serde")]
+    pub fn insert_serialized<'a, M, T>(&self, value: &T) -> crate::Result<M::Entity>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+        T: serde::Serialize + ?Sized,
+    {
+        let params = crate::to_params(value)?;
+
+        let mut tuple = Vec::new();
+        let mut fields = Vec::new();
+
+        for field in M::Structure::columns() {
+            if let Some(value) = params.get(field) {
+                tuple.push(value);
+                fields.push(*field);
+            }
+        }
+
+        let query = self.insert_query::<M>(&fields, None);
+
+        let results = self.send_query(&query, tuple.as_slice())?;
+        self.emit::<M, _>(&results, |_| crate::events::Kind::Created)?;
+
+        match results.try_get(0) {
+            Some(tuple) => Ok(M::create_entity(&tuple)),
+            None => Err(crate::Error::MissingField("0".to_string())),
+        }
+    }
+
+    /**
+     * Try to insert a new entity in the database. On constraint violation error
+     * on `target` you can do an alternative action `action`.
+     *
+     * See [ON CONFLICT clause](https://www.postgresql.org/docs/current/sql-insert.html#SQL-ON-CONFLICT).
+     *
+     * Returns the entity with values from database (ie: default values).
+     */
+    pub fn upsert_one<'a, M>(
+        &self,
+        entity: &M::Entity,
+        target: &str,
+        action: &str,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let suffix = format!("on conflict {} do {}", target, action);
+        self.insert::<M>(entity, Some(suffix.as_str()))
+    }
+
+    /**
+     * Returns the entity matching `clause`, or inserts the one built by `f`.
+     *
+     * The insertion is done with `on conflict do nothing`: if a concurrent
+     * session inserts the same row between the select and the insert, the
+     * row is selected again instead of failing on the unique constraint.
+     * `clause` should therefore match the unique key of the built entity.
+     *
+     * Returns [`Error::Conflict`] if the conflicting row stays invisible, for
+     * example in a `repeatable read` transaction started before its
+     * insertion.
+     *
+     * [`Error::Conflict`]: crate::Error::Conflict
+     */
+    pub fn get_or_insert<'a, M, F>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+        f: F,
+    ) -> crate::Result<M::Entity>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+        F: FnOnce() -> M::Entity,
+    {
+        if let Some(entity) = self
+            .find_where::<M>(clause, params, Some("limit 1"))?
+            .try_get(0)
+        {
+            return Ok(entity);
+        }
+
+        let entity = f();
+
+        for _ in 0..3 {
+            if let Some(entity) = self.insert::<M>(&entity, Some("on conflict do nothing"))? {
+                return Ok(entity);
+            }
+
+            if let Some(entity) = self
+                .find_where::<M>(clause, params, Some("limit 1"))?
+                .try_get(0)
+            {
+                return Ok(entity);
+            }
+        }
+
+        Err(crate::Error::Conflict(self.relation::<M::Structure>()))
+    }
+
+    /**
+     * Insert many entities with multi-row queries, chunked to respect the
+     * parameters limit of PostgreSQL. Conflicting rows are handled by
+     * `on_conflict`.
+     *
+     * Returns entities with values from database, marked as inserted or
+     * updated. Skipped rows aren’t returned.
+     */
+    pub fn upsert_many<'a, M>(
+        &self,
+        entities: &[M::Entity],
+        on_conflict: crate::OnConflict<'_>,
+    ) -> crate::Result<Vec<crate::Upsert<M::Entity>>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        use crate::Entity;
+
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let fields = M::Structure::columns()
+            .iter()
+            .filter(|x| entities.iter().any(|entity| entity.get(x).is_some()))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut upserted = Vec::new();
+
+        for chunk in entities.chunks(crate::association::MAX_PARAMS / fields.len().max(1)) {
+            upserted.extend(self.upsert_chunk::<M>(chunk, &fields, &on_conflict)?);
+        }
+
+        Ok(upserted)
+    }
+
+    fn upsert_chunk<'a, M>(
+        &self,
+        entities: &[M::Entity],
+        fields: &[&str],
+        on_conflict: &crate::OnConflict<'_>,
+    ) -> crate::Result<Vec<crate::Upsert<M::Entity>>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        use crate::Entity;
+
+        let mut tuples = Vec::new();
+        let mut params = Vec::new();
+        let mut x = 1;
+
+        for entity in entities {
+            let mut values = Vec::new();
+
+            for field in fields {
+                match entity.get(field) {
+                    Some(value) => {
+                        params.push(value);
+                        values.push(format!("${}", x));
+                        x += 1;
+                    }
+                    None => values.push("default".to_string()),
+                }
+            }
+
+            tuples.push(format!("({})", values.join(", ")));
+        }
+
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES {} {} RETURNING {}, xmax = 0 as \"__inserted\";",
+            self.relation::<M::Structure>(),
+            fields.join(", "),
+            tuples.join(", "),
+            on_conflict.to_sql::<M>(fields),
+            self.projection::<M>(),
+        );
+
+        let results = self.send_query(&query, params.as_slice())?;
+        self.emit::<M, _>(&results, |tuple| {
+            if tuple.try_get("__inserted").unwrap_or(true) {
+                crate::events::Kind::Created
+            } else {
+                crate::events::Kind::Updated
+            }
+        })?;
+
+        let mut upserted = Vec::new();
+
+        for tuple in &results {
+            let entity = M::create_entity(&tuple);
+
+            if tuple.try_get("__inserted")? {
+                upserted.push(crate::Upsert::Inserted(entity));
+            } else {
+                upserted.push(crate::Upsert::Updated(entity));
+            }
+        }
+
+        Ok(upserted)
+    }
+
+    /**
+     * Copy rows of the `S` relation matching `clause` into the `T` relation.
+     *
+     * `column_mapping` is a list of `(target column, source column)`. If it’s
+     * empty, the columns present in both structures are copied.
+     *
+     * Returns the inserted entities.
+     */
+    pub fn insert_from<'a, T, S>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+        column_mapping: &[(&str, &str)],
+    ) -> crate::Result<crate::Rows<T::Entity>>
+    where
+        T: crate::Model<'a>,
+        T::Structure: crate::Writable,
+        S: crate::Model<'a>,
+    {
+        let (targets, sources): (Vec<_>, Vec<_>) = if column_mapping.is_empty() {
+            T::Structure::columns()
+                .iter()
+                .filter(|x| S::Structure::columns().contains(x))
+                .map(|x| (*x, *x))
+                .unzip()
+        } else {
+            column_mapping.iter().copied().unzip()
+        };
+
+        let quote = |columns: Vec<&str>| {
+            columns
+                .iter()
+                .map(|x| self.escape_identifier(x))
+                .collect::<crate::Result<Vec<_>>>()
+                .map(|x| x.join(", "))
+        };
+
+        let query = format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {} WHERE {} RETURNING {};",
+            self.relation::<T::Structure>(),
+            quote(targets)?,
+            quote(sources)?,
+            self.relation::<S::Structure>(),
+            clause,
+            self.projection::<T>(),
+        );
+
+        let results = self.send_query(&query, params)?;
+        self.emit::<T, _>(&results, |_| crate::events::Kind::Created)?;
+
+        Ok(results.into())
+    }
+
+    fn insert<'a, M>(
+        &self,
+        entity: &M::Entity,
+        suffix: Option<&str>,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+    {
+        use crate::Entity;
+
+        let mut tuple = Vec::new();
+        let mut fields = Vec::new();
+
+        for field in M::Structure::columns() {
+            if let Some(value) = entity.get(field) {
+                tuple.push(value);
+                fields.push(*field);
+            }
+        }
+
+        let query = self.insert_query::<M>(&fields, suffix);
+
+        let results = self.send_query(&query, tuple.as_slice())?;
+        self.emit::<M, _>(&results, |_| crate::events::Kind::Created)?;
+
+        let result = results.try_get(0).map(|x| M::create_entity(&x));
+
+        Ok(result)
+    }
+
+    pub(crate) fn insert_query<'a, M>(&self, fields: &[&str], suffix: Option<&str>) -> String
+    where
+        M: crate::Model<'a>,
+    {
+        let params = (1..=fields.len())
+            .map(|x| format!("${}", x))
+            .collect::<Vec<_>>();
+
+        format!(
+            "INSERT INTO {} ({}) VALUES({}) {} RETURNING {};",
+            self.relation::<M::Structure>(),
+            fields.join(", "),
+            params.join(", "),
+            suffix.unwrap_or_default(),
+            self.projection::<M>(),
+        )
+    }
+
+    /**
+     * Update the entity.
+     *
+     * Returns the entity with values from database.
+     */
+    pub fn update_one<'a, M>(
+        &self,
+        pk: &HashMap<&str, &dyn crate::ToSql>,
+        entity: &M::Entity,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        use crate::Entity;
+
+        let mut data = HashMap::new();
+
+        for field in M::Structure::columns() {
+            let value = match entity.get(field) {
+                Some(value) => value,
+                None => &Option::<&str>::None,
+            };
+            data.insert(field.to_string(), value);
+        }
+
+        self.update_by_pk::<M>(pk, &data)
+    }
+
+    /**
+     * Update a record and fetch it with its new values. If no records match
+     * the given key, `None` is returned.
+     */
+    pub fn update_by_pk<'a, M>(
+        &self,
+        pk: &HashMap<&str, &dyn crate::ToSql>,
+        data: &HashMap<String, &dyn crate::ToSql>,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let (query, params) = match self.update_by_pk_query::<M>(pk, data)? {
+            Some(update) => update,
+            None => return Ok(None),
+        };
+
+        let results = self.send_query(&query, &params)?;
+        self.emit::<M, _>(&results, |_| crate::events::Kind::Updated)?;
+
+        let entity = results.try_get(0).map(|x| M::create_entity(&x));
+
+        Ok(entity)
+    }
+
+    /**
+     * Builds the update query of [`update_by_pk`], `None` if there is no field
+     * to update.
+     *
+     * [`update_by_pk`]: Self::update_by_pk
+     */
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn update_by_pk_query<'a, 'b, M>(
+        &self,
+        pk: &HashMap<&str, &'b dyn crate::ToSql>,
+        data: &HashMap<String, &'b dyn crate::ToSql>,
+    ) -> crate::Result<Option<(String, Vec<&'b dyn crate::ToSql>)>>
+    where
+        M: crate::Model<'a>,
+    {
+        let (clause, mut params) = self.pk_clause::<M>(pk)?;
+        let set = self.update_set::<M>(data, &mut params);
+
+        if set.is_empty() {
+            log::warn!("No field to update");
+            return Ok(None);
+        }
+
+        Ok(Some((self.update_query::<M>(&set, &clause), params)))
+    }
+
+    /**
+     * Builds the `SET` assignments of the known columns in `data`, their
+     * values are appended to `params`.
+     */
+    fn update_set<'a, 'b, M>(
+        &self,
+        data: &HashMap<String, &'b dyn crate::ToSql>,
+        params: &mut Vec<&'b dyn crate::ToSql>,
+    ) -> Vec<String>
+    where
+        M: crate::Model<'a>,
+    {
+        let mut set = Vec::new();
+        let projection = M::default_projection();
+
+        for key in M::Structure::columns() {
+            if !projection.has_field(key) {
+                continue;
+            }
+
+            if let Some(value) = data.get(*key) {
+                params.push(*value);
+                set.push(format!("{} = ${}", key, params.len()));
+            }
+        }
+
+        set
+    }
+
+    /**
+     * Update records by a given condition and returns the number of updated
+     * rows.
+     *
+     * Unlike [`update_by_pk`], the updated rows aren’t sent back by the
+     * server, so no [`events`] are emitted.
+     *
+     * [`events`]: crate::events
+     * [`update_by_pk`]: Self::update_by_pk
+     */
+    pub fn update_where_count<'a, 'b, M>(
+        &self,
+        clause: &str,
+        params: &[&'b dyn crate::ToSql],
+        data: &HashMap<String, &'b dyn crate::ToSql>,
+    ) -> crate::Result<u64>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let mut params = params.to_vec();
+        let set = self.update_set::<M>(data, &mut params);
+
+        if set.is_empty() {
+            log::warn!("No field to update");
+            return Ok(0);
+        }
+
+        let query = format!(
+            "UPDATE {} SET {} WHERE {};",
+            self.relation::<M::Structure>(),
+            set.join(", "),
+            clause,
+        );
+
+        Ok(self.send_query(&query, &params)?.affected_rows())
+    }
+
+    pub(crate) fn update_query<'a, M>(&self, set: &[String], clause: &str) -> String
+    where
+        M: crate::Model<'a>,
+    {
+        format!(
+            "UPDATE {} SET {} WHERE {} RETURNING {};",
+            self.relation::<M::Structure>(),
+            set.join(", "),
+            clause,
+            self.projection::<M>(),
+        )
+    }
+
+    /**
+     * Delete an entity from a table.
+     *
+     * Returns the entity fetched from the deleted record.
+     */
+    pub fn delete_one<'a, M>(&self, entity: &M::Entity) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let pk = M::primary_key(entity)?;
+
+        self.delete_by_pk::<M>(&pk)
+    }
+
+    /**
+     * Delete a record from its primary key. The deleted entity is returned or
+     * `None` if not found.
+     */
+    pub fn delete_by_pk<'a, M>(
+        &self,
+        pk: &HashMap<&str, &dyn crate::ToSql>,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let (clause, params) = self.pk_clause::<M>(pk)?;
+        let mut results = self.delete_where::<M>(&clause, &params)?;
+
+        Ok(results.next())
+    }
+
+    /**
+     * Same as [`delete_by_pk`] with a typed primary key, checked at compile
+     * time.
+     *
+     * [`delete_by_pk`]: Self::delete_by_pk
+     */
+    pub fn delete_by_primary_key<'a, M>(
+        &self,
+        pk: &M::PrimaryKey,
+    ) -> crate::Result<Option<M::Entity>>
+    where
+        M: crate::HasPrimaryKey<'a>,
+        M::Structure: crate::Writable,
+    {
+        self.delete_by_pk::<M>(&M::primary_key_values(pk)?)
+    }
+
+    /**
+     * Delete records by a given condition. A collection of all deleted entries
+     * is returned, the number of deleted rows is available via
+     * [`affected_rows`].
+     *
+     * [`affected_rows`]: crate::pq::Result::affected_rows
+     */
+    pub fn delete_where<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::Rows<M::Entity>>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let query = self.delete_query::<M>(clause);
+
+        let results = self.send_query(&query, params)?;
+        self.emit::<M, _>(&results, |_| crate::events::Kind::Deleted)?;
+
+        Ok(results.into())
+    }
+
+    /**
+     * Delete records by a given condition and returns the number of deleted
+     * rows.
+     *
+     * Unlike [`delete_where`], the deleted rows aren’t sent back by the
+     * server, so no [`events`] are emitted.
+     *
+     * [`delete_where`]: Self::delete_where
+     * [`events`]: crate::events
+     */
+    pub fn delete_where_count<'a, M>(
+        &self,
+        clause: &str,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<u64>
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let query = format!(
+            "DELETE FROM {} WHERE {};",
+            self.relation::<M::Structure>(),
+            clause,
+        );
+
+        Ok(self.send_query(&query, params)?.affected_rows())
+    }
+
+    pub(crate) fn delete_query<'a, M>(&self, clause: &str) -> String
+    where
+        M: crate::Model<'a>,
+    {
+        format!(
+            "DELETE FROM {} WHERE {} RETURNING {};",
+            self.relation::<M::Structure>(),
+            clause,
+            self.projection::<M>(),
+        )
+    }
+
+    /**
+     * Refreshes the content of a materialized view.
+     */
+    pub fn refresh<'a, M>(&self) -> crate::Result
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::MaterializedView,
+    {
+        let query = format!(
+            "refresh materialized view {};",
+            self.relation::<M::Structure>()
+        );
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Likes [`refresh`] without locking out concurrent selects on the view,
+     * which needs an unique index.
+     *
+     * [`refresh`]: crate::Connection::refresh
+     */
+    pub fn refresh_concurrently<'a, M>(&self) -> crate::Result
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::MaterializedView,
+    {
+        let query = format!(
+            "refresh materialized view concurrently {};",
+            self.relation::<M::Structure>()
+        );
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Creates an index on the relation of the model.
+     *
+     * A concurrent build can’t run inside a transaction: returns
+     * [`Error::TransactionStatus`] instead of executing it.
+     *
+     * [`Error::TransactionStatus`]: crate::Error::TransactionStatus
+     */
+    pub fn create_index<'a, M>(&self, spec: &crate::IndexSpec) -> crate::Result
+    where
+        M: crate::Model<'a>,
+    {
+        if spec.is_concurrently() {
+            let status = self.transaction_status()?;
+
+            if status != libpq::transaction::Status::Idle {
+                return Err(crate::Error::TransactionStatus(status));
+            }
+        }
+
+        let query = spec.to_sql(&crate::identifier::relation(
+            &self.relation::<M::Structure>(),
+        ));
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Adds the check constraint `name` to the table of the model.
+     *
+     * `check` is a SQL boolean expression, not escaped.
+     */
+    pub fn add_check_constraint<'a, M>(&self, name: &str, check: &str) -> crate::Result
+    where
+        M: crate::Model<'a>,
+        M::Structure: crate::Writable,
+    {
+        let query = format!(
+            "alter table {} add constraint {} check ({});",
+            crate::identifier::relation(&self.relation::<M::Structure>()),
+            crate::identifier::quote(name),
+            check,
+        );
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Dispatches lifecycle events of `results` rows, or queues them until the
+     * end of the current transaction.
+     */
+    pub(crate) fn emit<'a, M, F>(&self, results: &crate::pq::Result, kind: F) -> crate::Result
+    where
+        M: crate::Model<'a>,
+        F: Fn(&crate::Tuple<'_>) -> crate::events::Kind,
+    {
+        let events = crate::events::prepare(M::Structure::relation(), results, kind)?;
+
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        self.events.push(events)?;
+
+        if self.transaction_status()? == libpq::transaction::Status::Idle {
+            self.events.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn pk_clause<'a, 'b, M>(
+        &self,
+        pk: &HashMap<&str, &'b dyn crate::ToSql>,
+    ) -> crate::Result<(String, Vec<&'b dyn crate::ToSql>)>
+    where
+        M: crate::Model<'a>,
+    {
+        let keys = M::Structure::primary_key();
+
+        if keys.len() != pk.len() {
+            return Err(crate::Error::PrimaryKey);
+        }
+
+        let mut params = Vec::new();
+
+        for key in keys {
+            params.push(*pk.get(key).ok_or(crate::Error::PrimaryKey)?);
+        }
+
+        Ok((Self::pk_condition::<M>(), params))
+    }
+
+    fn pk_condition<'a, M>() -> String
+    where
+        M: crate::Model<'a>,
+    {
+        M::Structure::primary_key()
+            .iter()
+            .enumerate()
+            .fold(String::new(), |acc, (i, x)| {
+                let field = format!("\"{}\"", x.replace("\"", "\\\""));
+
+                if acc.is_empty() {
+                    format!("{} = ${}", field, i + 1)
+                } else {
+                    format!("{} AND {} = ${}", acc, field, i + 1)
+                }
+            })
+    }
+
+    /**
+     * Determines if the connection is no longer usable.
+     */
+    pub fn has_broken(&self) -> crate::Result<bool> {
+        let status = self.lock()?.status();
+
+        Ok(status == libpq::connection::Status::Bad)
+    }
+
+    /**
+     * Resets the communication channel to the server: closes the connection
+     * and reconnects with the same parameters. The password provider of the
+     * [`Config`], if any, is called again.
+     *
+     * [`Config`]: crate::Config
+     */
+    pub fn reset(&self) -> crate::Result {
+        let mut connection = self.lock()?;
+
+        self.reconnect(&mut connection)?;
+        self.forget_prepared();
+        self.types.forget_missing();
+
+        if connection.status() == libpq::connection::Status::Bad {
+            return Err(crate::Error::Connect {
+                dsn: connection.db(),
+                message: connection.error_message().unwrap_or_default(),
+            });
+        }
+        drop(connection);
+
+        self.apply_timezone_policy()
+    }
+
+    /**
+     * Send a NOTIFY event to the database server. An optional data can be sent
+     * with the notification.
+     */
+    pub fn notify(&self, channel: &str, data: Option<&str>) -> crate::Result {
+        let data = self.escape_literal(data.unwrap_or_default())?;
+
+        let query = format!("notify {}, {}", channel, data);
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Executes an anonymous code block.
+     *
+     * `do` doesn’t accept parameters: the `$1`, `$2`, … placeholders of
+     * `body` are replaced by the escaped literal of the corresponding
+     * binding, casted to its type. The placeholders inside string literals,
+     * quoted identifiers, dollar-quoted strings and comments are kept as is,
+     * for dynamic queries run with `execute … using`.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * connection.do_block(
+     *     "begin
+     *         if not exists (select 1 from pg_roles where rolname = $1) then
+     *             execute format('create role %I', $1);
+     *         end if;
+     *     end",
+     *     &[&"reader"],
+     * )?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     */
+    pub fn do_block(&self, body: &str, bindings: &[&dyn crate::ToSql]) -> crate::Result {
+        let mut literals = Vec::new();
+
+        for binding in bindings {
+            let literal = match binding.to_text()? {
+                Some(value) => format!("{}::{}", self.escape_literal(&value)?, binding.ty().name),
+                None => "null".to_string(),
+            };
+
+            literals.push(literal);
+        }
+
+        let body = Self::bind_literals(body, &literals)?;
+
+        // The dollar quote tag can’t appear in the body
+        let mut tag = "$elephantry$".to_string();
+        while body.contains(&tag) {
+            tag.insert(tag.len() - 1, '_');
+        }
+
+        self.execute(&format!("do {tag}\n{}\n{tag}", body, tag = tag))
+            .map(|_| ())
+    }
+
+    /*
+     * Replaces the `$n` placeholders of `body` by `literals`, skipping the
+     * quoted parts and the comments.
+     */
+    fn bind_literals(body: &str, literals: &[String]) -> crate::Result<String> {
+        let mut bound = String::with_capacity(body.len());
+        let mut rest = body;
+
+        while let Some(c) = rest.chars().next() {
+            let is_word = bound
+                .chars()
+                .last()
+                .map(|x| x.is_alphanumeric() || x == '_' || x == '$')
+                .unwrap_or_default();
+
+            let len = match c {
+                '\'' => {
+                    let escaped = is_word && bound.ends_with(['e', 'E']);
+                    Self::quoted_len(rest, '\'', escaped)
+                }
+                '"' => Self::quoted_len(rest, '"', false),
+                '-' if rest.starts_with("--") => rest.find('\n').unwrap_or(rest.len()),
+                '/' if rest.starts_with("/*") => rest.find("*/").map_or(rest.len(), |x| x + 2),
+                '$' if !is_word => {
+                    let digits = rest[1..]
+                        .find(|x: char| !x.is_ascii_digit())
+                        .unwrap_or(rest.len() - 1);
+
+                    if digits > 0 {
+                        let literal = rest[1..=digits]
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|x| x.checked_sub(1))
+                            .and_then(|x| literals.get(x))
+                            .ok_or_else(|| {
+                                crate::Error::Parse(format!(
+                                    "Missing binding for {}",
+                                    &rest[..=digits]
+                                ))
+                            })?;
+
+                        bound.push_str(literal);
+                        rest = &rest[digits + 1..];
+
+                        continue;
+                    }
+
+                    Self::dollar_quoted_len(rest).unwrap_or(1)
+                }
+                _ => c.len_utf8(),
+            };
+
+            bound.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+
+        Ok(bound)
+    }
+
+    /*
+     * Length of the string starting by `quote` at the start of `s`, until
+     * the closing quote. A doubled quote is escaped, like a backslash one
+     * for the `E''` strings.
+     */
+    fn quoted_len(s: &str, quote: char, escaped: bool) -> usize {
+        let mut chars = s.char_indices().skip(1).peekable();
+
+        while let Some((n, c)) = chars.next() {
+            if escaped && c == '\\' {
+                chars.next();
+            } else if c == quote {
+                match chars.peek() {
+                    Some((_, next)) if *next == quote => {
+                        chars.next();
+                    }
+                    _ => return n + 1,
+                }
+            }
+        }
+
+        s.len()
+    }
+
+    /*
+     * Length of the `$tag$…$tag$` string at the start of `s`, `None` if `s`
+     * doesn’t start with a dollar quote tag.
+     */
+    fn dollar_quoted_len(s: &str) -> Option<usize> {
+        let end = s[1..].find('$')? + 2;
+        let tag = &s[..end];
+
+        let valid = tag[1..end - 1]
+            .chars()
+            .enumerate()
+            .all(|(n, c)| c == '_' || c.is_alphabetic() || (n > 0 && c.is_ascii_digit()));
+
+        if !valid {
+            return None;
+        }
+
+        let len = s[end..].find(tag).map_or(s.len(), |x| end + x + tag.len());
+
+        Some(len)
+    }
+
+    /**
+     * Start to listen on the given channel.
+     *
+     * Note: when listen is issued in a transaction it is unlisten when the
+     * transaction is committed or rollback.
+     */
+    pub fn listen(&self, channel: &str) -> crate::Result {
+        let query = format!("listen {}", channel);
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Stop to listen on the given channel.
+     */
+    pub fn unlisten(&self, channel: &str) -> crate::Result {
+        let query = format!("unlisten {}", channel);
+
+        self.execute(&query).map(|_| ())
+    }
+
+    /**
+     * Check if a notification is pending. If so, the payload is returned.
+     * Otherwise, `None` is returned.
+     */
+    pub fn notifies(&self) -> crate::Result<Option<crate::pq::Notify>> {
+        let connection = self.lock()?;
+
+        connection.consume_input().ok();
+        Ok(connection.notifies())
+    }
+
+    /**
+     * Generates the `create` statements of the enums, sequences, tables and
+     * indexes of `schemas`.
+     *
+     * See [`inspect::dump`](crate::inspect::dump).
+     */
+    pub fn dump_schema(&self, schemas: &[&str]) -> crate::Result<String> {
+        crate::inspect::dump(self, schemas)
+    }
+
+    /**
+     * Takes a session level advisory lock, waits until it’s available.
+     */
+    pub fn advisory_lock(&self, key: i64) -> crate::Result<crate::AdvisoryLock<'_>> {
+        self.query_one::<()>("select pg_advisory_lock($1)", &[&key])?;
+
+        Ok(crate::AdvisoryLock::new(self, key))
+    }
+
+    /**
+     * Takes a session level advisory lock if it’s available.
+     */
+    pub fn try_advisory_lock(&self, key: i64) -> crate::Result<Option<crate::AdvisoryLock<'_>>> {
+        let locked = self.query_one::<bool>("select pg_try_advisory_lock($1)", &[&key])?;
+
+        Ok(if locked {
+            Some(crate::AdvisoryLock::new(self, key))
+        } else {
+            None
+        })
+    }
+
+    /**
+     * Waits for a notification, up to `timeout` or forever if `None`.
+     * Returns `None` if no notification was received before the timeout.
+     *
+     * The connection isn’t locked while waiting.
+     */
+    #[cfg(unix)]
+    pub fn wait_for_notification(
+        &self,
+        timeout: Option<std::time::Duration>,
+    ) -> crate::Result<Option<crate::pq::Notify>> {
+        let deadline = timeout.map(|x| std::time::Instant::now() + x);
+
+        loop {
+            if let Some(notify) = self.notifies()? {
+                return Ok(Some(notify));
+            }
+
+            if self.has_broken()? {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionAborted,
+                    "Connection lost while waiting for notifications",
+                )
+                .into());
+            }
+
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let now = std::time::Instant::now();
+
+                    if now >= deadline {
+                        return Ok(None);
+                    }
+
+                    // Rounds up to avoid spinning on sub-millisecond delays
+                    let ms = (deadline - now).as_micros().div_ceil(1_000);
+                    ms.min(i32::MAX as u128) as i32
+                }
+                None => -1,
+            };
+
+            let socket = {
+                let connection = self.lock()?;
+
+                connection.socket().map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotConnected,
+                        connection
+                            .error_message()
+                            .unwrap_or_else(|| "Invalid socket".to_string()),
+                    )
+                })?
+            };
+
+            let mut fd = libc::pollfd {
+                fd: socket,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            if unsafe { libc::poll(&mut fd, 1, timeout) } < 0 {
+                let err = std::io::Error::last_os_error();
+
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(err.into());
+                }
+            }
+        }
+    }
+
+    fn escape_literal(&self, str: &str) -> crate::Result<String> {
+        self.lock()?
+            .escape_literal(str)
+            .map_err(|e| crate::Error::Escape(str.to_string(), e))
+    }
+
+    /**
+     * Reports the status of the server.
+     */
+    pub fn ping(&self) -> crate::Result {
+        let connection = self.lock()?;
+
+        let mut params = HashMap::new();
+        params.insert("dbname".to_string(), connection.db());
+        params.insert("host".to_string(), connection.host());
+        params.insert("port".to_string(), connection.port());
+        params.insert("user".to_string(), connection.user());
+        if let Some(password) = connection.pass() {
+            params.insert("password".to_string(), password);
+        }
+
+        match libpq::Connection::ping_params(&params, false) {
+            PingStatus::Ok => Ok(()),
+            status => Err(crate::Error::Ping(status)),
+        }
+    }
+
+    /**
+     * Retreives the authentication method used by this connection.
+     */
+    pub fn auth_method(&self) -> crate::Result<AuthMethod> {
+        let (used_password, ssl_in_use) = {
+            let connection = self.lock()?;
+
+            (connection.used_password(), connection.ssl_in_use())
+        };
+
+        if used_password {
+            let channel_binding = ssl_in_use
+                && self.config()?.channel_binding == Some(crate::ChannelBinding::Require);
+
+            return Ok(AuthMethod::Password { channel_binding });
+        }
+
+        let results = self.execute(
+            "select gss_authenticated, encrypted from pg_stat_gssapi where pid = pg_backend_pid()",
+        )?;
+
+        let method = match results.try_get(0) {
+            Some(tuple) if tuple.try_get("gss_authenticated")? => AuthMethod::Gss {
+                encrypted: tuple.try_get("encrypted")?,
+            },
+            _ => AuthMethod::Other,
+        };
+
+        Ok(method)
+    }
+
+    /**
+     * Retreives the labels of the `name` enum type, in sort order.
+     */
+    pub fn enum_labels(&self, name: &str) -> crate::Result<Vec<String>> {
+        let labels = self
+            .query::<String>(
+                "select enumlabel from pg_catalog.pg_enum where enumtypid = $1::regtype order by enumsortorder",
+                &[&name],
+            )?
+            .into_vec();
+
+        Ok(labels)
+    }
+
+    /**
+     * Retreives connection configuration.
+     */
+    pub fn config(&self) -> crate::Result<crate::Config> {
+        let connection = self.lock()?;
+        let info = libpq::v2::connection::info(&connection);
+
+        let config = crate::Config {
+            application_name: info
+                .get("application_name")
+                .map(|x| x.val.clone())
+                .flatten(),
+            channel_binding: self.config_get(&info, "channel_binding")?,
+            client_encoding: info.get("client_encoding").map(|x| x.val.clone()).flatten(),
+            connect_timeout: self.config_get(&info, "connect_timeout")?,
+            dbname: info.get("dbname").map(|x| x.val.clone()).flatten(),
+            fallback_application_name: info
+                .get("fallback_application_name")
+                .map(|x| x.val.clone())
+                .flatten(),
+            gssencmode: self.config_get(&info, "gssencmode")?,
+            gsslib: info.get("gsslib").map(|x| x.val.clone()).flatten(),
+            hostaddr: info.get("hostaddr").map(|x| x.val.clone()).flatten(),
+            host: info.get("host").map(|x| x.val.clone()).flatten(),
+            keepalives_count: self.config_get(&info, "keepalives_count")?,
+            keepalives_idle: self.config_get(&info, "keepalives_idle")?,
+            keepalives_interval: self.config_get(&info, "keepalives_interval")?,
+            keepalives: self.config_get::<i32>(&info, "keepalives")?.map(|x| x == 1),
+            krbsrvname: info.get("krbsrvname").map(|x| x.val.clone()).flatten(),
+            options: info.get("options").map(|x| x.val.clone()).flatten(),
+            passfile: info.get("passfile").map(|x| x.val.clone()).flatten(),
+            password: info.get("password").map(|x| x.val.clone()).flatten(),
+            password_provider: None,
+            port: info.get("port").map(|x| x.val.clone()).flatten(),
+            replication: info.get("replication").map(|x| x.val.clone()).flatten(),
+            requirepeer: info.get("requirepeer").map(|x| x.val.clone()).flatten(),
+            service: info.get("service").map(|x| x.val.clone()).flatten(),
+            sslcert: info.get("sslcert").map(|x| x.val.clone()).flatten(),
+            sslcompression: self
+                .config_get::<i32>(&info, "sslcompression")?
+                .map(|x| x == 1),
+            sslcrl: info.get("sslcrl").map(|x| x.val.clone()).flatten(),
+            sslkey: info.get("sslkey").map(|x| x.val.clone()).flatten(),
+            ssl_max_protocol_version: info
+                .get("ssl_max_protocol_version")
+                .map(|x| x.val.clone())
+                .flatten(),
+            ssl_min_protocol_version: info
+                .get("ssl_min_protocol_version")
+                .map(|x| x.val.clone())
+                .flatten(),
+            sslmode: self.config_get(&info, "sslmode")?,
+            sslpassword: info.get("sslpassword").map(|x| x.val.clone()).flatten(),
+            sslrootcert: info.get("sslrootcert").map(|x| x.val.clone()).flatten(),
+            target_session_attrs: self.config_get(&info, "target_session_attrs")?,
+            tcp_user_timeout: self.config_get(&info, "tcp_user_timeout")?,
+            timezone_policy: self.timezone_policy.read().ok().map(|x| *x),
+            tls: None,
+            user: info.get("user").map(|x| x.val.clone()).flatten(),
+        };
+
+        Ok(config)
+    }
+
+    fn config_get<T>(
+        &self,
+        info: &HashMap<String, libpq::connection::Info>,
+        name: &str,
+    ) -> Result<Option<T>, <T as std::str::FromStr>::Err>
+    where
+        T: std::str::FromStr,
+    {
+        let r = match info.get(name).map(|x| x.val.clone()) {
+            Some(Some(val)) => Some(val.parse()?),
+            _ => None,
+        };
+
+        Ok(r)
+    }
+
+    /**
+     * Bulk insert entities via COPY mode.
+     */
+    pub fn copy<'m, M, I>(&self, entities: I) -> crate::Result
+    where
+        I: Iterator<Item = M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.copy_with_progress::<M, _, _>(entities, |_| std::ops::ControlFlow::Continue(()))
+    }
+
+    /**
+     * Likes [`copy`] but calls `progress` after each sent row. Returns
+     * `ControlFlow::Break` from the callback to abort the COPY: no rows are
+     * inserted and an [`Error::Copy`] is returned.
+     *
+     * [`copy`]: crate::Connection::copy
+     * [`Error::Copy`]: crate::Error::Copy
+     */
+    pub fn copy_with_progress<'m, M, I, F>(&self, entities: I, progress: F) -> crate::Result
+    where
+        I: Iterator<Item = M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+        F: FnMut(crate::CopyProgress) -> std::ops::ControlFlow<()>,
+    {
+        self.copy_with_options::<M, _, _>(entities, &crate::CopyOptions::default(), progress)
+    }
+
+    /**
+     * Bulk insert entities via COPY mode in csv format.
+     */
+    pub fn copy_csv<'m, M, I>(&self, entities: I) -> crate::Result
+    where
+        I: Iterator<Item = M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.copy_with_options::<M, _, _>(entities, &crate::CopyOptions::csv(), |_| {
+            std::ops::ControlFlow::Continue(())
+        })
+    }
+
+    /**
+     * Bulk insert entities via COPY mode in binary format.
+     *
+     * See [`CopyFormat::Binary`](crate::CopyFormat::Binary).
+     */
+    pub fn copy_binary<'m, M, I>(&self, entities: I) -> crate::Result
+    where
+        I: Iterator<Item = M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.copy_with_options::<M, _, _>(entities, &crate::CopyOptions::binary(), |_| {
+            std::ops::ControlFlow::Continue(())
+        })
+    }
+
+    /**
+     * Likes [`copy_with_progress`] with COPY options.
+     *
+     * [`copy_with_progress`]: crate::Connection::copy_with_progress
+     */
+    pub fn copy_with_options<'m, M, I, F>(
+        &self,
+        entities: I,
+        options: &crate::CopyOptions,
+        progress: F,
+    ) -> crate::Result
+    where
+        I: Iterator<Item = M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+        F: FnMut(crate::CopyProgress) -> std::ops::ControlFlow<()>,
+    {
+        self.copy_entities::<M, _, _>(entities.map(Ok), options, progress)
+    }
+
+    /**
+     * Bulk insert via COPY mode any values convertible in entities.
+     *
+     * ```no_run
+     * # #[derive(elephantry::Entity)]
+     * # #[elephantry(model = "Model", structure = "Structure", relation = "employee")]
+     * # struct Employee { name: String }
+     * # let connection = elephantry::Connection::new("")?;
+     * let names = vec!["Alice", "Bob"];
+     *
+     * connection.copy_from_iter::<Model, _, _>(names.into_iter().map(|name| Employee {
+     *     name: name.to_string(),
+     * }))?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     */
+    pub fn copy_from_iter<'m, M, I, T>(&self, iter: I) -> crate::Result
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.copy::<M, _>(iter.into_iter().map(Into::into))
+    }
+
+    /**
+     * Likes [`copy_from_iter`] for fallible sources: the first error aborts
+     * the COPY, no rows are inserted and the error is returned.
+     *
+     * [`copy_from_iter`]: crate::Connection::copy_from_iter
+     */
+    pub fn try_copy_from_iter<'m, M, I, T>(&self, iter: I) -> crate::Result
+    where
+        I: IntoIterator<Item = crate::Result<T>>,
+        T: Into<M::Entity>,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.copy_entities::<M, _, _>(
+            iter.into_iter().map(|x| x.map(Into::into)),
+            &crate::CopyOptions::default(),
+            |_| std::ops::ControlFlow::Continue(()),
+        )
+    }
+
+    /**
+     * Reads [JSON Lines](https://jsonlines.org/) from `reader` and bulk
+     * inserts them via COPY mode, see [`read_jsonl`](crate::read_jsonl).
+     */
+    #[cfg(feature = "import")]
+    pub fn copy_from_jsonl<'m, M, T, R>(&self, reader: R) -> crate::Result
+    where
+        T: serde::de::DeserializeOwned + Into<M::Entity>,
+        R: std::io::Read,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.try_copy_from_iter::<M, _, _>(crate::read_jsonl::<T, _>(reader))
+    }
+
+    /**
+     * Reads csv, with a header line, from `reader` and bulk inserts them via
+     * COPY mode, see [`read_csv`](crate::read_csv).
+     */
+    #[cfg(feature = "import")]
+    pub fn copy_from_csv<'m, M, T, R>(&self, reader: R) -> crate::Result
+    where
+        T: serde::de::DeserializeOwned + Into<M::Entity>,
+        R: std::io::Read,
+        M: crate::Model<'m>,
+        M::Structure: crate::Writable,
+    {
+        self.try_copy_from_iter::<M, _, _>(crate::read_csv::<T, _>(reader))
+    }
+
+    fn copy_entities<'m, M, I, F>(
+        &self,
+        entities: I,
+        options: &crate::CopyOptions,
+        mut progress: F,
+    ) -> crate::Result
+    where
+        I: Iterator<Item = crate::Result<M::Entity>>,
+        M: crate::Model<'m>,
+        F: FnMut(crate::CopyProgress) -> std::ops::ControlFlow<()>,
+    {
+        use crate::Entity;
+
+        let field_names = if options.columns.is_empty() {
+            M::default_projection().field_names()
+        } else {
+            options.columns.clone()
+        };
+
+        let query = format!(
+            "copy {} ({}) from stdin {};",
+            self.relation::<M::Structure>(),
+            field_names.join(", "),
+            options.to_sql(),
+        );
+        self.execute(&query)?;
+
+        let connection = self.lock()?;
+
+        let mut status = crate::CopyProgress::default();
+        let mut canceled = false;
+        let mut error = None;
+
+        let encoder = crate::copy::Encoder::new(options);
+
+        let mut header = Vec::new();
+        encoder.header(&field_names, &mut header);
+
+        if !header.is_empty() {
+            libpq::v2::connection::put_copy_data(&connection, &header)
+                .map_err(crate::Error::Copy)?;
+        }
+
+        'rows: for entity in entities {
+            let entity = match entity {
+                Ok(entity) => entity,
+                Err(err) => {
+                    error = Some(err);
+                    break;
+                }
+            };
+
+            let mut data = Vec::new();
+
+            encoder.begin_row(field_names.len(), &mut data);
+            for field in &field_names {
+                let value = match entity.get(field).map(|x| encoder.value(x)) {
+                    Some(Ok(value)) => value,
+                    Some(Err(err)) => {
+                        error = Some(err);
+                        break 'rows;
+                    }
+                    None => None,
+                };
+
+                encoder.field(value.as_deref(), &mut data);
+            }
+            encoder.end_row(&mut data);
+
+            libpq::v2::connection::put_copy_data(&connection, &data).map_err(crate::Error::Copy)?;
+
+            status.rows += 1;
+            status.bytes += data.len();
+
+            if progress(status).is_break() {
+                canceled = true;
+                break;
+            }
+        }
+
+        let errormsg = if let Some(err) = &error {
+            Some(err.to_string())
+        } else if canceled {
+            Some("canceled by client".to_string())
+        } else {
+            let mut trailer = Vec::new();
+            encoder.trailer(&mut trailer);
+
+            if !trailer.is_empty() {
+                libpq::v2::connection::put_copy_data(&connection, &trailer)
+                    .map_err(crate::Error::Copy)?;
+            }
+
+            None
+        };
+
+        connection
+            .put_copy_end(errormsg.as_deref())
+            .map_err(crate::Error::Copy)?;
+
+        if let Some(err) = error {
+            while connection.result().is_some() {}
+
+            return Err(err);
+        }
+
+        if let Some(result) = connection.result() {
+            if result.status() == libpq::Status::FatalError {
+                return Err(crate::Error::Copy(
                     result.error_message().unwrap_or_default(),
                 ));
             }
@@ -769,3 +3832,587 @@ impl Connection {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn auth_method() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert!(conn.auth_method().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_returning_count() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        conn.execute("create temporary table affected (id int)")?;
+        assert_eq!(
+            conn.execute_returning_count("insert into affected select generate_series(1, 3)")?,
+            3
+        );
+        assert_eq!(
+            conn.execute_returning_count("update affected set id = 0 where id > 1")?,
+            2
+        );
+        assert_eq!(
+            conn.execute_returning_count("delete from affected where id > 1")?,
+            0
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn single_checkout() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.set_timezone_policy(crate::TimezonePolicy::Utc)?;
+        conn.set_transaction_watchdog(Some(crate::TransactionWatchdog {
+            max_duration: Some(std::time::Duration::from_secs(60)),
+            max_idle: None,
+            action: crate::WatchdogAction::Abort,
+        }))?;
+
+        let checkouts = conn.stats.checkouts();
+        conn.execute("select 1")?;
+        assert_eq!(conn.stats.checkouts(), checkouts + 1);
+
+        conn.query::<i32>("select $1::int", &[&1])?;
+        assert_eq!(conn.stats.checkouts(), checkouts + 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn param_type_error() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table param (id int, name text, day date)")?;
+
+        match conn.query::<()>("select * from param where id = $1", &[&"1"]) {
+            Err(crate::Error::ParamType {
+                index, expected, ..
+            }) => {
+                assert_eq!(index, 1);
+                assert_eq!(expected, "integer");
+            }
+            result => panic!("Unexpected result: {:?}", result.map(|_| ())),
+        }
+
+        match conn.query::<()>("select * from param where id = $1 and $2 = name", &[&1, &1]) {
+            Err(crate::Error::ParamType {
+                index,
+                rust_type,
+                expected,
+                ..
+            }) => {
+                assert_eq!(index, 2);
+                assert_eq!(rust_type, "i32");
+                assert_eq!(expected, "text");
+            }
+            result => panic!("Unexpected result: {:?}", result.map(|_| ())),
+        }
+
+        match conn.query::<()>("insert into param (day) values ($1)", &[&1_i64]) {
+            Err(crate::Error::ParamType {
+                index, expected, ..
+            }) => {
+                assert_eq!(index, 1);
+                assert_eq!(expected, "date");
+            }
+            result => panic!("Unexpected result: {:?}", result.map(|_| ())),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn command_tag() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        let results = conn.execute("select generate_series(1, 3)")?;
+        assert_eq!(
+            results.command_tag(),
+            Some(crate::pq::CommandTag::Select(3))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_with_progress() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+
+        let entities = (0..10).map(|id| crate::test::Entity {
+            id,
+            first_name: "first name".to_string(),
+            last_name: "last name".to_string(),
+        });
+
+        let mut rows = 0;
+        let result =
+            conn.copy_with_progress::<crate::test::Model<'_>, _, _>(entities, |progress| {
+                rows = progress.rows;
+
+                if progress.rows == 5 {
+                    std::ops::ControlFlow::Break(())
+                } else {
+                    std::ops::ControlFlow::Continue(())
+                }
+            });
+
+        assert!(result.is_err());
+        assert_eq!(rows, 5);
+        assert_eq!(conn.count_where::<crate::test::Model<'_>>("true", &[])?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_escaping() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+
+        let names = ["tab\there", "new\nline\r\n", "back\\slash", "\\N", "\\."];
+        let entities = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| crate::test::Entity {
+                id: id as i32,
+                first_name: name.to_string(),
+                last_name: String::new(),
+            });
+
+        conn.copy::<crate::test::Model<'_>, _>(entities)?;
+
+        let entities = conn.find_all::<crate::test::Model<'_>>(Some("order by employee_id"))?;
+        for (entity, name) in entities.zip(names.iter()) {
+            assert_eq!(entity.first_name, *name);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_binary() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table entity (employee_id int, first_name text, last_name varchar)",
+        )?;
+
+        let names = ["tab\there", "new\nline\r\n", "back\\slash", "\\N", "é"];
+        let entities = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| crate::test::Entity {
+                id: id as i32,
+                first_name: name.to_string(),
+                last_name: name.to_string(),
+            });
+
+        conn.copy_binary::<crate::test::Model<'_>, _>(entities)?;
+
+        let entities = conn.find_all::<crate::test::Model<'_>>(Some("order by employee_id"))?;
+        assert_eq!(entities.len(), names.len());
+        for (id, (entity, name)) in entities.zip(names.iter()).enumerate() {
+            assert_eq!(entity.id, id as i32);
+            assert_eq!(entity.first_name, *name);
+            assert_eq!(entity.last_name, *name);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_columns() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table entity (employee_id int generated always as identity, first_name text, last_name text)",
+        )?;
+
+        let entities = (0..3).map(|_| crate::test::Entity {
+            id: 0,
+            first_name: "first name".to_string(),
+            last_name: "last name".to_string(),
+        });
+
+        let options = crate::CopyOptions {
+            columns: vec!["first_name".to_string(), "last_name".to_string()],
+            ..crate::CopyOptions::new()
+        };
+
+        conn.copy_with_options::<crate::test::Model<'_>, _, _>(entities, &options, |_| {
+            std::ops::ControlFlow::Continue(())
+        })?;
+
+        assert!(conn.exist_where::<crate::test::Model<'_>>("employee_id = $1", &[&3])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn poison_recovery() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let other = conn.clone();
+
+        let thread = std::thread::spawn(move || {
+            let _ = other.execute("begin");
+            let _ = other.execute("select 1 / 0");
+            let _connection = other.lock().unwrap();
+            panic!("Poisoning the connection");
+        });
+        assert!(thread.join().is_err());
+        assert!(conn.connection.is_poisoned());
+
+        assert_eq!(conn.query_one::<i32>("select 1", &[])?, 1);
+        assert!(!conn.connection.is_poisoned());
+        assert_eq!(conn.transaction_status()?, libpq::transaction::Status::Idle);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_async_detach() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table heartbeat (beat int)")?;
+
+        conn.execute_async_detach("insert into heartbeat values (1)")?;
+        conn.execute_async_detach("select 1 / 0")?;
+        conn.execute_async_detach("insert into heartbeat values (2)")?;
+
+        assert_eq!(
+            conn.query_one::<i64>("select count(*) from heartbeat", &[])?,
+            2
+        );
+        assert_eq!(conn.stats.status(false).errors, 1);
+
+        Ok(())
+    }
+
+    #[derive(elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "Model",
+        structure = "Structure",
+        relation = "pg_namespace"
+    )]
+    struct Namespace {
+        nspname: String,
+    }
+
+    fn terminate(conn: &crate::Connection) -> crate::Result {
+        let other = crate::test::new_conn()?;
+        let pid = conn.query_one::<i32>("select pg_backend_pid()", &[])?;
+        other.query_one::<bool>("select pg_terminate_backend($1)", &[&pid])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn retry() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.set_retry_policy(Some(crate::RetryPolicy {
+            delay: std::time::Duration::from_millis(10),
+            ..Default::default()
+        }))?;
+
+        terminate(&conn)?;
+        assert!(!conn.find_all::<Model<'_>>(None)?.is_empty());
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        terminate(&conn)?;
+        assert!(conn.find_all::<Model<'_>>(None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_multi() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        let summaries = conn.execute_multi(
+            "create temporary table multi (n int);
+            insert into multi values (1), (2);
+            select * from multi;
+            select 1 / 0;
+            select 2;",
+        )?;
+
+        assert_eq!(summaries.len(), 4);
+        assert_eq!(
+            summaries[1].command_tag,
+            Some(crate::pq::CommandTag::Insert { oid: 0, count: 2 })
+        );
+        assert_eq!(
+            summaries[2].command_tag,
+            Some(crate::pq::CommandTag::Select(2))
+        );
+        assert_eq!(summaries[2].result.as_ref().unwrap().len(), 2);
+        assert!(summaries[3].result.is_err());
+
+        conn.set_timezone_policy(crate::TimezonePolicy::Utc)?;
+        conn.execute("set time zone 'Europe/Paris'")?;
+        assert!(matches!(
+            conn.execute_multi("select 1; select 2"),
+            Err(crate::Error::Timezone(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn do_block() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table block (id int, name text)")?;
+
+        conn.do_block(
+            "begin
+                insert into block values ($1, $2);
+                insert into block values ($1 + 1, $3);
+            end",
+            &[&41, &"it's $1 $elephantry$", &None::<String>],
+        )?;
+
+        let rows =
+            conn.query::<(i32, Option<String>)>("select (id, name) from block order by id", &[])?;
+        assert_eq!(
+            rows.collect::<Vec<_>>(),
+            vec![(41, Some("it's $1 $elephantry$".to_string())), (42, None)]
+        );
+
+        assert!(matches!(
+            conn.do_block("begin perform $2; end", &[&1]),
+            Err(crate::Error::Parse(_))
+        ));
+
+        conn.do_block(
+            "begin
+                -- $2 isn’t bound
+                insert into block values ($1, '$2' || E'\\'$2');
+                execute $q$insert into block values ($1 + 1, 'dynamic')$q$ using $1 + 10;
+            end",
+            &[&50],
+        )?;
+
+        let rows = conn.query::<(i32, Option<String>)>(
+            "select (id, name) from block where id >= 50 order by id",
+            &[],
+        )?;
+        assert_eq!(
+            rows.collect::<Vec<_>>(),
+            vec![
+                (50, Some("$2'$2".to_string())),
+                (61, Some("dynamic".to_string())),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_or_insert() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, true)?;
+
+        let entity = || crate::test::Entity {
+            id: 1,
+            first_name: "first name".to_string(),
+            last_name: "last name".to_string(),
+        };
+
+        let inserted =
+            conn.get_or_insert::<crate::test::Model<'_>, _>("employee_id = $1", &[&1], entity)?;
+        assert_eq!(inserted.first_name, "first name");
+
+        conn.execute("update entity set first_name = 'updated'")?;
+        let existing = conn.get_or_insert::<crate::test::Model<'_>, _>(
+            "employee_id = $1",
+            &[&1],
+            || unreachable!(),
+        )?;
+        assert_eq!(existing.first_name, "updated");
+
+        // A row inserted behind the select is selected again.
+        let raced =
+            conn.get_or_insert::<crate::test::Model<'_>, _>("employee_id = $1", &[&2], || {
+                conn.execute("insert into entity values (2, 'concurrent', 'row')")
+                    .unwrap();
+                crate::test::Entity { id: 2, ..entity() }
+            })?;
+        assert_eq!(raced.first_name, "concurrent");
+        assert_eq!(conn.count_where::<crate::test::Model<'_>>("true", &[])?, 2);
+
+        Ok(())
+    }
+
+    #[derive(Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.membership", pk(group_id, user_id))]
+    struct Membership {
+        group_id: i32,
+        user_id: i32,
+        role: String,
+    }
+
+    #[test]
+    fn find_by_pks() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table membership (group_id int, user_id int, role text, primary key (group_id, user_id));
+            insert into membership values (1, 1, 'owner'), (1, 2, 'member'), (2, 1, 'member');",
+        )?;
+
+        let entities = conn.find_by_pks::<MembershipModel<'_>, (i32, i32)>(&[
+            crate::pk!(group_id => 1, user_id => 2),
+            crate::pk!(group_id => 2, user_id => 1),
+            crate::pk!(group_id => 2, user_id => 2),
+        ])?;
+        assert_eq!(entities.len(), 2);
+        assert_eq!(entities[&(1, 2)].role, "member");
+        assert_eq!(entities[&(2, 1)].group_id, 2);
+        assert!(!entities.contains_key(&(2, 2)));
+
+        assert!(conn
+            .find_by_pks::<MembershipModel<'_>, (i32, i32)>(&[])?
+            .is_empty());
+        assert!(matches!(
+            conn.find_by_pks::<MembershipModel<'_>, (i32, i32)>(&[crate::pk!(group_id => 1)]),
+            Err(crate::Error::PrimaryKey)
+        ));
+
+        crate::test::create_entity_table(&conn, true)?;
+        conn.execute("insert into entity values (1, 'first name', 'last name')")?;
+        let entities = conn.find_by_pks::<crate::test::Model<'_>, i32>(&[
+            crate::pk!(employee_id => 1),
+            crate::pk!(employee_id => 3),
+        ])?;
+        assert_eq!(entities.keys().collect::<Vec<_>>(), [&1]);
+
+        Ok(())
+    }
+
+    #[derive(Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.draft", pk = "id")]
+    struct Draft {
+        id: i32,
+        user: String,
+        body: String,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.post", pk = "id")]
+    struct Post {
+        id: i32,
+        user: String,
+    }
+
+    #[test]
+    fn insert_from() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            r#"create temporary table draft (id int primary key, "user" text, body text);
+            create temporary table post (id int primary key, "user" text);
+            insert into draft values (1, 'first', 'a'), (2, 'second', 'b'), (3, 'third', 'c');"#,
+        )?;
+
+        let posts = conn
+            .insert_from::<PostModel<'_>, DraftModel<'_>>("id < $1", &[&3], &[])?
+            .into_vec();
+        assert_eq!(
+            posts.iter().map(|x| x.user.as_str()).collect::<Vec<_>>(),
+            ["first", "second"]
+        );
+
+        let posts = conn
+            .insert_from::<PostModel<'_>, DraftModel<'_>>(
+                "id = $1",
+                &[&3],
+                &[("id", "id"), ("user", "body")],
+            )?
+            .into_vec();
+        assert_eq!(posts[0].user, "c");
+        assert_eq!(conn.count_where::<PostModel<'_>>("true", &[])?, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn password_provider() -> crate::Result {
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let mut config = crate::test::new_conn()?.config()?;
+        let counter = calls.clone();
+        config.password_provider = Some(crate::PasswordProvider::new(move || {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(String::new())
+        }));
+
+        let conn = crate::Connection::from_config(&config)?;
+        let pid = conn.query_one::<i32>("select pg_backend_pid()", &[])?;
+        conn.reset()?;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_ne!(conn.query_one::<i32>("select pg_backend_pid()", &[])?, pid);
+
+        Ok(())
+    }
+
+    #[test]
+    fn where_count() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+        conn.execute(
+            "insert into entity select id, 'first name', 'last name' from generate_series(1, 10) id",
+        )?;
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("last_name".to_string(), &"updated" as &dyn crate::ToSql);
+        assert_eq!(
+            conn.update_where_count::<crate::test::Model<'_>>("employee_id > $1", &[&7], &data)?,
+            3
+        );
+        assert_eq!(
+            conn.count_where::<crate::test::Model<'_>>("last_name = $1", &[&"updated"])?,
+            3
+        );
+
+        assert_eq!(
+            conn.delete_where_count::<crate::test::Model<'_>>("employee_id <= $1", &[&5])?,
+            5
+        );
+        assert_eq!(conn.count_where::<crate::test::Model<'_>>("true", &[])?, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wait_until_ready() -> crate::Result {
+        let interval = std::time::Duration::from_millis(10);
+
+        crate::Connection::wait_until_ready(
+            &crate::test::dsn(),
+            std::time::Duration::from_secs(1),
+            interval,
+        )?;
+
+        let timeout = std::time::Duration::from_millis(50);
+        assert!(matches!(
+            crate::Connection::wait_until_ready("host=localhost port=1", timeout, interval),
+            Err(crate::Error::Timeout(x)) if x == timeout
+        ));
+
+        let start = std::time::Instant::now();
+        assert!(matches!(
+            crate::Connection::wait_until_ready(
+                "host=localhost port=1",
+                timeout,
+                std::time::Duration::ZERO
+            ),
+            Err(crate::Error::Timeout(_))
+        ));
+        assert!(start.elapsed() >= timeout);
+
+        Ok(())
+    }
+}
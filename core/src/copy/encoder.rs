@@ -0,0 +1,249 @@
+/**
+ * Encodes rows in the text, csv or binary COPY format.
+ *
+ * <https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9.4>
+ */
+pub(crate) struct Encoder<'a> {
+    options: &'a crate::CopyOptions,
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(options: &'a crate::CopyOptions) -> Self {
+        Self { options }
+    }
+
+    /**
+     * Appends the header line to `data`.
+     */
+    pub fn header(&self, field_names: &[String], data: &mut Vec<u8>) {
+        if self.options.format == crate::CopyFormat::Binary {
+            data.extend_from_slice(b"PGCOPY\n\xff\r\n\0");
+            // flags
+            data.extend_from_slice(&0_i32.to_be_bytes());
+            // header extension length
+            data.extend_from_slice(&0_i32.to_be_bytes());
+            return;
+        }
+
+        if !self.options.header {
+            return;
+        }
+
+        for field in field_names {
+            self.field(Some(field.as_bytes()), data);
+        }
+
+        self.end_row(data);
+    }
+
+    /**
+     * Appends the trailer to `data`.
+     */
+    pub fn trailer(&self, data: &mut Vec<u8>) {
+        if self.options.format == crate::CopyFormat::Binary {
+            data.extend_from_slice(&(-1_i16).to_be_bytes());
+        }
+    }
+
+    /**
+     * Representation of `value` expected by [`field`]: binary if the value
+     * and the COPY format are binary, text otherwise.
+     *
+     * In a binary COPY, a text value is only accepted if its text is also
+     * its binary representation (string-like, enum, json and xml types).
+     *
+     * [`field`]: Self::field
+     */
+    pub fn value(&self, value: &dyn crate::ToSql) -> crate::Result<Option<Vec<u8>>> {
+        if self.options.format != crate::CopyFormat::Binary {
+            return value.to_text().map(|x| x.map(String::into_bytes));
+        }
+
+        if value.format() == crate::pq::Format::Binary {
+            return value.to_sql();
+        }
+
+        let text = value.to_text()?;
+        let ty = value.ty();
+
+        if text.is_some() && !is_binary_text(&ty) {
+            return Err(crate::Error::Copy(format!(
+                "{} values can't be sent in binary format, use a text or csv COPY",
+                ty.name
+            )));
+        }
+
+        Ok(text.map(String::into_bytes))
+    }
+
+    /**
+     * Starts a row of `len` fields.
+     */
+    pub fn begin_row(&self, len: usize, data: &mut Vec<u8>) {
+        if self.options.format == crate::CopyFormat::Binary {
+            data.extend_from_slice(&(len as i16).to_be_bytes());
+        }
+    }
+
+    /**
+     * Appends a field to `data`, `value` is returned by [`value`].
+     *
+     * [`value`]: Self::value
+     */
+    pub fn field(&self, value: Option<&[u8]>, data: &mut Vec<u8>) {
+        if self.options.format == crate::CopyFormat::Binary {
+            match value {
+                Some(value) => {
+                    data.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                    data.extend_from_slice(value);
+                }
+                None => data.extend_from_slice(&(-1_i32).to_be_bytes()),
+            }
+
+            return;
+        }
+
+        match (value, self.options.format) {
+            (None, _) => data.extend_from_slice(self.null_marker().as_bytes()),
+            (Some(value), crate::CopyFormat::Text) => {
+                for c in value {
+                    match c {
+                        b'\\' => data.extend_from_slice(b"\\\\"),
+                        b'\t' => data.extend_from_slice(b"\\t"),
+                        b'\n' => data.extend_from_slice(b"\\n"),
+                        b'\r' => data.extend_from_slice(b"\\r"),
+                        _ => data.push(*c),
+                    }
+                }
+            }
+            (Some(value), _) => {
+                let quote = self.options.quote.unwrap_or('"') as u8;
+                let escape = self.options.escape.unwrap_or(quote as char) as u8;
+
+                data.push(quote);
+                for c in value {
+                    if *c == quote || *c == escape {
+                        data.push(escape);
+                    }
+                    data.push(*c);
+                }
+                data.push(quote);
+            }
+        }
+
+        data.push(self.delimiter());
+    }
+
+    /**
+     * Replaces the trailing delimiter by the end of line.
+     */
+    pub fn end_row(&self, data: &mut Vec<u8>) {
+        if self.options.format == crate::CopyFormat::Binary {
+            return;
+        }
+
+        if data.last() == Some(&self.delimiter()) {
+            data.pop();
+        }
+
+        data.push(b'\n');
+    }
+
+    fn delimiter(&self) -> u8 {
+        match self.options.format {
+            crate::CopyFormat::Text => b'\t',
+            crate::CopyFormat::Csv | crate::CopyFormat::Binary => b',',
+        }
+    }
+
+    fn null_marker(&self) -> &str {
+        match (&self.options.null, self.options.format) {
+            (Some(null), _) => null,
+            (None, crate::CopyFormat::Text) => "\\N",
+            (None, crate::CopyFormat::Csv) | (None, crate::CopyFormat::Binary) => "",
+        }
+    }
+}
+
+/*
+ * Types whose binary representation is the text one.
+ */
+fn is_binary_text(ty: &crate::pq::Type) -> bool {
+    use libpq::types::Kind;
+
+    matches!(ty.kind, Kind::String | Kind::Enum | Kind::Unknow)
+        || ty.oid == crate::pq::types::JSON.oid
+        || ty.oid == crate::pq::types::XML.oid
+        || ty.name == "citext"
+}
+
+#[cfg(test)]
+mod test {
+    fn encode(options: &crate::CopyOptions, values: &[Option<&str>]) -> Vec<u8> {
+        let encoder = super::Encoder::new(options);
+        let mut data = Vec::new();
+
+        encoder.begin_row(values.len(), &mut data);
+        for value in values {
+            encoder.field(value.map(str::as_bytes), &mut data);
+        }
+        encoder.end_row(&mut data);
+
+        data
+    }
+
+    #[test]
+    fn text() {
+        let options = crate::CopyOptions::new();
+
+        let tests: &[(&[Option<&str>], &[u8])] = &[
+            (&[Some("a"), None], b"a\t\\N\n"),
+            (&[Some("tab\there")], b"tab\\there\n"),
+            (&[Some("new\nline\r\n")], b"new\\nline\\r\\n\n"),
+            (&[Some("back\\slash")], b"back\\\\slash\n"),
+            (&[Some("\\N")], b"\\\\N\n"),
+            (&[Some("\\.")], b"\\\\.\n"),
+        ];
+
+        for (values, expected) in tests {
+            assert_eq!(encode(&options, values), *expected);
+        }
+    }
+
+    #[test]
+    fn csv() {
+        let options = crate::CopyOptions::csv();
+
+        let tests: &[(&[Option<&str>], &[u8])] = &[
+            (
+                &[Some("a \"quoted\", value"), None],
+                b"\"a \"\"quoted\"\", value\",\n",
+            ),
+            (&[Some("new\nline")], b"\"new\nline\"\n"),
+            (&[Some("")], b"\"\"\n"),
+        ];
+
+        for (values, expected) in tests {
+            assert_eq!(encode(&options, values), *expected);
+        }
+    }
+
+    #[test]
+    fn binary() {
+        let options = crate::CopyOptions::binary();
+
+        assert_eq!(
+            encode(&options, &[Some("tab\t"), None]),
+            b"\x00\x02\x00\x00\x00\x04tab\t\xff\xff\xff\xff"
+        );
+
+        let encoder = super::Encoder::new(&options);
+        assert_eq!(encoder.value(&1_i16).unwrap(), Some(vec![0, 1]));
+        assert_eq!(encoder.value(&"a").unwrap(), Some(b"a".to_vec()));
+        assert_eq!(encoder.value(&None::<i32>).unwrap(), None);
+        assert!(matches!(
+            encoder.value(&vec!["a".to_string()]),
+            Err(crate::Error::Copy(_))
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+mod encoder;
+
+pub(crate) use encoder::*;
+
+/**
+ * Progress of a COPY, given to the callback of [`Connection::copy_with_progress`].
+ *
+ * [`Connection::copy_with_progress`]: crate::Connection::copy_with_progress
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CopyProgress {
+    /** Number of rows sent. */
+    pub rows: usize,
+    /** Number of bytes sent. */
+    pub bytes: usize,
+}
+
+/**
+ * Data format of a COPY.
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CopyFormat {
+    #[default]
+    Text,
+    Csv,
+    /**
+     * Binary representation of the values: escaping free and faster, but the
+     * value types have to match the column types (an `i32` can’t be copied
+     * in a `bigint` column). Text values are sent as is, which suits text,
+     * varchar, enum and json columns.
+     */
+    Binary,
+}
+
+impl std::fmt::Display for CopyFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Text => "text",
+            Self::Csv => "csv",
+            Self::Binary => "binary",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/**
+ * Options of [`Connection::copy_with_options`].
+ *
+ * See <https://www.postgresql.org/docs/current/sql-copy.html>.
+ *
+ * [`Connection::copy_with_options`]: crate::Connection::copy_with_options
+ */
+#[derive(Clone, Debug, Default)]
+pub struct CopyOptions {
+    /**
+     * Columns to copy, all the columns of the structure if empty. Useful to
+     * skip generated or identity columns.
+     */
+    pub columns: Vec<String>,
+    pub format: CopyFormat,
+    /** Requests copying the data with rows already frozen. */
+    pub freeze: bool,
+    /** String that represents a null value. */
+    pub null: Option<String>,
+    /** Sends a header line with the column names (csv only). */
+    pub header: bool,
+    /** Quoting character (csv only). */
+    pub quote: Option<char>,
+    /** Escaping character of the quoting character (csv only). */
+    pub escape: Option<char>,
+    /** Columns never matched against the null string (csv only). */
+    pub force_not_null: Vec<String>,
+}
+
+impl CopyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Default options for the csv format.
+     */
+    pub fn csv() -> Self {
+        Self {
+            format: CopyFormat::Csv,
+            ..Self::default()
+        }
+    }
+
+    /**
+     * Default options for the binary format.
+     */
+    pub fn binary() -> Self {
+        Self {
+            format: CopyFormat::Binary,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn to_sql(&self) -> String {
+        let mut options = vec![format!("format {}", self.format)];
+
+        if self.freeze {
+            options.push("freeze".to_string());
+        }
+
+        if let Some(null) = &self.null {
+            options.push(format!("null {}", literal(null)));
+        }
+
+        if self.header {
+            options.push("header".to_string());
+        }
+
+        if let Some(quote) = self.quote {
+            options.push(format!("quote {}", literal(&quote.to_string())));
+        }
+
+        if let Some(escape) = self.escape {
+            options.push(format!("escape {}", literal(&escape.to_string())));
+        }
+
+        if !self.force_not_null.is_empty() {
+            let columns = self
+                .force_not_null
+                .iter()
+                .map(|x| crate::identifier::quote(x))
+                .collect::<Vec<_>>();
+
+            options.push(format!("force_not_null ({})", columns.join(", ")));
+        }
+
+        format!("with ({})", options.join(", "))
+    }
+}
+
+fn literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn options() {
+        let options = crate::CopyOptions {
+            freeze: true,
+            null: Some("it's null".to_string()),
+            header: true,
+            quote: Some('\''),
+            escape: Some('\\'),
+            force_not_null: vec!["first_name".to_string()],
+            ..crate::CopyOptions::csv()
+        };
+
+        assert_eq!(
+            options.to_sql(),
+            "with (format csv, freeze, null 'it''s null', header, quote '''', escape '\\', force_not_null (\"first_name\"))"
+        );
+    }
+}
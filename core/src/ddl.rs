@@ -0,0 +1,194 @@
+/**
+ * Index definition for [`Connection::create_index`].
+ *
+ * ```no_run
+ * # #[derive(elephantry::Entity)]
+ * # #[elephantry(model = "Model", structure = "Structure", relation = "event")]
+ * # struct Event { visitor_id: i32 }
+ * # let connection = elephantry::Connection::new("")?;
+ * use elephantry::IndexSpec;
+ *
+ * connection.create_index::<Model>(&IndexSpec::new(&["visitor_id"]).unique().concurrently())?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * [`Connection::create_index`]: crate::Connection::create_index
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexSpec {
+    columns: Vec<String>,
+    name: Option<String>,
+    method: Option<String>,
+    r#where: Option<String>,
+    unique: bool,
+    concurrently: bool,
+    if_not_exists: bool,
+}
+
+impl IndexSpec {
+    pub fn new(columns: &[&str]) -> Self {
+        Self {
+            columns: columns.iter().map(|x| x.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /**
+     * Name of the index, chosen by postgresql if not set.
+     */
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /**
+     * Index method, like `gin` or `brin`. Defaults to `btree`.
+     */
+    pub fn using(mut self, method: &str) -> Self {
+        self.method = Some(method.to_string());
+        self
+    }
+
+    /**
+     * Predicate of a partial index.
+     */
+    pub fn r#where(mut self, predicate: &str) -> Self {
+        self.r#where = Some(predicate.to_string());
+        self
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /**
+     * Builds the index without locking out writes, which isn’t possible in
+     * a transaction.
+     */
+    pub fn concurrently(mut self) -> Self {
+        self.concurrently = true;
+        self
+    }
+
+    /**
+     * Doesn’t fail if an index with the same name already exists, needs a
+     * [`name`].
+     *
+     * [`name`]: Self::name
+     */
+    pub fn if_not_exists(mut self) -> Self {
+        self.if_not_exists = true;
+        self
+    }
+
+    pub(crate) fn is_concurrently(&self) -> bool {
+        self.concurrently
+    }
+
+    pub(crate) fn to_sql(&self, relation: &str) -> String {
+        let mut sql = "create".to_string();
+
+        if self.unique {
+            sql.push_str(" unique");
+        }
+
+        sql.push_str(" index");
+
+        if self.concurrently {
+            sql.push_str(" concurrently");
+        }
+
+        if self.if_not_exists {
+            sql.push_str(" if not exists");
+        }
+
+        if let Some(name) = &self.name {
+            sql.push_str(&format!(" {}", crate::identifier::quote(name)));
+        }
+
+        sql.push_str(&format!(" on {}", relation));
+
+        if let Some(method) = &self.method {
+            sql.push_str(&format!(" using {}", crate::identifier::quote(method)));
+        }
+
+        let columns = self
+            .columns
+            .iter()
+            .map(|x| crate::identifier::quote(x))
+            .collect::<Vec<_>>();
+        sql.push_str(&format!(" ({})", columns.join(", ")));
+
+        if let Some(predicate) = &self.r#where {
+            sql.push_str(&format!(" where {}", predicate));
+        }
+
+        sql.push(';');
+
+        sql
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn to_sql() {
+        assert_eq!(
+            crate::IndexSpec::new(&["visitor_id"]).to_sql("event"),
+            r#"create index on event ("visitor_id");"#
+        );
+
+        assert_eq!(
+            crate::IndexSpec::new(&["visitor_id", "created\"at"])
+                .name("event_visitor")
+                .using("brin")
+                .r#where("visitor_id is not null")
+                .unique()
+                .concurrently()
+                .if_not_exists()
+                .to_sql("public.event"),
+            r#"create unique index concurrently if not exists "event_visitor" on public.event using "brin" ("visitor_id", "created""at") where visitor_id is not null;"#
+        );
+    }
+
+    #[test]
+    fn create_index() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+
+        conn.create_index::<crate::test::Model<'_>>(
+            &crate::IndexSpec::new(&["first_name", "last_name"])
+                .name("entity_name")
+                .unique()
+                .concurrently(),
+        )?;
+        conn.create_index::<crate::test::Model<'_>>(
+            &crate::IndexSpec::new(&["first_name", "last_name"])
+                .name("entity_name")
+                .if_not_exists(),
+        )?;
+
+        assert!(conn.query_one::<bool>(
+            "select indisunique from pg_index where indexrelid = 'entity_name'::regclass",
+            &[]
+        )?);
+
+        conn.execute("begin")?;
+        let result = conn.create_index::<crate::test::Model<'_>>(
+            &crate::IndexSpec::new(&["employee_id"]).concurrently(),
+        );
+        conn.execute("rollback")?;
+        assert!(matches!(result, Err(crate::Error::TransactionStatus(_))));
+
+        conn.add_check_constraint::<crate::test::Model<'_>>(
+            "first_name_not_empty",
+            "first_name <> ''",
+        )?;
+        assert!(conn
+            .execute("insert into entity (employee_id, first_name) values (1, '')")
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+use crate::value::Value;
+use serde::de::IntoDeserializer;
+
+impl serde::de::Error for crate::Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Deserialize(msg.to_string())
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for Value {
+    type Error = crate::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self {
+            Self::Null => visitor.visit_unit(),
+            Self::Bool(v) => visitor.visit_bool(v),
+            Self::I16(v) => visitor.visit_i16(v),
+            Self::I32(v) => visitor.visit_i32(v),
+            Self::I64(v) => visitor.visit_i64(v),
+            Self::U32(v) => visitor.visit_u32(v),
+            Self::F32(v) => visitor.visit_f32(v),
+            Self::F64(v) => visitor.visit_f64(v),
+            Self::String(v) => visitor.visit_string(v),
+            Self::Bytes(v) => visitor.visit_byte_buf(v),
+            Self::Array(v) => {
+                visitor.visit_seq(serde::de::value::SeqDeserializer::new(v.into_iter()))
+            }
+            #[cfg(feature = "json")]
+            Self::Json(v) => serde::Deserializer::deserialize_any(v, visitor)
+                .map_err(<crate::Error as serde::de::Error>::custom),
+        }
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        match self {
+            Self::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        match self {
+            Self::String(v) => visitor.visit_enum(v.into_deserializer()),
+            #[cfg(feature = "json")]
+            Self::Json(v) => serde::Deserializer::deserialize_enum(v, name, variants, visitor)
+                .map_err(<crate::Error as serde::de::Error>::custom),
+            _ => {
+                let _ = (name, variants);
+                Err(serde::de::Error::invalid_type(
+                    serde::de::Unexpected::Other("non text value"),
+                    &visitor,
+                ))
+            }
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, crate::Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'a> crate::Tuple<'a> {
+    /**
+     * Deserializes the tuple with serde, to build any
+     * `#[derive(serde::Deserialize)]` type without implementing
+     * [`Entity`].
+     *
+     * Structs and maps are filled by field name, sequences and tuples by
+     * position.
+     *
+     * ```no_run
+     * #[derive(serde::Deserialize)]
+     * struct Employee {
+     *     id: i32,
+     *     name: String,
+     *     manager_id: Option<i32>,
+     * }
+     *
+     * # let connection = elephantry::Connection::new("")?;
+     * let rows = connection.execute("select id, name, manager_id from employee")?;
+     *
+     * for tuple in &rows {
+     *     let employee: Employee = tuple.deserialize()?;
+     * }
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     *
+     * [`Entity`]: crate::Entity
+     */
+    pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> crate::Result<T> {
+        T::deserialize(self.clone())
+    }
+
+    fn values(&self) -> crate::Result<Vec<Value>> {
+        (0..self.len()).map(|n| self.try_get_by_index(n)).collect()
+    }
+
+    fn fields(&self) -> crate::Result<Vec<(String, Value)>> {
+        (0..self.len())
+            .map(|n| {
+                let name = self.field_name(n).unwrap_or_default();
+
+                self.try_get_by_index(n).map(|value| (name, value))
+            })
+            .collect()
+    }
+}
+
+impl<'de, 'a> serde::Deserializer<'de> for crate::Tuple<'a> {
+    type Error = crate::Error;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: serde::de::Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        visitor.visit_map(serde::de::value::MapDeserializer::new(
+            self.fields()?.into_iter(),
+        ))
+    }
+
+    fn deserialize_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: serde::de::Visitor<'de>>(self, visitor: V) -> crate::Result<V::Value> {
+        visitor.visit_seq(serde::de::value::SeqDeserializer::new(
+            self.values()?.into_iter(),
+        ))
+    }
+
+    fn deserialize_tuple<V: serde::de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: serde::de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> crate::Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct enum identifier ignored_any
+    }
+}
+
+/**
+ * Entity built by deserializing the tuple, to query any
+ * `#[derive(serde::Deserialize)]` type, like a DTO shared with web handlers,
+ * without deriving [`Entity`].
+ *
+ * ```no_run
+ * use elephantry::Deserialized;
+ *
+ * #[derive(serde::Deserialize)]
+ * struct Employee {
+ *     id: i32,
+ *     name: String,
+ * }
+ *
+ * # let connection = elephantry::Connection::new("")?;
+ * let employees = connection
+ *     .query::<Deserialized<Employee>>("select id, name from employee", &[])?
+ *     .try_into_vec()?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * As for [`Tuple::get`], [`Entity::from`] panics if the tuple can’t be
+ * deserialized, use [`Rows::try_into_vec`] to get an error instead.
+ *
+ * [`Entity`]: crate::Entity
+ * [`Entity::from`]: crate::Entity::from
+ * [`Rows::try_into_vec`]: crate::Rows::try_into_vec
+ * [`Tuple::get`]: crate::Tuple::get
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deserialized<T>(T);
+
+impl<T> Deserialized<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Deserialized<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Deserialized<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> crate::Entity for Deserialized<T> {
+    fn from(tuple: &crate::Tuple<'_>) -> Self {
+        Self::try_from_tuple(tuple)
+            .unwrap_or_else(|err| panic!("Unable to deserialize tuple: {}", err))
+    }
+
+    fn try_from_tuple(tuple: &crate::Tuple<'_>) -> crate::Result<Self> {
+        tuple.deserialize().map(Self)
+    }
+
+    fn get(&self, _: &str) -> Option<&dyn crate::ToSql> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Active,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Employee {
+        id: i32,
+        name: String,
+        manager_id: Option<i64>,
+        skills: Vec<String>,
+        status: Status,
+    }
+
+    #[test]
+    fn deserialize() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        let rows = connection.query::<i32>(
+            "select 1 as id, 'Ada' as name, null::int8 as manager_id,
+                array['math', 'poetry'] as skills, 'active' as status",
+            &[],
+        )?;
+        let tuple = (*rows).get(0);
+
+        assert_eq!(
+            tuple.deserialize::<Employee>()?,
+            Employee {
+                id: 1,
+                name: "Ada".to_string(),
+                manager_id: None,
+                skills: vec!["math".to_string(), "poetry".to_string()],
+                status: Status::Active,
+            }
+        );
+        assert_eq!(
+            tuple.deserialize::<(i32, String, Option<i64>, Vec<String>, String)>()?,
+            (
+                1,
+                "Ada".to_string(),
+                None,
+                vec!["math".to_string(), "poetry".to_string()],
+                "active".to_string()
+            )
+        );
+        assert!(matches!(
+            tuple.deserialize::<(String, String, Option<i64>, Vec<String>, String)>(),
+            Err(crate::Error::Deserialize(_))
+        ));
+
+        let rows = connection.execute("select 2::int4 as id, true as active")?;
+        assert_eq!(rows.get(0).deserialize::<(i32, bool)>()?, (2, true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn query() -> crate::Result {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Dto {
+            id: i32,
+            name: String,
+        }
+
+        let connection = crate::test::new_conn()?;
+        let query = "select id, 'name ' || id as name from generate_series(1, 2) as id";
+
+        let rows = connection
+            .query::<crate::Deserialized<Dto>>(query, &[])?
+            .try_into_vec()?;
+        assert_eq!(
+            rows.into_iter()
+                .map(crate::Deserialized::into_inner)
+                .collect::<Vec<_>>(),
+            vec![
+                Dto {
+                    id: 1,
+                    name: "name 1".to_string()
+                },
+                Dto {
+                    id: 2,
+                    name: "name 2".to_string()
+                },
+            ]
+        );
+
+        let dto = connection.query_one::<crate::Deserialized<Dto>>(query, &[])?;
+        assert_eq!(dto.name, "name 1");
+
+        assert!(matches!(
+            connection
+                .query::<crate::Deserialized<Dto>>("select 'a' as id, 'b' as name", &[])?
+                .try_into_vec(),
+            Err(crate::Error::Deserialize(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+/**
+ * Duplicate rows elimination of [`Connection::find_distinct_where`].
+ *
+ * See [DISTINCT clause](https://www.postgresql.org/docs/current/sql-select.html#SQL-DISTINCT).
+ *
+ * [`Connection::find_distinct_where`]: crate::Connection::find_distinct_where
+ */
+pub enum Distinct<'a> {
+    /**
+     * Keeps one row of each group of duplicates.
+     */
+    All,
+    /**
+     * Keeps only the first row of each set of rows where the given
+     * expressions evaluate to equal.
+     */
+    On(&'a [&'a str]),
+}
+
+impl<'a> Distinct<'a> {
+    /**
+     * Generates the `ORDER BY` clause. For `DISTINCT ON`, the leftmost
+     * `ORDER BY` expressions must match the `DISTINCT ON` expressions, they
+     * are prepended to `order_by`.
+     */
+    pub(crate) fn order_by(&self, order_by: &[&str]) -> String {
+        let expressions = match self {
+            Self::All => order_by.to_vec(),
+            Self::On(on) => on
+                .iter()
+                .chain(order_by.iter().filter(|x| !on.contains(x)))
+                .copied()
+                .collect(),
+        };
+
+        if expressions.is_empty() {
+            String::new()
+        } else {
+            format!("ORDER BY {}", expressions.join(", "))
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Distinct<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => f.write_str("DISTINCT"),
+            Self::On(on) => write!(f, "DISTINCT ON ({})", on.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn all() {
+        let distinct = crate::Distinct::All;
+
+        assert_eq!(distinct.to_string(), "DISTINCT");
+        assert_eq!(distinct.order_by(&[]), "");
+        assert_eq!(distinct.order_by(&["name"]), "ORDER BY name");
+    }
+
+    #[test]
+    fn on() {
+        let distinct = crate::Distinct::On(&["author_id"]);
+
+        assert_eq!(distinct.to_string(), "DISTINCT ON (author_id)");
+        assert_eq!(distinct.order_by(&[]), "ORDER BY author_id");
+        assert_eq!(
+            distinct.order_by(&["author_id", "created_at desc"]),
+            "ORDER BY author_id, created_at desc"
+        );
+    }
+}
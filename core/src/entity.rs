@@ -11,8 +11,110 @@ use std::collections::HashMap;
 pub trait Entity {
     /** Create a new struct from SQL result. */
     fn from(tuple: &crate::Tuple<'_>) -> Self;
+    /**
+     * Create a new struct from SQL result, returning an error instead of
+     * panicking if a field can’t be decoded or is rejected by its validator.
+     */
+    fn try_from_tuple(tuple: &crate::Tuple<'_>) -> crate::Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::from(tuple))
+    }
     /** Get the value of the field named `field`. */
     fn get(&self, field: &str) -> Option<&dyn crate::ToSql>;
+    /**
+     * Names of the fields readable with [`Entity::get`].
+     */
+    fn fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+    /**
+     * Fields stored in the relation, without the virtual ones, included in
+     * the columns of the entities embedding this one.
+     */
+    #[doc(hidden)]
+    fn column_fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        Self::fields()
+    }
+    /**
+     * Fields declared as primary key by the [`Entity`] derive, included in
+     * the primary key of the entities embedding this one.
+     *
+     * [`Entity`]: derive.Entity.html
+     */
+    #[doc(hidden)]
+    fn primary_key_fields() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+    /**
+     * Adds the `projection` attributes of the [`Entity`] derive to
+     * `projection`, for the entities embedding this one.
+     *
+     * [`Entity`]: derive.Entity.html
+     */
+    #[doc(hidden)]
+    fn add_projection(projection: crate::Projection) -> crate::Projection
+    where
+        Self: Sized,
+    {
+        projection
+    }
+    /**
+     * Set the value of the field named `field`.
+     *
+     * The value must have the exact type of the field, otherwise
+     * [`Error::FieldType`] is returned.
+     *
+     * [`Error::FieldType`]: crate::Error::FieldType
+     */
+    fn set_dyn(&mut self, field: &str, _value: Box<dyn std::any::Any>) -> crate::Result<()> {
+        Err(crate::Error::MissingField(field.to_string()))
+    }
+}
+
+/**
+ * List of names computed once for the type `T`, used by the derives when it
+ * depends on embedded entities: a `static` in a generic function is shared
+ * by all its instantiations.
+ */
+#[doc(hidden)]
+pub fn names_of<T: ?Sized>(
+    kind: &'static str,
+    init: impl FnOnce() -> Vec<&'static str>,
+) -> &'static [&'static str] {
+    type Cache = HashMap<(&'static str, &'static str), &'static [&'static str]>;
+
+    lazy_static::lazy_static! {
+        static ref CACHE: std::sync::RwLock<Cache> = Default::default();
+    }
+
+    let key = (std::any::type_name::<T>(), kind);
+
+    if let Some(names) = CACHE
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(&key)
+    {
+        return names;
+    }
+
+    let mut cache = CACHE
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    cache
+        .entry(key)
+        .or_insert_with(|| Box::leak(init().into_boxed_slice()))
 }
 
 impl<T: crate::ToSql + crate::FromSql> Entity for T {
@@ -20,6 +122,10 @@ impl<T: crate::ToSql + crate::FromSql> Entity for T {
         tuple.nth(0)
     }
 
+    fn try_from_tuple(tuple: &crate::Tuple<'_>) -> crate::Result<T> {
+        tuple.try_nth(0)
+    }
+
     fn get(&self, _: &str) -> Option<&dyn crate::ToSql> {
         Some(self)
     }
@@ -98,4 +204,239 @@ mod test {
 
         Ok(())
     }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct Contact {
+        #[elephantry(validate = "validate_email")]
+        email: String,
+    }
+
+    fn validate_email(email: &str) -> Result<(), String> {
+        if email.contains('@') {
+            Ok(())
+        } else {
+            Err(format!("'{}' isn’t an email address", email))
+        }
+    }
+
+    #[test]
+    fn validate() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+
+        let contacts = elephantry
+            .query::<Contact>("SELECT 'admin@example.org' as email", &[])?
+            .try_into_vec()?;
+        assert_eq!(contacts[0].email, "admin@example.org");
+
+        let contacts = elephantry
+            .query::<Contact>("SELECT 'admin' as email", &[])?
+            .try_into_vec();
+        assert!(matches!(
+            contacts,
+            Err(crate::Error::Validation { field, .. }) if field == "email"
+        ));
+
+        Ok(())
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct Employee {
+        name: String,
+        #[elephantry(optional_column)]
+        department: Option<String>,
+        #[elephantry(optional_column)]
+        salary: i32,
+    }
+
+    #[test]
+    fn optional_column() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+
+        let employee = elephantry.query_one::<Employee>(
+            "SELECT 'Jane' as name, 'R&D' as department, 1000 as salary",
+            &[],
+        )?;
+        assert_eq!(employee.department, Some("R&D".to_string()));
+        assert_eq!(employee.salary, 1000);
+
+        let employee = elephantry.query_one::<Employee>("SELECT 'Jane' as name", &[])?;
+        assert_eq!(employee.department, None);
+        assert_eq!(employee.salary, 0);
+
+        let employees = elephantry
+            .query::<Employee>("SELECT 'Jane' as name, null::int4 as salary", &[])?
+            .try_into_vec();
+        assert!(matches!(employees, Err(crate::Error::NotNull)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dyn_fields() -> crate::Result {
+        use crate::Entity;
+
+        let mut entity = crate::test::Entity {
+            id: 1,
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+        };
+
+        assert_eq!(
+            crate::test::Entity::fields(),
+            &["employee_id", "first_name", "last_name"]
+        );
+
+        entity.set_dyn("first_name", Box::new("John".to_string()))?;
+        assert_eq!(entity.first_name, "John");
+
+        assert!(matches!(
+            entity.set_dyn("employee_id", Box::new("2")),
+            Err(crate::Error::FieldType { .. })
+        ));
+        assert!(matches!(
+            entity.set_dyn("unknow", Box::new(2)),
+            Err(crate::Error::MissingField(_))
+        ));
+
+        Ok(())
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct Audit {
+        created_by: String,
+        #[elephantry(default)]
+        revision: i32,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "PageModel",
+        structure = "PageStructure",
+        relation = "page"
+    )]
+    struct Page {
+        #[elephantry(pk)]
+        id: i32,
+        title: String,
+        #[elephantry(flatten)]
+        audit: Audit,
+    }
+
+    #[test]
+    fn flatten() -> crate::Result {
+        use crate::{Entity, Structure};
+
+        assert_eq!(Page::fields(), &["id", "title", "created_by", "revision"]);
+        assert_eq!(PageStructure::columns(), Page::fields());
+
+        let elephantry = crate::test::new_conn()?;
+        elephantry.execute(
+            "create temporary table page (id int primary key, title text, created_by text, revision int default 1)",
+        )?;
+
+        let mut page = elephantry.insert_one::<PageModel<'_>>(&Page {
+            id: 1,
+            title: "Home".to_string(),
+            audit: Audit {
+                created_by: "Jane".to_string(),
+                revision: 2,
+            },
+        })?;
+        assert_eq!(page.audit.created_by, "Jane");
+        assert_eq!(page.audit.revision, 2);
+
+        assert!(page.get("created_by").is_some());
+        assert!(page.get("unknow").is_none());
+
+        page.set_dyn("created_by", Box::new("John".to_string()))?;
+        assert_eq!(page.audit.created_by, "John");
+        assert!(matches!(
+            page.set_dyn("unknow", Box::new(2)),
+            Err(crate::Error::MissingField(_))
+        ));
+
+        let page = elephantry.query_one::<Page>(
+            "select 2 as id, 'About' as title, 'John' as created_by",
+            &[],
+        )?;
+        assert_eq!(page.audit.created_by, "John");
+        assert_eq!(page.audit.revision, 0);
+
+        Ok(())
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct Tenant {
+        #[elephantry(pk)]
+        tenant_id: i32,
+        #[elephantry(virtual = "upper(%:name:%)")]
+        upper_name: String,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "DocumentModel",
+        structure = "DocumentStructure",
+        relation = "pg_temp.document"
+    )]
+    struct Document {
+        #[elephantry(pk)]
+        id: i32,
+        name: String,
+        #[elephantry(flatten)]
+        tenant: Tenant,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct Wrapper<T: crate::Entity> {
+        id: i32,
+        #[elephantry(flatten)]
+        inner: T,
+    }
+
+    #[test]
+    fn flatten_attributes() -> crate::Result {
+        use crate::{Entity, Structure};
+
+        assert_eq!(DocumentStructure::primary_key(), &["id", "tenant_id"]);
+        assert_eq!(DocumentStructure::columns(), &["id", "name", "tenant_id"]);
+
+        let elephantry = crate::test::new_conn()?;
+        elephantry.execute(
+            "create temporary table document (id int, name text, tenant_id int, primary key (id, tenant_id))",
+        )?;
+
+        elephantry.insert_one::<DocumentModel<'_>>(&Document {
+            id: 1,
+            name: "draft".to_string(),
+            tenant: Tenant {
+                tenant_id: 2,
+                upper_name: String::new(),
+            },
+        })?;
+        let document = elephantry
+            .find_by_pk::<DocumentModel<'_>>(&crate::pk!(id => 1, tenant_id => 2))?
+            .unwrap();
+        assert_eq!(document.tenant.upper_name, "DRAFT");
+
+        // Each instantiation has its own fields
+        assert_eq!(
+            Wrapper::<Audit>::fields(),
+            &["id", "created_by", "revision"]
+        );
+        assert_eq!(
+            Wrapper::<Tenant>::fields(),
+            &["id", "tenant_id", "upper_name"]
+        );
+        assert_eq!(Wrapper::<Tenant>::primary_key_fields(), &["tenant_id"]);
+
+        Ok(())
+    }
 }
@@ -2,19 +2,39 @@ pub type Result<T = ()> = std::result::Result<T, crate::Error>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /** Arrow error */
+    #[cfg(feature = "arrow")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
     /** An error in async context. */
     #[error("Async error: {0}")]
     Async(String),
+    /** The connection is reserved to a live [`crate::RowStream`] */
+    #[error("The connection is used by a row stream, consume or drop it first")]
+    Busy,
+    /** The connection was closed by its [`crate::Pool`] */
+    #[error("The connection is closed")]
+    Closed,
     /** Configuration error */
     #[cfg(feature = "config-support")]
     #[error("Config error: {0}")]
     Config(#[from] config::ConfigError),
+    /** Conflicting row not visible by the current transaction */
+    #[error("Conflicting row on {0} isn't visible")]
+    Conflict(String),
     /** Connection error */
     #[error("{message}")]
     Connect { dsn: String, message: String },
     /** Copy error */
     #[error("Copy error: {0}")]
     Copy(String),
+    /** Field encryption or decryption error */
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    /** Unable to deserialize a tuple with serde */
+    #[cfg(feature = "serde")]
+    #[error("Deserialize error: {0}")]
+    Deserialize(String),
     /** Escaping error */
     #[error("Unable to escape '{0}': {1}")]
     Escape(String, String),
@@ -25,9 +45,24 @@ pub enum Error {
         rust_type: String,
         value: String,
     },
+    /** Value of wrong type given to a field */
+    #[error("Invalid value for field {field}: expected {rust_type}")]
+    FieldType { field: String, rust_type: String },
+    /** The current transaction stayed idle too long, see [`TransactionWatchdog`](crate::TransactionWatchdog) */
+    #[error("Transaction idle for {0:?}, aborted")]
+    IdleInTransaction(std::time::Duration),
     /** Inspector error */
     #[error("{0}")]
     Inspect(String),
+    /** Interval with months, which have no fixed duration */
+    #[error("Unable to convert an interval of {0} months to a duration")]
+    IntervalMonths(i32),
+    /** Inconsistent connection configuration */
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    /** Malformed connection string */
+    #[error("Invalid DSN at position {position}: {reason}")]
+    InvalidDsn { position: usize, reason: String },
     /** Input/Output error */
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -37,9 +72,25 @@ pub enum Error {
     /** Connection mutex poisoned */
     #[error("Mutex error: {0}")]
     Mutex(String),
+    /** The current transaction is open for too long, see [`TransactionWatchdog`](crate::TransactionWatchdog) */
+    #[error("Transaction open for {0:?}, aborted")]
+    LongTransaction(std::time::Duration),
     /** Fetch a null value in a non-option type */
     #[error("Try to retreive null field as non-option type")]
     NotNull,
+    /** A parameter type doesn’t match the type expected by the server */
+    #[error("Parameter ${index} of type {rust_type} (oid={oid}) doesn’t match the expected type {expected}: {message}")]
+    ParamType {
+        index: usize,
+        rust_type: String,
+        oid: crate::pq::Oid,
+        expected: String,
+        message: String,
+    },
+    /** Parquet error */
+    #[cfg(feature = "arrow")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
     /** Parse error */
     #[error("{0}")]
     Parse(String),
@@ -55,6 +106,10 @@ pub enum Error {
     /** Incomplete primary key */
     #[error("Invalid primary key")]
     PrimaryKey,
+    /** Unable to serialize a value as parameters with serde */
+    #[cfg(feature = "serde")]
+    #[error("Serialize error: {0}")]
+    Serialize(String),
     /** SQL error */
     #[error("{}", .0.error_message().unwrap_or_else(|| "Unknow SQL error".to_string()))]
     Sql(crate::pq::Result),
@@ -65,9 +120,24 @@ pub enum Error {
         rust_type: String,
         message: Option<String>,
     },
+    /** The operation didn’t complete in time */
+    #[error("Timeout after {0:?}")]
+    Timeout(std::time::Duration),
+    /** The session time zone isn’t UTC, with the `Utc` timezone policy */
+    #[error("Session time zone is '{0}' instead of UTC")]
+    Timezone(String),
+    /** The current transaction is aborted, commands are ignored until end of transaction block */
+    #[error("Current transaction is aborted, commands ignored until end of transaction block")]
+    TransactionAborted,
+    /** Unexpected transaction status */
+    #[error("Unexpected transaction status: {0:?}")]
+    TransactionStatus(libpq::transaction::Status),
     /** UTF8 error */
     #[error("Invalid utf8 value: {0}")]
     Utf8(#[from] std::string::FromUtf8Error),
+    /** A field value was rejected by its validator */
+    #[error("Invalid value for field {field}: {message}")]
+    Validation { field: String, message: String },
     /** XML error */
     #[cfg(feature = "xml")]
     #[error("Xml error: {0}")]
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+
+type Deferred = Box<dyn FnOnce() + Send>;
+type Handler = Box<dyn Fn(Kind, &crate::Tuple<'_>) -> Option<Deferred> + Send + Sync>;
+
+lazy_static::lazy_static! {
+    static ref SUBSCRIBERS: std::sync::RwLock<HashMap<&'static str, Vec<(usize, Handler)>>> =
+        std::sync::RwLock::new(HashMap::new());
+}
+
+/**
+ * Kind of lifecycle event.
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/**
+ * Lifecycle event of an entity.
+ */
+pub trait Event<E> {
+    fn kind() -> Kind;
+    fn new(entity: E) -> Self;
+}
+
+macro_rules! event {
+    ($name:ident, $kind:ident, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone, Debug)]
+        pub struct $name<E> {
+            pub entity: E,
+        }
+
+        impl<E> Event<E> for $name<E> {
+            fn kind() -> Kind {
+                Kind::$kind
+            }
+
+            fn new(entity: E) -> Self {
+                Self { entity }
+            }
+        }
+    };
+}
+
+event!(
+    EntityCreated,
+    Created,
+    "Emitted by [`Connection::insert_one`], [`Connection::upsert_one`], [`Connection::upsert_many`] and [`Connection::insert_from`].
+
+[`Connection::insert_one`]: crate::Connection::insert_one
+[`Connection::upsert_one`]: crate::Connection::upsert_one
+[`Connection::upsert_many`]: crate::Connection::upsert_many
+[`Connection::insert_from`]: crate::Connection::insert_from"
+);
+event!(
+    EntityUpdated,
+    Updated,
+    "Emitted by [`Connection::update_one`], [`Connection::update_by_pk`] and [`Connection::upsert_many`].
+
+[`Connection::update_one`]: crate::Connection::update_one
+[`Connection::update_by_pk`]: crate::Connection::update_by_pk
+[`Connection::upsert_many`]: crate::Connection::upsert_many"
+);
+event!(
+    EntityDeleted,
+    Deleted,
+    "Emitted by [`Connection::delete_one`], [`Connection::delete_by_pk`] and [`Connection::delete_where`].
+
+[`Connection::delete_one`]: crate::Connection::delete_one
+[`Connection::delete_by_pk`]: crate::Connection::delete_by_pk
+[`Connection::delete_where`]: crate::Connection::delete_where"
+);
+
+/**
+ * Handle returned by [`subscribe`], to [`unsubscribe`].
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Subscription {
+    relation: &'static str,
+    id: usize,
+}
+
+/**
+ * Register `handler` for the `V` events of the `M` model.
+ *
+ * Subscribers are shared by all connections of the process. Events are
+ * dispatched after the write query or, inside a transaction, once it’s
+ * committed, with [`Transaction::commit`] or a raw `COMMIT`; they are dropped
+ * on rollback.
+ *
+ * ```
+ * # use elephantry::events::EntityCreated;
+ * # #[derive(elephantry::Entity)]
+ * # #[elephantry(model = "Model", structure = "Structure", relation = "country")]
+ * # struct Country { #[elephantry(pk)] code: String }
+ * elephantry::events::subscribe::<Model<'static>, EntityCreated<_>, _>(|event| {
+ *     println!("New country {}", event.entity.code);
+ * })?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * [`Transaction::commit`]: crate::Transaction::commit
+ */
+pub fn subscribe<M, V, F>(handler: F) -> crate::Result<Subscription>
+where
+    M: crate::Model<'static> + 'static,
+    V: Event<M::Entity> + Send + 'static,
+    F: Fn(&V) + Send + Sync + 'static,
+{
+    use crate::Structure;
+
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    let handler = std::sync::Arc::new(handler);
+    let handler: Handler = Box::new(move |kind, tuple| {
+        if kind != V::kind() {
+            return None;
+        }
+
+        let event = V::new(M::create_entity(tuple));
+        let handler = handler.clone();
+
+        Some(Box::new(move || handler(&event)))
+    });
+
+    let subscription = Subscription {
+        relation: M::Structure::relation(),
+        id: COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+    };
+
+    SUBSCRIBERS
+        .write()
+        .map_err(|e| crate::Error::Mutex(e.to_string()))?
+        .entry(subscription.relation)
+        .or_default()
+        .push((subscription.id, handler));
+
+    Ok(subscription)
+}
+
+/**
+ * Removes a handler registered with [`subscribe`].
+ */
+pub fn unsubscribe(subscription: Subscription) -> crate::Result {
+    let mut subscribers = SUBSCRIBERS
+        .write()
+        .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+
+    if let Some(handlers) = subscribers.get_mut(subscription.relation) {
+        handlers.retain(|(id, _)| *id != subscription.id);
+    }
+
+    Ok(())
+}
+
+/**
+ * Decodes the events of `result` rows for the subscribers of `relation`.
+ */
+pub(crate) fn prepare<F>(
+    relation: &str,
+    result: &crate::pq::Result,
+    kind: F,
+) -> crate::Result<Vec<Deferred>>
+where
+    F: Fn(&crate::Tuple<'_>) -> Kind,
+{
+    let subscribers = SUBSCRIBERS
+        .read()
+        .map_err(|e| crate::Error::Mutex(e.to_string()))?;
+
+    let handlers = match subscribers.get(relation) {
+        Some(handlers) => handlers,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut events = Vec::new();
+
+    for n in 0..result.len() {
+        let tuple = result.get(n);
+        let kind = kind(&tuple);
+
+        events.extend(
+            handlers
+                .iter()
+                .filter_map(|(_, handler)| handler(kind, &tuple)),
+        );
+    }
+
+    Ok(events)
+}
+
+/**
+ * Events waiting for the end of the current transaction.
+ */
+#[derive(Default)]
+pub(crate) struct Queue(std::sync::Mutex<Vec<Deferred>>);
+
+impl Queue {
+    pub fn push(&self, events: Vec<Deferred>) -> crate::Result {
+        self.lock()?.extend(events);
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> crate::Result<usize> {
+        Ok(self.lock()?.len())
+    }
+
+    pub fn truncate(&self, len: usize) -> crate::Result {
+        self.lock()?.truncate(len);
+
+        Ok(())
+    }
+
+    pub fn clear(&self) -> crate::Result {
+        self.truncate(0)
+    }
+
+    pub fn flush(&self) -> crate::Result {
+        let events = std::mem::take(&mut *self.lock()?);
+
+        for event in events {
+            event();
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> crate::Result<std::sync::MutexGuard<'_, Vec<Deferred>>> {
+        self.0
+            .lock()
+            .map_err(|e| crate::Error::Mutex(e.to_string()))
+    }
+}
+
+impl std::fmt::Debug for Queue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Queue")
+            .field(&self.len().unwrap_or_default())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(elephantry_derive::Entity)]
+    #[elephantry(internal, model = "Model", structure = "Structure", relation = "event")]
+    struct Event {
+        #[elephantry(pk)]
+        name: String,
+    }
+
+    #[test]
+    fn dispatch() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table event (name text primary key)")?;
+
+        let created = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let deleted = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let events = created.clone();
+        let on_created =
+            super::subscribe::<Model<'static>, super::EntityCreated<_>, _>(move |event| {
+                events.lock().unwrap().push(event.entity.name.clone());
+            })?;
+        let events = deleted.clone();
+        let on_deleted =
+            super::subscribe::<Model<'static>, super::EntityDeleted<_>, _>(move |event| {
+                events.lock().unwrap().push(event.entity.name.clone());
+            })?;
+
+        let event = |name: &str| Event {
+            name: name.to_string(),
+        };
+
+        conn.insert_one::<Model<'_>>(&event("autocommit"))?;
+        assert_eq!(*created.lock().unwrap(), ["autocommit"]);
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        conn.insert_one::<Model<'_>>(&event("committed"))?;
+        conn.delete_one::<Model<'_>>(&event("autocommit"))?;
+        assert_eq!(created.lock().unwrap().len(), 1);
+        assert!(deleted.lock().unwrap().is_empty());
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+        assert_eq!(*created.lock().unwrap(), ["autocommit", "committed"]);
+        assert_eq!(*deleted.lock().unwrap(), ["autocommit"]);
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        conn.insert_one::<Model<'_>>(&event("outer"))?;
+
+        let nested = conn.transaction();
+        nested.start()?;
+        conn.insert_one::<Model<'_>>(&event("rolled back"))?;
+        assert_eq!(
+            nested.roolback(None)?,
+            crate::transaction::Outcome::RolledBack
+        );
+
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+        assert_eq!(
+            *created.lock().unwrap(),
+            ["autocommit", "committed", "outer"]
+        );
+
+        super::unsubscribe(on_created)?;
+        super::unsubscribe(on_deleted)?;
+
+        conn.insert_one::<Model<'_>>(&event("unsubscribed"))?;
+        assert_eq!(created.lock().unwrap().len(), 3);
+
+        Ok(())
+    }
+
+    #[derive(elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "RawModel",
+        structure = "RawStructure",
+        relation = "raw_event"
+    )]
+    struct RawEvent {
+        #[elephantry(pk)]
+        name: String,
+    }
+
+    #[test]
+    fn raw_transaction() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table raw_event (name text primary key)")?;
+
+        let created = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let events = created.clone();
+        let subscription =
+            super::subscribe::<RawModel<'static>, super::EntityCreated<_>, _>(move |event| {
+                events.lock().unwrap().push(event.entity.name.clone());
+            })?;
+
+        let event = |name: &str| RawEvent {
+            name: name.to_string(),
+        };
+
+        conn.execute("begin")?;
+        conn.insert_one::<RawModel<'_>>(&event("rolled back"))?;
+        conn.execute("rollback")?;
+        assert_eq!(conn.events.len()?, 0);
+
+        conn.execute("begin")?;
+        conn.insert_one::<RawModel<'_>>(&event("committed"))?;
+        assert!(created.lock().unwrap().is_empty());
+        conn.execute("commit")?;
+        assert_eq!(*created.lock().unwrap(), ["committed"]);
+
+        conn.execute("begin")?;
+        conn.insert_one::<RawModel<'_>>(&event("aborted"))?;
+        assert!(conn.execute("select 1 / 0").is_err());
+        conn.execute("commit")?;
+        assert_eq!(conn.events.len()?, 0);
+        assert_eq!(*created.lock().unwrap(), ["committed"]);
+
+        super::unsubscribe(subscription)?;
+
+        Ok(())
+    }
+}
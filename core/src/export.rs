@@ -0,0 +1,151 @@
+use crate::value::Value;
+
+impl crate::pq::Result {
+    /**
+     * Writes the result as csv, with a header line of the field names.
+     *
+     * `NULL` is written as an unquoted empty field, values are always
+     * quoted. Booleans are written as `true`/`false`, arrays as postgresql
+     * array literals and `bytea` in hex format.
+     *
+     * Rows are written one by one, wrap `writer` in a
+     * [`std::io::BufWriter`] if needed.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * let employees = connection.query::<elephantry::Row>("select * from employee", &[])?;
+     *
+     * employees.to_csv(std::io::stdout())?;
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     */
+    pub fn to_csv<W: std::io::Write>(&self, mut writer: W) -> crate::Result {
+        let options = crate::CopyOptions {
+            header: true,
+            ..crate::CopyOptions::csv()
+        };
+        let encoder = crate::copy::Encoder::new(&options);
+        let mut data = Vec::new();
+
+        encoder.header(&self.field_names(), &mut data);
+        writer.write_all(&data)?;
+
+        for tuple in self.tuples() {
+            data.clear();
+
+            for value in self.values(&tuple)? {
+                let text = value.to_text()?;
+
+                encoder.field(text.as_deref().map(str::as_bytes), &mut data);
+            }
+
+            encoder.end_row(&mut data);
+            writer.write_all(&data)?;
+        }
+
+        writer.flush().map_err(Into::into)
+    }
+
+    /**
+     * Writes the result as [JSON Lines](https://jsonlines.org/): one json
+     * object per row, keyed by field name.
+     *
+     * `NULL` is written as `null`, numbers, booleans, arrays and json values
+     * keep their json type, other values are written as strings.
+     */
+    #[cfg(feature = "json")]
+    pub fn to_jsonl<W: std::io::Write>(&self, mut writer: W) -> crate::Result {
+        let field_names = self.field_names();
+
+        for tuple in self.tuples() {
+            let mut object = serde_json::Map::new();
+
+            for (name, value) in field_names.iter().zip(self.values(&tuple)?) {
+                object.insert(name.clone(), value.to_json()?);
+            }
+
+            serde_json::to_writer(&mut writer, &object)
+                .map_err(|err| crate::Error::Io(err.into()))?;
+            writer.write_all(b"\n")?;
+        }
+
+        writer.flush().map_err(Into::into)
+    }
+
+    fn field_names(&self) -> Vec<String> {
+        (0..self.inner.nfields())
+            .map(|n| self.inner.field_name(n).unwrap_or_default())
+            .collect()
+    }
+
+    fn tuples(&self) -> impl Iterator<Item = crate::Tuple<'_>> {
+        (0..self.len()).map(move |n| self.get(n))
+    }
+
+    fn values(&self, tuple: &crate::Tuple<'_>) -> crate::Result<Vec<Value>> {
+        (0..tuple.len())
+            .map(|n| tuple.try_get_by_index(n))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    const QUERY: &str = "select x as id, 'name, \"' || x as name, x % 2 = 0 as even,
+            nullif(x, 2) as score, array['a', null] as tags
+        from generate_series(1, 2) x";
+
+    #[test]
+    fn to_csv() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        let rows = connection.query::<crate::Row>(QUERY, &[])?;
+
+        let mut csv = Vec::new();
+        rows.to_csv(&mut csv)?;
+        assert_eq!(
+            String::from_utf8(csv)?,
+            r#""id","name","even","score","tags"
+"1","name, ""1","false","1","{""a"",NULL}"
+"2","name, ""2","true",,"{""a"",NULL}"
+"#
+        );
+
+        let mut csv = Vec::new();
+        connection.execute(QUERY)?.to_csv(&mut csv)?;
+        assert!(String::from_utf8(csv)?.contains(r#""2","name, ""2","true",,"{a,NULL}""#));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn to_jsonl() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        let rows = connection.query::<crate::Row>(QUERY, &[])?;
+
+        let mut jsonl = Vec::new();
+        rows.to_jsonl(&mut jsonl)?;
+        let jsonl = String::from_utf8(jsonl)?;
+        let lines = jsonl.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(lines[1]).unwrap(),
+            serde_json::json!({
+                "id": 2,
+                "name": "name, \"2",
+                "even": true,
+                "score": null,
+                "tags": ["a", null],
+            })
+        );
+
+        let mut jsonl = Vec::new();
+        connection
+            .query::<crate::Row>("select '{\"a\": [1]}'::jsonb as extra", &[])?
+            .to_jsonl(&mut jsonl)?;
+        assert_eq!(String::from_utf8(jsonl)?, "{\"extra\":{\"a\":[1]}}\n");
+
+        Ok(())
+    }
+}
@@ -162,13 +162,82 @@ impl FromSql for String {
     }
 }
 
+/**
+ * A `u8` is read from a `"char"` or from a `bytea` byte, and from a `bit`
+ * with the `bit` feature.
+ */
+impl FromSql for u8 {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        #[cfg(feature = "bit")]
+        if ty.kind == libpq::types::Kind::BitString {
+            return crate::sql::bit::u8_from_text(ty, raw);
+        }
+
+        let s = not_null(raw)?;
+
+        // Non ASCII "char" are written as octal escape
+        let byte = match s.strip_prefix('\\') {
+            Some(octal) if octal.len() == 3 => u8::from_str_radix(octal, 8).ok(),
+            _ if s.len() == 1 => s.bytes().next(),
+            _ => None,
+        };
+
+        byte.ok_or_else(|| Self::error(ty, "u8", raw))
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        #[cfg(feature = "bit")]
+        if ty.kind == libpq::types::Kind::BitString {
+            return crate::sql::bit::u8_from_binary(ty, raw);
+        }
+
+        match not_null(raw)? {
+            [byte] => Ok(*byte),
+            _ => Err(Self::error(ty, "u8", raw)),
+        }
+    }
+}
+
+/**
+ * Decodes each byte of a `bytea` as a `T`.
+ */
+fn from_bytea<T: FromSql>(bytea: crate::Bytea) -> crate::Result<Vec<T>> {
+    bytea
+        .iter()
+        .map(|byte| T::from_binary(&crate::pq::types::CHAR, Some(&[*byte])))
+        .collect()
+}
+
+/**
+ * Arrays, or the bytes of a `bytea` for `Vec<u8>`.
+ */
 impl<T: FromSql> FromSql for Vec<T> {
     fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
-        Ok(crate::Array::from_text(ty, raw)?.into())
+        use std::convert::TryFrom;
+
+        if ty.oid == crate::pq::types::BYTEA.oid {
+            return from_bytea(crate::Bytea::from_text(ty, raw)?);
+        }
+
+        let elemtype = match ty.kind {
+            libpq::types::Kind::Array(oid) => {
+                crate::pq::Type::try_from(oid).unwrap_or_else(|_| ty.clone())
+            }
+            _ => ty.clone(),
+        };
+
+        crate::sql::array::text_to_vec(not_null(raw)?)?
+            .iter()
+            .map(|x| T::from_text(&elemtype, x.as_deref()))
+            .collect()
     }
 
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
-        Ok(crate::Array::from_binary(ty, raw)?.into())
+        if ty.oid == crate::pq::types::BYTEA.oid {
+            return from_bytea(crate::Bytea::from_binary(ty, raw)?);
+        }
+
+        crate::Array::from_binary(ty, raw)?.try_into_vec()
     }
 }
 
@@ -0,0 +1,83 @@
+/**
+ * Quotes `s` as an identifier, the embedded double quotes are doubled.
+ */
+pub(crate) fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/**
+ * Splits a possibly qualified name on the dots outside of the quoted parts,
+ * the parts are returned as written.
+ */
+pub(crate) fn split(name: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (n, c) in name.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '.' if !quoted => {
+                parts.push(&name[start..n]);
+                start = n + 1;
+            }
+            _ => (),
+        }
+    }
+
+    parts.push(&name[start..]);
+
+    parts
+}
+
+/**
+ * Quotes each part of the relation `name`. The unquoted parts are case
+ * folded, like the server does, the quoted ones are kept as is.
+ */
+pub(crate) fn relation(name: &str) -> String {
+    split(name)
+        .into_iter()
+        .map(|part| {
+            let part = part.trim();
+
+            if part.starts_with('"') && part.ends_with('"') && part.len() > 1 {
+                part.to_string()
+            } else {
+                quote(&part.to_lowercase())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn quote() {
+        assert_eq!(super::quote("visitor_id"), r#""visitor_id""#);
+        assert_eq!(super::quote(r#"created"at"#), r#""created""at""#);
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(super::split("event"), ["event"]);
+        assert_eq!(super::split("public.event"), ["public", "event"]);
+        assert_eq!(
+            super::split(r#""my.schema"."ev.ent""#),
+            [r#""my.schema""#, r#""ev.ent""#]
+        );
+    }
+
+    #[test]
+    fn relation() {
+        assert_eq!(super::relation("Event"), r#""event""#);
+        assert_eq!(
+            super::relation(r#"pg_temp."Ev""ent""#),
+            r#""pg_temp"."Ev""ent""#
+        );
+        assert_eq!(
+            super::relation(r#""my.schema".event"#),
+            r#""my.schema"."event""#
+        );
+    }
+}
@@ -0,0 +1,127 @@
+/**
+ * Reads [JSON Lines](https://jsonlines.org/) from `reader`, one value per
+ * line. Blank lines are skipped.
+ *
+ * ```no_run
+ * # #[derive(serde::Deserialize)]
+ * # struct Employee { name: String }
+ * let file = std::fs::File::open("employee.jsonl")?;
+ *
+ * for employee in elephantry::read_jsonl::<Employee, _>(file) {
+ *     println!("{}", employee?.name);
+ * }
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+pub fn read_jsonl<T, R>(reader: R) -> impl Iterator<Item = crate::Result<T>>
+where
+    T: serde::de::DeserializeOwned,
+    R: std::io::Read,
+{
+    use std::io::BufRead;
+
+    std::io::BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(n, line)| {
+            serde_json::from_str(&line?)
+                .map_err(|err| crate::Error::Deserialize(format!("line {}: {}", n + 1, err)))
+        })
+}
+
+/**
+ * Reads csv from `reader`. The first line is a header, columns are matched
+ * by name against the fields of `T`.
+ */
+pub fn read_csv<T, R>(reader: R) -> impl Iterator<Item = crate::Result<T>>
+where
+    T: serde::de::DeserializeOwned,
+    R: std::io::Read,
+{
+    csv::Reader::from_reader(reader)
+        .into_deserialize()
+        .map(|x| x.map_err(|err| crate::Error::Deserialize(err.to_string())))
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Employee {
+        first_name: String,
+        last_name: String,
+    }
+
+    impl From<Employee> for crate::test::Entity {
+        fn from(employee: Employee) -> Self {
+            Self {
+                id: 0,
+                first_name: employee.first_name,
+                last_name: employee.last_name,
+            }
+        }
+    }
+
+    #[test]
+    fn read_jsonl() {
+        let data =
+            "{\"first_name\": \"Ada\", \"last_name\": \"Lovelace\"}\n\n{\"first_name\": 1}\n";
+        let employees = crate::read_jsonl::<Employee, _>(data.as_bytes()).collect::<Vec<_>>();
+
+        assert_eq!(employees.len(), 2);
+        assert_eq!(
+            employees[0].as_ref().unwrap(),
+            &Employee {
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+            }
+        );
+        assert!(
+            matches!(&employees[1], Err(crate::Error::Deserialize(err)) if err.starts_with("line 3:"))
+        );
+    }
+
+    #[test]
+    fn copy_from_csv() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+
+        let count = || conn.query_one::<i64>("select count(*) from entity", &[]);
+
+        let csv = "last_name,first_name\nHopper,Grace\n\"Hopper, jr\",Grace\n";
+        conn.copy_from_csv::<crate::test::Model<'_>, Employee, _>(csv.as_bytes())?;
+        assert_eq!(count()?, 2);
+        assert_eq!(
+            conn.query_one::<String>("select last_name from entity order by last_name desc", &[])?,
+            "Hopper, jr"
+        );
+
+        let invalid = "last_name,first_name\nHopper,Grace\nHopper\n";
+        assert!(matches!(
+            conn.copy_from_csv::<crate::test::Model<'_>, Employee, _>(invalid.as_bytes()),
+            Err(crate::Error::Deserialize(_))
+        ));
+        assert_eq!(count()?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn copy_from_iter() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+
+        let employees = (0..3).map(|n| Employee {
+            first_name: format!("Iter {}", n),
+            last_name: "Copy".to_string(),
+        });
+
+        conn.copy_from_iter::<crate::test::Model<'_>, _, _>(employees)?;
+        assert_eq!(
+            conn.query_one::<i64>("select count(*) from entity", &[])?,
+            3
+        );
+
+        Ok(())
+    }
+}
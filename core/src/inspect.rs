@@ -90,6 +90,74 @@ pub struct Column {
     pub default: Option<String>,
     pub is_notnull: bool,
     pub comment: Option<String>,
+    identity: String,
+    generated: String,
+}
+
+/**
+ * Parsed default value of a [`Column`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnDefault {
+    /** Constant value, like `0` or `'draft'::text`. */
+    Literal(String),
+    /**
+     * Expression evaluated at insertion, like `now()` or the
+     * `nextval('…'::regclass)` of a serial column.
+     */
+    Expression(String),
+    /** Identity column, `always` if the value can’t be overridden. */
+    Identity { always: bool },
+    /** Stored generated column, computed from `expression`. */
+    Generated(String),
+}
+
+impl Column {
+    /**
+     * Parses the default value of the column, `None` if it doesn’t have one.
+     */
+    pub fn parsed_default(&self) -> Option<ColumnDefault> {
+        match self.identity.as_str() {
+            "a" => return Some(ColumnDefault::Identity { always: true }),
+            "d" => return Some(ColumnDefault::Identity { always: false }),
+            _ => (),
+        }
+
+        let default = self.default.clone()?;
+
+        if self.generated == "s" {
+            Some(ColumnDefault::Generated(default))
+        } else if is_literal(&default) {
+            Some(ColumnDefault::Literal(default))
+        } else {
+            Some(ColumnDefault::Expression(default))
+        }
+    }
+
+    /**
+     * Must the column be set at insertion? That is, it’s not null and
+     * doesn’t have a default value.
+     */
+    pub fn is_required(&self) -> bool {
+        self.is_notnull && self.parsed_default().is_none()
+    }
+}
+
+/**
+ * Is the default value, as formatted by `pg_get_expr`, a constant?
+ */
+fn is_literal(default: &str) -> bool {
+    lazy_static::lazy_static! {
+        static ref REGEX: regex::Regex = regex::Regex::new(
+            r#"(?xi)^(
+                \(?-?[0-9]+(\.[0-9]+)?([eE][+-]?[0-9]+)?\)?
+                | '([^']|'')*'
+                | true | false | null
+            )(::[a-z0-9_\ .\[\]"()]+)*$"#
+        ).unwrap();
+    }
+
+    REGEX.is_match(default.trim())
 }
 
 /**
@@ -129,7 +197,9 @@ select
     end as "ty",
     pg_catalog.pg_get_expr(def.adbin, def.adrelid) as "default",
     att.attnotnull as "is_notnull",
-    dsc.description as "comment"
+    dsc.description as "comment",
+    att.attidentity::text as "identity",
+    att.attgenerated::text as "generated"
 from
   pg_catalog.pg_attribute att
     join pg_catalog.pg_type  typ  on att.atttypid = typ.oid
@@ -215,6 +285,527 @@ pub fn composites(
     Ok(composites)
 }
 
+/**
+ * Action of a foreign key when the referenced row is deleted or updated.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForeignKeyAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+impl ForeignKeyAction {
+    fn from_code(code: &str) -> Self {
+        match code {
+            "r" => Self::Restrict,
+            "c" => Self::Cascade,
+            "n" => Self::SetNull,
+            "d" => Self::SetDefault,
+            _ => Self::NoAction,
+        }
+    }
+}
+
+/**
+ * Node of a [`Graph`]: a table.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Table {
+    pub schema: String,
+    pub name: String,
+    pub oid: crate::pq::Oid,
+}
+
+/**
+ * Edge of a [`Graph`]: `table` references `references`.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignKey {
+    pub name: String,
+    pub table: crate::pq::Oid,
+    pub columns: Vec<String>,
+    pub references: crate::pq::Oid,
+    pub foreign_columns: Vec<String>,
+    pub on_delete: ForeignKeyAction,
+    pub on_update: ForeignKeyAction,
+}
+
+/**
+ * Dependency graph of the tables, built from their foreign keys.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Graph {
+    nodes: Vec<Table>,
+    edges: Vec<ForeignKey>,
+}
+
+impl Graph {
+    pub fn nodes(&self) -> &[Table] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[ForeignKey] {
+        &self.edges
+    }
+
+    pub fn node(&self, oid: crate::pq::Oid) -> Option<&Table> {
+        self.nodes.iter().find(|x| x.oid == oid)
+    }
+
+    /**
+     * Foreign keys of the `table`.
+     */
+    pub fn dependencies(&self, table: crate::pq::Oid) -> impl Iterator<Item = &ForeignKey> {
+        self.edges.iter().filter(move |x| x.table == table)
+    }
+
+    /**
+     * Foreign keys referencing the `table`.
+     */
+    pub fn dependents(&self, table: crate::pq::Oid) -> impl Iterator<Item = &ForeignKey> {
+        self.edges.iter().filter(move |x| x.references == table)
+    }
+
+    /**
+     * Sorts the tables so that a table comes after the tables it references,
+     * the order to insert rows in. Tables without relation are sorted by
+     * name, self references are ignored.
+     *
+     * Returns [`Error::Inspect`] if the foreign keys contain a cycle.
+     *
+     * [`Error::Inspect`]: crate::Error::Inspect
+     */
+    pub fn insert_order(&self) -> crate::Result<Vec<&Table>> {
+        let mut remaining = self.nodes.iter().collect::<std::collections::BTreeSet<_>>();
+        let mut order = Vec::new();
+
+        while !remaining.is_empty() {
+            let next = remaining
+                .iter()
+                .find(|table| {
+                    self.dependencies(table.oid).all(|fk| {
+                        fk.references == table.oid
+                            || !remaining.iter().any(|x| x.oid == fk.references)
+                    })
+                })
+                .copied();
+
+            match next {
+                Some(table) => {
+                    remaining.remove(table);
+                    order.push(table);
+                }
+                None => {
+                    let cycle = remaining
+                        .iter()
+                        .map(|x| format!("{}.{}", x.schema, x.name))
+                        .collect::<Vec<_>>();
+
+                    return Err(crate::Error::Inspect(format!(
+                        "Cycle between foreign keys of {}",
+                        cycle.join(", ")
+                    )));
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /**
+     * Reverse of [`insert_order`], the order to delete or truncate the
+     * tables in.
+     *
+     * [`insert_order`]: Self::insert_order
+     */
+    pub fn delete_order(&self) -> crate::Result<Vec<&Table>> {
+        let mut order = self.insert_order()?;
+        order.reverse();
+
+        Ok(order)
+    }
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct GraphTable {
+    oid: crate::pq::Oid,
+    schema: String,
+    name: String,
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct GraphForeignKey {
+    name: String,
+    table: crate::pq::Oid,
+    columns: Vec<String>,
+    references: crate::pq::Oid,
+    foreign_columns: Vec<String>,
+    on_delete: String,
+    on_update: String,
+}
+
+/**
+ * Builds the foreign keys graph of the tables of `schemas`.
+ */
+pub fn graph(connection: &crate::Connection, schemas: &[&str]) -> crate::Result<Graph> {
+    let schemas = schemas.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+
+    let nodes = connection
+        .query::<GraphTable>(
+            r#"
+select c.oid as "oid", n.nspname as "schema", c.relname as "name"
+from pg_catalog.pg_class c
+    join pg_catalog.pg_namespace n on n.oid = c.relnamespace
+where c.relkind in ('r', 'p')
+    and not c.relispartition
+    and n.nspname = any($*)
+order by n.nspname, c.relname;
+"#,
+            &[&schemas],
+        )?
+        .map(|x| Table {
+            schema: x.schema,
+            name: x.name,
+            oid: x.oid,
+        })
+        .collect();
+
+    let edges = connection
+        .query::<GraphForeignKey>(
+            r#"
+select co.conname as "name",
+    co.conrelid as "table",
+    array(
+        select a.attname
+        from unnest(co.conkey) with ordinality k(attnum, n)
+            join pg_catalog.pg_attribute a on a.attrelid = co.conrelid and a.attnum = k.attnum
+        order by k.n
+    ) as "columns",
+    co.confrelid as "references",
+    array(
+        select a.attname
+        from unnest(co.confkey) with ordinality k(attnum, n)
+            join pg_catalog.pg_attribute a on a.attrelid = co.confrelid and a.attnum = k.attnum
+        order by k.n
+    ) as "foreign_columns",
+    co.confdeltype::text as "on_delete",
+    co.confupdtype::text as "on_update"
+from pg_catalog.pg_constraint co
+    join pg_catalog.pg_class c on c.oid = co.conrelid
+    join pg_catalog.pg_namespace n on n.oid = c.relnamespace
+where co.contype = 'f'
+    and co.conparentid = 0
+    and n.nspname = any($*)
+order by n.nspname, c.relname, co.conname;
+"#,
+            &[&schemas],
+        )?
+        .map(|x| ForeignKey {
+            name: x.name,
+            table: x.table,
+            columns: x.columns,
+            references: x.references,
+            foreign_columns: x.foreign_columns,
+            on_delete: ForeignKeyAction::from_code(&x.on_delete),
+            on_update: ForeignKeyAction::from_code(&x.on_update),
+        })
+        .collect();
+
+    Ok(Graph { nodes, edges })
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct DumpEnum {
+    name: String,
+    elements: Vec<String>,
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct DumpTable {
+    oid: crate::pq::Oid,
+    name: String,
+    partition_of: Option<String>,
+    bound: Option<String>,
+    partition_key: Option<String>,
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct DumpColumn {
+    name: String,
+    ty: String,
+    default: Option<String>,
+    is_notnull: bool,
+    identity: String,
+    generated: String,
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct DumpConstraint {
+    name: String,
+    definition: String,
+}
+
+#[derive(Debug, elephantry_derive::Entity)]
+#[elephantry(internal)]
+struct DumpForeignKey {
+    table: String,
+    name: String,
+    definition: String,
+}
+
+/**
+ * Generates the `create` statements of the enums, sequences, tables and
+ * indexes of `schemas`, from the catalog.
+ *
+ * The partitions are created after their partitioned table and the foreign
+ * keys are added at the end, with `alter table` statements, so the tables
+ * referencing each other can be restored.
+ *
+ * The objects are sorted by name, the output is stable and can be compared.
+ */
+pub fn dump(connection: &crate::Connection, schemas: &[&str]) -> crate::Result<String> {
+    let mut sql = String::new();
+    let mut foreign_keys = Vec::new();
+
+    for schema in schemas {
+        let oid = crate::inspect::schema_oid(connection, schema)?;
+
+        sql.push_str(&format!(
+            "create schema if not exists {};\n\n",
+            connection.query_one::<String>("select quote_ident($1)", &[schema])?
+        ));
+
+        let enums = connection.query::<DumpEnum>(
+            r#"
+select format('%I.%I', n.nspname, t.typname) as "name",
+    array(
+        select quote_literal(e.enumlabel)
+        from pg_catalog.pg_enum e
+        where e.enumtypid = t.oid
+        order by e.enumsortorder
+    ) as "elements"
+from pg_catalog.pg_type t
+    join pg_catalog.pg_namespace n on n.oid = t.typnamespace
+where t.typtype = 'e'
+    and n.oid = $*
+order by t.typname;
+"#,
+            &[&oid],
+        )?;
+
+        for enumeration in enums {
+            sql.push_str(&format!(
+                "create type {} as enum ({});\n\n",
+                enumeration.name,
+                enumeration.elements.join(", ")
+            ));
+        }
+
+        let sequences = connection.query::<String>(
+            r#"
+select format('%I.%I', n.nspname, c.relname)
+from pg_catalog.pg_class c
+    join pg_catalog.pg_namespace n on n.oid = c.relnamespace
+where c.relkind = 'S'
+    and n.oid = $*
+    -- created with their identity column
+    and not exists(
+        select 1 from pg_catalog.pg_depend d
+        where d.classid = 'pg_catalog.pg_class'::regclass and d.objid = c.oid and d.deptype = 'i'
+    )
+order by c.relname;
+"#,
+            &[&oid],
+        )?;
+
+        for sequence in sequences {
+            sql.push_str(&format!("create sequence {};\n\n", sequence));
+        }
+
+        let tables = connection.query::<DumpTable>(
+            r#"
+select c.oid as "oid",
+    format('%I.%I', n.nspname, c.relname) as "name",
+    (
+        select format('%I.%I', pn.nspname, p.relname)
+        from pg_catalog.pg_inherits i
+            join pg_catalog.pg_class p on p.oid = i.inhparent
+            join pg_catalog.pg_namespace pn on pn.oid = p.relnamespace
+        where i.inhrelid = c.oid and c.relispartition
+    ) as "partition_of",
+    case when c.relispartition then pg_catalog.pg_get_expr(c.relpartbound, c.oid) end as "bound",
+    case when c.relkind = 'p' then pg_catalog.pg_get_partkeydef(c.oid) end as "partition_key"
+from pg_catalog.pg_class c
+    join pg_catalog.pg_namespace n on n.oid = c.relnamespace
+where c.relkind in ('r', 'p')
+    and n.oid = $*
+-- partitioned tables before their partitions
+order by (select count(*) from pg_catalog.pg_partition_ancestors(c.oid)), c.relname;
+"#,
+            &[&oid],
+        )?;
+
+        for table in tables {
+            sql.push_str(&dump_table(connection, &table)?);
+        }
+
+        foreign_keys.extend(connection.query::<DumpForeignKey>(
+            r#"
+select format('%I.%I', n.nspname, c.relname) as "table",
+    quote_ident(co.conname) as "name",
+    pg_catalog.pg_get_constraintdef(co.oid, true) as "definition"
+from pg_catalog.pg_constraint co
+    join pg_catalog.pg_class c on c.oid = co.conrelid
+    join pg_catalog.pg_namespace n on n.oid = c.relnamespace
+where co.contype = 'f'
+    -- inherited by the partitions
+    and co.conparentid = 0
+    and n.oid = $*
+order by c.relname, co.conname;
+"#,
+            &[&oid],
+        )?);
+    }
+
+    for foreign_key in foreign_keys {
+        sql.push_str(&format!(
+            "alter table {} add constraint {} {};\n\n",
+            foreign_key.table, foreign_key.name, foreign_key.definition
+        ));
+    }
+
+    Ok(sql)
+}
+
+fn dump_table(connection: &crate::Connection, table: &DumpTable) -> crate::Result<String> {
+    let columns = connection.query::<DumpColumn>(
+        r#"
+select quote_ident(a.attname) as "name",
+    pg_catalog.format_type(a.atttypid, a.atttypmod) as "ty",
+    pg_catalog.pg_get_expr(d.adbin, d.adrelid) as "default",
+    a.attnotnull as "is_notnull",
+    a.attidentity::text as "identity",
+    a.attgenerated::text as "generated"
+from pg_catalog.pg_attribute a
+    left join pg_catalog.pg_attrdef d on a.attrelid = d.adrelid and a.attnum = d.adnum
+where a.attrelid = $*
+    and a.attnum > 0
+    and not a.attisdropped
+order by a.attnum;
+"#,
+        &[&table.oid],
+    )?;
+
+    let constraints = connection.query::<DumpConstraint>(
+        r#"
+select quote_ident(c.conname) as "name",
+    pg_catalog.pg_get_constraintdef(c.oid, true) as "definition"
+from pg_catalog.pg_constraint c
+where c.conrelid = $*
+    and c.contype <> 'f'
+    -- inherited from a parent table
+    and c.conparentid = 0
+    and c.conislocal
+order by c.contype desc, c.conname;
+"#,
+        &[&table.oid],
+    )?;
+
+    let indexes = connection.query::<String>(
+        r#"
+select pg_catalog.pg_get_indexdef(i.indexrelid)
+from pg_catalog.pg_index i
+    join pg_catalog.pg_class c on c.oid = i.indexrelid
+where i.indrelid = $*
+    and not exists(
+        select 1 from pg_catalog.pg_constraint co where co.conindid = i.indexrelid
+    )
+    -- created with the partition
+    and not exists(
+        select 1 from pg_catalog.pg_inherits h where h.inhrelid = i.indexrelid
+    )
+order by c.relname;
+"#,
+        &[&table.oid],
+    )?;
+
+    let mut definitions = columns
+        .map(|column| {
+            let mut definition = format!("{} {}", column.name, column.ty);
+
+            if column.is_notnull {
+                definition.push_str(" not null");
+            }
+
+            match (column.identity.as_str(), column.generated.as_str()) {
+                ("a", _) => definition.push_str(" generated always as identity"),
+                ("d", _) => definition.push_str(" generated by default as identity"),
+                (_, "s") => definition.push_str(&format!(
+                    " generated always as ({}) stored",
+                    column.default.unwrap_or_default()
+                )),
+                _ => {
+                    if let Some(default) = column.default {
+                        definition.push_str(&format!(" default {}", default));
+                    }
+                }
+            }
+
+            definition
+        })
+        .collect::<Vec<_>>();
+
+    let constraints = constraints
+        .map(|constraint| format!("constraint {} {}", constraint.name, constraint.definition));
+
+    let mut sql = match (&table.partition_of, &table.bound) {
+        // The columns are inherited
+        (Some(parent), Some(bound)) => {
+            let constraints = constraints.collect::<Vec<_>>();
+            let definitions = if constraints.is_empty() {
+                String::new()
+            } else {
+                format!(" (\n    {}\n)", constraints.join(",\n    "))
+            };
+
+            format!(
+                "create table {} partition of {}{} {}",
+                table.name, parent, definitions, bound
+            )
+        }
+        _ => {
+            definitions.extend(constraints);
+
+            format!(
+                "create table {} (\n    {}\n)",
+                table.name,
+                definitions.join(",\n    ")
+            )
+        }
+    };
+
+    if let Some(partition_key) = &table.partition_key {
+        sql.push_str(&format!(" partition by {}", partition_key));
+    }
+
+    sql.push_str(";\n\n");
+
+    for index in indexes {
+        sql.push_str(&format!("{};\n\n", index));
+    }
+
+    Ok(sql)
+}
+
 pub(crate) fn composite_fields(
     connection: &crate::Connection,
     composite: &str,
@@ -284,3 +875,184 @@ where s.nspname = $*
             return crate::Error::Inspect(format!("Unknow schema {}", name));
         })
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parsed_default() -> crate::Result {
+        use crate::inspect::ColumnDefault::*;
+
+        let connection = crate::test::new_conn()?;
+        connection.execute(
+            r#"
+drop schema if exists elephantry_default cascade;
+create schema elephantry_default;
+create table elephantry_default.item (
+    id serial primary key,
+    uid int generated always as identity,
+    name text not null,
+    status text not null default 'draft',
+    price numeric default -1.5,
+    created timestamp not null default now(),
+    price_ttc numeric generated always as (price * 1.2) stored,
+    code varchar(3) default 'abc',
+    comment text
+);
+"#,
+        )?;
+
+        let columns = crate::inspect::relation(&connection, "elephantry_default", "item")?;
+        let defaults = columns
+            .iter()
+            .map(|x| (x.name.as_str(), x.parsed_default(), x.is_required()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            defaults,
+            [
+                (
+                    "id",
+                    Some(Expression(
+                        "nextval('elephantry_default.item_id_seq'::regclass)".to_string()
+                    )),
+                    false
+                ),
+                ("uid", Some(Identity { always: true }), false),
+                ("name", None, true),
+                ("status", Some(Literal("'draft'::text".to_string())), false),
+                ("price", Some(Literal("'-1.5'::numeric".to_string())), false),
+                ("created", Some(Expression("now()".to_string())), false),
+                (
+                    "price_ttc",
+                    Some(Generated("(price * 1.2)".to_string())),
+                    false
+                ),
+                (
+                    "code",
+                    Some(Literal("'abc'::character varying".to_string())),
+                    false
+                ),
+                ("comment", None, false),
+            ]
+        );
+
+        connection.execute("drop schema elephantry_default cascade")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn graph() -> crate::Result {
+        use crate::inspect::ForeignKeyAction;
+
+        let connection = crate::test::new_conn()?;
+        connection.execute(
+            r#"
+drop schema if exists elephantry_graph cascade;
+create schema elephantry_graph;
+create table elephantry_graph.comment (id int primary key, post_id int, parent_id int);
+create table elephantry_graph.author (id int primary key);
+create table elephantry_graph.post (id int primary key, author_id int references elephantry_graph.author on delete cascade);
+alter table elephantry_graph.comment
+    add constraint comment_post foreign key (post_id) references elephantry_graph.post (id) on update set null,
+    add constraint comment_parent foreign key (parent_id) references elephantry_graph.comment (id);
+"#,
+        )?;
+
+        let graph = crate::inspect::graph(&connection, &["elephantry_graph"])?;
+        let names = |tables: Vec<&crate::inspect::Table>| {
+            tables.iter().map(|x| x.name.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(graph.edges().len(), 3);
+
+        let post = graph.nodes().iter().find(|x| x.name == "post").unwrap();
+        let fk = graph.dependencies(post.oid).next().unwrap();
+        assert_eq!(fk.columns, ["author_id"]);
+        assert_eq!(fk.foreign_columns, ["id"]);
+        assert_eq!(fk.on_delete, ForeignKeyAction::Cascade);
+        assert_eq!(fk.on_update, ForeignKeyAction::NoAction);
+        assert_eq!(graph.node(fk.references).unwrap().name, "author");
+
+        let dependent = graph.dependents(post.oid).next().unwrap();
+        assert_eq!(dependent.name, "comment_post");
+        assert_eq!(dependent.on_update, ForeignKeyAction::SetNull);
+
+        assert_eq!(names(graph.insert_order()?), ["author", "post", "comment"]);
+        assert_eq!(names(graph.delete_order()?), ["comment", "post", "author"]);
+
+        connection.execute(
+            "alter table elephantry_graph.author add column post_id int references elephantry_graph.post",
+        )?;
+        let graph = crate::inspect::graph(&connection, &["elephantry_graph"])?;
+        assert!(matches!(
+            graph.insert_order(),
+            Err(crate::Error::Inspect(_))
+        ));
+
+        connection.execute("drop schema elephantry_graph cascade")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        connection.execute(
+            r#"
+drop schema if exists elephantry_dump cascade;
+create schema elephantry_dump;
+create type elephantry_dump.status as enum ('draft', 'it''s published');
+create table elephantry_dump."Post" (
+    id serial primary key,
+    title varchar(100) not null,
+    status elephantry_dump.status default 'draft',
+    check (length(title) > 0)
+);
+create index post_title on elephantry_dump."Post" (title);
+create table elephantry_dump.author (
+    author_id int generated always as identity primary key,
+    name text not null,
+    slug text generated always as (lower(name)) stored,
+    post_id int
+);
+alter table elephantry_dump."Post" add column author_id int references elephantry_dump.author;
+alter table elephantry_dump.author add foreign key (post_id) references elephantry_dump."Post";
+create table elephantry_dump.event (
+    created date not null,
+    name text,
+    primary key (created, name)
+) partition by range (created);
+create index event_name on elephantry_dump.event (name);
+create table elephantry_dump.event_2000 partition of elephantry_dump.event
+    for values from ('2000-01-01') to ('2001-01-01');
+"#,
+        )?;
+
+        let dump = connection.dump_schema(&["elephantry_dump"])?;
+        assert!(dump
+            .contains("create type elephantry_dump.status as enum ('draft', 'it''s published');"));
+        assert!(dump.contains("create table elephantry_dump.\"Post\" ("));
+        assert!(dump.contains("    title character varying(100) not null,"));
+        assert!(dump.contains("create sequence elephantry_dump.\"Post_id_seq\";"));
+        assert!(dump.contains("constraint \"Post_pkey\" PRIMARY KEY (id)"));
+        assert!(dump.contains("CREATE INDEX post_title ON elephantry_dump.\"Post\""));
+        assert!(dump.contains("    author_id integer not null generated always as identity,"));
+        assert!(dump.contains("    slug text generated always as (lower(name)) stored,"));
+        assert!(!dump.contains("create sequence elephantry_dump.author_author_id_seq"));
+        assert!(dump.contains("\n) partition by RANGE (created);"));
+        assert!(dump.contains(
+            "create table elephantry_dump.event_2000 partition of elephantry_dump.event FOR VALUES FROM ('2000-01-01') TO ('2001-01-01');"
+        ));
+        assert!(dump.contains("alter table elephantry_dump.author add constraint author_post_id_fkey FOREIGN KEY (post_id) REFERENCES elephantry_dump.\"Post\"(id);"));
+
+        connection.execute("drop schema elephantry_dump cascade")?;
+        connection.execute(&dump)?;
+        assert_eq!(connection.dump_schema(&["elephantry_dump"])?, dump);
+
+        connection.execute("drop schema elephantry_dump cascade")?;
+
+        Ok(())
+    }
+}
@@ -3,50 +3,75 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 /*!
- * | SQL type                    | Rust type                | Feature |
- * |-----------------------------|--------------------------|---------|
- * | `bigint`                    | `i64`                    |         |
- * | `bit`                       | `u8`                     | bit     |
- * | `bit varying`               | `bit_vec::BitVec`        | bit     |
- * | `boolean`                   | `bool`                   |         |
- * | `box`                       | `elephantry::Box`        | geo     |
- * | `bytea`                     | `elephantry::Bytea`      |         |
- * | `char`                      | `char`                   |         |
- * | `varchar`                   | `String`                 |         |
- * | `cidr`                      | `ipnetwork::IpNetwork`   | net     |
- * | `circle`                    | `elephantry::Circle`     | geo     |
- * | `date`                      | `chrono::NaiveDate`      | date    |
- * | `double precision`/`float8` | `f64`                    |         |
- * | `hstore`                    | `elephantry::Hstore`     |         |
- * | `inet`                      | `std::net::IpAddr`       | net     |
- * | `integer`                   | `i32`                    |         |
- * | `json`/`jsonb`              | `serde_json::Value`      | json    |
- * | `line`                      | `elephantry::Line`       | geo     |
- * | `lseg`                      | `elephantry::Segment`    | geo     |
- * | `null`                      | `()`                     |         |
- * | `macaddr`                   | `macaddr::MacAddr6`      | net     |
- * | `macaddr8`                  | `macaddr::MacAddr8`      | net     |
- * | `money`                     | `f32`                    |         |
- * | `numeric`                   | `bigdecimal::BigDecimal` | numeric |
- * | `path`                      | `elephantry::Path`       | geo     |
- * | `point`                     | `elephantry::Point`      | geo     |
- * | `polygon`                   | `elephantry::Polygon`    | geo     |
- * | `real`/`float4`             | `f32`                    |         |
- * | `record`                    | `tuple`                  |         |
- * | `smallint`                  | `i16`                    |         |
- * | `text`                      | `String`                 |         |
- * | `time`                      | `elephantry::Time`       | time    |
- * | `timetz`                    | `elephantry::TimeTz`     | time    |
- * | `timestamp`                 | `chrono::NaiveDateTime`  | date    |
- * | `timestamptz`               | `chrono::DateTime`       | date    |
- * | `uuid`                      | `uuid::Uuid`             | uuid    |
- * | `xml`                       | `xmltree::Element`       | xml     |
+ * | SQL type                    | Rust type                 | Feature |
+ * |-----------------------------|---------------------------|---------|
+ * | `bigint`                    | `i64`                     |         |
+ * | `bit`                       | `elephantry::Bit`         | bit     |
+ * | `bit varying`               | `bit_vec::BitVec`         | bit     |
+ * | `boolean`                   | `bool`                    |         |
+ * | `box`                       | `elephantry::Box`         | geo     |
+ * | `bytea`                     | `elephantry::Bytea`       |         |
+ * | `bytea`                     | `&[u8]`/`Vec<u8>`         |         |
+ * | `char`                      | `char`                    |         |
+ * | `"char"`                    | `u8`                      |         |
+ * | `citext`                    | `elephantry::Citext`      | citext  |
+ * | `varchar`                   | `String`                  |         |
+ * | `cidr`                      | `ipnetwork::IpNetwork`    | net     |
+ * | `circle`                    | `elephantry::Circle`      | geo     |
+ * | `date`                      | `chrono::NaiveDate`       | date    |
+ * | `date`                      | `jiff::civil::Date`       | jiff    |
+ * | `date`                      | `time::Date`              | time    |
+ * | `double precision`/`float8` | `f64`                     |         |
+ * | `hstore`                    | `elephantry::Hstore`      | hstore  |
+ * | `inet`                      | `std::net::IpAddr`        | net     |
+ * | `integer`                   | `i32`                     |         |
+ * | `interval`                  | `elephantry::Interval`    | date    |
+ * | `interval`                  | `chrono::Duration`        | date    |
+ * | `interval`                  | `time::Duration`          | time    |
+ * | `json`/`jsonb`              | `serde_json::Value`       | json    |
+ * | `line`                      | `elephantry::Line`        | geo     |
+ * | `lquery`                    | `elephantry::Lquery`      | ltree   |
+ * | `lseg`                      | `elephantry::Segment`     | geo     |
+ * | `ltree`                     | `elephantry::Ltree`       | ltree   |
+ * | `null`                      | `()`                      |         |
+ * | `macaddr`                   | `macaddr::MacAddr6`       | net     |
+ * | `macaddr8`                  | `macaddr::MacAddr8`       | net     |
+ * | `money`                     | `f32`                     |         |
+ * | `numeric`                   | `bigdecimal::BigDecimal`  | numeric |
+ * | `path`                      | `elephantry::Path`        | geo     |
+ * | `point`                     | `elephantry::Point`       | geo     |
+ * | `polygon`                   | `elephantry::Polygon`     | geo     |
+ * | `real`/`float4`             | `f32`                     |         |
+ * | `record`                    | `tuple`                   |         |
+ * | `smallint`                  | `i16`                     |         |
+ * | `text`                      | `String`                  |         |
+ * | `text`                      | `std::path::PathBuf`      |         |
+ * | `text`                      | `url::Url`                | url     |
+ * | `time`                      | `elephantry::Time`        | time    |
+ * | `timetz`                    | `elephantry::TimeTz`      | time    |
+ * | `timestamp`                 | `chrono::NaiveDateTime`   | date    |
+ * | `timestamp`                 | `jiff::civil::DateTime`   | jiff    |
+ * | `timestamp`                 | `time::PrimitiveDateTime` | time    |
+ * | `timestamptz`               | `chrono::DateTime`        | date    |
+ * | `timestamptz`               | `jiff::Timestamp`         | jiff    |
+ * | `timestamptz`               | `jiff::Zoned`             | jiff    |
+ * | `timestamptz`               | `time::OffsetDateTime`    | time    |
+ * | `uuid`                      | `uuid::Uuid`              | uuid    |
+ * | `xml`                       | `xmltree::Element`        | xml     |
  */
 
+/** Timing helpers, to compare configurations. */
+pub mod bench;
+/** Entity lifecycle events. */
+pub mod events;
 /** database inspection module. */
 pub mod inspect;
+/** LISTEN consumer. */
+pub mod listener;
 /** libpq abstraction layer. */
 pub mod pq;
+/** Common traits and types, to glob import in small programs. */
+pub mod prelude;
 #[cfg(feature = "r2d2")]
 #[cfg_attr(docsrs, doc(cfg(feature = "r2d2")))]
 pub mod r2d2;
@@ -54,42 +79,106 @@ pub mod r2d2;
 #[doc(hidden)]
 pub mod rocket;
 pub mod transaction;
+/** Conversion of errors to HTTP responses. */
+pub mod web;
 
+mod advisory_lock;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod association;
 mod r#async;
+#[cfg(all(feature = "async", unix))]
+mod async_connection;
+mod bounded_pool;
+mod cipher;
 mod config;
 mod connection;
+mod copy;
+mod ddl;
+#[cfg(feature = "serde")]
+mod de;
+mod distinct;
 mod entity;
 mod errors;
+mod export;
 mod from_sql;
+mod identifier;
+#[cfg(feature = "import")]
+mod import;
+mod masking;
 mod model;
 mod pager;
+mod param;
 mod pool;
 mod projection;
+mod reference;
+mod retry;
+mod row;
 mod rows;
+#[cfg(feature = "serde")]
+mod ser;
 mod sql;
+mod statement;
+mod stats;
+mod stream;
 mod structure;
+mod tag;
+mod timezone;
 mod to_sql;
 mod tuple;
+mod upsert;
+mod value;
+mod watchdog;
 mod r#where;
 
 pub use crate::config::*;
+pub use advisory_lock::*;
+pub use association::{BelongsTo, HasMany};
+#[cfg(all(feature = "async", unix))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use async_connection::*;
+pub use bounded_pool::*;
+pub use cipher::*;
 pub use connection::*;
+pub use copy::*;
+pub use ddl::*;
+#[cfg(feature = "serde")]
+pub use de::*;
+pub use distinct::*;
 pub use elephantry_derive::*;
 pub use entity::*;
 pub use errors::*;
 pub use from_sql::*;
+#[cfg(feature = "import")]
+pub use import::*;
+pub use listener::Listener;
+pub use masking::*;
 pub use model::*;
 pub use pager::*;
+pub use param::*;
 pub use pool::*;
 pub use projection::*;
 pub use r#async::*;
 pub use r#where::*;
+pub use reference::*;
+pub use retry::*;
+pub use row::*;
 pub use rows::*;
+#[cfg(feature = "serde")]
+pub use ser::*;
 pub use sql::*;
+pub use statement::Statement;
+pub use stats::{Status, WaitTime};
+pub use stream::*;
 pub use structure::*;
+pub use tag::QueryContext;
+pub use timezone::*;
 pub use to_sql::*;
 pub use transaction::Transaction;
 pub use tuple::*;
+pub use upsert::*;
+pub use watchdog::TransactionWatchdog;
+pub use watchdog::WatchdogAction;
 
 /**
  * Easily create pk argument for where clause, including [`find_by_pk`]
@@ -153,6 +242,77 @@ macro_rules! values {
     }}
 }
 
+/**
+ * Creates the parameters slice of queries, temporary values are borrowed
+ * until the end of the statement.
+ *
+ * ```
+ * # #[macro_use] extern crate elephantry;
+ * # fn main() {
+ * # let name = "";
+ * let params: &[&dyn elephantry::ToSql] = params![name, 1 + 1, name.to_string()];
+ * # }
+ * ```
+ */
+#[macro_export]
+macro_rules! params {
+    ($($param:expr),* $(,)?) => {
+        &[$(&$param as &dyn $crate::ToSql),*] as &[&dyn $crate::ToSql]
+    };
+}
+
+/**
+ * Declares an entity with its structure and model, for quick scripts which
+ * don’t need the flexibility of the [`Entity`] and [`Model`] derives.
+ *
+ * The fields are public, the structure and the model are named after the
+ * entity, like `EventStructure` and `EventModel`.
+ *
+ * ```
+ * elephantry::model!(Event { uuid: Option<String>, name: String } from "public.event" pk uuid);
+ *
+ * use elephantry::Structure;
+ *
+ * assert_eq!(EventStructure::relation(), "public.event");
+ * assert_eq!(EventStructure::primary_key(), &["uuid"]);
+ * assert_eq!(EventStructure::columns(), &["uuid", "name"]);
+ * ```
+ *
+ * [`Entity`]: derive@crate::Entity
+ * [`Model`]: derive@crate::Model
+ */
+#[macro_export]
+macro_rules! model {
+    (
+        $(#[$meta:meta])*
+        $vis:vis $name:ident { $($field:ident : $ty:ty),* $(,)? }
+        from $relation:literal $(pk $($pk:ident),+)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, $crate::Entity, $crate::Model)]
+        #[elephantry(relation = $relation $(, pk($($pk),+))?)]
+        $vis struct $name {
+            $(pub $field: $ty),*
+        }
+    };
+}
+
+/**
+ * Opens a connection, for applications that need only one and don’t want to
+ * set up a [`Pool`].
+ *
+ * ```no_run
+ * use elephantry::prelude::*;
+ *
+ * let connection = elephantry::connect("postgres://localhost/elephantry")?;
+ * let answer = connection.query_one::<i32>("select 42", &[])?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+pub fn connect(dsn: &str) -> Result<Connection> {
+    Connection::new(dsn)
+}
+
 #[cfg(test)]
 mod test {
     static INIT: std::sync::Once = std::sync::Once::new();
@@ -240,6 +400,8 @@ mod test {
 
         let conn = crate::Pool::new(&dsn())?;
         conn.execute("create extension if not exists hstore")?;
+        conn.execute("create extension if not exists ltree")?;
+        conn.execute("create extension if not exists citext")?;
         conn.execute("set lc_monetary to 'en_US.UTF-8';")?;
         conn.execute(
             "
@@ -270,6 +432,21 @@ end$$;
         Ok(conn)
     }
 
+    pub fn create_entity_table(conn: &crate::Connection, primary_key: bool) -> crate::Result {
+        let employee_id = if primary_key {
+            "employee_id int primary key"
+        } else {
+            "employee_id int"
+        };
+
+        conn.execute(&format!(
+            "create temporary table entity ({}, first_name text, last_name text)",
+            employee_id
+        ))?;
+
+        Ok(())
+    }
+
     #[test]
     fn test_pk_one() {
         let uuid = "1234";
@@ -302,6 +479,23 @@ end$$;
         assert!(pk.contains_key("name"));
     }
 
+    #[test]
+    fn test_params() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let name = "name";
+
+        let results = conn.query::<i32>(
+            "select $1 + $2 + length($3)::int",
+            crate::params![1, 1 + 1, name.to_string()],
+        )?;
+        assert_eq!(results.get(0), 7);
+
+        let params = crate::params![];
+        assert!(params.is_empty());
+
+        Ok(())
+    }
+
     #[derive(elephantry_derive::Entity)]
     #[elephantry(
         internal,
@@ -0,0 +1,259 @@
+use std::collections::{HashMap, HashSet};
+
+type Callback<'c> = Box<dyn FnMut(&crate::pq::Notify) + 'c>;
+
+/**
+ * Event returned by [`Listener::poll`].
+ *
+ * [`Listener::poll`]: crate::listener::Listener::poll
+ */
+#[derive(Debug)]
+pub enum Event {
+    /** A notification was received. */
+    Notification(crate::pq::Notify),
+    /**
+     * The connection was reset and the channels listened again.
+     * Notifications sent in the meantime are lost, you should reconcile your
+     * state.
+     */
+    Resubscribed,
+}
+
+/**
+ * LISTEN consumer resilient to connection reset.
+ *
+ * This `struct` tracks the listened channels and listens them again after a
+ * connection reset.
+ *
+ * Notifications can be polled, waited for or dispatched to callbacks:
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * let mut listener = elephantry::Listener::new(&connection);
+ * listener.subscribe("orders", |notify| println!("new order {}", notify.extra()))?;
+ * listener.on_resubscribe(|| println!("notifications may have been lost"));
+ * listener.run()?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+pub struct Listener<'c> {
+    connection: &'c crate::Connection,
+    channels: HashSet<String>,
+    callbacks: HashMap<String, Vec<Callback<'c>>>,
+    on_resubscribe: Option<Box<dyn FnMut() + 'c>>,
+}
+
+impl<'c> Listener<'c> {
+    pub fn new(connection: &'c crate::Connection) -> Self {
+        Self {
+            connection,
+            channels: HashSet::new(),
+            callbacks: HashMap::new(),
+            on_resubscribe: None,
+        }
+    }
+
+    /**
+     * Start to listen on the given channel.
+     */
+    pub fn listen(&mut self, channel: &str) -> crate::Result {
+        self.connection.listen(channel)?;
+        self.channels.insert(channel.to_string());
+
+        Ok(())
+    }
+
+    /**
+     * Stop to listen on the given channel.
+     */
+    pub fn unlisten(&mut self, channel: &str) -> crate::Result {
+        self.connection.unlisten(channel)?;
+        self.channels.remove(channel);
+        self.callbacks.remove(channel);
+
+        Ok(())
+    }
+
+    /**
+     * Listens on `channel` and calls `callback` for each notification
+     * dispatched on it.
+     */
+    pub fn subscribe<F>(&mut self, channel: &str, callback: F) -> crate::Result
+    where
+        F: FnMut(&crate::pq::Notify) + 'c,
+    {
+        if !self.channels.contains(channel) {
+            self.listen(channel)?;
+        }
+
+        self.callbacks
+            .entry(channel.to_string())
+            .or_default()
+            .push(Box::new(callback));
+
+        Ok(())
+    }
+
+    /**
+     * Calls `callback` when the channels are listened again after a
+     * connection reset.
+     */
+    pub fn on_resubscribe<F: FnMut() + 'c>(&mut self, callback: F) {
+        self.on_resubscribe = Some(Box::new(callback));
+    }
+
+    /**
+     * Listened channels.
+     */
+    pub fn channels(&self) -> &HashSet<String> {
+        &self.channels
+    }
+
+    /**
+     * Check if an event is pending. Otherwise, `None` is returned.
+     */
+    pub fn poll(&mut self) -> crate::Result<Option<Event>> {
+        if self.connection.has_broken()? {
+            return self.resubscribe();
+        }
+
+        match self.connection.notifies()? {
+            Some(notify) => Ok(Some(Event::Notification(notify))),
+            None if self.connection.has_broken()? => self.resubscribe(),
+            None => Ok(None),
+        }
+    }
+
+    /**
+     * Waits for an event, up to `timeout` or forever if `None`.
+     */
+    #[cfg(unix)]
+    pub fn wait(&mut self, timeout: Option<std::time::Duration>) -> crate::Result<Option<Event>> {
+        if self.connection.has_broken()? {
+            return self.resubscribe();
+        }
+
+        match self.connection.wait_for_notification(timeout) {
+            Ok(notify) => Ok(notify.map(Event::Notification)),
+            Err(_) if self.connection.has_broken()? => self.resubscribe(),
+            Err(err) => Err(err),
+        }
+    }
+
+    /**
+     * Waits for an event, up to `timeout` or forever if `None`, and calls
+     * the matching callbacks. Returns `false` on timeout.
+     */
+    #[cfg(unix)]
+    pub fn dispatch(&mut self, timeout: Option<std::time::Duration>) -> crate::Result<bool> {
+        match self.wait(timeout)? {
+            Some(Event::Notification(notify)) => {
+                if let Some(callbacks) = self.callbacks.get_mut(&notify.relname()) {
+                    for callback in callbacks {
+                        callback(&notify);
+                    }
+                }
+            }
+            Some(Event::Resubscribed) => {
+                if let Some(callback) = &mut self.on_resubscribe {
+                    callback();
+                }
+            }
+            None => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /**
+     * Dispatches the events until an error occurs.
+     */
+    #[cfg(unix)]
+    pub fn run(&mut self) -> crate::Result {
+        loop {
+            self.dispatch(None)?;
+        }
+    }
+
+    fn resubscribe(&self) -> crate::Result<Option<Event>> {
+        self.connection.reset()?;
+
+        for channel in &self.channels {
+            self.connection.listen(channel)?;
+        }
+
+        Ok(Some(Event::Resubscribed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn wait(listener: &mut crate::Listener<'_>) -> crate::Result<Option<crate::listener::Event>> {
+        for _ in 0..10 {
+            if let Some(event) = listener.poll()? {
+                return Ok(Some(event));
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+
+        Ok(None)
+    }
+
+    #[test]
+    fn resubscribe() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let other = crate::test::new_conn()?;
+
+        let mut listener = crate::Listener::new(&conn);
+        listener.listen("listener")?;
+
+        let pid = conn.query_one::<i32>("select pg_backend_pid()", &[])?;
+        other.query_one::<bool>("select pg_terminate_backend($1)", &[&pid])?;
+
+        assert!(matches!(
+            wait(&mut listener)?,
+            Some(crate::listener::Event::Resubscribed)
+        ));
+
+        other.notify("listener", Some("payload"))?;
+
+        assert!(matches!(
+            wait(&mut listener)?,
+            Some(crate::listener::Event::Notification(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dispatch() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let other = crate::test::new_conn()?;
+        let payloads = std::cell::RefCell::new(Vec::new());
+
+        let mut listener = crate::Listener::new(&conn);
+        listener.subscribe("dispatch", |notify| {
+            payloads.borrow_mut().push(notify.extra())
+        })?;
+        listener.listen("ignored")?;
+
+        assert!(!listener.dispatch(Some(std::time::Duration::from_millis(20)))?);
+
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            other.notify("ignored", None)?;
+            other.notify("dispatch", Some("payload"))
+        });
+
+        let timeout = Some(std::time::Duration::from_secs(5));
+        assert!(listener.dispatch(timeout)?);
+        assert!(listener.dispatch(timeout)?);
+        thread.join().unwrap()?;
+        drop(listener);
+
+        assert_eq!(payloads.into_inner(), vec!["payload".to_string()]);
+
+        Ok(())
+    }
+}
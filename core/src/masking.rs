@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+/**
+ * How a masked column is rewritten.
+ *
+ * `Hash` and `Partial` return a text value.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mask {
+    /** Replaces the value by `NULL`. */
+    Null,
+    /** Replaces the value by its md5 hash. */
+    Hash,
+    /** Keeps the first `visible` characters and replaces the others by `*`. */
+    Partial { visible: usize },
+    /** Replaces the value by this constant. */
+    Constant(String),
+    /** SQL expression, `{}` is replaced by the column. */
+    Sql(String),
+}
+
+impl Mask {
+    fn apply(&self, row: &str) -> String {
+        match self {
+            Self::Null => "null".to_string(),
+            Self::Hash => format!("md5(({})::text)", row),
+            Self::Partial { visible } => format!(
+                "left(({0})::text, {1}) || repeat('*', greatest(length(({0})::text) - {1}, 0))",
+                row, visible
+            ),
+            Self::Constant(value) => format!("'{}'", value.replace('\'', "''")),
+            Self::Sql(sql) => sql.replace("{}", &format!("({})", row)),
+        }
+    }
+}
+
+/**
+ * Rewrites columns of the generated projections, to read production-like
+ * data without exposing sensitive values.
+ *
+ * Only the queries generated from models are masked, not raw SQL queries.
+ *
+ * ```no_run
+ * # mod employee {
+ * # #[derive(elephantry::Entity)]
+ * # #[elephantry(model = "Model", structure = "Structure")]
+ * # pub struct Employee { email: String }
+ * # }
+ * # let connection = elephantry::Connection::new("")?;
+ * let policy = elephantry::MaskingPolicy::new()
+ *     .mask::<employee::Model<'_>>("email", elephantry::Mask::Hash);
+ *
+ * connection.set_masking_policy(Some(policy))?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+#[derive(Clone, Debug, Default)]
+pub struct MaskingPolicy {
+    rules: HashMap<String, HashMap<String, Mask>>,
+}
+
+impl MaskingPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Masks the field `field` of the model `M`.
+     */
+    pub fn mask<'a, M: crate::Model<'a>>(self, field: &str, mask: Mask) -> Self {
+        use crate::Structure;
+
+        self.mask_relation(M::Structure::relation(), field, mask)
+    }
+
+    /**
+     * Masks the field `field` of all models of the relation `relation`.
+     */
+    pub fn mask_relation(mut self, relation: &str, field: &str, mask: Mask) -> Self {
+        self.rules
+            .entry(relation.to_string())
+            .or_default()
+            .insert(field.to_string(), mask);
+
+        self
+    }
+
+    /**
+     * Rewrites the masked fields of `projection`.
+     */
+    pub(crate) fn apply(
+        &self,
+        relation: &str,
+        mut projection: crate::Projection,
+    ) -> crate::Projection {
+        let rules = match self.rules.get(relation) {
+            Some(rules) => rules,
+            None => return projection,
+        };
+
+        for (field, mask) in rules {
+            let row = match projection.fields().get(field) {
+                Some(row) => mask.apply(row),
+                None => continue,
+            };
+
+            projection = projection.add_field(field, &row);
+        }
+
+        projection
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn mask() {
+        assert_eq!(
+            crate::Mask::Hash.apply("%:email:%"),
+            "md5((%:email:%)::text)"
+        );
+        assert_eq!(
+            crate::Mask::Constant("it's secret".to_string()).apply("%:email:%"),
+            "'it''s secret'"
+        );
+        assert_eq!(
+            crate::Mask::Sql("lower({})".to_string()).apply("%:email:%"),
+            "lower((%:email:%))"
+        );
+    }
+
+    #[test]
+    fn masking_policy() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        crate::test::create_entity_table(&connection, true)?;
+        connection.execute("insert into entity values (1, 'Ada', 'Lovelace')")?;
+
+        let policy = crate::MaskingPolicy::new()
+            .mask::<crate::test::Model<'_>>("first_name", crate::Mask::Partial { visible: 1 })
+            .mask::<crate::test::Model<'_>>("last_name", crate::Mask::Hash);
+        connection.set_masking_policy(Some(policy))?;
+
+        let entity = connection
+            .find_by_pk::<crate::test::Model<'_>>(&crate::pk!(employee_id => 1))?
+            .unwrap();
+        assert_eq!(entity.first_name, "A**");
+        assert_eq!(
+            entity.last_name,
+            connection.query_one::<String>("select md5('Lovelace')", &[])?
+        );
+
+        connection.set_masking_policy(None)?;
+        let entity = connection
+            .find_by_pk::<crate::test::Model<'_>>(&crate::pk!(employee_id => 1))?
+            .unwrap();
+        assert_eq!(entity.first_name, "Ada");
+
+        Ok(())
+    }
+}
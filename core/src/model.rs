@@ -58,4 +58,208 @@ pub trait Model<'a> {
 
         Ok(pk)
     }
+
+    /**
+     * Server side prepares the standard find by primary key, insert, update
+     * and delete queries of this model on `connection`. Once prepared,
+     * [`Connection::find_by_pk`], [`Connection::insert_one`],
+     * [`Connection::update_one`] and [`Connection::delete_one`] use them
+     * automatically.
+     *
+     * [`Connection::find_by_pk`]: crate::Connection::find_by_pk
+     * [`Connection::insert_one`]: crate::Connection::insert_one
+     * [`Connection::update_one`]: crate::Connection::update_one
+     * [`Connection::delete_one`]: crate::Connection::delete_one
+     */
+    fn prepare_crud(connection: &crate::Connection) -> crate::Result
+    where
+        Self: Sized,
+    {
+        connection.prepare_crud::<Self>()
+    }
+}
+
+/**
+ * Typed primary key of a model, implemented by the [`Model`] derive for the
+ * models with a primary key. The typed helpers aren’t available for the
+ * other ones:
+ *
+ * ```compile_fail
+ * #[derive(elephantry::Entity)]
+ * #[elephantry(model = "Model", structure = "Structure", relation = "event")]
+ * struct Event {
+ *     name: String,
+ * }
+ *
+ * # let connection = elephantry::Connection::new("")?;
+ * connection.find_by_primary_key::<Model>(&"name".to_string())?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * [`Model`]: derive@crate::Model
+ */
+pub trait HasPrimaryKey<'a>: Model<'a> {
+    /**
+     * Type of the primary key: the field type for a single column primary
+     * key, a tuple for a composite one.
+     */
+    type PrimaryKey;
+
+    /**
+     * Maps a typed primary key value to its columns, as expected by
+     * [`Connection::find_by_pk`].
+     *
+     * [`Connection::find_by_pk`]: crate::Connection::find_by_pk
+     */
+    fn primary_key_values(
+        pk: &Self::PrimaryKey,
+    ) -> crate::Result<HashMap<&'static str, &dyn crate::ToSql>>;
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "Model",
+        structure = "Structure",
+        relation = "membership"
+    )]
+    struct Membership {
+        #[elephantry(pk)]
+        group_id: i32,
+        #[elephantry(pk, column = "user_name")]
+        user: String,
+        role: String,
+    }
+
+    #[test]
+    fn primary_key() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, true)?;
+        conn.execute(
+            "create temporary table membership (group_id int, user_name text, role text, primary key (group_id, user_name));
+            insert into membership values (1, 'alice', 'admin'), (1, 'bob', 'member');
+            insert into entity values (1, 'Alice', 'Liddell');",
+        )?;
+
+        let membership = conn
+            .find_by_primary_key::<Model<'_>>(&(1, "bob".to_string()))?
+            .unwrap();
+        assert_eq!(membership.role, "member");
+        assert!(conn
+            .find_by_primary_key::<Model<'_>>(&(2, "bob".to_string()))?
+            .is_none());
+
+        conn.delete_by_primary_key::<Model<'_>>(&(1, "alice".to_string()))?;
+        assert_eq!(conn.count_where::<Model<'_>>("true", &[])?, 1);
+
+        let entity = conn
+            .find_by_primary_key::<crate::test::Model<'_>>(&1)?
+            .unwrap();
+        assert_eq!(entity.first_name, "Alice");
+
+        Ok(())
+    }
+
+    #[test]
+    fn prepare_crud() -> crate::Result {
+        use crate::Model as _;
+
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, true)?;
+
+        crate::test::Model::prepare_crud(&conn)?;
+        assert_eq!(
+            conn.query_one::<i64>("select count(*) from pg_prepared_statements", &[])?,
+            4
+        );
+
+        let mut entity = crate::test::Entity {
+            id: 1,
+            first_name: "Alice".to_string(),
+            last_name: "Liddell".to_string(),
+        };
+        conn.insert_one::<crate::test::Model<'_>>(&entity)?;
+
+        entity.first_name = "Bob".to_string();
+        conn.update_one::<crate::test::Model<'_>>(&crate::pk!(employee_id => 1), &entity)?;
+
+        let entity = conn
+            .find_by_pk::<crate::test::Model<'_>>(&crate::pk!(employee_id => 1))?
+            .unwrap();
+        assert_eq!(entity.first_name, "Bob");
+
+        let calls = conn.query_one::<i64>(
+            "select count(*) from pg_prepared_statements where generic_plans + custom_plans > 0",
+            &[],
+        )?;
+        assert_eq!(calls, 3);
+
+        conn.delete_one::<crate::test::Model<'_>>(&entity)?;
+        assert!(!conn.exist_where::<crate::test::Model<'_>>("true", &[])?);
+
+        conn.execute("deallocate all")?;
+        conn.insert_one::<crate::test::Model<'_>>(&entity)?;
+
+        Ok(())
+    }
+
+    #[derive(elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.counter", pk = "id")]
+    struct Counter {
+        id: i64,
+        value: i32,
+    }
+
+    #[test]
+    fn prepare_crud_types() -> crate::Result {
+        use crate::Model as _;
+
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table counter (id int8 primary key, value int)")?;
+
+        CounterModel::prepare_crud(&conn)?;
+
+        let mut counter = conn.insert_one::<CounterModel<'_>>(&Counter { id: 1, value: 1 })?;
+        counter.value = 2;
+        // The server infers an `int8` parameter
+        conn.update_one::<CounterModel<'_>>(&crate::pk!(id => 1_i32), &counter)?;
+
+        let counter = conn
+            .find_by_pk::<CounterModel<'_>>(&crate::pk!(id => 1_i32))?
+            .unwrap();
+        assert_eq!(counter.value, 2);
+
+        Ok(())
+    }
+
+    #[derive(elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.event", pk = "uuid")]
+    struct Event {
+        uuid: String,
+        name: String,
+    }
+
+    #[test]
+    fn derive_model() -> crate::Result {
+        use crate::Structure;
+
+        assert_eq!(EventStructure::relation(), "pg_temp.event");
+        assert_eq!(EventStructure::primary_key(), &["uuid"]);
+
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table event (uuid text primary key, name text)")?;
+
+        conn.insert_one::<EventModel<'_>>(&Event {
+            uuid: "1".to_string(),
+            name: "launch".to_string(),
+        })?;
+        let event = conn
+            .find_by_primary_key::<EventModel<'_>>(&"1".to_string())?
+            .unwrap();
+        assert_eq!(event.name, "launch");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,173 @@
+/**
+ * Parameter sent with a forced type, to help the server when it can’t infer
+ * it or infers the wrong one.
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * use elephantry::{pq::types, Param};
+ *
+ * let document = r#"{"a": 1}"#;
+ * connection.query::<()>(
+ *     "select $1 ? 'a'",
+ *     &[&Param::with_type(document, types::JSONB)],
+ * )?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * The value is sent as is if the type is unchanged, otherwise with its text
+ * representation for the server to parse it as the forced type.
+ */
+#[derive(Clone, Debug)]
+pub struct Param<T> {
+    value: T,
+    ty: crate::pq::Type,
+}
+
+impl<T: crate::ToSql> Param<T> {
+    pub fn with_type(value: T, ty: crate::pq::Type) -> Self {
+        Self { value, ty }
+    }
+
+    /**
+     * Lets the server infer the type, as a quoted literal in the query.
+     */
+    pub fn unknown(value: T) -> Self {
+        Self::with_type(value, crate::pq::types::UNKNOWN)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    fn is_converted(&self) -> bool {
+        self.value.ty().oid != self.ty.oid
+    }
+}
+
+impl<T: crate::ToSql> crate::ToSql for Param<T> {
+    fn ty(&self) -> crate::pq::Type {
+        self.ty.clone()
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        if self.is_converted() {
+            crate::pq::Format::Text
+        } else {
+            self.value.format()
+        }
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        if !self.is_converted() {
+            return self.value.to_sql();
+        }
+
+        match self.value.to_text()? {
+            Some(text) => text.as_str().to_sql(),
+            None => Ok(None),
+        }
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        self.value.to_text()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.value.type_name()
+    }
+}
+
+/**
+ * `NULL` parameter of a given type.
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * use elephantry::{pq::types, Null};
+ *
+ * connection.query::<()>(
+ *     "select coalesce($1, current_date)",
+ *     &[&Null(types::DATE)],
+ * )?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct Null(pub crate::pq::Type);
+
+impl crate::ToSql for Null {
+    fn ty(&self) -> crate::pq::Type {
+        self.0.clone()
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pq::types;
+    use crate::{Null, Param};
+
+    #[test]
+    fn with_type() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert!(conn.query_one::<bool>(
+            "select $1 ? 'a'",
+            &[&Param::with_type(r#"{"a": 1}"#, types::JSONB)],
+        )?);
+        assert_eq!(
+            conn.query_one::<String>(
+                "select pg_typeof($1)::text",
+                &[&Param::with_type(1_i32, types::INT8)],
+            )?,
+            "bigint"
+        );
+        assert_eq!(
+            conn.query_one::<i32>("select $1 + 1", &[&Param::with_type(1_i32, types::INT4)])?,
+            2
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert_eq!(
+            conn.query_one::<String>("select pg_typeof($1 + 1)::text", &[&Param::unknown("1")])?,
+            "integer"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn null() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table null_param (day date)")?;
+
+        assert_eq!(
+            conn.query_one::<String>("select pg_typeof($1)::text", &[&Null(types::INT4)])?,
+            "integer"
+        );
+        assert_eq!(
+            conn.query_one::<Option<i32>>("select $1 + 1", &[&Null(types::INT4)])?,
+            None
+        );
+
+        conn.query::<()>("insert into null_param values ($1)", &[&Null(types::DATE)])?;
+        assert_eq!(
+            conn.query_one::<i64>("select count(*) from null_param where day is null", &[])?,
+            1
+        );
+
+        Ok(())
+    }
+}
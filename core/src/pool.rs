@@ -1,5 +1,33 @@
 use std::collections::HashMap;
 
+type Callback = std::sync::Arc<dyn Fn(&str, std::time::Duration) + Send + Sync>;
+
+#[derive(Clone)]
+struct CheckoutTimeout {
+    timeout: std::time::Duration,
+    callback: Callback,
+}
+
+impl CheckoutTimeout {
+    fn watch(&self, name: &str, connection: &crate::Connection) {
+        let name = name.to_string();
+        let callback = self.callback.clone();
+
+        connection.stats.set_checkout_timeout(
+            self.timeout,
+            std::sync::Arc::new(move |duration| callback(&name, duration)),
+        );
+    }
+}
+
+impl std::fmt::Debug for CheckoutTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckoutTimeout")
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 /**
  * Connections pool.
  */
@@ -7,6 +35,10 @@ use std::collections::HashMap;
 pub struct Pool {
     default: String,
     connections: HashMap<String, crate::Connection>,
+    dsns: HashMap<String, String>,
+    checkout_timeout: Option<CheckoutTimeout>,
+    /* Shared by the clones, so a closed pool doesn’t hand out connections */
+    closed: std::sync::Arc<std::sync::atomic::AtomicBool>,
 }
 
 impl Pool {
@@ -23,7 +55,14 @@ impl Pool {
      * [`Config`]: crate::Config
      */
     pub fn from_config(config: &crate::Config) -> crate::Result<Self> {
-        Self::default().add_default("default", &config.to_string())
+        let mut pool = Self::default();
+        let connection = crate::Connection::from_config(config)?;
+
+        pool.connections.insert("default".to_string(), connection);
+        pool.dsns.insert("default".to_string(), config.to_string());
+        pool.set_default("default")?;
+
+        Ok(pool)
     }
 
     /**
@@ -41,8 +80,10 @@ impl Pool {
     }
 
     fn add(mut self, name: &str, url: &str, default: bool) -> crate::Result<Self> {
-        self.connections
-            .insert(name.to_string(), crate::Connection::new(url)?);
+        let connection = self.connect(name, url)?;
+
+        self.connections.insert(name.to_string(), connection);
+        self.dsns.insert(name.to_string(), url.to_string());
 
         if default {
             self.set_default(name)?;
@@ -52,10 +93,10 @@ impl Pool {
     }
 
     /**
-     * Retreive the default connection.
+     * Retreive the default connection, `None` once the pool is closed.
      */
     pub fn get_default(&self) -> Option<&crate::Connection> {
-        self.connections.get(&self.default)
+        self.get(&self.default)
     }
 
     /**
@@ -78,17 +119,164 @@ impl Pool {
     }
 
     /**
-     * Retreive the connection `name`, on `None` if not exists.
+     * Retreive the connection `name`, on `None` if not exists or once the
+     * pool is closed.
      */
     pub fn get(&self, name: &str) -> Option<&crate::Connection> {
-        self.connections.get(&name.to_string())
+        if self.is_closed() {
+            return None;
+        }
+
+        self.connections.get(name)
+    }
+
+    /**
+     * Returns `true` once the pool, or one of its clones, is closed.
+     */
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(std::sync::atomic::Ordering::SeqCst)
     }
 
     /**
      * Remove the connection `name`.
      */
     pub fn remove(&mut self, name: &str) {
-        self.connections.remove(&name.to_string());
+        self.connections.remove(name);
+        self.dsns.remove(name);
+    }
+
+    /**
+     * Reloads connections from the DSNs returned by `source`, indexed by
+     * connection name.
+     *
+     * New and changed connections are established before anything is
+     * replaced: on error, the pool is left untouched. Unchanged connections
+     * are kept. Removed and replaced connections are closed once their
+     * in-flight query is finished: their clones, kept by users or by other
+     * clones of the pool, fail with [`Error::Closed`] and have to be
+     * retreived again from the pool.
+     *
+     * [`Error::Closed`]: crate::Error::Closed
+     */
+    pub fn reload_config<F>(&mut self, source: F) -> crate::Result
+    where
+        F: FnOnce() -> crate::Result<HashMap<String, String>>,
+    {
+        let dsns = source()?;
+
+        if !self.default.is_empty() && !dsns.contains_key(&self.default) {
+            return Err(crate::Error::Connect {
+                dsn: self.default.clone(),
+                message: format!(
+                    "Unable to remove {} connection, it's the default one",
+                    self.default
+                ),
+            });
+        }
+
+        let mut connections = HashMap::new();
+
+        for (name, dsn) in &dsns {
+            let connection = match (self.dsns.get(name), self.connections.get(name)) {
+                (Some(old), Some(connection)) if old == dsn => connection.clone(),
+                _ => self.connect(name, dsn)?,
+            };
+
+            connections.insert(name.clone(), connection);
+        }
+
+        let old_connections = std::mem::replace(&mut self.connections, connections);
+        let old_dsns = std::mem::replace(&mut self.dsns, dsns);
+
+        for (name, connection) in old_connections {
+            if old_dsns.get(&name) != self.dsns.get(&name) {
+                connection.close();
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Retreives the status of each connection, indexed by name.
+     */
+    pub fn status(&self) -> HashMap<String, crate::Status> {
+        self.connections
+            .iter()
+            .map(|(name, connection)| (name.clone(), connection.status()))
+            .collect()
+    }
+
+    /**
+     * Calls `callback` with the connection name and the waited time each time
+     * a thread waits more than `timeout` to use a connection.
+     */
+    pub fn on_checkout_timeout<F>(&mut self, timeout: std::time::Duration, callback: F)
+    where
+        F: Fn(&str, std::time::Duration) + Send + Sync + 'static,
+    {
+        let checkout_timeout = CheckoutTimeout {
+            timeout,
+            callback: std::sync::Arc::new(callback),
+        };
+
+        for (name, connection) in &self.connections {
+            checkout_timeout.watch(name, connection);
+        }
+
+        self.checkout_timeout = Some(checkout_timeout);
+    }
+
+    /**
+     * Closes the pool: neither it nor its clones hand out connections
+     * anymore, and the checkout timeout callbacks are dropped. Waits up to
+     * `timeout` for in-flight queries to finish, then closes every
+     * connection: their remaining clones fail with [`Error::Closed`].
+     *
+     * Returns [`Error::Timeout`] if queries are still running after
+     * `timeout`: the idle connections are closed, the busy ones once their
+     * last clone is dropped.
+     *
+     * [`Error::Closed`]: crate::Error::Closed
+     * [`Error::Timeout`]: crate::Error::Timeout
+     */
+    pub fn close(self, timeout: std::time::Duration) -> crate::Result {
+        self.closed.store(true, std::sync::atomic::Ordering::SeqCst);
+
+        for connection in self.connections.values() {
+            connection.stats.clear_checkout_timeout();
+        }
+
+        let deadline = std::time::Instant::now() + timeout;
+
+        while self.connections.values().any(|x| x.status().in_use) {
+            if std::time::Instant::now() >= deadline {
+                self.connections
+                    .values()
+                    .filter(|x| !x.status().in_use)
+                    .for_each(crate::Connection::close);
+
+                return Err(crate::Error::Timeout(timeout));
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        for connection in self.connections.values() {
+            connection.close();
+        }
+
+        Ok(())
+    }
+
+    fn connect(&self, name: &str, url: &str) -> crate::Result<crate::Connection> {
+        let connection = crate::Connection::new(url)?;
+
+        if let Some(checkout_timeout) = &self.checkout_timeout {
+            checkout_timeout.watch(name, &connection);
+        }
+
+        Ok(connection)
     }
 }
 
@@ -97,6 +285,9 @@ impl Default for Pool {
         Self {
             default: String::new(),
             connections: HashMap::new(),
+            dsns: HashMap::new(),
+            checkout_timeout: None,
+            closed: Default::default(),
         }
     }
 }
@@ -116,3 +307,102 @@ impl std::ops::Deref for Pool {
         self.get_default().unwrap()
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn reload_config() -> crate::Result {
+        let mut pool = crate::Pool::new(&crate::test::dsn())?;
+
+        pool.reload_config(|| {
+            let mut dsns = std::collections::HashMap::new();
+            dsns.insert("default".to_string(), crate::test::dsn());
+            dsns.insert("other".to_string(), crate::test::dsn());
+
+            Ok(dsns)
+        })?;
+        assert!(pool.get("other").is_some());
+
+        assert!(pool
+            .reload_config(|| Ok(std::collections::HashMap::new()))
+            .is_err());
+        assert!(pool.get("other").is_some());
+
+        let default = pool.get_default().unwrap().clone();
+        let other = pool["other"].clone();
+        pool.reload_config(|| {
+            let mut dsns = std::collections::HashMap::new();
+            dsns.insert("default".to_string(), crate::test::dsn());
+
+            Ok(dsns)
+        })?;
+        assert!(pool.get("other").is_none());
+        assert!(default.execute("select 1").is_ok());
+        assert!(matches!(
+            other.execute("select 1"),
+            Err(crate::Error::Closed)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn status() -> crate::Result {
+        let mut pool = crate::Pool::new(&crate::test::dsn())?;
+
+        let timeouts = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = timeouts.clone();
+        let default = pool.get_default().unwrap().clone();
+        pool.on_checkout_timeout(std::time::Duration::from_millis(50), move |name, _| {
+            assert_eq!(name, "default");
+            // The connection is released before calling the callback
+            assert!(!default.status().in_use);
+            default.execute("select 1").unwrap();
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        assert!(pool.execute("select 1 / 0").is_err());
+
+        let connection = pool.get_default().unwrap().clone();
+        let thread = std::thread::spawn(move || connection.execute("select pg_sleep(0.2)"));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(pool.status()["default"].in_use);
+        pool.execute("select 1")?;
+        thread.join().unwrap()?;
+
+        let status = &pool.status()["default"];
+        assert!(!status.in_use);
+        assert_eq!(status.errors, 1);
+        assert!(status.wait_time.max >= std::time::Duration::from_millis(50));
+        assert_eq!(timeouts.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn close() -> crate::Result {
+        let pool = crate::Pool::new(&crate::test::dsn())?;
+
+        let connection = pool.get_default().unwrap().clone();
+        let thread = std::thread::spawn(move || connection.execute("select pg_sleep(0.2)"));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        assert!(matches!(
+            pool.clone().close(std::time::Duration::from_millis(10)),
+            Err(crate::Error::Timeout(_))
+        ));
+
+        let clone = pool.clone();
+        assert!(clone.get_default().is_none());
+
+        let connection = pool.connections["default"].clone();
+        pool.close(std::time::Duration::from_secs(1))?;
+        assert!(thread.join().unwrap().is_ok());
+        assert!(matches!(
+            connection.execute("select 1"),
+            Err(crate::Error::Closed)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+/**
+ * Command status tag of a result.
+ *
+ * See [CommandComplete](https://www.postgresql.org/docs/current/protocol-message-formats.html).
+ */
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CommandTag {
+    Insert { oid: crate::pq::Oid, count: u64 },
+    Update(u64),
+    Delete(u64),
+    Select(u64),
+    Copy(u64),
+    Other(String),
+}
+
+impl From<&str> for CommandTag {
+    fn from(tag: &str) -> Self {
+        let parts = tag.split(' ').collect::<Vec<_>>();
+
+        let command_tag = match parts.as_slice() {
+            ["INSERT", oid, count] => match (oid.parse(), count.parse()) {
+                (Ok(oid), Ok(count)) => Some(Self::Insert { oid, count }),
+                _ => None,
+            },
+            ["UPDATE", count] => count.parse().ok().map(Self::Update),
+            ["DELETE", count] => count.parse().ok().map(Self::Delete),
+            ["SELECT", count] => count.parse().ok().map(Self::Select),
+            ["COPY", count] => count.parse().ok().map(Self::Copy),
+            _ => None,
+        };
+
+        command_tag.unwrap_or_else(|| Self::Other(tag.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pq::CommandTag;
+
+    #[test]
+    fn parse() {
+        let tests = [
+            ("INSERT 0 3", CommandTag::Insert { oid: 0, count: 3 }),
+            ("UPDATE 2", CommandTag::Update(2)),
+            ("DELETE 0", CommandTag::Delete(0)),
+            ("SELECT 10", CommandTag::Select(10)),
+            ("COPY 5", CommandTag::Copy(5)),
+            (
+                "CREATE TABLE",
+                CommandTag::Other("CREATE TABLE".to_string()),
+            ),
+            ("MOVE 1", CommandTag::Other("MOVE 1".to_string())),
+        ];
+
+        for (tag, expected) in tests.iter() {
+            assert_eq!(CommandTag::from(*tag), *expected);
+        }
+    }
+}
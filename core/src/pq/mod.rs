@@ -1,7 +1,11 @@
+mod command_tag;
+pub(crate) mod registry;
 mod result;
 mod r#type;
 
+pub use command_tag::*;
 pub use r#type::*;
+pub use registry::Registry;
 pub use result::*;
 
 pub use libpq::connection::Notify;
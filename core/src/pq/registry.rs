@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/**
+ * Per connection cache of the types unknown to libpq.
+ *
+ * Enums, composites, domains or types created by extensions don’t have a
+ * fixed OID: they are looked up from `pg_type` the first time they are seen
+ * and kept here, see [`Connection::type_by_oid`] and
+ * [`Connection::type_oid`].
+ *
+ * Failed lookups are also remembered, until a `create` or `alter` command is
+ * executed by the connection or it’s reset.
+ *
+ * [`Connection::type_by_oid`]: crate::Connection::type_by_oid
+ * [`Connection::type_oid`]: crate::Connection::type_oid
+ */
+#[derive(Debug, Default)]
+pub struct Registry {
+    types: RwLock<HashMap<crate::pq::Oid, crate::pq::Type>>,
+    names: RwLock<HashMap<String, crate::pq::Oid>>,
+    missing_oids: RwLock<HashSet<crate::pq::Oid>>,
+    missing_names: RwLock<HashSet<String>>,
+}
+
+impl Registry {
+    /**
+     * Retreives a built-in or an already resolved type.
+     */
+    pub fn get(&self, oid: crate::pq::Oid) -> Option<crate::pq::Type> {
+        use std::convert::TryFrom;
+
+        if let Ok(ty) = crate::pq::Type::try_from(oid) {
+            return Some(ty);
+        }
+
+        self.types.read().ok()?.get(&oid).cloned()
+    }
+
+    /**
+     * OID of an already resolved type, by name or by schema qualified name.
+     */
+    pub fn oid(&self, name: &str) -> Option<crate::pq::Oid> {
+        self.names.read().ok()?.get(name).copied()
+    }
+
+    /**
+     * Like [`get`], but returns an unknown type instead of `None`.
+     *
+     * [`get`]: Self::get
+     */
+    pub(crate) fn get_or_unknown(&self, oid: crate::pq::Oid) -> crate::pq::Type {
+        self.get(oid).unwrap_or_else(|| unknown(oid))
+    }
+
+    pub(crate) fn is_known(&self, oid: crate::pq::Oid) -> bool {
+        self.get(oid).is_some()
+    }
+
+    /**
+     * `true` if `oid` was already looked up without success.
+     */
+    pub(crate) fn is_missing(&self, oid: crate::pq::Oid) -> bool {
+        self.missing_oids
+            .read()
+            .is_ok_and(|missing| missing.contains(&oid))
+    }
+
+    /**
+     * `true` if `name` was already looked up without success.
+     */
+    pub(crate) fn is_missing_name(&self, name: &str) -> bool {
+        self.missing_names
+            .read()
+            .is_ok_and(|missing| missing.contains(name))
+    }
+
+    /**
+     * Remembers the `oids` still unknown after a lookup.
+     */
+    pub(crate) fn set_missing(&self, oids: &[crate::pq::Oid]) {
+        if let Ok(mut missing) = self.missing_oids.write() {
+            missing.extend(oids.iter().filter(|oid| !self.is_known(**oid)));
+        }
+    }
+
+    /**
+     * Remembers `name` if it’s still unknown after a lookup.
+     */
+    pub(crate) fn set_missing_name(&self, name: &str) {
+        if self.oid(name).is_some() {
+            return;
+        }
+
+        if let Ok(mut missing) = self.missing_names.write() {
+            missing.insert(name.to_string());
+        }
+    }
+
+    /**
+     * Forgets the failed lookups, the types may have been created since.
+     */
+    pub(crate) fn forget_missing(&self) {
+        if let Ok(mut missing) = self.missing_oids.write() {
+            missing.clear();
+        }
+
+        if let Ok(mut missing) = self.missing_names.write() {
+            missing.clear();
+        }
+    }
+
+    pub(crate) fn insert(&self, schema: &str, ty: crate::pq::Type, visible: bool) {
+        if let Ok(mut names) = self.names.write() {
+            names.insert(format!("{}.{}", schema, ty.name), ty.oid);
+            if visible {
+                names.insert(ty.name.to_string(), ty.oid);
+            }
+        }
+
+        if let Ok(mut types) = self.types.write() {
+            types.insert(ty.oid, ty);
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: std::cell::RefCell<Option<Arc<Registry>>> = const { std::cell::RefCell::new(None) };
+}
+
+/**
+ * Runs `f` with `registry` as the registry of the values being converted,
+ * for the nested types (array elements) to be resolved.
+ */
+pub(crate) fn with<F, R>(registry: Option<&Arc<Registry>>, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let previous = CURRENT.with(|current| current.replace(registry.cloned()));
+    let result = f();
+    CURRENT.with(|current| current.replace(previous));
+
+    result
+}
+
+/**
+ * Resolves `oid` with the registry of the values being converted.
+ */
+pub(crate) fn lookup(oid: crate::pq::Oid) -> crate::pq::Type {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(registry) => registry.get_or_unknown(oid),
+        None => {
+            use std::convert::TryFrom;
+
+            crate::pq::Type::try_from(oid).unwrap_or_else(|_| unknown(oid))
+        }
+    })
+}
+
+pub(crate) fn unknown(oid: crate::pq::Oid) -> crate::pq::Type {
+    crate::pq::Type {
+        oid,
+        name: "unknow",
+        descr: "Unknow type",
+        kind: libpq::types::Kind::Unknow,
+    }
+}
+
+/**
+ * Kind of a type from its `pg_type` description.
+ */
+pub(crate) fn kind(
+    typtype: &str,
+    category: &str,
+    elem: crate::pq::Oid,
+    subtype: crate::pq::Oid,
+) -> libpq::types::Kind {
+    use libpq::types::Kind;
+
+    match (typtype, category) {
+        ("c", _) => Kind::Composite,
+        ("e", _) => Kind::Enum,
+        ("p", _) => Kind::Pseudo,
+        ("r", _) | ("m", _) => Kind::Range(subtype),
+        (_, "A") => Kind::Array(elem),
+        (_, "B") => Kind::Boolean,
+        (_, "C") => Kind::Composite,
+        (_, "D") => Kind::DateTime,
+        (_, "E") => Kind::Enum,
+        (_, "G") => Kind::Geometric,
+        (_, "I") => Kind::Network,
+        (_, "N") => Kind::Numeric,
+        (_, "P") => Kind::Pseudo,
+        (_, "R") => Kind::Range(subtype),
+        (_, "S") => Kind::String,
+        (_, "T") => Kind::Timestamp,
+        (_, "V") => Kind::BitString,
+        (_, "X") => Kind::Unknow,
+        _ => Kind::UserDefined,
+    }
+}
+
+/**
+ * `pq::Type` only contains static strings, the names of the resolved types
+ * are leaked once for the whole process.
+ */
+pub(crate) fn intern(s: &str) -> &'static str {
+    lazy_static::lazy_static! {
+        static ref STRINGS: std::sync::Mutex<HashSet<&'static str>> = Default::default();
+    }
+
+    let mut strings = match STRINGS.lock() {
+        Ok(strings) => strings,
+        Err(err) => err.into_inner(),
+    };
+
+    match strings.get(s) {
+        Some(s) => s,
+        None => {
+            let s: &'static str = Box::leak(s.to_string().into_boxed_str());
+            strings.insert(s);
+            s
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn resolve() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+        elephantry.execute("create type pg_temp.registry_mood as enum ('sad', 'ok', 'happy')")?;
+
+        let schema = elephantry.query_one::<String>(
+            "select nspname::text from pg_namespace where oid = pg_my_temp_schema()",
+            &[],
+        )?;
+        let oid = elephantry.type_oid("registry_mood")?.unwrap();
+        assert_eq!(
+            elephantry.type_oid(&format!("{schema}.registry_mood"))?,
+            Some(oid)
+        );
+
+        // The failed lookups are cached until a type is created
+        assert_eq!(elephantry.type_oid("registry_unknown")?, None);
+        assert!(elephantry.types().is_missing_name("registry_unknown"));
+        elephantry.execute("create type pg_temp.registry_unknown as enum ('a')")?;
+        assert!(!elephantry.types().is_missing_name("registry_unknown"));
+        assert!(elephantry.type_oid("registry_unknown")?.is_some());
+
+        let ty = elephantry.type_by_oid(oid)?.unwrap();
+        assert_eq!(ty.name, "registry_mood");
+        assert_eq!(ty.kind, libpq::types::Kind::Enum);
+
+        let result = elephantry.execute("select array['ok'::registry_mood, 'happy']")?;
+        let tuple = result.get(0);
+        assert_eq!(tuple.field_type(0).name, "_registry_mood");
+        assert_eq!(tuple.field_type(0).kind, libpq::types::Kind::Array(oid));
+
+        let moods: Vec<String> =
+            elephantry.query_one("select array['ok'::registry_mood, 'happy']", &[])?;
+        assert_eq!(moods, vec!["ok".to_string(), "happy".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+        elephantry.execute(
+            "create type pg_temp.nested_mood as enum ('sad', 'ok', 'happy');
+            create domain pg_temp.nested_dmood as nested_mood;
+            create type pg_temp.nested_range as range (subtype = nested_dmood);",
+        )?;
+
+        let oid = |name: &str| -> crate::Result<crate::pq::Oid> {
+            elephantry
+                .query_one::<i64>("select $1::regtype::oid::int8", &[&name])
+                .map(|x| x as crate::pq::Oid)
+        };
+        let mood = oid("nested_mood")?;
+        let dmood = oid("nested_dmood")?;
+        let range = oid("nested_range")?;
+
+        let result = elephantry.execute(
+            "select array[pg_temp.nested_range('ok', 'happy'), pg_temp.nested_range('sad', null)] as ranges",
+        )?;
+        let registry = result.types().unwrap();
+        assert_eq!(
+            registry.get(range).map(|x| x.kind),
+            Some(libpq::types::Kind::Range(dmood))
+        );
+        assert_eq!(
+            registry.get(dmood).map(|x| x.kind),
+            Some(libpq::types::Kind::Enum)
+        );
+        assert_eq!(
+            registry.get(mood).map(|x| x.kind),
+            Some(libpq::types::Kind::Enum)
+        );
+
+        let ranges = result.get(0).get::<Vec<crate::Range<String>>>("ranges");
+        assert_eq!(
+            ranges,
+            vec![
+                crate::Range::from("ok".to_string().."happy".to_string()),
+                crate::Range::new(
+                    std::ops::Bound::Included("sad".to_string()),
+                    std::ops::Bound::Unbounded
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "bit")]
+    fn nested_domain() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+        elephantry.execute(
+            "create domain pg_temp.nested_bit as bit(1);
+            create type pg_temp.nested_bit_range as range (subtype = nested_bit);",
+        )?;
+
+        let result = elephantry.execute("select pg_temp.nested_bit_range('0', '1') as bits")?;
+        assert_eq!(
+            result.get(0).get::<crate::Range<u8>>("bits"),
+            crate::Range::from(0..1)
+        );
+
+        assert_eq!(
+            elephantry.query_one::<(i32, u8)>("select (1, '1'::nested_bit)", &[])?,
+            (1, 1)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn lookup() {
+        let unknown = super::lookup(0);
+        assert_eq!(unknown.name, "unknow");
+        assert_eq!(unknown.kind, libpq::types::Kind::Unknow);
+
+        assert_eq!(
+            super::lookup(crate::pq::types::INT4.oid),
+            crate::pq::types::INT4
+        );
+    }
+}
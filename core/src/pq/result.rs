@@ -2,6 +2,7 @@
 pub struct Result {
     pub(crate) inner: libpq::Result,
     current_tuple: std::cell::RefCell<usize>,
+    types: Option<std::sync::Arc<crate::pq::Registry>>,
 }
 
 impl Result {
@@ -14,11 +15,24 @@ impl Result {
             return None;
         }
 
-        let tuple = crate::Tuple::from(&self.inner, n);
+        let tuple = crate::Tuple::from(&self.inner, self.types.as_ref(), n);
 
         Some(tuple)
     }
 
+    /**
+     * Attaches the registry used to resolve the types unknown to libpq.
+     */
+    pub(crate) fn with_types(mut self, types: std::sync::Arc<crate::pq::Registry>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    #[cfg(any(test, feature = "arrow"))]
+    pub(crate) fn types(&self) -> Option<&std::sync::Arc<crate::pq::Registry>> {
+        self.types.as_ref()
+    }
+
     pub fn len(&self) -> usize {
         self.inner.ntuples()
     }
@@ -27,6 +41,24 @@ impl Result {
         self.len() == 0
     }
 
+    /**
+     * Returns the number of rows affected by the command, parsed from the
+     * command tag. Returns 0 for commands without count.
+     */
+    pub fn affected_rows(&self) -> u64 {
+        self.inner.cmd_tuples() as u64
+    }
+
+    /**
+     * Returns the command status tag.
+     */
+    pub fn command_tag(&self) -> Option<crate::pq::CommandTag> {
+        self.inner
+            .cmd_status()
+            .filter(|x| !x.is_empty())
+            .map(|x| crate::pq::CommandTag::from(x.as_str()))
+    }
+
     pub fn state(&self) -> Option<crate::pq::State> {
         self.inner
             .error_field(libpq::result::ErrorField::Sqlstate)
@@ -63,10 +95,12 @@ impl std::convert::TryFrom<libpq::Result> for Result {
             BadResponse | FatalError | NonFatalError => Err(crate::Error::Sql(Self {
                 inner,
                 current_tuple: std::cell::RefCell::new(0),
+                types: None,
             })),
             _ => Ok(Self {
                 inner,
                 current_tuple: std::cell::RefCell::new(0),
+                types: None,
             }),
         }
     }
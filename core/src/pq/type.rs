@@ -179,9 +179,9 @@ lazy_static::lazy_static! {
 
         types.insert(
             types::TIME.name,
-            #[cfg(feature = "time")]
+            #[cfg(feature = "date")]
             t::<chrono::NaiveTime>(),
-            #[cfg(not(feature = "time"))]
+            #[cfg(not(feature = "date"))]
             "chrono::NaiveTime",
         );
         types.insert(
@@ -193,16 +193,16 @@ lazy_static::lazy_static! {
         );
         types.insert(
             types::TIMESTAMP.name,
-            #[cfg(feature = "time")]
+            #[cfg(feature = "date")]
             t::<chrono::NaiveDateTime>(),
-            #[cfg(not(feature = "time"))]
+            #[cfg(not(feature = "date"))]
             "chrono::NaiveDateTime",
         );
         types.insert(
             types::TIMESTAMPTZ.name,
-            #[cfg(feature = "time")]
+            #[cfg(feature = "date")]
             t::<chrono::DateTime<chrono::FixedOffset>>(),
-            #[cfg(not(feature = "time"))]
+            #[cfg(not(feature = "date"))]
             "chrono::DateTime<chrono::FixedOffset>>",
         );
 
@@ -257,6 +257,7 @@ impl ToArray for Type {
             types::CSTRING => types::CSTRING_ARRAY,
             types::DATE => types::DATE_ARRAY,
             types::DATE_RANGE => types::DATE_RANGE_ARRAY,
+            types::DATEMULTI_RANGE => types::DATEMULTI_RANGE_ARRAY,
             types::FLOAT4 => types::FLOAT4_ARRAY,
             types::FLOAT8 => types::FLOAT8_ARRAY,
             types::GTS_VECTOR => types::GTS_VECTOR_ARRAY,
@@ -265,8 +266,10 @@ impl ToArray for Type {
             types::INT2_VECTOR => types::INT2_VECTOR_ARRAY,
             types::INT4 => types::INT4_ARRAY,
             types::INT4_RANGE => types::INT4_RANGE_ARRAY,
+            types::INT4MULTI_RANGE => types::INT4MULTI_RANGE_ARRAY,
             types::INT8 => types::INT8_ARRAY,
             types::INT8_RANGE => types::INT8_RANGE_ARRAY,
+            types::INT8MULTI_RANGE => types::INT8MULTI_RANGE_ARRAY,
             types::INTERVAL => types::INTERVAL_ARRAY,
             types::JSON => types::JSON_ARRAY,
             types::JSONB => types::JSONB_ARRAY,
@@ -278,6 +281,7 @@ impl ToArray for Type {
             types::MONEY => types::MONEY_ARRAY,
             types::NAME => types::NAME_ARRAY,
             types::NUMERIC => types::NUMERIC_ARRAY,
+            types::NUMMULTI_RANGE => types::NUMMULTI_RANGE_ARRAY,
             types::NUM_RANGE => types::NUM_RANGE_ARRAY,
             types::OID => types::OID_ARRAY,
             types::OID_VECTOR => types::OID_VECTOR_ARRAY,
@@ -304,6 +308,8 @@ impl ToArray for Type {
             types::TIME => types::TIME_ARRAY,
             types::TIMETZ => types::TIMETZ_ARRAY,
             types::TSQUERY => types::TSQUERY_ARRAY,
+            types::TSMULTI_RANGE => types::TSMULTI_RANGE_ARRAY,
+            types::TSTZMULTI_RANGE => types::TSTZMULTI_RANGE_ARRAY,
             types::TSTZ_RANGE => types::TSTZ_RANGE_ARRAY,
             types::TS_RANGE => types::TS_RANGE_ARRAY,
             types::TS_VECTOR => types::TS_VECTOR_ARRAY,
@@ -313,6 +319,21 @@ impl ToArray for Type {
             types::VARCHAR => types::VARCHAR_ARRAY,
             types::XID => types::XID_ARRAY,
             types::XML => types::XML_ARRAY,
+            // Types without fixed OID are resolved later by the name of
+            // their array type
+            Type { oid: 0, name, .. } => {
+                let name = match name.rfind('.') {
+                    Some(x) => format!("{}._{}", &name[..x], &name[x + 1..]),
+                    None => format!("_{}", name),
+                };
+
+                Type {
+                    oid: 0,
+                    name: crate::pq::registry::intern(&name),
+                    descr: self.descr,
+                    kind: libpq::types::Kind::Array(0),
+                }
+            }
             _ => self.clone(),
         }
     }
@@ -0,0 +1,9 @@
+/*!
+ * ```
+ * use elephantry::prelude::*;
+ * ```
+ */
+
+pub use crate::{connect, params, pk, values};
+pub use crate::{Connection, Error, Pool, Result, Rows, Tuple};
+pub use crate::{Entity, FromSql, Model, Structure, ToSql};
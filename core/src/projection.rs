@@ -7,6 +7,7 @@ use std::collections::HashMap;
 pub struct Projection {
     relation: String,
     alias: Option<String>,
+    prefix: Option<String>,
     fields: HashMap<String, String>,
 }
 
@@ -23,6 +24,7 @@ impl Projection {
         Self {
             relation: relation.to_string(),
             alias: None,
+            prefix: None,
             fields: map,
         }
     }
@@ -36,6 +38,38 @@ impl Projection {
         self
     }
 
+    /**
+     * Prefix the output names of the fields with `prefix.`, to select
+     * several projections in a JOIN without names conflict.
+     *
+     * ```no_run
+     * # let connection = elephantry::Connection::new("")?;
+     * let order = elephantry::Projection::new("order", &["id", "total"])
+     *     .alias("o")
+     *     .prefix("order");
+     * let customer = elephantry::Projection::new("customer", &["id", "name"])
+     *     .alias("c")
+     *     .prefix("customer");
+     *
+     * // select o."id" as "order.id", o."total" as "order.total", c."id" as "customer.id", …
+     * let query = format!(
+     *     "select {}, {} from \"order\" o join customer c on c.id = o.customer_id",
+     *     order, customer
+     * );
+     * for tuple in &connection.execute(&query)? {
+     *     let name: String = tuple.prefixed("customer").get("name");
+     * }
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     *
+     * See [`Tuple::prefixed`](crate::Tuple::prefixed) to read them back.
+     */
+    pub fn prefix(mut self, prefix: &str) -> Projection {
+        self.prefix = Some(prefix.to_string());
+
+        self
+    }
+
     /**
      * Add a field from the projection.
      */
@@ -84,9 +118,12 @@ impl std::fmt::Display for Projection {
 
         let relation = self.alias.as_ref().unwrap_or(&self.relation);
 
-        let s = self
-            .fields
-            .iter()
+        // Sorted to generate the same query each time, for prepared statements
+        let mut fields = self.fields.iter().collect::<Vec<_>>();
+        fields.sort();
+
+        let s = fields
+            .into_iter()
             .map(|(alias, row)| {
                 let field = REGEX
                     .replace_all(
@@ -94,7 +131,12 @@ impl std::fmt::Display for Projection {
                         format!("{}.\"$1\"", relation).as_str(),
                     )
                     .to_string();
-                format!(r#"{} as "{}""#, field, alias)
+                let alias = match &self.prefix {
+                    Some(prefix) => format!("{}.{}", prefix, alias),
+                    None => alias.to_string(),
+                };
+
+                format!("{} as {}", field, crate::identifier::quote(&alias))
             })
             .fold(String::new(), |acc, x| {
                 if acc.is_empty() {
@@ -107,3 +149,119 @@ impl std::fmt::Display for Projection {
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::Model;
+
+    #[derive(Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.customer", pk = "id")]
+    struct Customer {
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity, elephantry_derive::Model)]
+    #[elephantry(internal, relation = "pg_temp.purchase", pk = "id")]
+    struct Purchase {
+        id: i32,
+        customer_id: i32,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct PurchaseWithCustomer {
+        #[elephantry(flatten, prefix = "purchase")]
+        purchase: Purchase,
+        #[elephantry(flatten, prefix = "customer")]
+        customer: Customer,
+    }
+
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal)]
+    struct PurchaseWithOptionalCustomer {
+        #[elephantry(flatten, prefix = "purchase")]
+        purchase: Purchase,
+        #[elephantry(flatten, prefix = "customer")]
+        customer: Option<Customer>,
+    }
+
+    #[test]
+    fn prefix() {
+        let projection = crate::Projection::new("customer", &["id", "name"])
+            .alias("c")
+            .prefix("customer");
+
+        assert_eq!(
+            projection.to_string(),
+            r#"c."id" as "customer.id", c."name" as "customer.name""#
+        );
+
+        let projection = crate::Projection::new("customer", &["id"]).prefix(r#"my"customer"#);
+
+        assert_eq!(
+            projection.to_string(),
+            r#"customer."id" as "my""customer.id""#
+        );
+    }
+
+    #[test]
+    fn join() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table customer (id int primary key, name text);
+            create temporary table purchase (id int primary key, customer_id int);
+            insert into customer values (1, 'alice'), (2, 'bob');
+            insert into purchase values (1, 2), (2, 1);",
+        )?;
+
+        let query = format!(
+            "select {}, {} from pg_temp.purchase p join pg_temp.customer c on c.id = p.customer_id order by p.id",
+            PurchaseModel::create_projection().alias("p").prefix("purchase"),
+            CustomerModel::create_projection().alias("c").prefix("customer"),
+        );
+
+        let rows = conn
+            .query::<PurchaseWithCustomer>(&query, &[])?
+            .try_into_vec()?;
+        assert_eq!(
+            rows.iter()
+                .map(|x| (x.purchase.id, x.customer.name.as_str()))
+                .collect::<Vec<_>>(),
+            [(1, "bob"), (2, "alice")]
+        );
+
+        let result = conn.execute(&query)?;
+        let tuple = result.get(0);
+        let (purchase, customer) = (
+            tuple.try_entity::<Purchase>("purchase")?,
+            tuple.try_entity::<Customer>("customer")?,
+        );
+        assert_eq!(purchase.customer_id, customer.id);
+
+        assert!(matches!(
+            tuple.try_entity::<Customer>("unknow"),
+            Err(crate::Error::MissingField(field)) if field == "unknow.id"
+        ));
+
+        conn.execute("insert into purchase values (3, 3)")?;
+
+        let query = format!(
+            "select {}, {} from pg_temp.purchase p left join pg_temp.customer c on c.id = p.customer_id order by p.id",
+            PurchaseModel::create_projection().alias("p").prefix("purchase"),
+            CustomerModel::create_projection().alias("c").prefix("customer"),
+        );
+
+        let rows = conn
+            .query::<PurchaseWithOptionalCustomer>(&query, &[])?
+            .try_into_vec()?;
+        assert_eq!(
+            rows.iter()
+                .map(|x| (x.purchase.id, x.customer.as_ref().map(|x| x.name.as_str())))
+                .collect::<Vec<_>>(),
+            [(1, Some("bob")), (2, Some("alice")), (3, None)]
+        );
+
+        Ok(())
+    }
+}
@@ -1,16 +1,36 @@
+enum Source {
+    Dsn(String),
+    Config(Box<crate::Config>),
+}
+
 pub struct ConnectionManager {
-    dsn: String,
+    source: Source,
 }
 
 impl ConnectionManager {
     pub fn new(dsn: &str) -> Self {
         Self {
-            dsn: dsn.to_string(),
+            source: Source::Dsn(dsn.to_string()),
+        }
+    }
+
+    /**
+     * Creates a new manager from [`Config`]. The password provider, if any,
+     * is called for each new connection.
+     *
+     * [`Config`]: crate::Config
+     */
+    pub fn from_config(config: &crate::Config) -> Self {
+        Self {
+            source: Source::Config(Box::new(config.clone())),
         }
     }
 
     fn etablish(&self) -> crate::Result<crate::Connection> {
-        crate::Connection::new(&self.dsn)
+        match &self.source {
+            Source::Dsn(dsn) => crate::Connection::new(dsn),
+            Source::Config(config) => crate::Connection::from_config(config),
+        }
     }
 }
 
@@ -40,4 +60,22 @@ mod test {
 
         assert!(pool.get().is_ok())
     }
+
+    #[test]
+    fn password_provider() -> crate::Result {
+        let mut config = crate::test::new_conn()?.config()?;
+        config.password_provider = Some(crate::PasswordProvider::new(|| {
+            Err(crate::Error::Parse("expired token".to_string()))
+        }));
+
+        let manager = crate::r2d2::ConnectionManager::from_config(&config);
+        let pool = r2d2::Pool::builder()
+            .max_size(1)
+            .connection_timeout(std::time::Duration::from_millis(100))
+            .build_unchecked(manager);
+
+        assert!(pool.get().is_err());
+
+        Ok(())
+    }
 }
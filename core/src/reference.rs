@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+/*
+ * Text representation of the key values: unlike the binary one, it doesn’t
+ * depend on the rust type, a `i64` finds an `int4` key.
+ */
+type Key = Vec<Option<String>>;
+
+/**
+ * In-memory snapshot of a small lookup table, indexed by primary key and
+ * unique keys.
+ *
+ * This `struct` is created by the [`Connection::load_reference`] method.
+ *
+ * [`Connection::load_reference`]: crate::Connection::load_reference
+ */
+pub struct Reference<'c, M: crate::Model<'c>> {
+    connection: &'c crate::Connection,
+    entities: Vec<M::Entity>,
+    indexes: Vec<(&'static [&'static str], HashMap<Key, usize>)>,
+    listener: Option<crate::Listener<'c>>,
+}
+
+impl<'c, M: crate::Model<'c>> Reference<'c, M> {
+    pub(crate) fn new(connection: &'c crate::Connection) -> crate::Result<Self> {
+        let mut reference = Self {
+            connection,
+            entities: Vec::new(),
+            indexes: Vec::new(),
+            listener: None,
+        };
+
+        reference.reload()?;
+
+        Ok(reference)
+    }
+
+    /**
+     * Fetches the rows again and rebuilds the indexes.
+     */
+    pub fn reload(&mut self) -> crate::Result {
+        use crate::Structure;
+
+        let entities = self.connection.find_all::<M>(None)?.into_vec();
+
+        let mut indexes = Vec::new();
+
+        for columns in std::iter::once(M::Structure::primary_key())
+            .chain(M::Structure::unique_keys().iter().copied())
+            .filter(|x| !x.is_empty())
+        {
+            let mut index = HashMap::new();
+
+            for (n, entity) in entities.iter().enumerate() {
+                index.insert(Self::entity_key(columns, entity)?, n);
+            }
+
+            indexes.push((columns, index));
+        }
+
+        self.entities = entities;
+        self.indexes = indexes;
+
+        Ok(())
+    }
+
+    /**
+     * Reloads the snapshot when a notification is received on `channel`.
+     *
+     * Notifications are checked by [`sync`]. As every notification of the
+     * connection are consumed, you should use a dedicated connection.
+     *
+     * [`sync`]: Self::sync
+     */
+    pub fn invalidate_on(&mut self, channel: &str) -> crate::Result {
+        let connection = self.connection;
+
+        self.listener
+            .get_or_insert_with(|| crate::Listener::new(connection))
+            .listen(channel)
+    }
+
+    /**
+     * Reloads the snapshot if an invalidation notification was received since
+     * the last call. Returns `true` if the snapshot was reloaded.
+     */
+    pub fn sync(&mut self) -> crate::Result<bool> {
+        let mut invalidated = false;
+
+        if let Some(listener) = &mut self.listener {
+            while listener.poll()?.is_some() {
+                invalidated = true;
+            }
+        }
+
+        if invalidated {
+            self.reload()?;
+        }
+
+        Ok(invalidated)
+    }
+
+    /**
+     * Retreives an entity by its primary key.
+     */
+    pub fn get(&self, pk: &HashMap<&str, &dyn crate::ToSql>) -> Option<&M::Entity> {
+        use crate::Structure;
+
+        self.find(M::Structure::primary_key(), pk)
+    }
+
+    /**
+     * Retreives an entity by its primary key or one of its unique keys.
+     *
+     * Returns `None` if no key has the columns of `key`.
+     */
+    pub fn find_by(&self, key: &HashMap<&str, &dyn crate::ToSql>) -> Option<&M::Entity> {
+        let columns = self
+            .indexes
+            .iter()
+            .map(|(columns, _)| *columns)
+            .find(|columns| {
+                columns.len() == key.len() && columns.iter().all(|x| key.contains_key(x))
+            })?;
+
+        self.find(columns, key)
+    }
+
+    /**
+     * All the entities of the snapshot.
+     */
+    pub fn entities(&self) -> &[M::Entity] {
+        &self.entities
+    }
+
+    /**
+     * Number of entities.
+     */
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /**
+     * Is the snapshot empty?
+     */
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn find(&self, columns: &[&str], key: &HashMap<&str, &dyn crate::ToSql>) -> Option<&M::Entity> {
+        let index = self
+            .indexes
+            .iter()
+            .find(|(x, _)| *x == columns)
+            .map(|(_, index)| index)?;
+
+        let mut values = Vec::new();
+
+        for column in columns {
+            values.push(key.get(column)?.to_text().ok()?);
+        }
+
+        index.get(&values).map(|n| &self.entities[*n])
+    }
+
+    fn entity_key(columns: &[&str], entity: &M::Entity) -> crate::Result<Key> {
+        use crate::Entity;
+
+        columns
+            .iter()
+            .map(|x| match entity.get(x) {
+                Some(value) => value.to_text(),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(elephantry_derive::Entity)]
+    #[elephantry(
+        internal,
+        model = "Model",
+        structure = "Structure",
+        relation = "country"
+    )]
+    struct Country {
+        #[elephantry(pk)]
+        id: i32,
+        #[elephantry(unique)]
+        code: String,
+        name: String,
+    }
+
+    #[test]
+    fn load() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table country (id int primary key, code text unique, name text);
+            insert into country values (1, 'FR', 'France'), (2, 'DE', 'Germany');",
+        )?;
+
+        let mut countries = conn.load_reference::<Model<'_>>()?;
+        assert_eq!(countries.len(), 2);
+        assert_eq!(countries.get(&crate::pk!(id => 2)).unwrap().code, "DE");
+        assert_eq!(countries.get(&crate::pk!(id => 2_i64)).unwrap().code, "DE");
+        assert_eq!(
+            countries.find_by(&crate::pk!(code => "FR")).unwrap().name,
+            "France"
+        );
+        assert!(countries.find_by(&crate::pk!(name => "France")).is_none());
+
+        conn.execute("insert into country values (3, 'IT', 'Italy')")?;
+        assert!(countries.find_by(&crate::pk!(code => "IT")).is_none());
+        countries.reload()?;
+        assert_eq!(countries.get(&crate::pk!(id => 3)).unwrap().name, "Italy");
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalidate() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table country (id int primary key, code text unique, name text);",
+        )?;
+
+        let mut countries = conn.load_reference::<Model<'_>>()?;
+        countries.invalidate_on("country")?;
+        assert!(!countries.sync()?);
+
+        conn.execute("insert into country values (1, 'FR', 'France')")?;
+        conn.notify("country", None)?;
+        assert!(countries.sync()?);
+        assert_eq!(countries.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+/**
+ * Retry policy of read queries on connection failure.
+ *
+ * Set by [`Connection::set_retry_policy`]. Only the `find_*`, `count_where`
+ * and `exist_where` helpers are retried, outside of transactions: the
+ * connection is reset and the query sent again after a delay.
+ *
+ * [`Connection::set_retry_policy`]: crate::Connection::set_retry_policy
+ */
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    /** Maximum number of retries. */
+    pub max_retries: u32,
+    /** Delay before the first retry. */
+    pub delay: std::time::Duration,
+    /** Multiplier applied to the delay after each retry. */
+    pub backoff: u32,
+}
+
+impl RetryPolicy {
+    /**
+     * Delay before the retry number `attempt`, starting at 1.
+     */
+    pub fn delay(&self, attempt: u32) -> std::time::Duration {
+        self.delay * self.backoff.saturating_pow(attempt.saturating_sub(1))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            delay: std::time::Duration::from_millis(100),
+            backoff: 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn delay() {
+        let policy = crate::RetryPolicy::default();
+
+        assert_eq!(policy.delay(1), std::time::Duration::from_millis(100));
+        assert_eq!(policy.delay(2), std::time::Duration::from_millis(200));
+        assert_eq!(policy.delay(3), std::time::Duration::from_millis(400));
+    }
+}
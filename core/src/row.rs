@@ -0,0 +1,242 @@
+/**
+ * Owned row of any query, to read the fields without declaring an entity.
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * let rows = connection.query::<elephantry::Row>(
+ *     "select department, count(*) as count from employee group by department",
+ *     &[],
+ * )?;
+ *
+ * for row in rows {
+ *     let department: String = row.get("department");
+ *     let count = row.nth::<i64>(1);
+ * }
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Row {
+    fields: Vec<Field>,
+}
+
+#[derive(Clone, Debug)]
+struct Field {
+    name: String,
+    ty: crate::pq::Type,
+    format: crate::pq::Format,
+    raw: Option<Vec<u8>>,
+}
+
+impl Row {
+    /**
+     * Retreive the value of field `name` of the row.
+     *
+     * # Panics
+     *
+     * Panics if the row doesn’t have this field or if the conversion fails.
+     */
+    pub fn get<T: crate::FromSql>(&self, name: &str) -> T {
+        self.try_get(name)
+            .unwrap_or_else(|err| panic!("Unable to retreive '{}' field: {}", name, err))
+    }
+
+    /**
+     * Retreive the value of field `name` of the row.
+     *
+     * Returns [`Error::MissingField`] if the row doesn’t have this field, or
+     * the conversion error.
+     *
+     * [`Error::MissingField`]: crate::Error::MissingField
+     */
+    pub fn try_get<T: crate::FromSql>(&self, name: &str) -> crate::Result<T> {
+        match self.position(name) {
+            Some(n) => self.try_nth(n),
+            None => Err(crate::Error::MissingField(name.to_string())),
+        }
+    }
+
+    /**
+     * Retreive the nth field.
+     *
+     * # Panics
+     *
+     * Panics if `n` is greater than or equal to the length of the row or if
+     * the conversion fails.
+     */
+    pub fn nth<T: crate::FromSql>(&self, n: usize) -> T {
+        self.try_nth(n)
+            .unwrap_or_else(|err| panic!("Unable to retreive field {}: {}", n, err))
+    }
+
+    /**
+     * Retreive the nth field.
+     *
+     * Returns [`Error::MissingField`] if `n` is greater than or equal to the
+     * length of the row, or the conversion error.
+     *
+     * [`Error::MissingField`]: crate::Error::MissingField
+     */
+    pub fn try_nth<T: crate::FromSql>(&self, n: usize) -> crate::Result<T> {
+        let field = match self.fields.get(n) {
+            Some(field) => field,
+            None => return Err(crate::Error::MissingField(format!("#{}", n))),
+        };
+
+        T::from_sql(&field.ty, field.format, field.raw.as_deref())
+    }
+
+    /**
+     * Number of field.
+     */
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /**
+     * Is the row is empty (doesn’t contain field)?
+     */
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /**
+     * Retreive the name of field at position `n`.
+     */
+    pub fn field_name(&self, n: usize) -> Option<&str> {
+        self.fields.get(n).map(|x| x.name.as_str())
+    }
+
+    /**
+     * Names of the fields, in order.
+     */
+    pub fn field_names(&self) -> Vec<&str> {
+        self.fields.iter().map(|x| x.name.as_str()).collect()
+    }
+
+    /**
+     * SQL type of the field `name`.
+     */
+    pub fn field_type(&self, name: &str) -> Option<&crate::pq::Type> {
+        self.position(name).map(|n| &self.fields[n].ty)
+    }
+
+    /**
+     * Is the field `name` null?
+     */
+    pub fn is_null(&self, name: &str) -> bool {
+        self.position(name)
+            .map(|n| self.fields[n].raw.is_none())
+            .unwrap_or(true)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|x| x.name == name)
+    }
+}
+
+impl crate::Entity for Row {
+    fn from(tuple: &crate::Tuple<'_>) -> Self {
+        let fields = (0..tuple.len())
+            .map(|n| {
+                let (ty, format, raw) = tuple.raw(n);
+
+                Field {
+                    name: tuple.field_name(n).unwrap_or_default(),
+                    ty,
+                    format,
+                    raw: raw.map(<[u8]>::to_vec),
+                }
+            })
+            .collect();
+
+        Self { fields }
+    }
+
+    fn get(&self, field: &str) -> Option<&dyn crate::ToSql> {
+        self.position(field)
+            .map(|n| &self.fields[n] as &dyn crate::ToSql)
+    }
+}
+
+/**
+ * Fields are sent back as read, to copy rows between queries.
+ */
+impl crate::ToSql for Field {
+    fn ty(&self) -> crate::pq::Type {
+        self.ty.clone()
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        self.format
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let mut raw = match &self.raw {
+            Some(raw) => raw.clone(),
+            None => return Ok(None),
+        };
+
+        if self.format == crate::pq::Format::Text {
+            raw.push(0);
+        }
+
+        Ok(Some(raw))
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        match self.format {
+            crate::pq::Format::Text => self
+                .raw
+                .clone()
+                .map(String::from_utf8)
+                .transpose()
+                .map_err(Into::into),
+            crate::pq::Format::Binary => Err(self.error(
+                self.type_name(),
+                Some(&"no text representation".to_string()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn row() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+
+        let row = connection.query_one::<crate::Row>(
+            "select 3::int8 as count, 'Ada' as name, null::text as comment",
+            &[],
+        )?;
+        assert_eq!(row.len(), 3);
+        assert_eq!(row.field_names(), ["count", "name", "comment"]);
+        assert_eq!(row.get::<i64>("count"), 3);
+        assert_eq!(row.nth::<String>(1), "Ada");
+        assert_eq!(row.try_get::<Option<String>>("comment")?, None);
+        assert!(row.is_null("comment"));
+        assert_eq!(
+            row.field_type("count").map(|x| x.oid),
+            Some(crate::pq::types::INT8.oid)
+        );
+        assert!(matches!(
+            row.try_get::<i32>("unknow"),
+            Err(crate::Error::MissingField(_))
+        ));
+        assert!(matches!(
+            row.try_nth::<i32>(3),
+            Err(crate::Error::MissingField(_))
+        ));
+
+        let row = connection
+            .execute("select 42 as answer")
+            .map(|x| <crate::Row as crate::Entity>::from(&x.get(0)))?;
+        assert_eq!(row.get::<i32>("answer"), 42);
+
+        let value = crate::Entity::get(&row, "answer").unwrap();
+        assert_eq!(connection.query_one::<i32>("select $1 + 1", &[value])?, 43);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,11 @@
 /**
  * Represent a rows of result.
+ *
+ * The rows are fetched before the query returns: `Rows` doesn’t use the
+ * connection, which can execute other queries while iterating. See
+ * [`Connection::query_stream`] to fetch them lazily.
+ *
+ * [`Connection::query_stream`]: crate::Connection::query_stream
  */
 #[derive(Debug)]
 pub struct Rows<E: crate::Entity> {
@@ -39,6 +45,14 @@ impl<E: crate::Entity> Rows<E> {
     pub fn into_vec(self) -> Vec<E> {
         self.collect()
     }
+
+    /**
+     * Converts `Rows` into a vector, stopping at the first row that can’t be
+     * decoded.
+     */
+    pub fn try_into_vec(self) -> crate::Result<Vec<E>> {
+        (&self.result).map(|x| E::try_from_tuple(&x)).collect()
+    }
 }
 
 impl<E: crate::Entity> std::iter::Iterator for Rows<E> {
@@ -0,0 +1,763 @@
+use std::collections::HashMap;
+
+impl serde::ser::Error for crate::Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Serialize(msg.to_string())
+    }
+}
+
+/**
+ * Parameter value, built from a serde value.
+ */
+#[derive(Clone, Debug)]
+enum Param {
+    Null,
+    Bool(bool),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(crate::Bytea),
+    Array(Vec<Param>),
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+}
+
+impl Param {
+    fn inner(&self) -> &dyn crate::ToSql {
+        match self {
+            Self::Null => &(),
+            Self::Bool(v) => v,
+            Self::I16(v) => v,
+            Self::I32(v) => v,
+            Self::I64(v) => v,
+            Self::F32(v) => v,
+            Self::F64(v) => v,
+            Self::String(v) => v,
+            Self::Bytes(v) => v,
+            Self::Array(v) => v,
+            #[cfg(feature = "json")]
+            Self::Json(v) => v,
+        }
+    }
+}
+
+impl crate::ToSql for Param {
+    fn ty(&self) -> crate::pq::Type {
+        self.inner().ty()
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        self.inner().format()
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.inner().to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        self.inner().to_text()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.inner().type_name()
+    }
+}
+
+/**
+ * Named parameters, created by [`to_params`].
+ *
+ * The fields keep the declaration order of the serialized struct.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    fields: Vec<(String, Param)>,
+}
+
+impl Params {
+    /**
+     * Value of the parameter `name`.
+     */
+    pub fn get(&self, name: &str) -> Option<&dyn crate::ToSql> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value as &dyn crate::ToSql)
+    }
+
+    /**
+     * Names of the parameters.
+     */
+    pub fn names(&self) -> Vec<&str> {
+        self.fields.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /**
+     * Values of the parameters, in order, to use as positional parameters.
+     */
+    pub fn values(&self) -> Vec<&dyn crate::ToSql> {
+        self.fields
+            .iter()
+            .map(|(_, value)| value as &dyn crate::ToSql)
+            .collect()
+    }
+
+    /**
+     * Parameters by name, like the [`values!`] macro.
+     *
+     * [`values!`]: crate::values
+     */
+    pub fn to_map(&self) -> HashMap<String, &dyn crate::ToSql> {
+        self.fields
+            .iter()
+            .map(|(name, value)| (name.clone(), value as &dyn crate::ToSql))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/**
+ * Serializes a struct or a map as named parameters, to write any
+ * `#[derive(serde::Serialize)]` type without implementing [`Entity`].
+ *
+ * Nested structs and maps are sent as `jsonb`, they require the `json`
+ * feature. Unit enum variants are sent as text.
+ *
+ * ```no_run
+ * #[derive(serde::Serialize)]
+ * struct NewEmployee {
+ *     first_name: String,
+ *     last_name: String,
+ * }
+ *
+ * # let connection = elephantry::Connection::new("")?;
+ * let employee = NewEmployee {
+ *     first_name: "Ada".to_string(),
+ *     last_name: "Lovelace".to_string(),
+ * };
+ * let params = elephantry::to_params(&employee)?;
+ *
+ * connection.update_by_pk::<employee::Model<'_>>(&elephantry::pk!(employee_id => 1), &params.to_map())?;
+ * # mod employee {
+ * # #[derive(elephantry::Entity)]
+ * # #[elephantry(model = "Model", structure = "Structure", relation = "employee")]
+ * # pub struct Employee { #[elephantry(pk)] employee_id: i32, first_name: String, last_name: String }
+ * # }
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * [`Entity`]: crate::Entity
+ */
+pub fn to_params<T: serde::Serialize + ?Sized>(value: &T) -> crate::Result<Params> {
+    value.serialize(ParamsSerializer)
+}
+
+fn unsupported<T>(what: &str) -> crate::Result<T> {
+    Err(crate::Error::Serialize(format!(
+        "{} can't be used as parameters, expected a struct or a map",
+        what
+    )))
+}
+
+/**
+ * Top level serializer, only accepts structs and maps.
+ */
+struct ParamsSerializer;
+
+impl serde::Serializer for ParamsSerializer {
+    type Ok = Params;
+    type Error = crate::Error;
+    type SerializeSeq = serde::ser::Impossible<Params, crate::Error>;
+    type SerializeTuple = serde::ser::Impossible<Params, crate::Error>;
+    type SerializeTupleStruct = serde::ser::Impossible<Params, crate::Error>;
+    type SerializeTupleVariant = serde::ser::Impossible<Params, crate::Error>;
+    type SerializeMap = ParamsMap;
+    type SerializeStruct = ParamsMap;
+    type SerializeStructVariant = serde::ser::Impossible<Params, crate::Error>;
+
+    fn serialize_bool(self, _: bool) -> crate::Result<Params> {
+        unsupported("bool")
+    }
+
+    fn serialize_i8(self, _: i8) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_i16(self, _: i16) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_i32(self, _: i32) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_i64(self, _: i64) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_u8(self, _: u8) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_u16(self, _: u16) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_u32(self, _: u32) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_u64(self, _: u64) -> crate::Result<Params> {
+        unsupported("integer")
+    }
+
+    fn serialize_f32(self, _: f32) -> crate::Result<Params> {
+        unsupported("float")
+    }
+
+    fn serialize_f64(self, _: f64) -> crate::Result<Params> {
+        unsupported("float")
+    }
+
+    fn serialize_char(self, _: char) -> crate::Result<Params> {
+        unsupported("char")
+    }
+
+    fn serialize_str(self, _: &str) -> crate::Result<Params> {
+        unsupported("string")
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> crate::Result<Params> {
+        unsupported("bytes")
+    }
+
+    fn serialize_none(self) -> crate::Result<Params> {
+        unsupported("none")
+    }
+
+    fn serialize_some<T: serde::Serialize + ?Sized>(self, value: &T) -> crate::Result<Params> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> crate::Result<Params> {
+        unsupported("unit")
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> crate::Result<Params> {
+        unsupported("unit struct")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> crate::Result<Params> {
+        unsupported("enum")
+    }
+
+    fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> crate::Result<Params> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> crate::Result<Params> {
+        unsupported("enum")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> crate::Result<Self::SerializeSeq> {
+        unsupported("sequence")
+    }
+
+    fn serialize_tuple(self, _: usize) -> crate::Result<Self::SerializeTuple> {
+        unsupported("tuple")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> crate::Result<Self::SerializeTupleStruct> {
+        unsupported("tuple struct")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> crate::Result<Self::SerializeTupleVariant> {
+        unsupported("enum")
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> crate::Result<Self::SerializeMap> {
+        Ok(ParamsMap::default())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> crate::Result<Self::SerializeStruct> {
+        Ok(ParamsMap::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> crate::Result<Self::SerializeStructVariant> {
+        unsupported("enum")
+    }
+}
+
+#[derive(Default)]
+struct ParamsMap {
+    params: Params,
+    key: Option<String>,
+}
+
+impl serde::ser::SerializeMap for ParamsMap {
+    type Ok = Params;
+    type Error = crate::Error;
+
+    fn serialize_key<T: serde::Serialize + ?Sized>(&mut self, key: &T) -> crate::Result {
+        match key.serialize(ParamSerializer)? {
+            Param::String(key) => self.key = Some(key),
+            _ => return unsupported("map with non string keys"),
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> crate::Result {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| crate::Error::Serialize("value without key".to_string()))?;
+
+        self.params
+            .fields
+            .push((key, value.serialize(ParamSerializer)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> crate::Result<Params> {
+        Ok(self.params)
+    }
+}
+
+impl serde::ser::SerializeStruct for ParamsMap {
+    type Ok = Params;
+    type Error = crate::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> crate::Result {
+        self.params
+            .fields
+            .push((key.to_string(), value.serialize(ParamSerializer)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> crate::Result<Params> {
+        Ok(self.params)
+    }
+}
+
+/**
+ * Serializer of a parameter value.
+ */
+struct ParamSerializer;
+
+#[cfg(feature = "json")]
+type JsonSerializer = serde_json::value::Serializer;
+
+#[cfg(feature = "json")]
+type SerializeMap = Json<<JsonSerializer as serde::Serializer>::SerializeMap>;
+#[cfg(not(feature = "json"))]
+type SerializeMap = serde::ser::Impossible<Param, crate::Error>;
+
+#[cfg(feature = "json")]
+type SerializeStruct = Json<<JsonSerializer as serde::Serializer>::SerializeStruct>;
+#[cfg(not(feature = "json"))]
+type SerializeStruct = serde::ser::Impossible<Param, crate::Error>;
+
+impl serde::Serializer for ParamSerializer {
+    type Ok = Param;
+    type Error = crate::Error;
+    type SerializeSeq = ParamSeq;
+    type SerializeTuple = ParamSeq;
+    type SerializeTupleStruct = ParamSeq;
+    type SerializeTupleVariant = serde::ser::Impossible<Param, crate::Error>;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = serde::ser::Impossible<Param, crate::Error>;
+
+    fn serialize_bool(self, v: bool) -> crate::Result<Param> {
+        Ok(Param::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> crate::Result<Param> {
+        Ok(Param::I16(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> crate::Result<Param> {
+        Ok(Param::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> crate::Result<Param> {
+        Ok(Param::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> crate::Result<Param> {
+        Ok(Param::I64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> crate::Result<Param> {
+        Ok(Param::I16(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> crate::Result<Param> {
+        Ok(Param::I32(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> crate::Result<Param> {
+        Ok(Param::I64(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> crate::Result<Param> {
+        use std::convert::TryFrom;
+
+        i64::try_from(v)
+            .map(Param::I64)
+            .map_err(|_| crate::Error::Serialize(format!("{} overflows bigint", v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> crate::Result<Param> {
+        Ok(Param::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> crate::Result<Param> {
+        Ok(Param::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> crate::Result<Param> {
+        Ok(Param::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> crate::Result<Param> {
+        Ok(Param::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> crate::Result<Param> {
+        Ok(Param::Bytes(v.to_vec().into()))
+    }
+
+    fn serialize_none(self) -> crate::Result<Param> {
+        Ok(Param::Null)
+    }
+
+    fn serialize_some<T: serde::Serialize + ?Sized>(self, value: &T) -> crate::Result<Param> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> crate::Result<Param> {
+        Ok(Param::Null)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> crate::Result<Param> {
+        Ok(Param::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> crate::Result<Param> {
+        Ok(Param::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> crate::Result<Param> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: &T,
+    ) -> crate::Result<Param> {
+        unsupported(&format!("enum variant {}", variant))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> crate::Result<ParamSeq> {
+        Ok(ParamSeq(Vec::with_capacity(len.unwrap_or_default())))
+    }
+
+    fn serialize_tuple(self, len: usize) -> crate::Result<ParamSeq> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> crate::Result<ParamSeq> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> crate::Result<Self::SerializeTupleVariant> {
+        unsupported(&format!("enum variant {}", variant))
+    }
+
+    #[cfg(feature = "json")]
+    fn serialize_map(self, len: Option<usize>) -> crate::Result<SerializeMap> {
+        serde_json::value::Serializer
+            .serialize_map(len)
+            .map(Json)
+            .map_err(serde::ser::Error::custom)
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn serialize_map(self, _: Option<usize>) -> crate::Result<SerializeMap> {
+        unsupported("nested map (requires the json feature)")
+    }
+
+    #[cfg(feature = "json")]
+    fn serialize_struct(self, name: &'static str, len: usize) -> crate::Result<SerializeStruct> {
+        serde_json::value::Serializer
+            .serialize_struct(name, len)
+            .map(Json)
+            .map_err(serde::ser::Error::custom)
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn serialize_struct(self, _: &'static str, _: usize) -> crate::Result<SerializeStruct> {
+        unsupported("nested struct (requires the json feature)")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> crate::Result<Self::SerializeStructVariant> {
+        unsupported(&format!("enum variant {}", variant))
+    }
+}
+
+struct ParamSeq(Vec<Param>);
+
+impl serde::ser::SerializeSeq for ParamSeq {
+    type Ok = Param;
+    type Error = crate::Error;
+
+    fn serialize_element<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> crate::Result {
+        self.0.push(value.serialize(ParamSerializer)?);
+
+        Ok(())
+    }
+
+    fn end(self) -> crate::Result<Param> {
+        Ok(Param::Array(self.0))
+    }
+}
+
+impl serde::ser::SerializeTuple for ParamSeq {
+    type Ok = Param;
+    type Error = crate::Error;
+
+    fn serialize_element<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> crate::Result {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> crate::Result<Param> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for ParamSeq {
+    type Ok = Param;
+    type Error = crate::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> crate::Result {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> crate::Result<Param> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+/**
+ * Nested map or struct, serialized as json.
+ */
+#[cfg(feature = "json")]
+struct Json<S>(S);
+
+#[cfg(feature = "json")]
+impl<S> serde::ser::SerializeMap for Json<S>
+where
+    S: serde::ser::SerializeMap<Ok = serde_json::Value, Error = serde_json::Error>,
+{
+    type Ok = Param;
+    type Error = crate::Error;
+
+    fn serialize_key<T: serde::Serialize + ?Sized>(&mut self, key: &T) -> crate::Result {
+        self.0.serialize_key(key).map_err(serde::ser::Error::custom)
+    }
+
+    fn serialize_value<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> crate::Result {
+        self.0
+            .serialize_value(value)
+            .map_err(serde::ser::Error::custom)
+    }
+
+    fn end(self) -> crate::Result<Param> {
+        self.0
+            .end()
+            .map(Param::Json)
+            .map_err(serde::ser::Error::custom)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<S> serde::ser::SerializeStruct for Json<S>
+where
+    S: serde::ser::SerializeStruct<Ok = serde_json::Value, Error = serde_json::Error>,
+{
+    type Ok = Param;
+    type Error = crate::Error;
+
+    fn serialize_field<T: serde::Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> crate::Result {
+        self.0
+            .serialize_field(key, value)
+            .map_err(serde::ser::Error::custom)
+    }
+
+    fn end(self) -> crate::Result<Param> {
+        self.0
+            .end()
+            .map(Param::Json)
+            .map_err(serde::ser::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(serde::Serialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Active,
+    }
+
+    #[derive(serde::Serialize)]
+    struct NewEntity {
+        employee_id: i32,
+        first_name: String,
+        last_name: Option<String>,
+        status: Status,
+        skills: Vec<String>,
+    }
+
+    #[test]
+    fn to_params() -> crate::Result {
+        let params = crate::to_params(&NewEntity {
+            employee_id: 1,
+            first_name: "Ada".to_string(),
+            last_name: None,
+            status: Status::Active,
+            skills: vec!["math".to_string()],
+        })?;
+
+        assert_eq!(
+            params.names(),
+            ["employee_id", "first_name", "last_name", "status", "skills"]
+        );
+        assert_eq!(
+            params.get("employee_id").unwrap().to_text()?,
+            Some("1".to_string())
+        );
+        assert_eq!(params.get("last_name").unwrap().to_sql()?, None);
+        assert_eq!(
+            params.get("status").unwrap().to_text()?,
+            Some("active".to_string())
+        );
+        assert_eq!(
+            params.get("skills").unwrap().to_text()?,
+            Some("{math}".to_string())
+        );
+
+        assert!(matches!(
+            crate::to_params(&1),
+            Err(crate::Error::Serialize(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn insert_serialized() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        crate::test::create_entity_table(&connection, true)?;
+
+        let entity = connection.insert_serialized::<crate::test::Model<'_>, _>(&NewEntity {
+            employee_id: 1,
+            first_name: "Ada".to_string(),
+            last_name: Some("Lovelace".to_string()),
+            status: Status::Active,
+            skills: Vec::new(),
+        })?;
+        assert_eq!(entity.first_name, "Ada");
+        assert_eq!(entity.last_name, "Lovelace");
+
+        let mut data = std::collections::HashMap::new();
+        data.insert("first_name", "Grace");
+        let params = crate::to_params(&data)?;
+        connection.update_by_pk::<crate::test::Model<'_>>(
+            &crate::pk!(employee_id => 1),
+            &params.to_map(),
+        )?;
+        assert_eq!(
+            connection.query_one::<String>("select first_name from entity", &[])?,
+            "Grace"
+        );
+
+        Ok(())
+    }
+}
@@ -3,7 +3,6 @@
  */
 
 use byteorder::ReadBytesExt;
-use std::convert::TryInto;
 
 /**
  * Rust type for [array](https://www.postgresql.org/docs/current/arrays.html).
@@ -15,35 +14,53 @@ pub struct Array<T> {
     has_nulls: bool,
     dimensions: Vec<i32>,
     lower_bounds: Vec<i32>,
-    data: Vec<u8>,
+    format: crate::pq::Format,
+    elements: std::collections::VecDeque<Option<Vec<u8>>>,
     maker: std::marker::PhantomData<T>,
 }
 
-impl<T: crate::FromSql> Iterator for Array<T> {
-    type Item = T;
+impl<T: crate::FromSql> Array<T> {
+    /**
+     * Converts the next element, like [`Iterator::next`] but returning the
+     * conversion error instead of ending the iteration.
+     */
+    pub fn try_next(&mut self) -> Option<crate::Result<T>> {
+        let element = self.elements.pop_front()?;
 
-    fn next(&mut self) -> Option<T> {
-        use bytes::Buf;
+        Some(T::from_sql(&self.elemtype, self.format, element.as_deref()))
+    }
 
-        if self.data.is_empty() {
-            return None;
+    /**
+     * Converts the array into a vector, stopping at the first element that
+     * can’t be converted, like a `NULL` for a non `Option` type.
+     *
+     * Elements of multidimensional arrays are flattened.
+     */
+    pub fn try_into_vec(mut self) -> crate::Result<Vec<T>> {
+        let mut vec = Vec::with_capacity(self.elements.len());
+
+        while let Some(element) = self.try_next() {
+            vec.push(element?);
         }
 
-        let mut buf = &self.data.clone()[..];
+        Ok(vec)
+    }
+}
 
-        let mut len = buf.get_u32() as usize;
-        let value = if len == 0xFFFF_FFFF {
-            len = 0;
-            None
-        } else {
-            Some(&buf[..len])
-        };
-        self.data = buf[len..].to_vec();
+/**
+ * Iterates over the elements, the iteration ends at the first element that
+ * can’t be converted: use [`Array::try_next`] or [`Array::try_into_vec`] to
+ * get the error.
+ */
+impl<T: crate::FromSql> Iterator for Array<T> {
+    type Item = T;
 
-        match T::from_sql(&self.elemtype, crate::pq::Format::Binary, value) {
+    fn next(&mut self) -> Option<T> {
+        match self.try_next()? {
             Ok(x) => Some(x),
             Err(err) => {
                 log::error!("Unable to convert array element from SQL: {}", err);
+                self.elements.clear();
                 None
             }
         }
@@ -51,27 +68,52 @@ impl<T: crate::FromSql> Iterator for Array<T> {
 }
 
 impl<T: crate::FromSql> crate::FromSql for Array<T> {
-    fn from_text(_ty: &crate::pq::Type, _raw: Option<&str>) -> crate::Result<Self> {
-        todo!()
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let s = crate::not_null(raw)?;
+
+        let elemtype = match ty.kind {
+            libpq::types::Kind::Array(oid) => crate::pq::registry::lookup(oid),
+            _ => ty.clone(),
+        };
+
+        let (dimensions, lower_bounds) =
+            text_dimensions(s).ok_or_else(|| Self::error(ty, "array", raw))?;
+        let elements = text_to_vec(s)?;
+
+        let expected = dimensions.iter().map(|x| *x as usize).product::<usize>();
+        if expected != elements.len() && !(dimensions.is_empty() && elements.is_empty()) {
+            return Err(Self::error(ty, "array", raw));
+        }
+
+        let array = Self {
+            ndim: dimensions.len(),
+            elemtype,
+            has_nulls: elements.iter().any(Option::is_none),
+            dimensions,
+            lower_bounds,
+            format: crate::pq::Format::Text,
+            elements: elements
+                .into_iter()
+                .map(|x| x.map(String::into_bytes))
+                .collect(),
+            maker: std::marker::PhantomData,
+        };
+
+        Ok(array)
     }
 
-    fn from_binary(_: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
         let mut data = crate::not_null(raw)?;
 
         let ndim = data.read_i32::<byteorder::BigEndian>()?;
         if ndim < 0 {
-            panic!("Invalid array");
+            return Err(Self::error(ty, "array", raw));
         }
 
         let has_nulls = data.read_i32::<byteorder::BigEndian>()? != 0;
 
         let oid = data.read_u32::<byteorder::BigEndian>()?;
-        let elemtype: crate::pq::Type = oid.try_into().unwrap_or(crate::pq::Type {
-            oid,
-            descr: "Custom type",
-            name: "custom",
-            kind: libpq::types::Kind::Composite,
-        });
+        let elemtype = crate::pq::registry::lookup(oid);
 
         let mut dimensions = Vec::new();
         let mut lower_bounds = Vec::new();
@@ -84,13 +126,33 @@ impl<T: crate::FromSql> crate::FromSql for Array<T> {
             lower_bounds.push(lower_bound);
         }
 
+        let mut elements = std::collections::VecDeque::new();
+
+        while !data.is_empty() {
+            let len = data.read_i32::<byteorder::BigEndian>()?;
+
+            if len < 0 {
+                elements.push_back(None);
+                continue;
+            }
+
+            let len = len as usize;
+            if len > data.len() {
+                return Err(Self::error(ty, "array", raw));
+            }
+
+            elements.push_back(Some(data[..len].to_vec()));
+            data = &data[len..];
+        }
+
         let array = Self {
             ndim: ndim as usize,
             elemtype,
             has_nulls,
             dimensions,
             lower_bounds,
-            data: data.to_vec(),
+            format: crate::pq::Format::Binary,
+            elements,
             maker: std::marker::PhantomData,
         };
 
@@ -111,8 +173,278 @@ impl<T: crate::FromSql> From<Array<T>> for Vec<T> {
     }
 }
 
+/**
+ * Quotes an element of an array if needed.
+ */
+pub(crate) fn quote(element: &str) -> std::borrow::Cow<'_, str> {
+    let special = |c: char| matches!(c, '{' | '}' | ',' | '"' | '\\') || c.is_whitespace();
+
+    if !element.is_empty() && !element.contains(special) && !element.eq_ignore_ascii_case("null") {
+        return element.into();
+    }
+
+    format!("\"{}\"", element.replace('\\', "\\\\").replace('"', "\\\"")).into()
+}
+
+/*
+ * Dimensions and lower bounds of an array in text format, from its
+ * decoration like `[0:1][1:2]=` or from the nesting of its braces.
+ *
+ * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/arrayfuncs.c#L268
+ */
+fn text_dimensions(s: &str) -> Option<(Vec<i32>, Vec<i32>)> {
+    if s.starts_with('[') {
+        let (decoration, _) = s.split_once('=')?;
+        let mut dimensions = Vec::new();
+        let mut lower_bounds = Vec::new();
+
+        for bounds in decoration.strip_prefix('[')?.strip_suffix(']')?.split("][") {
+            let (lower, upper) = bounds.split_once(':')?;
+            let lower = lower.trim().parse::<i32>().ok()?;
+            let upper = upper.trim().parse::<i32>().ok()?;
+
+            dimensions.push(upper - lower + 1);
+            lower_bounds.push(lower);
+        }
+
+        return Some((dimensions, lower_bounds));
+    }
+
+    // Counts the elements of the first sub-array of each level
+    let mut counts: Vec<i32> = Vec::new();
+    let mut closed: Vec<bool> = Vec::new();
+    let mut depth = 0;
+    let mut in_quotes = false;
+    let mut empty = true;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+                empty = false;
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                empty = false;
+            }
+            _ if in_quotes => (),
+            '{' => {
+                depth += 1;
+                if counts.len() < depth {
+                    counts.push(1);
+                    closed.push(false);
+                }
+            }
+            '}' => {
+                if depth == 0 {
+                    return None;
+                }
+                closed[depth - 1] = true;
+                depth -= 1;
+            }
+            ',' if depth > 0 && !closed[depth - 1] => counts[depth - 1] += 1,
+            c if !c.is_whitespace() && c != ',' => empty = false,
+            _ => (),
+        }
+    }
+
+    if depth != 0 || in_quotes {
+        return None;
+    }
+
+    if empty && counts.len() <= 1 {
+        return Some((Vec::new(), Vec::new()));
+    }
+
+    let lower_bounds = vec![1; counts.len()];
+
+    Some((counts, lower_bounds))
+}
+
+/*
+ * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/arrayfuncs.c#L171
+ *
+ * Elements of multidimensional arrays are flattened.
+ */
+pub(crate) fn text_to_vec(s: &str) -> crate::Result<Vec<Option<String>>> {
+    // Skips the dimensions decoration, like `[0:1]=`
+    let s = match s.strip_prefix('[') {
+        Some(_) => s.split_once('=').map(|x| x.1).unwrap_or(s),
+        None => s,
+    };
+
+    if !s.starts_with('{') || !s.ends_with('}') {
+        return Err(crate::Error::FromSql {
+            pg_type: crate::pq::types::UNKNOWN,
+            rust_type: "array".to_string(),
+            value: s.to_string(),
+        });
+    }
+
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut pending = false;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                value.extend(chars.next());
+                pending = true;
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+                pending = true;
+            }
+            c if in_quotes => value.push(c),
+            '{' => (),
+            ',' | '}' => {
+                if pending {
+                    let element = if quoted {
+                        Some(std::mem::take(&mut value))
+                    } else {
+                        let element = value.trim_end();
+
+                        if element.eq_ignore_ascii_case("null") {
+                            None
+                        } else {
+                            Some(element.to_string())
+                        }
+                    };
+
+                    values.push(element);
+                    value.clear();
+                    quoted = false;
+                    pending = false;
+                }
+            }
+            c if c.is_whitespace() && !pending => (),
+            c => {
+                value.push(c);
+                pending = true;
+            }
+        }
+    }
+
+    Ok(values)
+}
+
 #[cfg(test)]
 mod test {
+    #[test]
+    fn text_to_vec() -> crate::Result {
+        assert_eq!(super::text_to_vec("{}")?, Vec::<Option<String>>::new());
+        assert_eq!(
+            super::text_to_vec(r#"{1, NULL ,"null","a \"b\"",""}"#)?,
+            vec![
+                Some("1".to_string()),
+                None,
+                Some("null".to_string()),
+                Some("a \"b\"".to_string()),
+                Some(String::new()),
+            ]
+        );
+        assert_eq!(
+            super::text_to_vec("[0:1][1:1]={{1},{2}}")?,
+            vec![Some("1".to_string()), Some("2".to_string())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_dimensions() {
+        assert_eq!(super::text_dimensions("{}"), Some((vec![], vec![])));
+        assert_eq!(
+            super::text_dimensions(r#"{a,"b,c",NULL}"#),
+            Some((vec![3], vec![1]))
+        );
+        assert_eq!(
+            super::text_dimensions(r#"{{1,2,3},{4,5,6}}"#),
+            Some((vec![2, 3], vec![1, 1]))
+        );
+        assert_eq!(
+            super::text_dimensions(r#"{{"{",2},{"}",4}}"#),
+            Some((vec![2, 2], vec![1, 1]))
+        );
+        assert_eq!(
+            super::text_dimensions("[0:1][1:1]={{1},{2}}"),
+            Some((vec![2, 1], vec![0, 1]))
+        );
+        assert_eq!(super::text_dimensions("{1,2"), None);
+    }
+
+    #[test]
+    fn text_array() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+        let result = elephantry.execute(
+            r#"select '{{a,"b,c"},{NULL,"d\\"}}'::text[] as actual, '[0:1]={1,2}'::int4[] as bounds"#,
+        )?;
+        let tuple = result.get(0);
+
+        let array = tuple.get::<crate::Array<Option<String>>>("actual");
+        assert_eq!(array.ndim, 2);
+        assert_eq!(array.dimensions, vec![2, 2]);
+        assert!(array.has_nulls);
+        assert_eq!(
+            array.collect::<Vec<_>>(),
+            vec![
+                Some("a".to_string()),
+                Some("b,c".to_string()),
+                None,
+                Some("d\\".to_string())
+            ]
+        );
+
+        let array = tuple.get::<crate::Array<i32>>("bounds");
+        assert_eq!(array.lower_bounds, vec![0]);
+        assert_eq!(Vec::from(array), vec![1, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_into_vec() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+        let result = elephantry.execute("select '{1, null, 3}'::int4[] as actual")?;
+        let array = || result.get(0).get::<crate::Array<i32>>("actual");
+
+        assert_eq!(array().collect::<Vec<_>>(), vec![1]);
+        assert!(array().try_into_vec().is_err());
+        assert!(result.get(0).try_get::<Vec<i32>>("actual").is_err());
+
+        let mut array = array();
+        assert_eq!(array.try_next().transpose()?, Some(1));
+        assert!(array.try_next().unwrap().is_err());
+        assert_eq!(array.try_next().transpose()?, Some(3));
+        assert!(array.try_next().is_none());
+
+        let vec: Vec<Option<i32>> = elephantry.query_one("select '{1, null, 3}'::int4[]", &[])?;
+        assert_eq!(vec, vec![Some(1), None, Some(3)]);
+
+        let vec: Vec<i32> = elephantry.query_one("select '{{1, 2}, {3, 4}}'::int4[]", &[])?;
+        assert_eq!(vec, vec![1, 2, 3, 4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_vec() -> crate::Result {
+        let elephantry = crate::test::new_conn()?;
+        let result = elephantry.execute("select '{a, \"b,c\", null}'::text[] as actual")?;
+
+        assert_eq!(
+            result.get(0).get::<Vec<Option<String>>>("actual"),
+            vec![Some("a".to_string()), Some("b,c".to_string()), None]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn bin_vec() -> crate::Result {
         let elephantry = crate::test::new_conn()?;
@@ -1,5 +1,25 @@
+/**
+ * Rust type for a [bit](https://www.postgresql.org/docs/current/datatype-bit.html)
+ * of 8 bits, a plain `u8` being a `"char"`.
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(docsrs, doc(cfg(feature = "bit")))]
-impl crate::ToSql for u8 {
+pub struct Bit(pub u8);
+
+impl From<u8> for Bit {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Bit> for u8 {
+    fn from(bit: Bit) -> Self {
+        bit.0
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "bit")))]
+impl crate::ToSql for Bit {
     fn ty(&self) -> crate::pq::Type {
         crate::pq::types::BIT
     }
@@ -9,36 +29,50 @@ impl crate::ToSql for u8 {
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        let bytes = bit_vec::BitVec::from_bytes(&[*self]);
+        bit_vec::BitVec::from_bytes(&[self.0]).to_sql()
+    }
 
-        bytes.to_sql()
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(format!("{:08b}", self.0)))
     }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "bit")))]
-impl crate::FromSql for u8 {
+impl crate::FromSql for Bit {
     fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
-        let bytes = bit_vec::BitVec::from_text(ty, raw)?;
-
-        bytes
-            .get(0)
-            .map(|x| x as u8)
-            .ok_or_else(|| Self::error(ty, "u8", raw))
+        u8_from_text(ty, raw).map(Self)
     }
 
-    /*
-     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/varbit.c#L375
-     */
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
-        let bytes = bit_vec::BitVec::from_binary(ty, raw)?;
-
-        bytes
-            .get(0)
-            .map(|x| x as u8)
-            .ok_or_else(|| Self::error(ty, "u8", raw))
+        u8_from_binary(ty, raw).map(Self)
     }
 }
 
+pub(crate) fn u8_from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<u8> {
+    use crate::FromSql;
+
+    let bytes = bit_vec::BitVec::from_text(ty, raw)?;
+
+    bytes
+        .get(0)
+        .map(|x| x as u8)
+        .ok_or_else(|| u8::error(ty, "u8", raw))
+}
+
+/*
+ * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/varbit.c#L375
+ */
+pub(crate) fn u8_from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<u8> {
+    use crate::FromSql;
+
+    let bytes = bit_vec::BitVec::from_binary(ty, raw)?;
+
+    bytes
+        .get(0)
+        .map(|x| x as u8)
+        .ok_or_else(|| u8::error(ty, "u8", raw))
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "bit")))]
 impl crate::ToSql for bit_vec::BitVec {
     fn ty(&self) -> crate::pq::Type {
@@ -64,6 +98,12 @@ impl crate::ToSql for bit_vec::BitVec {
 
         Ok(Some(buf.to_vec()))
     }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        let s = self.iter().map(|x| if x { '1' } else { '0' }).collect();
+
+        Ok(Some(s))
+    }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "bit")))]
@@ -85,15 +125,31 @@ impl crate::FromSql for bit_vec::BitVec {
         use byteorder::ReadBytesExt;
 
         let mut buf = crate::not_null(raw)?;
-        let _size = buf.read_i32::<byteorder::BigEndian>()?;
+        let size = buf.read_i32::<byteorder::BigEndian>()?;
 
-        Ok(bit_vec::BitVec::from_bytes(buf))
+        let mut bits = bit_vec::BitVec::from_bytes(buf);
+        bits.truncate(size as usize);
+
+        Ok(bits)
     }
 }
 
 #[cfg(test)]
 mod test {
-    crate::sql_test!(bit, u8, [("'0'", 0), ("'1'", 1), ("0", 0), ("1", 1)]);
+    crate::sql_test!(
+        bit,
+        crate::Bit,
+        [
+            ("'0'", crate::Bit(0)),
+            ("'1'", crate::Bit(1)),
+            ("0", crate::Bit(0)),
+            ("1", crate::Bit(1)),
+        ]
+    );
+
+    mod bit_u8 {
+        crate::sql_test_from!(bit, u8, [("'0'", 0), ("'1'", 1), ("0", 0), ("1", 1)]);
+    }
 
     crate::sql_test!(
         varbit,
@@ -104,4 +160,22 @@ mod test {
             ("'11111111'", bit_vec::BitVec::from_bytes(&[0b11111111])),
         ]
     );
+
+    #[test]
+    fn to_text() -> crate::Result {
+        use crate::ToSql;
+
+        let mut bits = bit_vec::BitVec::from_bytes(&[0b10100000]);
+        bits.truncate(3);
+        assert_eq!(bits.to_text()?, Some("101".to_string()));
+
+        let conn = crate::test::new_conn()?;
+        let text = bits.to_text()?.unwrap();
+        assert_eq!(
+            conn.query_one::<bit_vec::BitVec>(&format!("select '{}'::varbit", text), &[])?,
+            bits
+        );
+
+        Ok(())
+    }
 }
@@ -1,15 +1,35 @@
 /**
  * Rust type for [bytea](https://www.postgresql.org/docs/current/datatype-binary.html).
+ *
+ * `&[u8]` and `Vec<u8>` are also read and written as `bytea`.
  */
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Bytea(Vec<u8>);
 
+impl Bytea {
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
 impl From<Vec<u8>> for Bytea {
     fn from(vec: Vec<u8>) -> Self {
         Self(vec)
     }
 }
 
+impl From<&[u8]> for Bytea {
+    fn from(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+}
+
+impl From<Bytea> for Vec<u8> {
+    fn from(bytea: Bytea) -> Self {
+        bytea.0
+    }
+}
+
 impl std::ops::Deref for Bytea {
     type Target = Vec<u8>;
 
@@ -27,9 +47,36 @@ impl crate::ToSql for Bytea {
         crate::pq::Format::Binary
     }
 
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.as_slice().to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        self.as_slice().to_text()
+    }
+}
+
+impl crate::ToSql for [u8] {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::BYTEA
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
         Ok(Some(self.to_vec()))
     }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        let hex = self
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect::<String>();
+
+        Ok(Some(format!("\\x{}", hex)))
+    }
 }
 
 impl crate::FromSql for Bytea {
@@ -59,4 +106,83 @@ mod test {
         crate::Bytea,
         [("'abcd'", crate::Bytea::from(Vec::from("abcd")))]
     );
+
+    #[test]
+    fn vec() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        for (value, expected) in [
+            ("'abcd'", b"abcd".to_vec()),
+            ("'\\x00ff'", vec![0, 255]),
+            ("''", Vec::new()),
+        ] {
+            let query = format!("select {}::bytea as actual", value);
+
+            assert_eq!(
+                conn.execute(&query)?.get(0).get::<Vec<u8>>("actual"),
+                expected
+            );
+            assert_eq!(conn.query_one::<Vec<u8>>(&query, &[])?, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn slice() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let bytes: &[u8] = &[0, 1, 255];
+
+        assert_eq!(
+            conn.query_one::<Vec<u8>>("select $1", &[&bytes])?,
+            bytes.to_vec()
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&bytes])?,
+            "\\x0001ff"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_vec() -> crate::Result {
+        use crate::ToSql;
+
+        let conn = crate::test::new_conn()?;
+        let bytes = vec![0_u8, 1, 255];
+
+        assert_eq!(bytes.ty(), crate::pq::types::BYTEA);
+        assert_eq!(
+            conn.query_one::<Vec<u8>>("select $1::bytea", &[&bytes])?,
+            bytes
+        );
+        assert_eq!(bytes.to_text()?, Some("\\x0001ff".to_string()));
+        assert_eq!(
+            conn.query_one::<Vec<u8>>("select $1::bytea", &[&Vec::<u8>::new()])?,
+            Vec::<u8>::new()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn char() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert_eq!(conn.query_one::<u8>("select 'a'::\"char\"", &[])?, b'a');
+        assert_eq!(
+            conn.execute("select 'é'::\"char\" as c")?
+                .get(0)
+                .get::<u8>("c"),
+            0xc3
+        );
+        assert_eq!(conn.query_one::<u8>("select $1::\"char\"", &[&b'a'])?, b'a');
+        assert_eq!(
+            conn.query_one::<String>("select $1::\"char\"::text", &[&b'a'])?,
+            "a"
+        );
+
+        Ok(())
+    }
 }
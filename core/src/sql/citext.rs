@@ -0,0 +1,111 @@
+/**
+ * Rust type for
+ * [citext](https://www.postgresql.org/docs/current/citext.html), a case
+ * insensitive string.
+ *
+ * Like the SQL type, comparisons ignore the case.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Citext(String);
+
+impl Citext {
+    pub fn new(s: &str) -> Self {
+        Self(s.to_string())
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl PartialEq for Citext {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_lowercase() == other.0.to_lowercase()
+    }
+}
+
+impl Eq for Citext {}
+
+impl std::hash::Hash for Citext {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_lowercase().hash(state);
+    }
+}
+
+impl std::ops::Deref for Citext {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Citext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for Citext {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<String> for Citext {
+    fn from(s: String) -> Self {
+        Self(s)
+    }
+}
+
+impl From<Citext> for String {
+    fn from(citext: Citext) -> Self {
+        citext.0
+    }
+}
+
+impl crate::ToSql for Citext {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::Type {
+            oid: 0,
+            descr: "citext",
+            name: "citext",
+            kind: libpq::types::Kind::UserDefined,
+        }
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.0.to_sql()
+    }
+}
+
+impl crate::FromSql for Citext {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        String::from_text(ty, raw).map(Self)
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        String::from_binary(ty, raw).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    crate::sql_test!(
+        citext,
+        crate::Citext,
+        [
+            ("'Foo'", crate::Citext::new("foo")),
+            ("'ÉTÉ'", crate::Citext::new("été")),
+        ]
+    );
+
+    #[test]
+    fn compare() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert!(conn.query_one::<bool>("select $1 = 'FOO'::citext", &[&crate::Citext::new("foo")])?);
+
+        Ok(())
+    }
+}
@@ -31,43 +31,22 @@ pub trait Composite {
     ) -> crate::Result<Box<Self>>;
 
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Box<Self>> {
-        let values = crate::sql::record::binary_to_vec(raw)?;
+        let values = crate::sql::record::binary_to_vec(raw)?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
 
         Self::from_binary_values(ty, &values)
     }
 
     fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Box<Self>> {
         let values = crate::sql::record::text_to_vec(raw)?;
+        let values = values.iter().map(Option::as_deref).collect::<Vec<_>>();
 
         Self::from_text_values(ty, &values)
     }
 }
 
-impl<C: Composite> crate::ToSql for C {
-    fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::Type {
-            oid: 0,
-            descr: Self::name(),
-            name: Self::name(),
-            kind: libpq::types::Kind::Composite,
-        }
-    }
-
-    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_sql()
-    }
-}
-
-impl<C: Composite> crate::FromSql for C {
-    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
-        Self::from_text(ty, raw).map(|x| *x)
-    }
-
-    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
-        Self::from_binary(ty, raw).map(|x| *x)
-    }
-}
-
 #[cfg(test)]
 mod test {
     #[derive(elephantry_derive::Composite, Debug, PartialEq)]
@@ -80,12 +59,120 @@ mod test {
     crate::sql_test!(
         compfoo,
         super::CompFoo,
-        [(
-            "'(1,foo)'",
-            super::CompFoo {
-                f1: 1,
-                f2: "foo".to_string()
-            }
-        )]
+        [
+            (
+                "'(1,foo)'",
+                super::CompFoo {
+                    f1: 1,
+                    f2: "foo".to_string()
+                }
+            ),
+            (
+                r#"'(2,"a (\"quoted\"), \\text")'"#,
+                super::CompFoo {
+                    f1: 2,
+                    f2: r#"a ("quoted"), \text"#.to_string()
+                }
+            ),
+        ]
     );
+
+    #[derive(elephantry_derive::Composite, Debug, PartialEq)]
+    #[elephantry(internal)]
+    struct NullableFoo {
+        f1: Option<i32>,
+        f2: Option<String>,
+    }
+
+    #[test]
+    fn nullable() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        for value in [
+            NullableFoo { f1: None, f2: None },
+            NullableFoo {
+                f1: Some(1),
+                f2: Some(String::new()),
+            },
+        ] {
+            let query = "select $1::compfoo as actual";
+            assert_eq!(conn.query_one::<NullableFoo>(query, &[&value])?, value);
+
+            let text = crate::ToSql::to_text(&value)?.unwrap();
+            let result = conn.execute(&format!("select '{}'::compfoo as actual", text))?;
+            assert_eq!(result.get(0).get::<NullableFoo>("actual"), value);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn array() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let values = vec![
+            CompFoo {
+                f1: 1,
+                f2: "a, b".to_string(),
+            },
+            CompFoo {
+                f1: 2,
+                f2: "{c}".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            conn.query_one::<Vec<CompFoo>>("select $1::compfoo[]", &[&values])?,
+            values
+        );
+
+        let text = crate::ToSql::to_text(&values)?.unwrap();
+        let result = conn.execute(&format!(
+            "select '{}'::compfoo[] as actual",
+            text.replace('\'', "''")
+        ))?;
+        assert_eq!(result.get(0).get::<Vec<CompFoo>>("actual"), values);
+
+        Ok(())
+    }
+
+    #[test]
+    fn slice() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create or replace function pg_temp.sum_compfoo(foos compfoo[]) returns bigint
+            language plpgsql as $$
+            declare
+                total bigint := 0;
+                foo compfoo;
+            begin
+                foreach foo in array foos loop
+                    total := total + foo.f1;
+                end loop;
+
+                return total;
+            end $$",
+        )?;
+
+        let values = [
+            CompFoo {
+                f1: 1,
+                f2: "a".to_string(),
+            },
+            CompFoo {
+                f1: 2,
+                f2: "(b)".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            conn.query_one::<i64>("select pg_temp.sum_compfoo($1)", &[&values.as_slice()])?,
+            3
+        );
+        assert_eq!(
+            conn.query_one::<String>("select pg_typeof($1)::text", &[&values.as_slice()])?,
+            "compfoo[]"
+        );
+
+        Ok(())
+    }
 }
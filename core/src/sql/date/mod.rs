@@ -2,7 +2,12 @@ mod interval;
 mod timestamp;
 mod timestamptz;
 
-pub use interval::*;
+/* 2000-01-01, the postgresql epoch */
+fn epoch() -> chrono::NaiveDateTime {
+    chrono::NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|x| x.and_hms_opt(0, 0, 0))
+        .unwrap()
+}
 
 #[cfg_attr(docsrs, doc(cfg(feature = "date")))]
 impl crate::ToSql for chrono::NaiveDate {
@@ -10,8 +15,22 @@ impl crate::ToSql for chrono::NaiveDate {
         crate::pq::types::DATE
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.format("%F").to_string().to_sql()
+        use std::convert::TryFrom;
+
+        let base = epoch().date();
+        let days = i32::try_from(self.signed_duration_since(base).num_days())
+            .map_err(|_| self.error("date", None))?;
+
+        days.to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.format("%F").to_string()))
     }
 }
 
@@ -26,7 +45,7 @@ impl crate::FromSql for chrono::NaiveDate {
 
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
         let t = i32::from_binary(ty, raw)?;
-        let base = chrono::NaiveDate::from_ymd(2000, 1, 1);
+        let base = epoch().date();
 
         Ok(base + chrono::Duration::days(t.into()))
     }
@@ -38,9 +57,18 @@ mod test {
         date,
         chrono::NaiveDate,
         [
-            ("'1970-01-01'", chrono::NaiveDate::from_ymd(1970, 01, 01)),
-            ("'2010-01-01'", chrono::NaiveDate::from_ymd(2010, 01, 01)),
-            ("'2100-12-30'", chrono::NaiveDate::from_ymd(2100, 12, 30)),
+            (
+                "'1970-01-01'",
+                chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+            ),
+            (
+                "'2010-01-01'",
+                chrono::NaiveDate::from_ymd_opt(2010, 1, 1).unwrap()
+            ),
+            (
+                "'2100-12-30'",
+                chrono::NaiveDate::from_ymd_opt(2100, 12, 30).unwrap()
+            ),
         ]
     );
 }
@@ -4,8 +4,21 @@ impl crate::ToSql for chrono::NaiveDateTime {
         crate::pq::types::TIMESTAMP
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.format("%F %T").to_string().to_sql()
+        let base = super::epoch();
+
+        match self.signed_duration_since(base).num_microseconds() {
+            Some(t) => t.to_sql(),
+            None => Err(self.error("timestamp", None)),
+        }
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.format("%F %T%.f").to_string()))
     }
 }
 
@@ -24,7 +37,7 @@ impl crate::FromSql for chrono::NaiveDateTime {
 
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
         let t = i64::from_binary(ty, raw)?;
-        let base = chrono::NaiveDate::from_ymd(2000, 1, 1).and_hms(0, 0, 0);
+        let base = super::epoch();
 
         Ok(base + chrono::Duration::microseconds(t))
     }
@@ -37,7 +50,7 @@ mod test {
         chrono::NaiveDateTime,
         [(
             "'1970-01-01 00:00:00'",
-            chrono::NaiveDateTime::from_timestamp(0, 0),
+            chrono::DateTime::UNIX_EPOCH.naive_utc(),
         )]
     );
 }
@@ -4,8 +4,16 @@ impl crate::ToSql for chrono::DateTime<chrono::Utc> {
         crate::pq::types::TIMESTAMPTZ
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.format("%F %T%z").to_string().to_sql()
+        self.naive_utc().to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.format("%F %T%.f%:z").to_string()))
     }
 }
 
@@ -19,7 +27,7 @@ impl crate::FromSql for chrono::DateTime<chrono::Utc> {
 
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
         let naive = chrono::NaiveDateTime::from_binary(ty, raw)?;
-        Ok(chrono::DateTime::from_utc(naive, chrono::Utc))
+        Ok(naive.and_utc())
     }
 }
 
@@ -29,8 +37,16 @@ impl crate::ToSql for chrono::DateTime<chrono::offset::FixedOffset> {
         crate::pq::types::TIMESTAMPTZ
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.format("%F %T%z").to_string().to_sql()
+        self.naive_utc().to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.format("%F %T%.f%:z").to_string()))
     }
 }
 
@@ -43,7 +59,7 @@ impl crate::FromSql for chrono::DateTime<chrono::offset::FixedOffset> {
 
     fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
         let utc = chrono::DateTime::<chrono::Utc>::from_binary(ty, raw)?;
-        Ok(utc.with_timezone(&chrono::offset::FixedOffset::east(0)))
+        Ok(utc.fixed_offset())
     }
 }
 
@@ -53,8 +69,16 @@ impl crate::ToSql for chrono::DateTime<chrono::offset::Local> {
         crate::pq::types::TIMESTAMPTZ
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.format("%F %T").to_string().to_sql()
+        self.naive_utc().to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.format("%F %T%.f%:z").to_string()))
     }
 }
 
@@ -78,10 +102,25 @@ mod test {
         chrono::DateTime<chrono::Utc>,
         [(
             "'1970-01-01 00:00:00+00'",
-            chrono::DateTime::<chrono::Utc>::from_utc(
-                chrono::NaiveDateTime::from_timestamp(0, 0),
-                chrono::Utc
-            ),
+            chrono::DateTime::<chrono::Utc>::UNIX_EPOCH,
         )]
     );
+
+    #[test]
+    fn to_binary() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("set timezone to 'UTC'")?;
+
+        let date = chrono::DateTime::parse_from_rfc3339("1999-12-31T23:59:59.5-02:00").unwrap();
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&date])?,
+            "2000-01-01 01:59:59.5+00"
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&date.naive_local()])?,
+            "1999-12-31 23:59:59.5"
+        );
+
+        Ok(())
+    }
 }
@@ -7,46 +7,30 @@ pub trait Enum: std::fmt::Debug {
     fn name() -> &'static str;
     /** Convert str to enum value */
     fn from_text(value: &str) -> crate::Result<Box<Self>>;
-}
-
-/*
- * @FIXME impl FromSql/ToSql instead
- * https://github.com/rust-lang/rfcs/blob/master/text/1210-impl-specialization.md
- */
-impl<E: Enum> crate::Composite for E {
-    fn name() -> &'static str {
-        E::name()
+    /**
+     * Labels of the variants, in declaration order. Generated by the derive,
+     * empty by default: [`Enum::ordinal`] is then always 0.
+     */
+    fn variants() -> &'static [&'static str] {
+        &[]
     }
 
-    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        use crate::ToSql;
+    /** Position of this variant in [`Enum::variants`] */
+    fn ordinal(&self) -> usize {
+        let label = format!("{:?}", self);
 
-        format!("{:?}", self).to_sql()
+        Self::variants()
+            .iter()
+            .position(|x| *x == label)
+            .unwrap_or_default()
     }
 
-    fn from_text(_: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Box<Self>> {
-        Self::from_text(crate::not_null(raw)?)
-    }
-
-    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Box<Self>> {
-        use crate::FromSql;
-
-        Self::from_text(&String::from_binary(ty, raw)?)
-    }
-
-    fn to_vec(&self) -> Vec<&dyn crate::ToSql> {
-        unreachable!()
-    }
-
-    fn from_text_values(_: &crate::pq::Type, _: &[Option<&str>]) -> crate::Result<Box<Self>> {
-        unreachable!();
-    }
-
-    fn from_binary_values(
-        _ty: &crate::pq::Type,
-        _values: &[Option<&[u8]>],
-    ) -> crate::Result<Box<Self>> {
-        unreachable!()
+    /** Convert str to enum value, `None` if the label is unknow */
+    fn try_from_text(value: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_text(value).ok().map(|x| *x)
     }
 }
 
@@ -60,6 +44,48 @@ mod test {
         Happy,
     }
 
+    #[test]
+    fn variants() {
+        use crate::Enum;
+
+        assert_eq!(Mood::variants(), &["Sad", "Ok", "Happy"]);
+        assert_eq!(Mood::Happy.ordinal(), 2);
+        assert_eq!(Mood::try_from_text("Ok"), Some(Mood::Ok));
+        assert_eq!(Mood::try_from_text("Angry"), None);
+        assert!(Mood::from_text("Angry").is_err());
+    }
+
+    #[derive(Debug)]
+    struct Manual;
+
+    impl crate::Enum for Manual {
+        fn name() -> &'static str {
+            "manual"
+        }
+
+        fn from_text(_: &str) -> crate::Result<Box<Self>> {
+            Ok(Box::new(Manual))
+        }
+    }
+
+    #[test]
+    fn default_variants() {
+        use crate::Enum;
+
+        assert!(Manual::variants().is_empty());
+        assert_eq!(Manual.ordinal(), 0);
+    }
+
+    #[test]
+    fn enum_labels() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert_eq!(conn.enum_labels("mood")?, ["Sad", "Ok", "Happy"]);
+        assert!(conn.enum_labels("unknow_type").is_err());
+
+        Ok(())
+    }
+
     crate::sql_test!(
         mood,
         super::Mood,
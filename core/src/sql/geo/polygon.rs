@@ -22,7 +22,7 @@ impl std::fmt::Display for Polygon {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = String::new();
 
-        for coordinate in self.0.exterior().points_iter() {
+        for coordinate in self.0.exterior().points() {
             s.push_str(&format!("({}, {}),", coordinate.x(), coordinate.y()));
         }
 
@@ -2,6 +2,9 @@ use std::collections::HashMap;
 
 /**
  * Rust type for [hstore](https://www.postgresql.org/docs/current/hstore.html).
+ *
+ * The OID of this type depends on the database, see
+ * [`Connection::hstore_oid`](crate::Connection::hstore_oid).
  */
 #[derive(Clone, Debug, PartialEq)]
 pub struct Hstore(HashMap<String, Option<String>>);
@@ -11,6 +14,10 @@ impl Hstore {
         Self(HashMap::new())
     }
 
+    pub fn into_inner(self) -> HashMap<String, Option<String>> {
+        self.0
+    }
+
     fn read_string(buf: &mut &[u8]) -> crate::Result<Option<String>> {
         use byteorder::ReadBytesExt;
 
@@ -29,6 +36,101 @@ impl Hstore {
 
         Ok(s)
     }
+
+    fn write_string(buf: &mut Vec<u8>, s: Option<&str>) {
+        match s {
+            Some(s) => {
+                buf.extend_from_slice(&(s.len() as i32).to_be_bytes());
+                buf.extend_from_slice(s.as_bytes());
+            }
+            None => buf.extend_from_slice(&(-1_i32).to_be_bytes()),
+        }
+    }
+
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/contrib/hstore/hstore_io.c#L57
+     */
+    fn parse(s: &str) -> Option<HashMap<String, Option<String>>> {
+        let mut map = HashMap::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some((key, _)) = Self::parse_word(&mut chars)? {
+            Self::skip_whitespaces(&mut chars);
+            if chars.next() != Some('=') || chars.next() != Some('>') {
+                return None;
+            }
+
+            let value = match Self::parse_word(&mut chars)? {
+                Some((value, false)) if value.eq_ignore_ascii_case("null") => None,
+                Some((value, _)) => Some(value),
+                None => return None,
+            };
+
+            map.insert(key, value);
+
+            Self::skip_whitespaces(&mut chars);
+            match chars.next() {
+                Some(',') => (),
+                None => break,
+                Some(_) => return None,
+            }
+        }
+
+        Some(map)
+    }
+
+    /**
+     * Parses a quoted or unquoted word, returns it with `true` if it was
+     * quoted.
+     */
+    fn parse_word(
+        chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    ) -> Option<Option<(String, bool)>> {
+        Self::skip_whitespaces(chars);
+
+        let mut word = String::new();
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+
+            loop {
+                match chars.next()? {
+                    '"' => return Some(Some((word, true))),
+                    '\\' => word.push(chars.next()?),
+                    c => word.push(c),
+                }
+            }
+        }
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '=' || c == ',' {
+                break;
+            }
+
+            chars.next();
+            if c == '\\' {
+                word.push(chars.next()?);
+            } else {
+                word.push(c);
+            }
+        }
+
+        if word.is_empty() {
+            Some(None)
+        } else {
+            Some(Some((word, false)))
+        }
+    }
+
+    fn skip_whitespaces(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+        while chars.peek().map(|x| x.is_whitespace()) == Some(true) {
+            chars.next();
+        }
+    }
 }
 
 impl Default for Hstore {
@@ -51,48 +153,72 @@ impl std::ops::Deref for Hstore {
     }
 }
 
+impl From<HashMap<String, Option<String>>> for Hstore {
+    fn from(map: HashMap<String, Option<String>>) -> Self {
+        Self(map)
+    }
+}
+
+impl From<Hstore> for HashMap<String, Option<String>> {
+    fn from(hstore: Hstore) -> Self {
+        hstore.0
+    }
+}
+
 impl crate::ToSql for crate::Hstore {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::TEXT
+        crate::pq::Type {
+            oid: 0,
+            descr: "hstore",
+            name: "hstore",
+            kind: libpq::types::Kind::UserDefined,
+        }
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
     }
 
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/contrib/hstore/hstore_io.c#L1289
+     */
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        let mut vec = Vec::new();
+        let mut buf = Vec::new();
 
-        for (key, value) in self.iter() {
-            let v = value
-                .as_ref()
-                .map(|x| format!("\"{}\"", x))
-                .unwrap_or_else(|| "NULL".to_string());
+        buf.extend_from_slice(&(self.len() as i32).to_be_bytes());
 
-            vec.push(format!("\"{}\"=>{}", key, v));
+        for (key, value) in self.iter() {
+            Self::write_string(&mut buf, Some(key));
+            Self::write_string(&mut buf, value.as_deref());
         }
 
-        vec.join(", ").to_sql()
+        Ok(Some(buf))
     }
-}
 
-impl crate::FromSql for Hstore {
-    fn from_text(_: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
-        lazy_static::lazy_static! {
-            static ref REGEX: regex::Regex = regex::Regex::new(
-                "\"(?P<key>.*?)\"=>(\"(?P<value>.*?)\"|(?P<null>NULL))",
-            ).unwrap();
-        }
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        let pairs = self
+            .iter()
+            .map(|(key, value)| {
+                let value = value
+                    .as_deref()
+                    .map(Self::quote)
+                    .unwrap_or_else(|| "NULL".to_string());
 
-        let mut hstore = Self::new();
+                format!("{}=>{}", Self::quote(key), value)
+            })
+            .collect::<Vec<_>>();
 
-        for capture in REGEX.captures_iter(crate::not_null(raw)?) {
-            let key = capture.name("key").unwrap().as_str().to_string();
-            let value = if capture.name("null").is_some() {
-                None
-            } else {
-                Some(capture.name("value").unwrap().as_str().to_string())
-            };
-            hstore.insert(key, value);
-        }
+        Ok(Some(pairs.join(", ")))
+    }
+}
 
-        Ok(hstore)
+impl crate::FromSql for Hstore {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let s = crate::not_null(raw)?;
+
+        Self::parse(s)
+            .map(Self)
+            .ok_or_else(|| Self::error(ty, "Hstore", raw))
     }
 
     /*
@@ -121,13 +247,54 @@ mod test {
     crate::sql_test!(
         hstore,
         crate::Hstore,
-        [("'a=>1, b => 2, c=>null'", {
-            let mut hstore = crate::Hstore::new();
-            hstore.insert("a".to_string(), Some("1".to_string()));
-            hstore.insert("b".to_string(), Some("2".to_string()));
-            hstore.insert("c".to_string(), None);
+        [
+            ("'a=>1, b => 2, c=>null'", {
+                let mut hstore = crate::Hstore::new();
+                hstore.insert("a".to_string(), Some("1".to_string()));
+                hstore.insert("b".to_string(), Some("2".to_string()));
+                hstore.insert("c".to_string(), None);
 
-            hstore
-        })]
+                hstore
+            }),
+            (r#"'"a b"=>"\"x\", y\\", "NULL"=>"NULL"'"#, {
+                let mut hstore = crate::Hstore::new();
+                hstore.insert("a b".to_string(), Some("\"x\", y\\".to_string()));
+                hstore.insert("NULL".to_string(), Some("NULL".to_string()));
+
+                hstore
+            }),
+            ("''", crate::Hstore::new()),
+        ]
     );
+
+    #[test]
+    fn hstore_oid() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let oid = conn
+            .query_one::<crate::pq::Oid>("select oid from pg_type where typname = 'hstore'", &[])?;
+
+        let lazy = crate::test::new_conn()?;
+        assert_eq!(lazy.types().oid("hstore"), None);
+        assert_eq!(lazy.hstore_oid(), Some(oid));
+        assert_eq!(lazy.types().oid("hstore"), Some(oid));
+
+        assert_eq!(conn.hstore_oid(), Some(oid));
+
+        let mut hstore = crate::Hstore::new();
+        hstore.insert("key, \"quoted\"".to_string(), None);
+
+        assert_eq!(
+            conn.query_one::<crate::Hstore>("select $1", &[&hstore])?,
+            hstore
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&hstore])?,
+            r#""key, \"quoted\""=>NULL"#
+        );
+
+        let map: std::collections::HashMap<_, _> = hstore.into();
+        assert_eq!(map.len(), 1);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,380 @@
+#[cfg_attr(docsrs, doc(cfg(any(feature = "date", feature = "time"))))]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Interval {
+    pub years: i32,
+    pub months: i32,
+    pub days: i32,
+    pub hours: i32,
+    pub mins: i32,
+    pub secs: i32,
+    pub usecs: i32,
+}
+
+impl Interval {
+    pub fn new(
+        years: i32,
+        months: i32,
+        days: i32,
+        hours: i32,
+        mins: i32,
+        secs: i32,
+        usecs: i32,
+    ) -> Self {
+        Self {
+            years,
+            months,
+            days,
+            hours,
+            mins,
+            secs,
+            usecs,
+        }
+    }
+
+    pub fn year() -> Self {
+        Self::years(1)
+    }
+
+    pub fn years(n: i32) -> Self {
+        Self::new(n, 0, 0, 0, 0, 0, 0)
+    }
+
+    pub fn month() -> Self {
+        Self::months(1)
+    }
+
+    pub fn months(n: i32) -> Self {
+        Self::new(0, n, 0, 0, 0, 0, 0)
+    }
+
+    pub fn day() -> Self {
+        Self::days(1)
+    }
+
+    pub fn days(n: i32) -> Self {
+        Self::new(0, 0, n, 0, 0, 0, 0)
+    }
+
+    pub fn hour() -> Self {
+        Self::hours(1)
+    }
+
+    pub fn hours(n: i32) -> Self {
+        Self::new(0, 0, 0, n, 0, 0, 0)
+    }
+
+    pub fn minute() -> Self {
+        Self::minutes(1)
+    }
+
+    pub fn minutes(n: i32) -> Self {
+        Self::new(0, 0, 0, 0, n, 0, 0)
+    }
+
+    pub fn second() -> Self {
+        Self::seconds(1)
+    }
+
+    pub fn seconds(n: i32) -> Self {
+        Self::new(0, 0, 0, 0, 0, n, 0)
+    }
+
+    pub fn microsecond() -> Self {
+        Self::microseconds(1)
+    }
+
+    pub fn microseconds(n: i32) -> Self {
+        Self::new(0, 0, 0, 0, 0, 0, n)
+    }
+
+    /*
+     * Interval of `usecs` microseconds, split in hours, minutes, seconds and
+     * microseconds, like the server stores it.
+     */
+    pub(crate) fn from_usecs(usecs: i64) -> Option<Self> {
+        use std::convert::TryFrom;
+
+        let hours = i32::try_from(usecs / (60 * 60 * 1_000_000)).ok()?;
+        let usecs = usecs % (60 * 60 * 1_000_000);
+
+        Some(Self::new(
+            0,
+            0,
+            0,
+            hours,
+            (usecs / (60 * 1_000_000)) as i32,
+            (usecs % (60 * 1_000_000) / 1_000_000) as i32,
+            (usecs % 1_000_000) as i32,
+        ))
+    }
+
+    /*
+     * Elapsed time in microseconds, a day counting for 24 hours. Months have
+     * no fixed duration and are rejected.
+     */
+    pub(crate) fn elapsed_usecs(&self) -> crate::Result<i64> {
+        use std::convert::TryFrom;
+
+        let months = self.years * 12 + self.months;
+
+        if months != 0 {
+            return Err(crate::Error::IntervalMonths(months));
+        }
+
+        let usecs = (((self.days as i128 * 24 + self.hours as i128) * 60 + self.mins as i128) * 60
+            + self.secs as i128)
+            * 1_000_000
+            + self.usecs as i128;
+
+        i64::try_from(usecs)
+            .map_err(|_| <Self as crate::FromSql>::error(&crate::pq::types::INTERVAL, "i64", self))
+    }
+
+    /*
+     * Parses the `postgres` interval style, `[N years] [N mons] [N days]
+     * [-]HH:MM:SS[.ffffff]`.
+     */
+    fn parse(s: &str) -> Option<Self> {
+        let mut interval = Self::default();
+        let mut tokens = s.split_whitespace();
+
+        while let Some(token) = tokens.next() {
+            if token.contains(':') {
+                let (sign, hms) = match token.strip_prefix('-') {
+                    Some(hms) => (-1, hms),
+                    None => (1, token.strip_prefix('+').unwrap_or(token)),
+                };
+
+                let mut parts = hms.splitn(3, ':');
+                let hours: i32 = parts.next()?.parse().ok()?;
+                let mins: i32 = parts.next()?.parse().ok()?;
+                let secs = parts.next()?;
+
+                let (secs, fraction) = match secs.find('.') {
+                    Some(x) => (&secs[..x], &secs[x + 1..]),
+                    None => (secs, ""),
+                };
+
+                // The fractional part drops its trailing zeros: `.5` is 500000µs
+                if fraction.len() > 6 || !fraction.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+
+                let secs: i32 = secs.parse().ok()?;
+                let usecs: i32 = format!("{:0<6}", fraction).parse().ok()?;
+
+                interval.hours += sign * hours;
+                interval.mins += sign * mins;
+                interval.secs += sign * secs;
+                interval.usecs += sign * usecs;
+            } else {
+                let n: i32 = token.parse().ok()?;
+                let unit = tokens.next()?;
+
+                if unit.starts_with("year") {
+                    interval.years += n;
+                } else if unit.starts_with("mon") {
+                    interval.months += n;
+                } else if unit.starts_with("day") {
+                    interval.days += n;
+                } else {
+                    return None;
+                }
+            }
+        }
+
+        Some(interval)
+    }
+}
+
+impl From<&Interval> for i64 {
+    fn from(interval: &Interval) -> Self {
+        interval.years as i64 * 12 * 30 * 24 * 60 * 60 * 1_000_000
+            + interval.months as i64 * 30 * 24 * 60 * 60 * 1_000_000
+            + interval.days as i64 * 24 * 60 * 60 * 1_000_000
+            + interval.hours as i64 * 60 * 60 * 1_000_000
+            + interval.mins as i64 * 60 * 1_000_000
+            + interval.secs as i64 * 1_000_000
+            + interval.usecs as i64
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        let a: i64 = i64::from(self);
+        let b: i64 = i64::from(other);
+
+        a.eq(&b)
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        let a: i64 = self.into();
+        let b: i64 = other.into();
+
+        a.partial_cmp(&b)
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} years {} months {} days {}:{}:{}.{}",
+            self.years, self.months, self.days, self.hours, self.mins, self.secs, self.usecs,
+        )
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(any(feature = "date", feature = "time"))))]
+impl crate::FromSql for Interval {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        Self::parse(crate::not_null(raw)?)
+            .ok_or_else(|| Self::error(ty, "elephantry::Interval", raw))
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/timestamp.c#L994
+     */
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        use byteorder::ReadBytesExt;
+
+        let mut buf = crate::not_null(raw)?;
+        let usecs = buf.read_i64::<byteorder::BigEndian>()?;
+        let days = buf.read_i32::<byteorder::BigEndian>()?;
+        let months = buf.read_i32::<byteorder::BigEndian>()?;
+
+        let mut interval =
+            Self::from_usecs(usecs).ok_or_else(|| Self::error(ty, "elephantry::Interval", raw))?;
+        interval.years = months / 12;
+        interval.months = months % 12;
+        interval.days = days;
+
+        Ok(interval)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(any(feature = "date", feature = "time"))))]
+impl crate::ToSql for Interval {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::INTERVAL
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/timestamp.c#L1016
+     */
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let usecs = ((self.hours as i64 * 60 + self.mins as i64) * 60 + self.secs as i64)
+            * 1_000_000
+            + self.usecs as i64;
+        let months = self.years * 12 + self.months;
+
+        let mut buf = Vec::with_capacity(16);
+        buf.extend_from_slice(&usecs.to_be_bytes());
+        buf.extend_from_slice(&self.days.to_be_bytes());
+        buf.extend_from_slice(&months.to_be_bytes());
+
+        Ok(Some(buf))
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(format!(
+            "{} years {} mons {} days {} hours {} mins {} secs {} microseconds",
+            self.years, self.months, self.days, self.hours, self.mins, self.secs, self.usecs,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::FromSql;
+
+    #[test]
+    fn from_text() {
+        let tests = vec![
+            ("00:00:00", crate::Interval::new(0, 0, 0, 0, 0, 0, 0)),
+            ("1 year", crate::Interval::new(0, 12, 0, 0, 0, 0, 0)),
+            ("1 years", crate::Interval::new(1, 0, 0, 0, 0, 0, 0)),
+            ("1 month", crate::Interval::new(0, 1, 0, 0, 0, 0, 0)),
+            ("1 year 10 days", crate::Interval::new(1, 0, 10, 0, 0, 0, 0)),
+            (
+                "1 year 2 months 3 days 04:05:06.000007",
+                crate::Interval::new(1, 2, 3, 4, 5, 6, 7),
+            ),
+            (
+                "-1 days -01:02:03.5",
+                crate::Interval::new(0, 0, -1, -1, -2, -3, -500_000),
+            ),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(
+                crate::Interval::from_text(&crate::pq::types::INTERVAL, Some(value)).unwrap(),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn from_binary() {
+        let tests = vec![
+            (
+                [0, 0, 0, 3, 48, 151, 149, 151, 0, 0, 0, 0, 0, 0, 0, 0],
+                crate::Interval::new(0, 0, 0, 3, 48, 20, 142487),
+            ),
+            (
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 3, 50],
+                crate::Interval::new(68, 2, 4, 0, 0, 0, 0),
+            ),
+            (
+                [0, 0, 0, 3, 108, 139, 192, 128, 0, 0, 0, 3, 0, 0, 0, 14],
+                crate::Interval::new(1, 2, 3, 4, 5, 6, 0),
+            ),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(
+                crate::Interval::from_binary(&crate::pq::types::INTERVAL, Some(&value),).unwrap(),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let mut rng = fastrand::Rng::with_seed(3024);
+
+        for _ in 0..100 {
+            let interval = crate::Interval::new(
+                rng.i32(0..100),
+                rng.i32(0..12),
+                rng.i32(0..31),
+                rng.i32(0..24),
+                rng.i32(0..60),
+                rng.i32(0..60),
+                rng.i32(0..1_000_000),
+            );
+
+            assert_eq!(
+                conn.query_one::<crate::Interval>("select $1::interval", &[&interval])?,
+                interval,
+            );
+            assert_eq!(
+                conn.query_one::<String>("select $1::interval::text", &[&interval])?,
+                conn.query_one::<String>(
+                    "select $1::interval::text",
+                    &[&crate::ToSql::to_text(&interval)?.unwrap()],
+                )?,
+            );
+        }
+
+        Ok(())
+    }
+}
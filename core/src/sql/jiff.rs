@@ -0,0 +1,316 @@
+/* Microseconds between the unix epoch and 2000-01-01, the postgresql epoch */
+const POSTGRES_EPOCH_USECS: i64 = 946_684_800_000_000;
+const USECS_PER_DAY: i64 = 86_400_000_000;
+
+fn to_usecs(timestamp: ::jiff::Timestamp) -> i64 {
+    timestamp.as_microsecond() - POSTGRES_EPOCH_USECS
+}
+
+fn from_usecs<T: crate::FromSql>(
+    ty: &crate::pq::Type,
+    usecs: i64,
+) -> crate::Result<::jiff::Timestamp> {
+    usecs
+        .checked_add(POSTGRES_EPOCH_USECS)
+        .and_then(|x| ::jiff::Timestamp::from_microsecond(x).ok())
+        .ok_or_else(|| T::error(ty, "", usecs))
+}
+
+fn utc(datetime: ::jiff::civil::DateTime) -> crate::Result<::jiff::Timestamp> {
+    ::jiff::tz::TimeZone::UTC
+        .to_timestamp(datetime)
+        .map_err(|e| crate::ToSql::error(&datetime, "timestamp", Some(&e.to_string())))
+}
+
+/*
+ * Parses the `+02`, `-05:30` or `+05:30:15` postgresql zones.
+ */
+fn parse_offset(s: &str) -> Option<::jiff::tz::Offset> {
+    let (sign, hms) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+
+    let mut seconds = 0;
+    let mut parts = 0;
+
+    for part in hms.split(':') {
+        if part.len() != 2 || parts == 3 {
+            return None;
+        }
+
+        seconds = seconds * 60 + part.parse::<i32>().ok()?;
+        parts += 1;
+    }
+
+    for _ in parts..3 {
+        seconds *= 60;
+    }
+
+    ::jiff::tz::Offset::from_seconds(sign * seconds).ok()
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::ToSql for ::jiff::civil::Date {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::DATE
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let days = to_usecs(utc(self.to_datetime(::jiff::civil::Time::midnight()))?)
+            .div_euclid(USECS_PER_DAY) as i32;
+
+        days.to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::FromSql for ::jiff::civil::Date {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        crate::not_null(raw)?
+            .parse()
+            .map_err(|_| Self::error(ty, "date", raw))
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/date.c#L218
+     */
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let days = i32::from_binary(ty, raw)?;
+        let timestamp = from_usecs::<Self>(ty, i64::from(days) * USECS_PER_DAY)?;
+
+        Ok(::jiff::tz::TimeZone::UTC.to_datetime(timestamp).date())
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::ToSql for ::jiff::civil::DateTime {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TIMESTAMP
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        to_usecs(utc(*self)?).to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::FromSql for ::jiff::civil::DateTime {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        crate::not_null(raw)?
+            .parse()
+            .map_err(|_| Self::error(ty, "timestamp", raw))
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/timestamp.c#L257
+     */
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let usecs = i64::from_binary(ty, raw)?;
+        let timestamp = from_usecs::<Self>(ty, usecs)?;
+
+        Ok(::jiff::tz::TimeZone::UTC.to_datetime(timestamp))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::ToSql for ::jiff::Timestamp {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TIMESTAMPTZ
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        to_usecs(*self).to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::FromSql for ::jiff::Timestamp {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        ::jiff::Zoned::from_text(ty, raw).map(|x| x.timestamp())
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let usecs = i64::from_binary(ty, raw)?;
+
+        from_usecs::<Self>(ty, usecs)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::ToSql for ::jiff::Zoned {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TIMESTAMPTZ
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.timestamp().to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(format!("{}{}", self.datetime(), self.offset())))
+    }
+}
+
+/**
+ * A `timestamptz` doesn’t store its time zone: the value is read with the
+ * fixed offset of the text format, or in UTC from the binary format.
+ */
+#[cfg_attr(docsrs, doc(cfg(feature = "jiff")))]
+impl crate::FromSql for ::jiff::Zoned {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let value = crate::not_null(raw)?;
+
+        // The zone starts after the time, the date also contains `-`
+        let x = match value.rfind(['+', '-']) {
+            Some(x) if value[..x].contains(' ') => x,
+            _ => return Err(Self::error(ty, "timestamptz", raw)),
+        };
+
+        let datetime = ::jiff::civil::DateTime::from_text(ty, Some(&value[..x]))?;
+        let offset =
+            parse_offset(&value[x..]).ok_or_else(|| Self::error(ty, "timestamptz", raw))?;
+
+        datetime
+            .to_zoned(::jiff::tz::TimeZone::fixed(offset))
+            .map_err(|_| Self::error(ty, "timestamptz", raw))
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let timestamp = ::jiff::Timestamp::from_binary(ty, raw)?;
+
+        Ok(timestamp.to_zoned(::jiff::tz::TimeZone::UTC))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    crate::sql_test!(
+        date,
+        ::jiff::civil::Date,
+        [
+            ("'1970-01-01'", ::jiff::civil::date(1970, 1, 1)),
+            ("'1999-12-31'", ::jiff::civil::date(1999, 12, 31)),
+            ("'2100-12-30'", ::jiff::civil::date(2100, 12, 30)),
+        ]
+    );
+
+    crate::sql_test!(
+        timestamp,
+        ::jiff::civil::DateTime,
+        [
+            (
+                "'1970-01-01 00:00:00'",
+                ::jiff::civil::datetime(1970, 1, 1, 0, 0, 0, 0)
+            ),
+            (
+                "'1999-12-31 23:59:59.5'",
+                ::jiff::civil::datetime(1999, 12, 31, 23, 59, 59, 500_000_000)
+            ),
+        ]
+    );
+
+    crate::sql_test!(
+        timestamptz,
+        ::jiff::Timestamp,
+        [
+            ("'1970-01-01 00:00:00+00'", ::jiff::Timestamp::UNIX_EPOCH),
+            (
+                "'2000-01-01 01:59:59.5+02'",
+                ::jiff::Timestamp::from_microsecond(946_684_799_500_000).unwrap()
+            ),
+        ]
+    );
+
+    #[test]
+    fn to_binary() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("set timezone to 'UTC'")?;
+
+        let datetime = ::jiff::civil::datetime(1999, 12, 31, 23, 59, 59, 500_000_000);
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&datetime])?,
+            "1999-12-31 23:59:59.5"
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&datetime.date()])?,
+            "1999-12-31"
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&::jiff::civil::date(1969, 7, 20)])?,
+            "1969-07-20"
+        );
+
+        let zoned = datetime
+            .to_zoned(::jiff::tz::TimeZone::fixed(::jiff::tz::offset(-2)))
+            .unwrap();
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&zoned])?,
+            "2000-01-01 01:59:59.5+00"
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&zoned.timestamp()])?,
+            "2000-01-01 01:59:59.5+00"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn zoned() -> crate::Result {
+        use crate::FromSql;
+
+        let zoned = ::jiff::Zoned::from_text(
+            &crate::pq::types::TIMESTAMPTZ,
+            Some("2000-01-01 01:59:59.5+05:30"),
+        )?;
+        assert_eq!(
+            zoned.offset(),
+            ::jiff::tz::Offset::from_seconds(19_800).unwrap()
+        );
+        assert_eq!(
+            zoned.datetime(),
+            ::jiff::civil::datetime(2000, 1, 1, 1, 59, 59, 500_000_000)
+        );
+
+        let conn = crate::test::new_conn()?;
+        let zoned = conn
+            .query_one::<::jiff::Zoned>("select '2000-01-01 01:59:59.5+05:30'::timestamptz", &[])?;
+        assert_eq!(zoned.time_zone(), &::jiff::tz::TimeZone::UTC);
+        assert_eq!(
+            zoned.datetime(),
+            ::jiff::civil::datetime(1999, 12, 31, 20, 29, 59, 500_000_000)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+/*
+ * https://github.com/postgres/postgres/blob/REL_13_0/contrib/ltree/ltree_io.c#L183
+ */
+const VERSION: u8 = 1;
+
+/**
+ * Rust type for [ltree](https://www.postgresql.org/docs/current/ltree.html).
+ *
+ * Label path, like `Top.Science.Astronomy`.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Ltree(String);
+
+impl Ltree {
+    pub fn new(path: &str) -> Self {
+        Self(path.to_string())
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    /**
+     * Labels of the path.
+     */
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.').filter(|x| !x.is_empty())
+    }
+
+    /**
+     * Path without its last label, `None` for an empty path.
+     */
+    pub fn parent(&self) -> Option<Self> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let parent = match self.0.rfind('.') {
+            Some(n) => &self.0[..n],
+            None => "",
+        };
+
+        Some(Self::new(parent))
+    }
+}
+
+/**
+ * Rust type for
+ * [lquery](https://www.postgresql.org/docs/current/ltree.html), a pattern
+ * to match `ltree` values, like `*.Astronomy.*`.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Lquery(String);
+
+impl Lquery {
+    pub fn new(query: &str) -> Self {
+        Self(query.to_string())
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+macro_rules! label {
+    ($ty:ident, $name:literal) => {
+        impl std::ops::Deref for $ty {
+            type Target = str;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $ty {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<&str> for $ty {
+            fn from(s: &str) -> Self {
+                Self::new(s)
+            }
+        }
+
+        impl From<String> for $ty {
+            fn from(s: String) -> Self {
+                Self(s)
+            }
+        }
+
+        impl From<$ty> for String {
+            fn from(value: $ty) -> Self {
+                value.0
+            }
+        }
+
+        impl crate::ToSql for $ty {
+            fn ty(&self) -> crate::pq::Type {
+                crate::pq::Type {
+                    oid: 0,
+                    descr: $name,
+                    name: $name,
+                    kind: libpq::types::Kind::UserDefined,
+                }
+            }
+
+            fn format(&self) -> crate::pq::Format {
+                crate::pq::Format::Binary
+            }
+
+            /*
+             * The binary format is the text one prefixed by a version byte.
+             */
+            fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+                let mut buf = vec![VERSION];
+                buf.extend_from_slice(self.0.as_bytes());
+
+                Ok(Some(buf))
+            }
+
+            fn to_text(&self) -> crate::Result<Option<String>> {
+                Ok(Some(self.0.clone()))
+            }
+        }
+
+        impl crate::FromSql for $ty {
+            fn from_text(_: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+                crate::not_null(raw).map(Self::new)
+            }
+
+            fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+                match crate::not_null(raw)?.split_first() {
+                    Some((&VERSION, path)) => Ok(Self(String::from_utf8(path.to_vec())?)),
+                    _ => Err(Self::error(ty, stringify!($ty), raw)),
+                }
+            }
+        }
+    };
+}
+
+label!(Ltree, "ltree");
+label!(Lquery, "lquery");
+
+#[cfg(test)]
+mod test {
+    crate::sql_test!(
+        ltree,
+        crate::Ltree,
+        [
+            (
+                "'Top.Science.Astronomy'",
+                crate::Ltree::new("Top.Science.Astronomy")
+            ),
+            ("''", crate::Ltree::new("")),
+        ]
+    );
+
+    crate::sql_test!(
+        lquery,
+        crate::Lquery,
+        [
+            ("'*.Astronomy.*'", crate::Lquery::new("*.Astronomy.*")),
+            (
+                "'Top.*{,2}.sport@*'",
+                crate::Lquery::new("Top.*{,2}.sport@*")
+            ),
+        ]
+    );
+
+    #[test]
+    fn ltree() {
+        let path = crate::Ltree::new("Top.Science.Astronomy");
+
+        assert_eq!(
+            path.labels().collect::<Vec<_>>(),
+            ["Top", "Science", "Astronomy"]
+        );
+        assert_eq!(path.parent(), Some(crate::Ltree::new("Top.Science")));
+        assert_eq!(
+            crate::Ltree::new("Top").parent(),
+            Some(crate::Ltree::new(""))
+        );
+        assert_eq!(crate::Ltree::new("").parent(), None);
+    }
+
+    #[test]
+    fn matches() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        let path = crate::Ltree::new("Top.Science.Astronomy");
+        let query = crate::Lquery::new("*.Astronomy.*");
+
+        assert!(conn.query_one::<bool>("select $1 ~ $2", &[&path, &query])?);
+        assert_eq!(
+            conn.query_one::<crate::Ltree>("select subpath($1, 1)", &[&path])?,
+            crate::Ltree::new("Science.Astronomy")
+        );
+
+        Ok(())
+    }
+}
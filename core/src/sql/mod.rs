@@ -1,26 +1,39 @@
-mod array;
+pub(crate) mod array;
 #[cfg(feature = "bit")]
-mod bit;
+pub(crate) mod bit;
 mod bytea;
+#[cfg(feature = "citext")]
+mod citext;
 mod composite;
 #[cfg(feature = "date")]
 mod date;
 mod r#enum;
 #[cfg(feature = "geo")]
 mod geo;
+#[cfg(feature = "hstore")]
 mod hstore;
+#[cfg(any(feature = "date", feature = "time"))]
+mod interval;
+#[cfg(feature = "jiff")]
+mod jiff;
 #[cfg(feature = "json")]
 mod json;
+#[cfg(feature = "ltree")]
+mod ltree;
 #[cfg(feature = "money")]
 mod money;
+mod multirange;
 #[cfg(feature = "net")]
 mod net;
 #[cfg(feature = "numeric")]
 mod numeric;
+mod path;
 mod range;
 mod record;
 #[cfg(feature = "time")]
 mod time;
+#[cfg(feature = "url")]
+mod url;
 #[cfg(feature = "uuid")]
 mod uuid;
 #[cfg(feature = "xml")]
@@ -34,14 +47,21 @@ pub use array::*;
 #[cfg(feature = "bit")]
 pub use bit::*;
 pub use bytea::*;
+#[cfg(feature = "citext")]
+pub use citext::*;
 pub use composite::*;
-#[cfg(feature = "date")]
-pub use date::*;
 #[cfg(feature = "geo")]
 pub use geo::*;
+#[cfg(feature = "hstore")]
 pub use hstore::*;
+#[cfg(any(feature = "date", feature = "time"))]
+pub use interval::*;
+#[cfg(feature = "ltree")]
+pub use ltree::*;
 #[cfg(feature = "money")]
 pub use money::*;
+pub use multirange::*;
 #[cfg(feature = "net")]
 pub use net::*;
 pub use r#enum::*;
+pub use range::*;
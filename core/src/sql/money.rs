@@ -7,8 +7,19 @@ impl crate::ToSql for Money {
         crate::pq::types::MONEY
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/cash.c#L535
+     */
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        Ok(Some(self.inner().to_be_bytes().to_vec()))
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
     }
 }
 
@@ -33,4 +44,28 @@ impl crate::FromSql for Money {
 #[cfg(test)]
 mod test {
     crate::sql_test!(money, crate::Money, [("1.00", crate::Money::from(100))]);
+
+    #[test]
+    fn round_trip() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let mut rng = fastrand::Rng::with_seed(3024);
+
+        for _ in 0..100 {
+            let money = crate::Money::from(rng.i64(-1_000_000_000_000..1_000_000_000_000));
+
+            assert_eq!(
+                conn.query_one::<crate::Money>("select $1::money", &[&money])?,
+                money,
+            );
+            assert_eq!(
+                conn.query_one::<i64>(
+                    "select count(*) from (values ($1::money)) v(m) where m = $2",
+                    &[&money, &money],
+                )?,
+                1,
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,253 @@
+/**
+ * Rust type for
+ * [multirange](https://www.postgresql.org/docs/current/rangetypes.html),
+ * available since PostgreSQL 14: an ordered list of non-overlapping,
+ * non-empty ranges.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Multirange<T>(Vec<crate::Range<T>>);
+
+impl<T> Multirange<T> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn into_inner(self) -> Vec<crate::Range<T>> {
+        self.0
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.0.iter().any(|x| x.contains(value))
+    }
+}
+
+impl<T> Default for Multirange<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> std::ops::Deref for Multirange<T> {
+    type Target = Vec<crate::Range<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for Multirange<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<crate::Range<T>>> for Multirange<T> {
+    fn from(ranges: Vec<crate::Range<T>>) -> Self {
+        Self(ranges)
+    }
+}
+
+impl<T> From<Multirange<T>> for Vec<crate::Range<T>> {
+    fn from(multirange: Multirange<T>) -> Self {
+        multirange.0
+    }
+}
+
+impl<T> std::iter::FromIterator<crate::Range<T>> for Multirange<T> {
+    fn from_iter<I: IntoIterator<Item = crate::Range<T>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+fn multirange_type(ty: &crate::pq::Type) -> crate::pq::Type {
+    use crate::pq::types::*;
+
+    match *ty {
+        INT4_RANGE => INT4MULTI_RANGE,
+        INT8_RANGE => INT8MULTI_RANGE,
+        NUM_RANGE => NUMMULTI_RANGE,
+        TS_RANGE => TSMULTI_RANGE,
+        TSTZ_RANGE => TSTZMULTI_RANGE,
+        DATE_RANGE => DATEMULTI_RANGE,
+        _ => UNKNOWN,
+    }
+}
+
+/**
+ * A multirange without non-empty range is sent as `unknown`, its type is
+ * infered by postgresql.
+ */
+impl<T: crate::ToSql> crate::ToSql for Multirange<T> {
+    fn ty(&self) -> crate::pq::Type {
+        self.0
+            .iter()
+            .map(|x| multirange_type(&x.ty()))
+            .find(|x| *x != crate::pq::types::UNKNOWN)
+            .unwrap_or(crate::pq::types::UNKNOWN)
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_14_0/src/backend/utils/adt/multirangetypes.c#L284
+     */
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let mut ranges = Vec::new();
+
+        for range in &self.0 {
+            if let Some(range) = range.to_text()? {
+                ranges.push(range);
+            }
+        }
+
+        format!("{{{}}}", ranges.join(",")).to_sql()
+    }
+}
+
+impl<T: crate::FromSql> crate::FromSql for Multirange<T> {
+    /*
+     * https://github.com/postgres/postgres/blob/REL_14_0/src/backend/utils/adt/multirangetypes.c#L120
+     */
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let s = crate::not_null(raw)?.trim();
+
+        let error = || crate::Error::FromSql {
+            pg_type: ty.clone(),
+            rust_type: "elephantry::Multirange".to_string(),
+            value: s.to_string(),
+        };
+
+        let ranges = s
+            .strip_prefix('{')
+            .and_then(|x| x.strip_suffix('}'))
+            .ok_or_else(error)?;
+
+        let mut multirange = Self::new();
+        let mut start = None;
+        let mut in_quotes = false;
+        let mut escaped = false;
+        let mut skip = 0;
+
+        for (n, c) in ranges.char_indices() {
+            match c {
+                _ if skip > 0 => skip -= 1,
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_quotes = !in_quotes,
+                _ if in_quotes => (),
+                '[' | '(' if start.is_none() => start = Some(n),
+                ']' | ')' => {
+                    let range = &ranges[start.ok_or_else(error)?..=n];
+
+                    multirange.push(crate::Range::from_text(ty, Some(range))?);
+                    start = None;
+                }
+                _ if start.is_none() && !(c == ',' || c.is_whitespace()) => {
+                    // empty ranges are absorbed by the multirange
+                    if !ranges[n..].to_lowercase().starts_with("empty") {
+                        return Err(error());
+                    }
+                    skip = "empty".len() - 1;
+                }
+                _ => (),
+            }
+        }
+
+        if start.is_some() || in_quotes {
+            return Err(error());
+        }
+
+        multirange.retain(|x| !x.is_empty());
+
+        Ok(multirange)
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_14_0/src/backend/utils/adt/multirangetypes.c#L327
+     */
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        use byteorder::ReadBytesExt;
+
+        let mut buf = crate::from_sql::not_null(raw)?;
+        let count = buf.read_i32::<byteorder::BigEndian>()?;
+
+        let mut multirange = Self::new();
+
+        for _ in 0..count {
+            let len = buf.read_i32::<byteorder::BigEndian>()? as usize;
+            if buf.len() < len {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            let (range, rest) = buf.split_at(len);
+            buf = rest;
+
+            multirange.push(crate::Range::from_binary(ty, Some(range))?);
+        }
+
+        Ok(multirange)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    crate::sql_test!(
+        int4multirange,
+        crate::Multirange<i32>,
+        [
+            ("'{}'", crate::Multirange::new()),
+            (
+                "'{[0, 10), [20, 30]}'",
+                vec![crate::Range::from(0..10), crate::Range::from(20..31)].into()
+            ),
+            (
+                "'{(,5), empty}'",
+                vec![crate::Range::new(
+                    std::ops::Bound::Unbounded,
+                    std::ops::Bound::Excluded(5)
+                )]
+                .into()
+            ),
+        ]
+    );
+
+    #[cfg(feature = "date")]
+    crate::sql_test!(
+        tsmultirange,
+        crate::Multirange<chrono::NaiveDateTime>,
+        [(
+            "'{[\"1970-01-01 00:00:00\", \"2010-01-01 00:00:00\")}'",
+            crate::Multirange::from(vec![crate::Range::from(
+                chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    ..chrono::NaiveDate::from_ymd_opt(2010, 1, 1)
+                        .unwrap()
+                        .and_hms_opt(0, 0, 0)
+                        .unwrap()
+            )])
+        )]
+    );
+
+    #[test]
+    fn to_sql() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        let multirange: crate::Multirange<i64> =
+            vec![crate::Range::from(1..3), crate::Range::from(2..=5)].into();
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&multirange])?,
+            "{[1,6)}"
+        );
+        assert_eq!(
+            conn.query_one::<String>(
+                "select $1::int4multirange::text",
+                &[&crate::Multirange::<i32>::new()]
+            )?,
+            "{}"
+        );
+
+        Ok(())
+    }
+}
@@ -6,8 +6,74 @@ impl crate::ToSql for bigdecimal::BigDecimal {
         crate::pq::types::NUMERIC
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/numeric.c#L952
+     */
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        const DEC_DIGITS: usize = 4;
+
+        let (bigint, exponent) = self.as_bigint_and_exponent();
+        let mut digits = bigint.to_string();
+
+        let sign: u16 = if digits.starts_with('-') {
+            digits.remove(0);
+            0x4000
+        } else {
+            0
+        };
+
+        let dscale = exponent.max(0) as usize;
+        if exponent < 0 {
+            digits.push_str(&"0".repeat(-exponent as usize));
+        }
+        if digits.len() <= dscale {
+            digits.insert_str(0, &"0".repeat(dscale - digits.len() + 1));
+        }
+
+        // Aligns the decimal point on a base 10 000 digit
+        let integer_len = digits.len() - dscale;
+        let padding = (DEC_DIGITS - integer_len % DEC_DIGITS) % DEC_DIGITS;
+        digits.insert_str(0, &"0".repeat(padding));
+        let fraction_padding = (DEC_DIGITS - dscale % DEC_DIGITS) % DEC_DIGITS;
+        digits.push_str(&"0".repeat(fraction_padding));
+
+        let mut groups = digits
+            .as_bytes()
+            .chunks(DEC_DIGITS)
+            .map(|x| std::str::from_utf8(x).unwrap_or("0").parse::<i16>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| self.error("numeric", None))?;
+        let mut weight = ((integer_len + padding) / DEC_DIGITS) as i16 - 1;
+
+        while groups.first() == Some(&0) {
+            groups.remove(0);
+            weight -= 1;
+        }
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+        if groups.is_empty() {
+            weight = 0;
+        }
+
+        let mut buf = Vec::with_capacity(8 + groups.len() * 2);
+        buf.extend_from_slice(&(groups.len() as i16).to_be_bytes());
+        buf.extend_from_slice(&weight.to_be_bytes());
+        buf.extend_from_slice(&sign.to_be_bytes());
+        buf.extend_from_slice(&(dscale as i16).to_be_bytes());
+        for group in groups {
+            buf.extend_from_slice(&group.to_be_bytes());
+        }
+
+        Ok(Some(buf))
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
     }
 }
 
@@ -95,4 +161,73 @@ mod test {
             ),
         ]
     );
+
+    #[test]
+    fn to_binary() -> crate::Result {
+        use std::str::FromStr;
+
+        let conn = crate::test::new_conn()?;
+
+        for value in &[
+            "0",
+            "1",
+            "-1",
+            "20000",
+            "20000.000001",
+            "3900.98",
+            "-0.12345",
+            "1e10",
+            "0.00001",
+            "12345678901234567890.123",
+        ] {
+            let numeric = bigdecimal::BigDecimal::from_str(value).unwrap();
+
+            assert_eq!(
+                conn.query_one::<String>("select $1::text", &[&numeric])?,
+                numeric.to_string(),
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn param() -> crate::Result {
+        use std::str::FromStr;
+
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table price (amount numeric(10, 2) not null)")?;
+
+        let amount = bigdecimal::BigDecimal::from_str("1234.5").unwrap();
+        conn.query::<()>("insert into price values ($1)", &[&amount])?;
+
+        let count = conn.query_one::<i64>(
+            "select count(*) from price where amount = $1",
+            &[&bigdecimal::BigDecimal::from_str("1234.50").unwrap()],
+        )?;
+        assert_eq!(count, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> crate::Result {
+        use std::str::FromStr;
+
+        let conn = crate::test::new_conn()?;
+        let mut rng = fastrand::Rng::with_seed(3024);
+
+        for _ in 0..100 {
+            let value = format!("{}e-{}", rng.i64(..), rng.u32(0..20));
+            let numeric = bigdecimal::BigDecimal::from_str(&value).unwrap();
+
+            assert_eq!(
+                conn.query_one::<String>("select $1::text", &[&numeric])?,
+                numeric.to_string(),
+            );
+            assert!(conn.query_one::<bool>("select $1 = $2::numeric", &[&numeric, &value])?);
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,65 @@
+impl crate::ToSql for std::path::Path {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TEXT
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        match self.to_str() {
+            Some(path) => path.to_sql(),
+            None => Err(self.error(
+                "path",
+                Some(&"path contains invalid UTF-8 characters".to_string()),
+            )),
+        }
+    }
+}
+
+impl crate::ToSql for std::path::PathBuf {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TEXT
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.as_path().to_sql()
+    }
+}
+
+impl crate::FromSql for std::path::PathBuf {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let path = crate::not_null(raw)?;
+
+        if path.is_empty() {
+            return Err(Self::error(ty, "path", raw));
+        }
+
+        Ok(path.into())
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let path = String::from_binary(ty, raw)?;
+
+        Self::from_text(ty, Some(&path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    crate::sql_test!(
+        text,
+        std::path::PathBuf,
+        [
+            ("'/etc/hosts'", std::path::PathBuf::from("/etc/hosts")),
+            ("'relative/path'", std::path::PathBuf::from("relative/path")),
+        ]
+    );
+
+    #[test]
+    fn empty() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let result = conn.execute("select ''::text as path")?;
+
+        assert!(result.get(0).try_get::<std::path::PathBuf>("path").is_err());
+
+        Ok(())
+    }
+}
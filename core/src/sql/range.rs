@@ -1,17 +1,107 @@
-fn ty<R, T>(range: &R) -> crate::pq::Type
-where
-    R: std::ops::RangeBounds<T>,
-    T: crate::ToSql,
-{
-    use crate::pq::types::*;
-    use std::ops::Bound::*;
+use std::ops::Bound;
 
-    let start = match range.start_bound() {
-        Included(start) | Excluded(start) => start,
-        Unbounded => panic!("Unsupported unbounded range"),
-    };
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+/**
+ * Rust type for [range](https://www.postgresql.org/docs/current/rangetypes.html).
+ *
+ * Unlike [`std::ops::Range`], the bounds can be inclusive, exclusive or
+ * unbounded and the range can be empty.
+ *
+ * ```
+ * use std::ops::Bound::*;
+ *
+ * let range = elephantry::Range::new(Included(1), Unbounded);
+ * assert!(range.contains(&42));
+ * assert!(!elephantry::Range::<i32>::Empty.contains(&42));
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Range<T> {
+    Empty,
+    Bounds { lower: Bound<T>, upper: Bound<T> },
+}
+
+impl<T> Range<T> {
+    pub fn new(lower: Bound<T>, upper: Bound<T>) -> Self {
+        Self::Bounds { lower, upper }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /**
+     * Lower bound, `None` for an empty range.
+     */
+    pub fn lower(&self) -> Option<Bound<&T>> {
+        match self {
+            Self::Empty => None,
+            Self::Bounds { lower, .. } => Some(lower.as_ref()),
+        }
+    }
+
+    /**
+     * Upper bound, `None` for an empty range.
+     */
+    pub fn upper(&self) -> Option<Bound<&T>> {
+        match self {
+            Self::Empty => None,
+            Self::Bounds { upper, .. } => Some(upper.as_ref()),
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialOrd,
+    {
+        use std::ops::RangeBounds;
+
+        match self {
+            Self::Empty => false,
+            Self::Bounds { lower, upper } => (lower.as_ref(), upper.as_ref()).contains(value),
+        }
+    }
 
-    match start.ty() {
+    fn value(&self) -> Option<&T> {
+        use Bound::*;
+
+        match (self.lower()?, self.upper()?) {
+            (Included(value), _) | (Excluded(value), _) => Some(value),
+            (_, Included(value)) | (_, Excluded(value)) => Some(value),
+            (Unbounded, Unbounded) => None,
+        }
+    }
+}
+
+impl<T> From<std::ops::Range<T>> for Range<T> {
+    fn from(range: std::ops::Range<T>) -> Self {
+        Self::new(Bound::Included(range.start), Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<std::ops::RangeInclusive<T>> for Range<T> {
+    fn from(range: std::ops::RangeInclusive<T>) -> Self {
+        let (start, end) = range.into_inner();
+
+        Self::new(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+impl<T> From<(Bound<T>, Bound<T>)> for Range<T> {
+    fn from((lower, upper): (Bound<T>, Bound<T>)) -> Self {
+        Self::new(lower, upper)
+    }
+}
+
+fn range_type(ty: &crate::pq::Type) -> crate::pq::Type {
+    use crate::pq::types::*;
+
+    match *ty {
         ANY => ANY_RANGE,
         INT4 => INT4_RANGE,
         INT8 => INT8_RANGE,
@@ -23,75 +113,122 @@ where
     }
 }
 
-fn to_sql<R, T>(range: &R) -> crate::Result<Option<Vec<u8>>>
-where
-    R: std::ops::RangeBounds<T>,
-    T: crate::ToSql,
-{
-    use std::ops::Bound::*;
-
-    let (start_char, start) = match range.start_bound() {
-        Included(start) => (b'[', start),
-        Excluded(start) => (b'(', start),
-        Unbounded => panic!("Unsupported unbounded range"),
-    };
-
-    let mut start = match start.to_sql()? {
-        Some(start) => start,
-        None => return Ok(None),
-    };
-    start.pop(); // removes \0
-
-    let (end_char, end) = match range.end_bound() {
-        Included(end) => (b']', end),
-        Excluded(end) => (b')', end),
-        Unbounded => panic!("Unsupported unbounded range"),
-    };
+fn element_type(ty: &crate::pq::Type) -> crate::pq::Type {
+    match ty.kind {
+        libpq::types::Kind::Range(oid) => crate::pq::registry::lookup(oid),
+        _ => ty.clone(),
+    }
+}
 
-    let mut end = match end.to_sql()? {
-        Some(end) => end,
-        None => return Ok(None),
+fn bound_to_text<T: crate::ToSql>(bound: Bound<&T>) -> crate::Result<String> {
+    let value = match bound {
+        Bound::Included(value) | Bound::Excluded(value) => value.to_text()?,
+        Bound::Unbounded => None,
     };
-    end.pop(); // removes \0
-
-    let mut vec = vec![start_char];
-    vec.append(&mut start);
-    vec.push(b',');
-    vec.append(&mut end);
-    vec.push(end_char);
-    vec.push(b'\0');
 
-    Ok(Some(vec))
+    Ok(value
+        .map(|x| crate::sql::record::quote(&x).into_owned())
+        .unwrap_or_default())
 }
 
-impl<T: crate::ToSql> crate::ToSql for std::ops::Range<T> {
+/**
+ * An unbounded or empty range without type is sent as `unknown`, its type is
+ * infered by postgresql.
+ */
+impl<T: crate::ToSql> crate::ToSql for Range<T> {
     fn ty(&self) -> crate::pq::Type {
-        ty(self)
+        match self.value() {
+            Some(value) => range_type(&value.ty()),
+            None => crate::pq::types::UNKNOWN,
+        }
     }
 
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/rangetypes.c#L2112
+     */
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        to_sql(self)
+        let (lower, upper) = match (self.lower(), self.upper()) {
+            (Some(lower), Some(upper)) => (lower, upper),
+            _ => return "empty".to_sql(),
+        };
+
+        let lower_char = if matches!(lower, Bound::Included(_)) {
+            '['
+        } else {
+            '('
+        };
+        let upper_char = if matches!(upper, Bound::Included(_)) {
+            ']'
+        } else {
+            ')'
+        };
+
+        format!(
+            "{}{},{}{}",
+            lower_char,
+            bound_to_text(lower)?,
+            bound_to_text(upper)?,
+            upper_char
+        )
+        .to_sql()
     }
 }
 
-impl<T: crate::FromSql> crate::FromSql for std::ops::Range<T> {
+impl<T: crate::FromSql> crate::FromSql for Range<T> {
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/rangetypes.c#L1928
+     */
     fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
-        lazy_static::lazy_static! {
-            static ref REGEX: regex::Regex =
-                regex::Regex::new(r"[\[\(](?P<start>.?*),(?P<end>.?*)[\]\)]")
-                    .unwrap();
+        let s = crate::not_null(raw)?.trim();
+
+        if s.eq_ignore_ascii_case("empty") {
+            return Ok(Self::Empty);
         }
 
-        let matches = REGEX.captures(crate::not_null(raw)?).unwrap();
+        let error = || crate::Error::FromSql {
+            pg_type: ty.clone(),
+            rust_type: "elephantry::Range".to_string(),
+            value: s.to_string(),
+        };
+
+        let lower_inc = match s.chars().next() {
+            Some('[') => true,
+            Some('(') => false,
+            _ => return Err(error()),
+        };
+        let upper_inc = match s.chars().last() {
+            Some(']') => true,
+            Some(')') => false,
+            _ => return Err(error()),
+        };
+
+        let bounds = crate::sql::record::split(s.get(1..s.len() - 1).ok_or_else(error)?);
+        if bounds.len() != 2 {
+            return Err(error());
+        }
 
-        // tstzrange are quoted
-        let start = matches.name("start").map(|x| x.as_str().trim_matches('\"'));
-        let end = matches.name("end").map(|x| x.as_str().trim_matches('\"'));
+        let elemtype = element_type(ty);
+        let bound = |value: &Option<String>, inclusive| -> crate::Result<Bound<T>> {
+            let bound = match value {
+                Some(value) => {
+                    let value = T::from_text(&elemtype, Some(value))?;
 
-        Ok(std::ops::Range {
-            start: T::from_text(ty, start)?,
-            end: T::from_text(ty, end)?,
-        })
+                    if inclusive {
+                        Bound::Included(value)
+                    } else {
+                        Bound::Excluded(value)
+                    }
+                }
+                None => Bound::Unbounded,
+            };
+
+            Ok(bound)
+        };
+
+        Ok(Self::new(
+            bound(&bounds[0], lower_inc)?,
+            bound(&bounds[1], upper_inc)?,
+        ))
     }
 
     /*
@@ -101,43 +238,100 @@ impl<T: crate::FromSql> crate::FromSql for std::ops::Range<T> {
         use byteorder::ReadBytesExt;
 
         let mut buf = crate::from_sql::not_null(raw)?;
-        let _flag = buf.read_u8()?;
-
-        let start_bound_len = buf.read_i32::<byteorder::BigEndian>()?;
-        let mut start = Vec::new();
-        for _ in 0..start_bound_len {
-            let b = buf.read_u8()?;
+        let flags = buf.read_u8()?;
 
-            start.push(b);
+        if flags & RANGE_EMPTY != 0 {
+            return Ok(Self::Empty);
         }
 
-        let end_bound_len = buf.read_i32::<byteorder::BigEndian>()?;
-        let mut end = Vec::new();
-        for _ in 0..end_bound_len {
-            let b = buf.read_u8()?;
+        let elemtype = element_type(ty);
+        let mut bound = |infinite, inclusive| -> crate::Result<Bound<T>> {
+            if flags & infinite != 0 {
+                return Ok(Bound::Unbounded);
+            }
 
-            end.push(b);
-        }
+            let len = buf.read_i32::<byteorder::BigEndian>()? as usize;
+            if buf.len() < len {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            let (data, rest) = buf.split_at(len);
+            buf = rest;
+
+            let value = T::from_binary(&elemtype, Some(data))?;
+
+            if flags & inclusive != 0 {
+                Ok(Bound::Included(value))
+            } else {
+                Ok(Bound::Excluded(value))
+            }
+        };
 
-        Ok(std::ops::Range {
-            start: T::from_binary(ty, Some(&start))?,
-            end: T::from_binary(ty, Some(&end))?,
-        })
+        let lower = bound(RANGE_LB_INF, RANGE_LB_INC)?;
+        let upper = bound(RANGE_UB_INF, RANGE_UB_INC)?;
+
+        Ok(Self::new(lower, upper))
+    }
+}
+
+impl<T: crate::ToSql> crate::ToSql for std::ops::Range<T> {
+    fn ty(&self) -> crate::pq::Type {
+        range_type(&self.start.ty())
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        Range::from(&self.start..&self.end).to_sql()
+    }
+}
+
+/**
+ * Only the values of the bounds are kept, empty or unbounded ranges can’t be
+ * converted.
+ */
+impl<T: crate::FromSql> crate::FromSql for std::ops::Range<T> {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        to_std(ty, Range::from_text(ty, raw)?)
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        to_std(ty, Range::from_binary(ty, raw)?)
+    }
+}
+
+fn to_std<T>(ty: &crate::pq::Type, range: Range<T>) -> crate::Result<std::ops::Range<T>> {
+    use Bound::*;
+
+    match range {
+        Range::Bounds {
+            lower: Included(start) | Excluded(start),
+            upper: Included(end) | Excluded(end),
+        } => Ok(start..end),
+        _ => Err(crate::Error::FromSql {
+            pg_type: ty.clone(),
+            rust_type: "std::ops::Range".to_string(),
+            value: "empty or unbounded range".to_string(),
+        }),
     }
 }
 
 impl<T: crate::ToSql> crate::ToSql for std::ops::RangeInclusive<T> {
     fn ty(&self) -> crate::pq::Type {
-        ty(self)
+        range_type(&self.start().ty())
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        to_sql(self)
+        Range::from(self.start()..=self.end()).to_sql()
     }
 }
 
 #[cfg(test)]
 mod test {
+    #[cfg(feature = "date")]
+    fn new_year(year: i32) -> chrono::NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|x| x.and_hms_opt(0, 0, 0))
+            .unwrap()
+    }
+
     crate::sql_test!(int4range, std::ops::Range<i32>, [("'[0, 10)'", 0_i32..10)]);
 
     crate::sql_test!(int8range, std::ops::Range<i64>, [("'[0, 10)'", 0_i64..10)]);
@@ -158,7 +352,7 @@ mod test {
         std::ops::Range<chrono::NaiveDate>,
         [(
             "'[1970-01-01, 2010-01-01)'",
-            chrono::NaiveDate::from_ymd(1970, 01, 01)..chrono::NaiveDate::from_ymd(2010, 01, 01)
+            super::new_year(1970).date()..super::new_year(2010).date()
         )]
     );
 
@@ -168,8 +362,7 @@ mod test {
         std::ops::Range<chrono::NaiveDateTime>,
         [(
             "'[1970-01-01 00:00:00, 2010-01-01 00:00:00)'",
-            chrono::NaiveDate::from_ymd(1970, 01, 01).and_hms(0, 0, 0)
-                ..chrono::NaiveDate::from_ymd(2010, 01, 01).and_hms(0, 0, 0)
+            super::new_year(1970)..super::new_year(2010)
         )]
     );
 
@@ -179,14 +372,90 @@ mod test {
         std::ops::Range<chrono::DateTime<chrono::Utc>>,
         [(
             "'[1970-01-01 00:00:00+00, 2010-01-01 00:00:00+00)'",
-            chrono::DateTime::<chrono::Utc>::from_utc(
-                chrono::NaiveDate::from_ymd(1970, 01, 01).and_hms(0, 0, 0),
-                chrono::Utc
-            )
-                ..chrono::DateTime::<chrono::Utc>::from_utc(
-                    chrono::NaiveDate::from_ymd(2010, 01, 01).and_hms(0, 0, 0),
-                    chrono::Utc
-                )
+            super::new_year(1970).and_utc()..super::new_year(2010).and_utc()
         )]
     );
+
+    mod range {
+        crate::sql_test!(
+            int4range,
+            crate::Range<i32>,
+            [
+                ("'empty'", crate::Range::Empty),
+                ("'[0, 10)'", crate::Range::from(0..10)),
+                ("'[0, 10]'", crate::Range::from(0..11)),
+                (
+                    "'[0,)'",
+                    crate::Range::new(std::ops::Bound::Included(0), std::ops::Bound::Unbounded)
+                ),
+                (
+                    "'(,)'",
+                    crate::Range::new(std::ops::Bound::Unbounded, std::ops::Bound::Unbounded)
+                ),
+            ]
+        );
+
+        #[cfg(feature = "numeric")]
+        crate::sql_test!(
+            numrange,
+            crate::Range<bigdecimal::BigDecimal>,
+            [(
+                "'(3900, 20000]'",
+                crate::Range::new(
+                    std::ops::Bound::Excluded(bigdecimal::BigDecimal::from(3_900)),
+                    std::ops::Bound::Included(bigdecimal::BigDecimal::from(20_000))
+                )
+            )]
+        );
+
+        #[cfg(feature = "date")]
+        crate::sql_test!(
+            tsrange,
+            crate::Range<chrono::NaiveDateTime>,
+            [(
+                "'(,\"2010-01-01 00:00:00\"]'",
+                crate::Range::new(
+                    std::ops::Bound::Unbounded,
+                    std::ops::Bound::Included(super::super::new_year(2010))
+                )
+            )]
+        );
+
+        #[test]
+        fn to_sql() -> crate::Result {
+            let conn = crate::test::new_conn()?;
+
+            let tests: [(&str, crate::Range<i32>); 4] = [
+                ("empty", crate::Range::Empty),
+                ("[1,11)", crate::Range::from(1..=10)),
+                (
+                    "(,5)",
+                    crate::Range::new(std::ops::Bound::Unbounded, std::ops::Bound::Excluded(5)),
+                ),
+                (
+                    "[2,)",
+                    crate::Range::new(std::ops::Bound::Excluded(1), std::ops::Bound::Unbounded),
+                ),
+            ];
+
+            for (expected, range) in &tests {
+                let actual = conn.query_one::<String>("select $1::int4range::text", &[range])?;
+                assert_eq!(&actual, expected);
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn contains() {
+            assert!(crate::Range::from(1..3).contains(&1));
+            assert!(!crate::Range::from(1..3).contains(&3));
+            assert!(crate::Range::from(1..=3).contains(&3));
+            assert!(
+                crate::Range::new(std::ops::Bound::Unbounded, std::ops::Bound::Excluded(3))
+                    .contains(&-10)
+            );
+            assert!(!crate::Range::<i32>::Empty.contains(&0));
+        }
+    }
 }
@@ -2,9 +2,8 @@ pub(crate) fn vec_to_sql(vec: &[&dyn crate::ToSql]) -> crate::Result<Option<Vec<
     let mut data = b"(".to_vec();
 
     for field in vec {
-        if let Some(mut value) = field.to_sql()? {
-            value.pop();
-            data.append(&mut value);
+        if let Some(value) = field.to_text()? {
+            data.extend_from_slice(quote(&value).as_bytes());
         }
         data.push(b',');
     }
@@ -16,49 +15,111 @@ pub(crate) fn vec_to_sql(vec: &[&dyn crate::ToSql]) -> crate::Result<Option<Vec<
     Ok(Some(data))
 }
 
-pub(crate) fn text_to_vec(raw: Option<&str>) -> crate::Result<Vec<Option<&str>>> {
-    let s = crate::not_null(raw)?;
+/**
+ * Quotes a field of a record, or a bound of a range, if needed: an unquoted
+ * empty field is null.
+ */
+pub(crate) fn quote(value: &str) -> std::borrow::Cow<'_, str> {
+    let special =
+        |c: char| matches!(c, '(' | ')' | '[' | ']' | ',' | '"' | '\\') || c.is_whitespace();
 
-    if !s.starts_with('(') && !s.ends_with(')') {
-        return Err(crate::Error::FromSql {
-            pg_type: crate::pq::types::UNKNOWN,
-            rust_type: "tuple".to_string(),
-            value: s.to_string(),
-        });
+    if !value.is_empty() && !value.contains(special) {
+        return value.into();
     }
 
-    let values = s
-        .trim_start_matches('(')
-        .trim_end_matches(')')
-        .split(',')
-        .map(|x| if x.is_empty() { None } else { Some(x) })
-        .collect::<Vec<_>>();
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\"\"")).into()
+}
 
-    Ok(values)
+/*
+ * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/rowtypes.c#L74
+ */
+pub(crate) fn text_to_vec(raw: Option<&str>) -> crate::Result<Vec<Option<String>>> {
+    let s = crate::not_null(raw)?;
+
+    let fields = match s.strip_prefix('(').and_then(|x| x.strip_suffix(')')) {
+        Some(fields) => fields,
+        None => {
+            return Err(crate::Error::FromSql {
+                pg_type: crate::pq::types::UNKNOWN,
+                rust_type: "tuple".to_string(),
+                value: s.to_string(),
+            })
+        }
+    };
+
+    Ok(split(fields))
+}
+
+/**
+ * Splits the comma separated, optionally quoted, fields of a record or a
+ * range. An unquoted empty field is `None`.
+ */
+pub(crate) fn split(fields: &str) -> Vec<Option<String>> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = fields.chars().peekable();
+
+    loop {
+        match chars.next() {
+            Some('"') if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                value.push('"');
+            }
+            Some('"') => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            Some('\\') => value.extend(chars.next()),
+            Some(',') if !in_quotes => {
+                values.push(if quoted || !value.is_empty() {
+                    Some(std::mem::take(&mut value))
+                } else {
+                    None
+                });
+                quoted = false;
+            }
+            Some(c) => value.push(c),
+            None => {
+                values.push(if quoted || !value.is_empty() {
+                    Some(value)
+                } else {
+                    None
+                });
+                break;
+            }
+        }
+    }
+
+    values
 }
 
 /*
  * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/rowtypes.c#L649
  */
-pub(crate) fn binary_to_vec(raw: Option<&[u8]>) -> crate::Result<Vec<Option<&[u8]>>> {
+#[allow(clippy::type_complexity)]
+pub(crate) fn binary_to_vec(
+    raw: Option<&[u8]>,
+) -> crate::Result<Vec<(crate::pq::Type, Option<&[u8]>)>> {
     use byteorder::ReadBytesExt;
 
     let mut data = crate::not_null(raw)?;
-    let mut values: Vec<Option<&[u8]>> = Vec::new();
+    let mut values = Vec::new();
 
     let validcols = data.read_i32::<byteorder::BigEndian>()?;
 
     for _ in 0..validcols {
-        let _column_type = data.read_i32::<byteorder::BigEndian>()?;
+        let column_type = crate::pq::registry::lookup(data.read_u32::<byteorder::BigEndian>()?);
         let length = data.read_i32::<byteorder::BigEndian>()?;
 
         if length < 0 {
-            values.push(None);
+            values.push((column_type, None));
             continue;
         }
 
         let value = &data[..length as usize];
-        values.push(Some(value));
+        values.push((column_type, Some(value)));
         data = &data[length as usize..];
     }
 
@@ -102,7 +163,7 @@ macro_rules! tuple_impls {
                     }
 
                     let tuple = (
-                        $($T::from_text(ty, values[$idx])?),+
+                        $($T::from_text(ty, values[$idx].as_deref())?),+
                     );
 
                     Ok(tuple)
@@ -125,7 +186,7 @@ macro_rules! tuple_impls {
                     }
 
                     let tuple = (
-                        $($T::from_binary(ty, values[$idx])?),+
+                        $($T::from_binary(&values[$idx].0, values[$idx].1)?),+
                     );
 
                     Ok(tuple)
@@ -5,21 +5,92 @@ pub use time::UtcOffset as Timezone;
 #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
 pub type TimeTz = (Time, Timezone);
 
+/* Julian day of 2000-01-01, the postgresql epoch */
+const POSTGRES_EPOCH_JDATE: i64 = 2_451_545;
+const USECS_PER_DAY: i64 = 86_400_000_000;
+
+/*
+ * `time::Time::parse` only supports nanoseconds with exactly 9 digits.
+ */
+fn parse_time(s: &str) -> Option<Time> {
+    let (hms, fraction) = match s.find('.') {
+        Some(x) => (&s[..x], &s[x + 1..]),
+        None => (s, ""),
+    };
+
+    let time = Time::parse(hms, "%T").ok()?;
+
+    if fraction.is_empty() {
+        return Some(time);
+    }
+
+    if fraction.len() > 9 || !fraction.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let nanos: i64 = format!("{:0<9}", fraction).parse().ok()?;
+
+    Some(time + time::Duration::nanoseconds(nanos))
+}
+
+/*
+ * Parses the `+02`, `-05:30` or `+0200` postgresql zones.
+ */
+fn parse_timezone(s: &str) -> Option<Timezone> {
+    let mut tz = s.replace(':', "");
+
+    if tz.len() == 3 {
+        tz.push_str("00");
+    }
+
+    Timezone::parse(&tz, "%z").ok()
+}
+
+fn date_from_days(ty: &crate::pq::Type, days: i64) -> crate::Result<time::Date> {
+    let julian = days + POSTGRES_EPOCH_JDATE;
+
+    // `Date::from_julian_day` panics outside of the supported years (like
+    // for the infinity dates)
+    let min = time::Date::try_from_ymd(-100_000, 1, 1).map(time::Date::julian_day);
+    let max = time::Date::try_from_ymd(100_000, 12, 31).map(time::Date::julian_day);
+
+    match (min, max) {
+        (Ok(min), Ok(max)) if (min..=max).contains(&julian) => {
+            Ok(time::Date::from_julian_day(julian))
+        }
+        _ => Err(<time::Date as crate::FromSql>::error(
+            ty,
+            "date",
+            Some(&days.to_string()),
+        )),
+    }
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
 impl crate::ToSql for Time {
     fn ty(&self) -> crate::pq::Type {
         crate::pq::types::TIME
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        let usec = (*self - Time::midnight()).whole_microseconds() as i64;
+
+        usec.to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
     }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
 impl crate::FromSql for Time {
     fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
-        Time::parse(crate::not_null(raw)?, "%T").map_err(|_| Self::error(ty, "time", raw))
+        parse_time(crate::not_null(raw)?).ok_or_else(|| Self::error(ty, "time", raw))
     }
 
     /*
@@ -38,8 +109,20 @@ impl crate::ToSql for TimeTz {
         crate::pq::types::TIMETZ
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        format!("{}{}", self.0, self.1).to_sql()
+        let mut buf = (self.0).to_sql()?.unwrap_or_default();
+        // postgresql stores the zone as seconds west of UTC
+        buf.extend_from_slice(&(-self.1.as_seconds()).to_be_bytes());
+
+        Ok(Some(buf))
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(format!("{}{}", self.0, self.1)))
     }
 }
 
@@ -47,28 +130,14 @@ impl crate::ToSql for TimeTz {
 impl crate::FromSql for TimeTz {
     fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
         let value = crate::not_null(raw)?;
-        dbg!(&value);
 
-        let x = match value.find(|c| c == '+' || c == '-') {
+        let x = match value.find(['+', '-']) {
             Some(x) => x,
             None => return Err(Self::error(ty, "timetz", raw)),
         };
 
-        let time = Time::parse(&value[0..x], "%T").map_err(|err| {
-            dbg!(err);
-            Self::error(ty, "timetz", raw)
-        })?;
-
-        let mut tz = value[x..].replace(':', "");
-
-        if tz.len() == 3 {
-            tz.push_str("00");
-        }
-
-        let timezone = Timezone::parse(&tz, "%z").map_err(|err| {
-            dbg!(err);
-            Self::error(ty, "timetz", raw)
-        })?;
+        let time = parse_time(&value[0..x]).ok_or_else(|| Self::error(ty, "timetz", raw))?;
+        let timezone = parse_timezone(&value[x..]).ok_or_else(|| Self::error(ty, "timetz", raw))?;
 
         Ok((time, timezone))
     }
@@ -90,6 +159,205 @@ impl crate::FromSql for TimeTz {
     }
 }
 
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::ToSql for time::Date {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::DATE
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        use std::convert::TryFrom;
+
+        let days = i32::try_from(self.julian_day() - POSTGRES_EPOCH_JDATE)
+            .map_err(|_| self.error("date", None))?;
+
+        days.to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(time::Date::format(*self, "%F")))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::FromSql for time::Date {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        time::Date::parse(crate::not_null(raw)?, "%F").map_err(|_| Self::error(ty, "date", raw))
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/date.c#L218
+     */
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let days = i32::from_binary(ty, raw)?;
+
+        date_from_days(ty, days.into())
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::ToSql for time::PrimitiveDateTime {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TIMESTAMP
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let days = self.date().julian_day() - POSTGRES_EPOCH_JDATE;
+        let usecs = (self.time() - Time::midnight()).whole_microseconds() as i64;
+
+        (days * USECS_PER_DAY + usecs).to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(format!(
+            "{} {}",
+            self.date().format("%F"),
+            self.time()
+        )))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::FromSql for time::PrimitiveDateTime {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let value = crate::not_null(raw)?;
+
+        let (date, time) = match value.find(' ') {
+            Some(x) => (&value[..x], &value[x + 1..]),
+            None => return Err(Self::error(ty, "timestamp", raw)),
+        };
+
+        let date = time::Date::parse(date, "%F").map_err(|_| Self::error(ty, "timestamp", raw))?;
+        let time = parse_time(time).ok_or_else(|| Self::error(ty, "timestamp", raw))?;
+
+        Ok(time::PrimitiveDateTime::new(date, time))
+    }
+
+    /*
+     * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/timestamp.c#L257
+     */
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let usecs = i64::from_binary(ty, raw)?;
+
+        let date = date_from_days(ty, usecs.div_euclid(USECS_PER_DAY))?;
+        let time = Time::midnight() + time::Duration::microseconds(usecs.rem_euclid(USECS_PER_DAY));
+
+        Ok(time::PrimitiveDateTime::new(date, time))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::ToSql for time::OffsetDateTime {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TIMESTAMPTZ
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        let utc = self.to_offset(Timezone::UTC);
+
+        time::PrimitiveDateTime::new(utc.date(), utc.time()).to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(format!(
+            "{} {}{}",
+            self.date().format("%F"),
+            self.time(),
+            self.offset()
+        )))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::FromSql for time::OffsetDateTime {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let value = crate::not_null(raw)?;
+
+        // The zone starts after the time, the date also contains `-`
+        let x = match value.rfind(['+', '-']) {
+            Some(x) if value[..x].contains(' ') => x,
+            _ => return Err(Self::error(ty, "timestamptz", raw)),
+        };
+
+        let timestamp = time::PrimitiveDateTime::from_text(ty, Some(&value[..x]))?;
+        let timezone =
+            parse_timezone(&value[x..]).ok_or_else(|| Self::error(ty, "timestamptz", raw))?;
+
+        Ok(timestamp.assume_offset(timezone))
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let timestamp = time::PrimitiveDateTime::from_binary(ty, raw)?;
+
+        Ok(timestamp.assume_utc())
+    }
+}
+
+/*
+ * Elapsed microseconds of `duration`, as an interval.
+ */
+fn interval(duration: &time::Duration) -> crate::Result<crate::Interval> {
+    use crate::ToSql;
+    use std::convert::TryFrom;
+
+    i64::try_from(duration.whole_microseconds())
+        .ok()
+        .and_then(crate::Interval::from_usecs)
+        .ok_or_else(|| duration.error("interval", None))
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::ToSql for time::Duration {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::INTERVAL
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        interval(self)?.to_sql()
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        interval(self)?.to_text()
+    }
+}
+
+/**
+ * Interval without months or years, [`Error::IntervalMonths`] is returned
+ * otherwise. A day counts for 24 hours.
+ *
+ * [`Error::IntervalMonths`]: crate::Error::IntervalMonths
+ */
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl crate::FromSql for time::Duration {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        let usecs = <crate::Interval as crate::FromSql>::from_text(ty, raw)?.elapsed_usecs()?;
+
+        Ok(time::Duration::microseconds(usecs))
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let usecs = <crate::Interval as crate::FromSql>::from_binary(ty, raw)?.elapsed_usecs()?;
+
+        Ok(time::Duration::microseconds(usecs))
+    }
+}
+
 #[cfg(test)]
 mod test {
     crate::sql_test!(
@@ -115,4 +383,113 @@ mod test {
             ),
         ]
     );
+
+    crate::sql_test!(
+        date,
+        ::time::Date,
+        [
+            ("'1970-01-01'", ::time::date!(1970 - 01 - 01)),
+            ("'2010-01-01'", ::time::date!(2010 - 01 - 01)),
+            ("'2100-12-30'", ::time::date!(2100 - 12 - 30)),
+        ]
+    );
+
+    crate::sql_test!(
+        timestamp,
+        ::time::PrimitiveDateTime,
+        [
+            (
+                "'1970-01-01 00:00:00'",
+                ::time::date!(1970 - 01 - 01).midnight()
+            ),
+            (
+                "'1999-12-31 23:59:59.5'",
+                ::time::date!(1999 - 12 - 31).with_time(::time::time!(23:59:59.5))
+            ),
+        ]
+    );
+
+    crate::sql_test!(
+        timestamptz,
+        ::time::OffsetDateTime,
+        [(
+            "'1970-01-01 00:00:00+00'",
+            ::time::OffsetDateTime::unix_epoch()
+        )]
+    );
+
+    crate::sql_test!(
+        interval,
+        ::time::Duration,
+        [
+            ("'00:00:00'", ::time::Duration::zero()),
+            (
+                "'1 day 02:03:04.5'",
+                ::time::Duration::microseconds(93_784_500_000)
+            ),
+            ("'-36 hours'", ::time::Duration::hours(-36)),
+        ]
+    );
+
+    #[test]
+    fn duration() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert!(matches!(
+            conn.query::<::time::Duration>("select '1 year 1 mon'::interval", &[])?
+                .try_into_vec(),
+            Err(crate::Error::IntervalMonths(13))
+        ));
+
+        let result = conn.execute("select '2 mons 3 days'::interval as actual")?;
+        assert!(matches!(
+            result.get(0).try_get::<::time::Duration>("actual"),
+            Err(crate::Error::IntervalMonths(2))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_binary() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("set timezone to 'UTC'")?;
+
+        let timestamp = ::time::date!(1999 - 12 - 31).with_time(::time::time!(23:59:59.5));
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&timestamp])?,
+            "1999-12-31 23:59:59.5"
+        );
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&timestamp.date()])?,
+            "1999-12-31"
+        );
+        assert_eq!(
+            conn.query_one::<String>(
+                "select $1::text",
+                &[&timestamp.assume_offset(::time::offset!(-2))]
+            )?,
+            "2000-01-01 01:59:59.5+00"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_text() -> crate::Result {
+        use crate::FromSql;
+
+        let timestamptz = ::time::OffsetDateTime::from_text(
+            &crate::pq::types::TIMESTAMPTZ,
+            Some("2000-01-01 01:59:59.5+05:30"),
+        )?;
+        assert_eq!(
+            timestamptz,
+            ::time::date!(2000 - 01 - 01)
+                .with_time(::time::time!(01:59:59.5))
+                .assume_offset(::time::offset!(+5:30))
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,48 @@
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+impl crate::ToSql for url::Url {
+    fn ty(&self) -> crate::pq::Type {
+        crate::pq::types::TEXT
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.as_str().to_sql()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+impl crate::FromSql for url::Url {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        match url::Url::parse(crate::not_null(raw)?) {
+            Ok(url) => Ok(url),
+            _ => Err(Self::error(ty, "url", raw)),
+        }
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        let url = String::from_binary(ty, raw)?;
+
+        Self::from_text(ty, Some(&url))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    crate::sql_test!(
+        text,
+        url::Url,
+        [(
+            "'https://elephantry.example/docs?page=1#top'",
+            url::Url::parse("https://elephantry.example/docs?page=1#top").unwrap()
+        )]
+    );
+
+    #[test]
+    fn invalid() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let result = conn.execute("select 'not an url'::text as url")?;
+
+        assert!(result.get(0).try_get::<url::Url>("url").is_err());
+
+        Ok(())
+    }
+}
@@ -4,8 +4,16 @@ impl crate::ToSql for uuid::Uuid {
         crate::pq::types::UUID
     }
 
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
+    }
+
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        Ok(Some(self.as_bytes().to_vec()))
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
     }
 }
 
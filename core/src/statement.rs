@@ -0,0 +1,456 @@
+use std::collections::{HashMap, HashSet};
+
+/**
+ * Server side prepared statement, created by [`Connection::prepare`].
+ *
+ * The statement is deallocated when dropped.
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * let statement = connection.prepare("select $1::int + 1")?;
+ *
+ * for x in 0..100 {
+ *     let y = statement.query_one::<i32>(&[&x])?;
+ * }
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * [`Connection::prepare`]: crate::Connection::prepare
+ */
+#[derive(Debug)]
+pub struct Statement {
+    connection: crate::Connection,
+    name: String,
+    query: String,
+    param_types: Vec<crate::pq::Oid>,
+}
+
+impl Statement {
+    pub(crate) fn new(
+        connection: &crate::Connection,
+        name: String,
+        query: String,
+        param_types: Vec<crate::pq::Oid>,
+    ) -> Self {
+        Self {
+            connection: connection.clone(),
+            name,
+            query,
+            param_types,
+        }
+    }
+
+    /**
+     * Name of the statement on the server.
+     */
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+     * The prepared query.
+     */
+    pub fn query_text(&self) -> &str {
+        &self.query
+    }
+
+    /**
+     * Types of the parameters, infered by the server.
+     */
+    pub fn param_types(&self) -> &[crate::pq::Oid] {
+        &self.param_types
+    }
+
+    /**
+     * Executes the statement.
+     */
+    pub fn execute(&self, params: &[&dyn crate::ToSql]) -> crate::Result<crate::pq::Result> {
+        self.check_params(params)?;
+
+        self.connection
+            .send_prepared(&self.query, &self.name, params)
+    }
+
+    /**
+     * Binary parameters are sent as is: the server misreads them if their
+     * type differs from the one infered during the preparation.
+     */
+    fn check_params(&self, params: &[&dyn crate::ToSql]) -> crate::Result {
+        use std::convert::TryFrom;
+
+        for (index, (param, expected)) in params.iter().zip(&self.param_types).enumerate() {
+            let ty = param.ty();
+
+            if param.format() == crate::pq::Format::Binary && ty.oid != *expected {
+                let expected = crate::pq::Type::try_from(*expected)
+                    .map(|x| x.name.to_string())
+                    .unwrap_or_else(|_| expected.to_string());
+
+                return Err(crate::Error::ParamType {
+                    index: index + 1,
+                    rust_type: param.type_name().to_string(),
+                    oid: ty.oid,
+                    expected,
+                    message: format!("statement {} was prepared with another type", self.name),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Executes the statement and returns the rows as `E`.
+     */
+    pub fn query<E: crate::Entity>(
+        &self,
+        params: &[&dyn crate::ToSql],
+    ) -> crate::Result<crate::Rows<E>> {
+        self.execute(params).map(Into::into)
+    }
+
+    /**
+     * Likes [`query`] but peaks only the first result.
+     *
+     * [`query`]: Self::query
+     */
+    pub fn query_one<E: crate::Entity>(&self, params: &[&dyn crate::ToSql]) -> crate::Result<E> {
+        match self.query(params)?.try_get(0) {
+            Some(e) => Ok(e),
+            None => Err(crate::Error::MissingField("0".to_string())),
+        }
+    }
+}
+
+impl Drop for Statement {
+    fn drop(&mut self) {
+        self.connection.deallocate(&self.name);
+    }
+}
+
+type Key = (String, Vec<crate::pq::Oid>);
+
+/**
+ * Least recently used cache of statements prepared automatically, keyed by
+ * query text and parameter types.
+ *
+ * A query is prepared the second time it’s seen, one-off queries are only
+ * remembered. The entries are chained from the most to the least recently
+ * used, in a vector to avoid an allocation per entry.
+ */
+#[derive(Debug, Default)]
+pub(crate) struct Cache {
+    capacity: usize,
+    counter: usize,
+    index: HashMap<Key, usize>,
+    entries: Vec<Entry>,
+    free: Vec<usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    evicted: Vec<String>,
+    stale: HashSet<String>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: Key,
+    name: Option<String>,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+pub(crate) enum Lookup {
+    /** The query is prepared as this name. */
+    Prepared(String),
+    /** The query was already seen, it should be prepared as this name. */
+    Prepare(String),
+    /** First time the query is seen. */
+    Unknown,
+}
+
+impl Cache {
+    /**
+     * Generates a unique statement name.
+     */
+    pub fn next_name(&mut self) -> String {
+        self.counter += 1;
+
+        format!("elephantry_{}", self.counter)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /**
+     * Changes the capacity, returns the names of evicted statements to
+     * deallocate.
+     */
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<String> {
+        self.capacity = capacity;
+
+        while self.index.len() > self.capacity {
+            self.evict();
+        }
+
+        self.take_evicted()
+    }
+
+    /**
+     * Finds `query`, the least recently used entry is evicted if a new one
+     * exceeds the capacity: see [`take_evicted`].
+     *
+     * [`take_evicted`]: Self::take_evicted
+     */
+    pub fn lookup(&mut self, query: &str, types: &[crate::pq::Oid]) -> Lookup {
+        if self.capacity == 0 {
+            return Lookup::Unknown;
+        }
+
+        let key = (query.to_string(), types.to_vec());
+
+        if let Some(&n) = self.index.get(&key) {
+            self.unlink(n);
+            self.push_front(n);
+
+            return match &self.entries[n].name {
+                Some(name) => Lookup::Prepared(name.clone()),
+                None => Lookup::Prepare(self.next_name()),
+            };
+        }
+
+        if self.index.len() >= self.capacity {
+            self.evict();
+        }
+
+        let entry = Entry {
+            key: key.clone(),
+            name: None,
+            prev: None,
+            next: None,
+        };
+
+        let n = match self.free.pop() {
+            Some(n) => {
+                self.entries[n] = entry;
+                n
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+
+        self.index.insert(key, n);
+        self.push_front(n);
+
+        Lookup::Unknown
+    }
+
+    /**
+     * Records `query` as prepared as `name`, returns the names of evicted
+     * statements to deallocate.
+     */
+    pub fn prepared(&mut self, query: &str, types: &[crate::pq::Oid], name: String) -> Vec<String> {
+        match self.index.get(&(query.to_string(), types.to_vec())) {
+            Some(&n) => self.entries[n].name = Some(name),
+            // Evicted during the preparation
+            None => self.evicted.push(name),
+        }
+
+        self.take_evicted()
+    }
+
+    /**
+     * Names of the statements evicted since the last call, to deallocate.
+     */
+    pub fn take_evicted(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.evicted)
+    }
+
+    /**
+     * Marks `name` to prepare again before its next execution, its result
+     * type changed.
+     */
+    pub fn mark_stale(&mut self, name: &str) {
+        self.stale.insert(name.to_string());
+    }
+
+    /**
+     * Unmarks `name`, returns `true` if it was marked stale.
+     */
+    pub fn take_stale(&mut self, name: &str) -> bool {
+        self.stale.remove(name)
+    }
+
+    /**
+     * Forgets all statements, without deallocating them.
+     */
+    pub fn clear(&mut self) {
+        *self = Self {
+            capacity: self.capacity,
+            counter: self.counter,
+            ..Self::default()
+        };
+    }
+
+    fn evict(&mut self) {
+        let n = match self.tail {
+            Some(n) => n,
+            None => return,
+        };
+
+        self.unlink(n);
+        self.index.remove(&self.entries[n].key);
+        self.free.push(n);
+
+        if let Some(name) = self.entries[n].name.take() {
+            self.evicted.push(name);
+        }
+    }
+
+    fn unlink(&mut self, n: usize) {
+        let (prev, next) = (self.entries[n].prev.take(), self.entries[n].next.take());
+
+        match prev {
+            Some(prev) => self.entries[prev].next = next,
+            None => self.head = next,
+        }
+
+        match next {
+            Some(next) => self.entries[next].prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    fn push_front(&mut self, n: usize) {
+        self.entries[n].next = self.head;
+
+        match self.head {
+            Some(head) => self.entries[head].prev = Some(n),
+            None => self.tail = Some(n),
+        }
+
+        self.head = Some(n);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn cache() {
+        let mut cache = super::Cache::default();
+        cache.set_capacity(2);
+
+        assert!(matches!(cache.lookup("a", &[]), super::Lookup::Unknown));
+        let name = match cache.lookup("a", &[]) {
+            super::Lookup::Prepare(name) => name,
+            _ => panic!(),
+        };
+        assert!(cache.prepared("a", &[], name.clone()).is_empty());
+        assert!(matches!(cache.lookup("a", &[]), super::Lookup::Prepared(x) if x == name));
+        assert!(matches!(cache.lookup("a", &[23]), super::Lookup::Unknown));
+
+        cache.lookup("b", &[]);
+        assert_eq!(cache.prepared("b", &[], "other".to_string()), vec![name]);
+
+        for n in 0..10 {
+            cache.lookup(&n.to_string(), &[]);
+        }
+        assert_eq!(cache.index.len(), 2);
+        assert_eq!(cache.take_evicted(), vec!["other".to_string()]);
+        assert!(matches!(cache.lookup("9", &[]), super::Lookup::Prepare(_)));
+        assert!(matches!(cache.lookup("b", &[]), super::Lookup::Unknown));
+    }
+
+    #[test]
+    fn prepare() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        let statement = connection.prepare("select $1::int + 1")?;
+        let name = statement.name().to_string();
+
+        assert_eq!(statement.query_one::<i32>(&[&1])?, 2);
+        assert_eq!(statement.query_one::<i32>(&[&41])?, 42);
+        assert!(matches!(
+            statement.query_one::<i32>(&[&crate::Bytea::from(vec![1])]),
+            Err(crate::Error::ParamType { index: 1, .. })
+        ));
+
+        drop(statement);
+        let count = connection.query_one::<i64>(
+            "select count(*) from pg_prepared_statements where name = $1",
+            &[&name],
+        )?;
+        assert_eq!(count, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn result_type_changed() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        connection.set_statement_cache_capacity(10)?;
+        connection.execute("create temporary table plan (id int)")?;
+        connection.execute("insert into plan values (1)")?;
+
+        let statement = connection.prepare("select * from plan")?;
+        for _ in 0..2 {
+            connection.query::<crate::Row>("select * from plan where id = $1", &[&1])?;
+        }
+
+        connection.execute("alter table plan add column name text")?;
+
+        assert_eq!(statement.query_one::<crate::Row>(&[])?.len(), 2);
+        assert_eq!(
+            connection
+                .query_one::<crate::Row>("select * from plan where id = $1", &[&1])?
+                .len(),
+            2
+        );
+
+        connection.execute("alter table plan add column age int")?;
+        connection.execute("begin")?;
+        assert!(statement.execute(&[]).is_err());
+        connection.execute("rollback")?;
+        connection.execute("begin")?;
+        assert_eq!(statement.query_one::<crate::Row>(&[])?.len(), 3);
+        connection.execute("commit")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn automatic() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        let query = "select $1::int * 2";
+
+        connection.query_one::<i32>(query, &[&1])?;
+        connection.query_one::<i32>(query, &[&1])?;
+        let count = connection.query_one::<i64>(
+            "select count(*) from pg_prepared_statements where statement = $1",
+            &[&query],
+        )?;
+        assert_eq!(count, 0);
+
+        connection.set_statement_cache_capacity(10)?;
+
+        for x in 0..3 {
+            assert_eq!(connection.query_one::<i32>(query, &[&x])?, x * 2);
+        }
+
+        let count = connection.query_one::<i64>(
+            "select count(*) from pg_prepared_statements where statement = $1",
+            &[&query],
+        )?;
+        assert_eq!(count, 1);
+
+        connection.set_statement_cache_capacity(0)?;
+        let count = connection.query_one::<i64>(
+            "select count(*) from pg_prepared_statements where statement = $1",
+            &[&query],
+        )?;
+        assert_eq!(count, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+/**
+ * Number of lock wait samples kept to compute percentiles.
+ */
+const SAMPLES: usize = 1_024;
+
+type Callback = std::sync::Arc<dyn Fn(std::time::Duration) + Send + Sync>;
+
+/**
+ * Status of a pool entry, returned by [`Pool::status`].
+ *
+ * [`Pool::status`]: crate::Pool::status
+ */
+#[derive(Clone, Debug)]
+pub struct Status {
+    /** A thread is currently using the connection. */
+    pub in_use: bool,
+    /** When the connection was created. */
+    pub created: std::time::SystemTime,
+    /** Number of failed queries. */
+    pub errors: u64,
+    /** Time spent waiting for the connection. */
+    pub wait_time: WaitTime,
+}
+
+/**
+ * Percentiles of the time spent waiting for a connection, computed on the
+ * last checkouts.
+ */
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WaitTime {
+    pub p50: std::time::Duration,
+    pub p90: std::time::Duration,
+    pub p99: std::time::Duration,
+    pub max: std::time::Duration,
+}
+
+impl WaitTime {
+    fn new(samples: &std::collections::VecDeque<std::time::Duration>) -> Self {
+        let mut samples = samples.iter().copied().collect::<Vec<_>>();
+        samples.sort();
+
+        let percentile = |p: usize| {
+            if samples.is_empty() {
+                std::time::Duration::default()
+            } else {
+                samples[(samples.len() - 1) * p / 100]
+            }
+        };
+
+        Self {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: percentile(100),
+        }
+    }
+}
+
+/**
+ * Exceeded checkout timeout.
+ */
+pub(crate) struct Timeout {
+    callback: Callback,
+    duration: std::time::Duration,
+}
+
+impl Timeout {
+    pub fn notify(self) {
+        (self.callback)(self.duration)
+    }
+}
+
+pub(crate) struct Stats {
+    created: std::time::SystemTime,
+    errors: std::sync::atomic::AtomicU64,
+    waits: std::sync::Mutex<std::collections::VecDeque<std::time::Duration>>,
+    checkout_timeout: std::sync::RwLock<Option<(std::time::Duration, Callback)>>,
+}
+
+impl Stats {
+    pub fn error(&self) {
+        self.errors
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /**
+     * Records a wait for the connection, returns the checkout timeout to
+     * notify if it’s exceeded.
+     */
+    pub fn waited(&self, duration: std::time::Duration) -> Option<Timeout> {
+        if let Ok(mut waits) = self.waits.lock() {
+            if waits.len() == SAMPLES {
+                waits.pop_front();
+            }
+
+            waits.push_back(duration);
+        }
+
+        match &*self.checkout_timeout.read().ok()? {
+            Some((timeout, callback)) if duration > *timeout => Some(Timeout {
+                callback: callback.clone(),
+                duration,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn set_checkout_timeout(&self, timeout: std::time::Duration, callback: Callback) {
+        if let Ok(mut checkout_timeout) = self.checkout_timeout.write() {
+            *checkout_timeout = Some((timeout, callback));
+        }
+    }
+
+    #[cfg(test)]
+    pub fn checkouts(&self) -> usize {
+        self.waits.lock().map(|x| x.len()).unwrap_or_default()
+    }
+
+    pub fn clear_checkout_timeout(&self) {
+        if let Ok(mut checkout_timeout) = self.checkout_timeout.write() {
+            *checkout_timeout = None;
+        }
+    }
+
+    pub fn status(&self, in_use: bool) -> Status {
+        let wait_time = match self.waits.lock() {
+            Ok(waits) => WaitTime::new(&waits),
+            Err(_) => WaitTime::default(),
+        };
+
+        Status {
+            in_use,
+            created: self.created,
+            errors: self.errors.load(std::sync::atomic::Ordering::Relaxed),
+            wait_time,
+        }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            created: std::time::SystemTime::now(),
+            errors: Default::default(),
+            waits: Default::default(),
+            checkout_timeout: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Stats")
+            .field("created", &self.created)
+            .field("errors", &self.errors)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn wait_time() {
+        let samples = (1..=100)
+            .map(std::time::Duration::from_millis)
+            .collect::<std::collections::VecDeque<_>>();
+
+        let wait_time = super::WaitTime::new(&samples);
+
+        assert_eq!(wait_time.p50, std::time::Duration::from_millis(50));
+        assert_eq!(wait_time.p90, std::time::Duration::from_millis(90));
+        assert_eq!(wait_time.p99, std::time::Duration::from_millis(99));
+        assert_eq!(wait_time.max, std::time::Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,155 @@
+/**
+ * Rows fetched one by one, created by [`Connection::query_stream`].
+ *
+ * The connection is locked until the stream is consumed or dropped, dropping
+ * it before the end cancels the query. Inside a transaction, the remaining
+ * rows are read instead: canceling the query would abort the transaction.
+ *
+ * [`Connection::query_stream`]: crate::Connection::query_stream
+ */
+pub struct RowStream<'c, E: crate::Entity> {
+    connection: &'c crate::Connection,
+    guard: Option<crate::connection::Guard<'c>>,
+    in_transaction: bool,
+    marker: std::marker::PhantomData<E>,
+}
+
+impl<'c, E: crate::Entity> RowStream<'c, E> {
+    pub(crate) fn new(
+        connection: &'c crate::Connection,
+        guard: crate::connection::Guard<'c>,
+        in_transaction: bool,
+    ) -> Self {
+        Self {
+            connection,
+            guard: Some(guard),
+            in_transaction,
+            marker: std::marker::PhantomData,
+        }
+    }
+
+    /**
+     * Reads the remaining results, to make the connection usable again.
+     */
+    fn finish(&mut self) {
+        if let Some(guard) = self.guard.take() {
+            while guard.result().is_some() {}
+
+            drop(guard);
+            self.connection.set_streaming_thread(None);
+        }
+    }
+}
+
+impl<'c, E: crate::Entity> Iterator for RowStream<'c, E> {
+    type Item = crate::Result<E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use std::convert::TryInto;
+
+        loop {
+            let result = match self.guard.as_ref()?.result() {
+                Some(result) => result,
+                None => {
+                    self.finish();
+                    return None;
+                }
+            };
+
+            match result.status() {
+                libpq::Status::SingleTuble => {
+                    let result: crate::Result<crate::pq::Result> = result.try_into();
+
+                    return Some(result.and_then(|x| E::try_from_tuple(&x.get(0))));
+                }
+                // Ends the rows
+                libpq::Status::TupplesOk => continue,
+                _ => {
+                    let result: crate::Result<crate::pq::Result> = result.try_into();
+
+                    if let Err(err) = self.connection.count_error(result) {
+                        self.finish();
+                        return Some(Err(err));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'c, E: crate::Entity> Drop for RowStream<'c, E> {
+    fn drop(&mut self) {
+        if let Some(guard) = self.guard.as_ref().filter(|_| !self.in_transaction) {
+            if let Err(err) = guard.cancel().request() {
+                log::warn!("Unable to cancel the query: {}", err);
+            }
+        }
+
+        self.finish();
+    }
+}
+
+impl<'c, E: crate::Entity> std::fmt::Debug for RowStream<'c, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RowStream")
+            .field("finished", &self.guard.is_none())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn query_stream() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+
+        let stream =
+            connection.query_stream::<i32>("select generate_series(1, $1::int)", &[&1_000])?;
+        let mut count = 0;
+
+        for (n, x) in stream.enumerate() {
+            assert_eq!(x?, n as i32 + 1);
+            count += 1;
+        }
+        assert_eq!(count, 1_000);
+
+        let mut stream =
+            connection.query_stream::<i32>("select generate_series(1, 1000000000)", &[])?;
+        assert_eq!(stream.next().transpose()?, Some(1));
+        assert!(matches!(
+            connection.query_one::<i32>("select 1", &[]),
+            Err(crate::Error::Busy)
+        ));
+        drop(stream);
+
+        let errors = connection
+            .query_stream::<i32>("select 1 / (x - 3) from generate_series(1, 5) x", &[])?
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[2].is_err());
+
+        assert_eq!(connection.query_one::<i32>("select 1", &[])?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transaction() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        connection.execute("begin")?;
+
+        let mut stream =
+            connection.query_stream::<i32>("select generate_series(1, $1::int)", &[&1_000])?;
+        assert_eq!(stream.next().transpose()?, Some(1));
+        drop(stream);
+
+        assert_eq!(
+            connection.transaction_status()?,
+            libpq::transaction::Status::InTrans
+        );
+        assert_eq!(connection.query_one::<i32>("select 1", &[])?, 1);
+        connection.execute("commit")?;
+
+        Ok(())
+    }
+}
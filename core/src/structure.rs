@@ -4,8 +4,150 @@
 pub trait Structure {
     /** Get relation name. */
     fn relation() -> &'static str;
+    /**
+     * Get the schema of the relation, `None` if the relation isn’t qualified
+     * and depends on the session `search_path`.
+     */
+    fn schema() -> Option<&'static str> {
+        match crate::identifier::split(Self::relation()).as_slice() {
+            [schema, _, ..] => Some(schema),
+            _ => None,
+        }
+    }
     /** Get the list of column contitutes the primary key. */
     fn primary_key() -> &'static [&'static str];
     /** Get the list for columns. */
     fn columns() -> &'static [&'static str];
+    /** Get the list of unique keys, each one is a list of columns. */
+    fn unique_keys() -> &'static [&'static [&'static str]] {
+        &[]
+    }
+}
+
+/**
+ * Marker of writable relations, like tables: enables the insert, update,
+ * delete and copy helpers of [`Connection`].
+ *
+ * Implemented by the [`Entity`](derive@crate::Entity) derive, unless the
+ * relation is declared as a view.
+ *
+ * [`Connection`]: crate::Connection
+ */
+pub trait Writable: Structure {}
+
+/**
+ * Marker of read-only relations, declared with `#[elephantry(view)]`: the
+ * write helpers are not available for their models.
+ *
+ * ```compile_fail
+ * #[derive(elephantry::Entity)]
+ * #[elephantry(model = "Model", structure = "Structure", relation = "employee_name", view)]
+ * struct EmployeeName {
+ *     name: String,
+ * }
+ *
+ * # let connection = elephantry::Connection::new("")?;
+ * connection.insert_one::<Model>(&EmployeeName { name: "Ada".to_string() })?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ */
+pub trait View: Structure {}
+
+/**
+ * Marker of materialized views, declared with
+ * `#[elephantry(materialized_view)]`, see [`Connection::refresh`].
+ *
+ * [`Connection::refresh`]: crate::Connection::refresh
+ */
+pub trait MaterializedView: View {}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, elephantry_derive::Entity)]
+    #[elephantry(internal, model = "Model", structure = "Structure")]
+    #[elephantry(schema = r#""elephantry.Schema""#, relation = "item")]
+    struct Qualified {
+        #[elephantry(pk)]
+        id: i32,
+    }
+
+    mod unqualified {
+        #[derive(Debug, elephantry_derive::Entity)]
+        #[elephantry(internal, model = "Model", structure = "Structure")]
+        #[elephantry(relation = "item")]
+        pub struct Unqualified {
+            #[elephantry(pk)]
+            pub id: i32,
+        }
+    }
+
+    mod view {
+        #[derive(Debug, elephantry_derive::Entity)]
+        #[elephantry(internal, model = "Model", structure = "Structure")]
+        #[elephantry(relation = "entity_count", materialized_view)]
+        pub struct EntityCount {
+            pub count: i64,
+        }
+    }
+
+    #[test]
+    fn refresh() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        connection.execute(
+            "drop table if exists refresh_source cascade;
+            create table refresh_source (id int);
+            create materialized view entity_count as select count(*) from refresh_source;",
+        )?;
+
+        let count = || {
+            connection
+                .find_all::<view::Model<'_>>(None)
+                .map(|mut x| x.next().map(|x| x.count))
+        };
+
+        connection.execute("insert into refresh_source values (1)")?;
+        assert_eq!(count()?, Some(0));
+
+        connection.refresh::<view::Model<'_>>()?;
+        assert_eq!(count()?, Some(1));
+
+        connection.execute("drop table refresh_source cascade")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn schema() -> crate::Result {
+        assert_eq!(
+            <Structure as crate::Structure>::relation(),
+            r#""elephantry.Schema".item"#
+        );
+        assert_eq!(
+            <Structure as crate::Structure>::schema(),
+            Some(r#""elephantry.Schema""#)
+        );
+        assert_eq!(<unqualified::Structure as crate::Structure>::schema(), None);
+
+        let connection = crate::test::new_conn()?;
+        connection.execute(
+            r#"drop schema if exists "elephantry.Schema" cascade;
+            create schema "elephantry.Schema";
+            create table "elephantry.Schema".item (id int primary key);
+            set search_path to public;"#,
+        )?;
+
+        connection.insert_one::<Model<'_>>(&Qualified { id: 1 })?;
+        assert_eq!(connection.find_all::<Model<'_>>(None)?.len(), 1);
+
+        assert!(connection.find_all::<unqualified::Model<'_>>(None).is_err());
+        connection.set_default_schema(Some("elephantry.Schema"))?;
+        assert_eq!(
+            connection.find_all::<unqualified::Model<'_>>(None)?.len(),
+            1
+        );
+
+        connection.execute(r#"drop schema "elephantry.Schema" cascade"#)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,95 @@
+/**
+ * Statement being sent, given to the function set by
+ * [`Connection::tag_queries`].
+ *
+ * [`Connection::tag_queries`]: crate::Connection::tag_queries
+ */
+#[derive(Debug)]
+pub struct QueryContext<'a> {
+    /** The query, without tag. */
+    pub query: &'a str,
+}
+
+type Tagger = dyn Fn(&QueryContext<'_>) -> String + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct QueryTagger(std::sync::Arc<Tagger>);
+
+impl QueryTagger {
+    pub fn new<F>(tagger: F) -> Self
+    where
+        F: Fn(&QueryContext<'_>) -> String + Send + Sync + 'static,
+    {
+        Self(std::sync::Arc::new(tagger))
+    }
+
+    /**
+     * Prepends the tag to `query`, on its own line to keep the query intact
+     * after a `--` comment.
+     */
+    pub fn tag(&self, query: &str) -> String {
+        let tag = (self.0)(&QueryContext { query });
+
+        if tag.is_empty() {
+            query.to_string()
+        } else {
+            format!("{}\n{}", tag, query)
+        }
+    }
+}
+
+impl std::fmt::Debug for QueryTagger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryTagger").finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn tag_queries() -> crate::Result {
+        let connection = crate::test::new_conn()?;
+        connection.tag_queries(|ctx| {
+            if ctx.query.contains("pg_stat_activity") {
+                "/* request_id=42 */".to_string()
+            } else {
+                String::new()
+            }
+        })?;
+
+        let query = connection.query_one::<String>(
+            "select query from pg_stat_activity where pid = pg_backend_pid()",
+            &[],
+        )?;
+        assert!(query.starts_with("/* request_id=42 */\n"));
+
+        assert!(matches!(
+            connection.query_one::<i32>("select 1 from pg_stat_activity where pid = $1", &[&"a"]),
+            Err(crate::Error::ParamType { index: 1, .. })
+        ));
+
+        connection.set_statement_cache_capacity(10)?;
+        for _ in 0..3 {
+            let query = connection.query_one::<String>(
+                "select query from pg_stat_activity where pid = $1",
+                &[&connection.query_one::<i32>("select pg_backend_pid()", &[])?],
+            )?;
+            assert!(query.starts_with("/* request_id=42 */\n"));
+        }
+
+        let statement = connection.prepare("select query from pg_stat_activity where pid = $1")?;
+        let query = statement.query_one::<String>(&[
+            &connection.query_one::<i32>("select pg_backend_pid()", &[])?
+        ])?;
+        assert!(query.starts_with("/* request_id=42 */\n"));
+
+        connection.untag_queries()?;
+        let query = connection.query_one::<String>(
+            "select query from pg_stat_activity where pid = pg_backend_pid()",
+            &[],
+        )?;
+        assert!(query.starts_with("select"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+/**
+ * How the session time zone is handled.
+ *
+ * The server converts `timestamptz` values to the session `TimeZone` in text
+ * format, and with it casts between `timestamp` and `timestamptz`: a session
+ * time zone changed behind the application back silently shifts the naive
+ * timestamps.
+ *
+ * Set by [`Connection::set_timezone_policy`] or the `timezone_policy` field of
+ * [`Config`].
+ *
+ * [`Config`]: crate::Config
+ * [`Connection::set_timezone_policy`]: crate::Connection::set_timezone_policy
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TimezonePolicy {
+    /** Uses the time zone of the server configuration, without check. */
+    #[default]
+    Server,
+    /**
+     * Sets the session time zone to UTC when connecting, and checks it’s
+     * still UTC before each query: [`Error::Timezone`] is returned otherwise,
+     * so the decoded offsets are always `+00`.
+     *
+     * [`Error::Timezone`]: crate::Error::Timezone
+     */
+    Utc,
+}
+
+impl TimezonePolicy {
+    /**
+     * Checks the session time zone, as reported by the server.
+     */
+    pub(crate) fn check(&self, timezone: &str) -> crate::Result {
+        if *self == Self::Utc && !is_utc(timezone) {
+            return Err(crate::Error::Timezone(timezone.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/*
+ * Queries ending the transaction or changing the session time zone, which
+ * can restore a time zone changed by `set local`.
+ */
+pub(crate) fn restores(query: &str) -> bool {
+    let query = query.to_lowercase();
+    let words = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|x| !x.is_empty())
+        .take(5)
+        .collect::<Vec<_>>();
+
+    matches!(
+        words.as_slice(),
+        ["commit" | "end" | "rollback" | "abort" | "reset", ..]
+            | ["set", "session" | "local", "time", "zone", ..]
+            | ["set", "session" | "local", "timezone", ..]
+            | ["set", "time", "zone", ..]
+            | ["set", "timezone", ..]
+    )
+}
+
+fn is_utc(timezone: &str) -> bool {
+    let timezone = timezone.strip_prefix("Etc/").unwrap_or(timezone);
+
+    ["UTC", "UCT", "Universal", "Zulu"]
+        .iter()
+        .any(|x| x.eq_ignore_ascii_case(timezone))
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimezonePolicy;
+
+    #[test]
+    fn check() {
+        assert!(TimezonePolicy::Utc.check("UTC").is_ok());
+        assert!(TimezonePolicy::Utc.check("Etc/UTC").is_ok());
+        assert!(matches!(
+            TimezonePolicy::Utc.check("Europe/Paris"),
+            Err(crate::Error::Timezone(_))
+        ));
+        assert!(TimezonePolicy::Server.check("Europe/Paris").is_ok());
+    }
+
+    #[test]
+    fn utc() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("set time zone 'Europe/Paris'")?;
+
+        conn.set_timezone_policy(TimezonePolicy::Utc)?;
+        assert_eq!(
+            conn.query_one::<String>("select '2000-01-01 00:00:00+02'::timestamptz::text", &[])?,
+            "1999-12-31 22:00:00+00"
+        );
+
+        conn.execute("set time zone 'Europe/Paris'").ok();
+        assert!(matches!(
+            conn.execute("select now()"),
+            Err(crate::Error::Timezone(timezone)) if timezone == "Europe/Paris"
+        ));
+
+        conn.set_timezone_policy(TimezonePolicy::Server)?;
+        assert!(conn.execute("select now()").is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_local() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.set_timezone_policy(TimezonePolicy::Utc)?;
+
+        conn.execute("begin")?;
+        conn.execute("set local time zone 'Europe/Paris'")?;
+        assert!(matches!(
+            conn.execute("select now()"),
+            Err(crate::Error::Timezone(_))
+        ));
+        conn.execute("rollback;")?;
+        assert!(conn.execute("select now()").is_ok());
+
+        conn.execute("set time zone 'Europe/Paris'")?;
+        conn.execute("set timezone = 'UTC'")?;
+        assert!(conn.execute("select now()").is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn restores() {
+        assert!(super::restores("COMMIT"));
+        assert!(super::restores("rollback to savepoint a"));
+        assert!(super::restores("set local time zone 'UTC'"));
+        assert!(super::restores("set timezone='UTC'"));
+        assert!(super::restores("reset all"));
+        assert!(!super::restores("select 'commit'"));
+        assert!(!super::restores("set search_path to public"));
+    }
+}
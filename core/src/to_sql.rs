@@ -1,3 +1,25 @@
+macro_rules! number {
+    ($type:ty, $sql_type:ident) => {
+        impl ToSql for $type {
+            fn ty(&self) -> crate::pq::Type {
+                crate::pq::types::$sql_type
+            }
+
+            fn format(&self) -> crate::pq::Format {
+                crate::pq::Format::Binary
+            }
+
+            fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+                Ok(Some(self.to_be_bytes().to_vec()))
+            }
+
+            fn to_text(&self) -> crate::Result<Option<String>> {
+                Ok(Some(self.to_string()))
+            }
+        }
+    };
+}
+
 /**
  * Trait to allow a rust type to be translated to a SQL value.
  */
@@ -7,10 +29,58 @@ pub trait ToSql {
     /** Convert the value */
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>>;
 
+    /**
+     * Format of the value returned by `to_sql`.
+     *
+     * Binary values are the representation read by the postgresql `*_recv`
+     * functions, text values are nul terminated.
+     */
     fn format(&self) -> crate::pq::Format {
         crate::pq::Format::Text
     }
 
+    /**
+     * Text representation of the value, without the trailing nul byte. Used
+     * to embed the value in another one, like a record or a COPY row.
+     *
+     * Types with a binary `format` have to implement it.
+     */
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        if self.format() == crate::pq::Format::Binary {
+            return Err(self.error(
+                self.type_name(),
+                Some(&"no text representation".to_string()),
+            ));
+        }
+
+        let mut raw = match self.to_sql()? {
+            Some(raw) => raw,
+            None => return Ok(None),
+        };
+
+        if raw.last() == Some(&0) {
+            raw.pop();
+        }
+
+        String::from_utf8(raw).map(Some).map_err(Into::into)
+    }
+
+    /**
+     * Is it a byte? A `Vec` of bytes is sent as a `bytea`, not as an array.
+     */
+    #[doc(hidden)]
+    fn is_byte() -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
+
+    /** Name of the rust type, used in diagnostics */
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
     fn error(&self, _rust_type: &str, message: Option<&String>) -> crate::Error {
         crate::Error::ToSql {
             pg_type: self.ty(),
@@ -20,39 +90,49 @@ pub trait ToSql {
     }
 }
 
-impl ToSql for bool {
+number!(f32, FLOAT4);
+number!(f64, FLOAT8);
+number!(i16, INT2);
+number!(i32, INT4);
+number!(i64, INT8);
+
+impl ToSql for u32 {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::BOOL
+        crate::pq::types::INT8
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        let v = if *self { b"t\0" } else { b"f\0" };
+        i64::from(*self).to_sql()
+    }
 
-        Ok(Some(v.to_vec()))
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(self.to_string()))
     }
 }
 
-impl ToSql for f32 {
+impl ToSql for bool {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::FLOAT4
+        crate::pq::types::BOOL
     }
 
-    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
     }
-}
 
-impl ToSql for f64 {
-    fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::FLOAT8
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        Ok(Some(vec![*self as u8]))
     }
 
-    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        Ok(Some(if *self { "t" } else { "f" }.to_string()))
     }
 }
 
-impl ToSql for &str {
+impl ToSql for str {
     fn ty(&self) -> crate::pq::Type {
         crate::pq::types::VARCHAR
     }
@@ -65,29 +145,42 @@ impl ToSql for &str {
     }
 }
 
-impl ToSql for char {
+/**
+ * A `u8` is a `"char"`, use [`Bit`] for a `bit`.
+ *
+ * [`Bit`]: crate::Bit
+ */
+impl ToSql for u8 {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::BPCHAR
+        crate::pq::types::CHAR
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        crate::pq::Format::Binary
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        Ok(Some(vec![*self]))
     }
-}
 
-impl ToSql for String {
-    fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::VARCHAR
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        let text = match *self {
+            0 => String::new(),
+            byte if byte.is_ascii() => char::from(byte).to_string(),
+            byte => format!("\\{:03o}", byte),
+        };
+
+        Ok(Some(text))
     }
 
-    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.as_str().to_sql()
+    fn is_byte() -> bool {
+        true
     }
 }
 
-impl ToSql for i16 {
+impl ToSql for char {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::INT2
+        crate::pq::types::BPCHAR
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
@@ -95,33 +188,39 @@ impl ToSql for i16 {
     }
 }
 
-impl ToSql for i32 {
+impl ToSql for String {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::INT4
+        crate::pq::types::VARCHAR
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        self.as_str().to_sql()
     }
 }
 
-impl ToSql for i64 {
+impl<T: ToSql + ?Sized> ToSql for &T {
     fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::INT8
+        (**self).ty()
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+        (**self).to_sql()
     }
-}
 
-impl ToSql for u32 {
-    fn ty(&self) -> crate::pq::Type {
-        crate::pq::types::INT8
+    fn format(&self) -> crate::pq::Format {
+        (**self).format()
     }
 
-    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        self.to_string().to_sql()
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        (**self).to_text()
+    }
+
+    fn type_name(&self) -> &'static str {
+        (**self).type_name()
+    }
+
+    fn error(&self, rust_type: &str, message: Option<&String>) -> crate::Error {
+        (**self).error(rust_type, message)
     }
 }
 
@@ -139,39 +238,190 @@ impl<T: ToSql> ToSql for Option<T> {
             None => Ok(None),
         }
     }
+
+    fn format(&self) -> crate::pq::Format {
+        match self {
+            Some(data) => data.format(),
+            None => crate::pq::Format::Text,
+        }
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        match self {
+            Some(data) => data.to_text(),
+            None => Ok(None),
+        }
+    }
+}
+
+/**
+ * Type of the first non null element.
+ */
+fn element_type<T: ToSql>(elements: &[T]) -> Option<crate::pq::Type> {
+    elements
+        .iter()
+        .map(ToSql::ty)
+        .find(|x| x.oid != crate::pq::types::UNKNOWN.oid)
 }
 
+/*
+ * https://github.com/postgres/postgres/blob/REL_12_0/src/backend/utils/adt/arrayfuncs.c#L1547
+ */
+fn array_to_binary<T: ToSql>(
+    elements: &[T],
+    elemtype: &crate::pq::Type,
+) -> crate::Result<Option<Vec<u8>>> {
+    let mut values = Vec::new();
+    let mut has_nulls = false;
+
+    for x in elements {
+        match x.to_sql()? {
+            Some(element) => {
+                values.extend_from_slice(&(element.len() as i32).to_be_bytes());
+                values.extend_from_slice(&element);
+            }
+            None => {
+                has_nulls = true;
+                values.extend_from_slice(&(-1_i32).to_be_bytes());
+            }
+        }
+    }
+
+    let mut data = Vec::with_capacity(values.len() + 20);
+    // ndim
+    data.extend_from_slice(&1_i32.to_be_bytes());
+    data.extend_from_slice(&(has_nulls as i32).to_be_bytes());
+    data.extend_from_slice(&elemtype.oid.to_be_bytes());
+    // dimension
+    data.extend_from_slice(&(elements.len() as i32).to_be_bytes());
+    // lower bound
+    data.extend_from_slice(&1_i32.to_be_bytes());
+    data.extend_from_slice(&values);
+
+    Ok(Some(data))
+}
+
+/**
+ * Arrays, or a `bytea` for `Vec<u8>`.
+ */
 impl<T: ToSql> ToSql for Vec<T> {
     fn ty(&self) -> crate::pq::Type {
-        use crate::pq::ToArray;
+        if T::is_byte() {
+            return crate::pq::types::BYTEA;
+        }
 
-        match self.get(0) {
-            Some(data) => data.ty().to_array(),
-            None => crate::pq::types::UNKNOWN,
+        array_type(self)
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        if T::is_byte() {
+            return crate::pq::Format::Binary;
         }
+
+        array_format(self)
     }
 
     fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
-        let mut data = vec![b'{'];
+        if T::is_byte() {
+            return bytes(self).map(Some);
+        }
 
-        for x in self {
-            let element = match x.to_sql()? {
-                Some(element) => element,
-                None => b"null\0".to_vec(),
-            };
+        array_to_sql(self)
+    }
 
-            data.extend_from_slice(&element[..element.len() - 1]);
-            data.push(b',');
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        if T::is_byte() {
+            return bytes(self)?.as_slice().to_text();
         }
 
-        if data.last() == Some(&b',') {
-            data.pop();
+        array_to_text(self)
+    }
+}
+
+fn bytes<T: ToSql>(elements: &[T]) -> crate::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(elements.len());
+
+    for x in elements {
+        bytes.extend(x.to_sql()?.unwrap_or_default());
+    }
+
+    Ok(bytes)
+}
+
+/**
+ * Arrays of composites, to send a batch of records to a function.
+ *
+ * Other slices than `[u8]` are sent as `Vec`.
+ */
+impl<T: crate::Composite + ToSql> ToSql for [T] {
+    fn ty(&self) -> crate::pq::Type {
+        array_type(self)
+    }
+
+    fn format(&self) -> crate::pq::Format {
+        array_format(self)
+    }
+
+    fn to_sql(&self) -> crate::Result<Option<Vec<u8>>> {
+        array_to_sql(self)
+    }
+
+    fn to_text(&self) -> crate::Result<Option<String>> {
+        array_to_text(self)
+    }
+}
+
+fn array_type<T: ToSql>(elements: &[T]) -> crate::pq::Type {
+    use crate::pq::ToArray;
+
+    match element_type(elements) {
+        Some(ty) => ty.to_array(),
+        None => crate::pq::types::UNKNOWN,
+    }
+}
+
+/**
+ * Arrays are sent in binary when all non null elements are binary.
+ */
+fn array_format<T: ToSql>(elements: &[T]) -> crate::pq::Format {
+    let binary = element_type(elements).is_some()
+        && elements
+            .iter()
+            .filter(|x| x.ty().oid != crate::pq::types::UNKNOWN.oid)
+            .all(|x| x.format() == crate::pq::Format::Binary);
+
+    if binary {
+        crate::pq::Format::Binary
+    } else {
+        crate::pq::Format::Text
+    }
+}
+
+fn array_to_sql<T: ToSql>(elements: &[T]) -> crate::Result<Option<Vec<u8>>> {
+    if array_format(elements) == crate::pq::Format::Binary {
+        if let Some(elemtype) = element_type(elements) {
+            return array_to_binary(elements, &elemtype);
         }
+    }
+
+    array_to_text(elements).map(|x| x.map(|x| format!("{}\0", x).into_bytes()))
+}
+
+fn array_to_text<T: ToSql>(elements: &[T]) -> crate::Result<Option<String>> {
+    let mut texts = Vec::new();
 
-        data.extend_from_slice(b"}\0");
+    for x in elements {
+        let element = match x.to_text()? {
+            // Nested arrays aren’t quoted
+            Some(text) if matches!(x.ty().kind, libpq::types::Kind::Array(_)) => text,
+            Some(text) => crate::sql::array::quote(&text).into_owned(),
+            None => "null".to_string(),
+        };
 
-        Ok(Some(data))
+        texts.push(element);
     }
+
+    Ok(Some(format!("{{{}}}", texts.join(","))))
 }
 
 impl ToSql for () {
@@ -190,9 +440,53 @@ mod test {
 
     #[test]
     fn vec_to_sql() {
-        let vec = vec![1, 2, 3];
+        let vec = vec!["a", "b"];
+
+        assert_eq!(vec.to_sql().unwrap(), Some(b"{a,b}\0".to_vec()));
+    }
+
+    #[test]
+    fn vec_to_text() {
+        let vec = vec![Some(1), None];
 
-        assert_eq!(vec.to_sql().unwrap(), Some(b"{1,2,3}\0".to_vec()));
+        assert_eq!(vec.to_text().unwrap(), Some("{1,null}".to_string()));
+    }
+
+    #[test]
+    fn binary_vec() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let vec = vec![Some(1), None, Some(3)];
+
+        assert_eq!(vec.format(), crate::pq::Format::Binary);
+        assert_eq!(vec.ty(), crate::pq::types::INT4_ARRAY);
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&vec])?,
+            "{1,NULL,3}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+
+        assert_eq!(1_i16.format(), crate::pq::Format::Binary);
+        assert_eq!(conn.query_one::<i16>("select $1", &[&-2_i16])?, -2);
+        assert_eq!(conn.query_one::<i32>("select $1", &[&i32::MIN])?, i32::MIN);
+        assert_eq!(conn.query_one::<i64>("select $1", &[&i64::MAX])?, i64::MAX);
+        assert_eq!(
+            conn.query_one::<i64>("select $1", &[&u32::MAX])?,
+            u32::MAX as i64
+        );
+        assert!(conn.query_one::<bool>("select $1", &[&true])?);
+        assert_eq!(conn.query_one::<f64>("select $1", &[&0.1_f64])?, 0.1);
+        assert_eq!(
+            conn.query_one::<String>("select $1::text", &[&f32::MIN_POSITIVE])?,
+            "1.1754944e-38"
+        );
+
+        Ok(())
     }
 
     #[test]
@@ -201,4 +495,29 @@ mod test {
 
         assert_eq!(vec.to_sql().unwrap(), Some(b"{}\0".to_vec()));
     }
+
+    #[test]
+    fn reference() {
+        let s = String::from("foo");
+
+        assert_eq!(
+            <&String as ToSql>::to_sql(&&s).unwrap(),
+            Some(b"foo\0".to_vec())
+        );
+        assert_eq!((&&1).to_sql().unwrap(), Some(1_i32.to_be_bytes().to_vec()));
+        assert_eq!((&&1).to_text().unwrap(), Some("1".to_string()));
+        assert_eq!((&"foo").ty(), crate::pq::types::VARCHAR);
+    }
+
+    #[test]
+    fn reference_params() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let names = ["foo".to_string(), "bar".to_string()];
+        let params = names.iter().map(|x| x as &dyn ToSql).collect::<Vec<_>>();
+
+        let results = conn.query::<String>("select $1 || $2", &params)?;
+        assert_eq!(results.get(0), "foobar");
+
+        Ok(())
+    }
 }
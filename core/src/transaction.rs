@@ -5,6 +5,9 @@
  */
 pub struct Transaction<'c> {
     connection: &'c crate::Connection,
+    auto_savepoint: bool,
+    savepoint: std::cell::RefCell<Option<String>>,
+    events: std::cell::Cell<usize>,
 }
 
 /**
@@ -83,48 +86,224 @@ impl std::fmt::Display for AccessMode {
     }
 }
 
+/**
+ * Result of [`Transaction::commit`] and [`Transaction::roolback`]: PostgreSQL
+ * rollbacks an aborted transaction instead of committing it.
+ *
+ * There is no failed outcome: a failed `COMMIT` or `ROLLBACK` is returned as
+ * an error, to be propagated with `?`.
+ */
+#[derive(Debug, Eq, PartialEq)]
+#[must_use]
+pub enum Outcome {
+    Committed,
+    RolledBack,
+}
+
 impl<'c> Transaction<'c> {
     pub(crate) fn new(connection: &'c crate::Connection) -> Self {
-        Self { connection }
+        Self {
+            connection,
+            auto_savepoint: true,
+            savepoint: std::cell::RefCell::new(None),
+            events: std::cell::Cell::new(0),
+        }
+    }
+
+    /**
+     * By default, starting a transaction inside another one creates a
+     * savepoint: [`commit`] releases it and [`roolback`] rollbacks to it.
+     * Disable this to always issue a `BEGIN`.
+     *
+     * [`commit`]: Self::commit
+     * [`roolback`]: Self::roolback
+     */
+    pub fn auto_savepoint(mut self, enabled: bool) -> Self {
+        self.auto_savepoint = enabled;
+
+        self
     }
 
     /**
-     * Start a new transaction.
+     * Start a new transaction, or a nested one if a transaction is already
+     * open.
      */
     pub fn start(&self) -> crate::Result {
+        if self.auto_savepoint && self.is_in_transaction()? {
+            static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+            let name = format!(
+                "elephantry_savepoint_{}",
+                COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            );
+            self.savepoint(&name)?;
+            *self.savepoint.borrow_mut() = Some(name);
+            self.events.set(self.connection.events.len()?);
+
+            return Ok(());
+        }
+
+        self.connection.begin()?;
+
         self.exec("begin transaction")
     }
 
     /**
-     * Commit a transaction.
+     * Is it a nested transaction?
      */
-    pub fn commit(&self) -> crate::Result {
-        self.exec("commit transaction")
+    pub fn is_nested(&self) -> bool {
+        self.savepoint.borrow().is_some()
+    }
+
+    /**
+     * Commit a transaction. If the transaction was aborted, PostgreSQL
+     * rollbacks it and [`Outcome::RolledBack`] is returned.
+     *
+     * Returns an error if the `COMMIT` fails.
+     */
+    pub fn commit(&self) -> crate::Result<Outcome> {
+        if self.is_aborted()? {
+            return Ok(Outcome::RolledBack);
+        }
+
+        if let Some(savepoint) = self.savepoint.borrow_mut().take() {
+            return self.commit_savepoint(&savepoint);
+        }
+
+        let outcome = match self.connection.execute("commit transaction") {
+            Ok(result) => match result.command_tag() {
+                Some(crate::pq::CommandTag::Other(tag)) if tag == "ROLLBACK" => Outcome::RolledBack,
+                _ => Outcome::Committed,
+            },
+            Err(err) => {
+                self.connection.events.clear()?;
+                return Err(err);
+            }
+        };
+
+        match outcome {
+            Outcome::Committed => self.connection.events.flush()?,
+            Outcome::RolledBack => self.connection.events.clear()?,
+        }
+
+        self.check_status(libpq::transaction::Status::Idle)?;
+
+        Ok(outcome)
     }
 
     /**
      * Rollback a transaction. If a `name` is specified, the transaction is
      * rollback to the given savepoint. Otherwise, the whole transaction is
      * rollback.
+     *
+     * Returns [`Outcome::RolledBack`], or an error if the `ROLLBACK` fails.
      */
-    pub fn roolback(&self, name: Option<&str>) -> crate::Result {
-        let query = match name {
-            Some(name) => format!("rollback to savepoint {}", name),
-            None => "rollback transaction".to_string(),
+    pub fn roolback(&self, name: Option<&str>) -> crate::Result<Outcome> {
+        if name.is_none() && self.is_aborted()? {
+            return Ok(Outcome::RolledBack);
+        }
+
+        if name.is_none() {
+            if let Some(savepoint) = self.savepoint.borrow_mut().take() {
+                return self.roolback_savepoint(&savepoint);
+            }
+        }
+
+        let (query, expected) = match name {
+            Some(name) => (
+                format!("rollback to savepoint {}", name),
+                libpq::transaction::Status::InTrans,
+            ),
+            None => (
+                "rollback transaction".to_string(),
+                libpq::transaction::Status::Idle,
+            ),
         };
 
-        self.exec(&query)
+        let result = self.exec(&query);
+
+        if name.is_none() {
+            self.connection.events.clear()?;
+        }
+
+        result?;
+
+        self.check_status(expected)?;
+
+        Ok(Outcome::RolledBack)
+    }
+
+    fn commit_savepoint(&self, savepoint: &str) -> crate::Result<Outcome> {
+        if self.connection.transaction_status()? == libpq::transaction::Status::InError {
+            return self.roolback_savepoint(savepoint);
+        }
+
+        self.release_savepoint(savepoint)?;
+        self.check_status(libpq::transaction::Status::InTrans)?;
+
+        Ok(Outcome::Committed)
+    }
+
+    fn roolback_savepoint(&self, savepoint: &str) -> crate::Result<Outcome> {
+        let outcome = self.roolback(Some(savepoint))?;
+        self.release_savepoint(savepoint)?;
+        self.connection.events.truncate(self.events.get())?;
+
+        self.check_status(libpq::transaction::Status::InTrans)?;
+
+        Ok(outcome)
+    }
+
+    /*
+     * The transaction may have been rollbacked by the watchdog, forgets its
+     * savepoint and its deferred events.
+     */
+    fn is_aborted(&self) -> crate::Result<bool> {
+        if self.is_in_transaction()? || !self.connection.is_aborted()? {
+            return Ok(false);
+        }
+
+        self.savepoint.borrow_mut().take();
+        self.connection.events.clear()?;
+
+        Ok(true)
+    }
+
+    fn check_status(&self, expected: libpq::transaction::Status) -> crate::Result {
+        let status = self.connection.transaction_status()?;
+
+        if status == expected {
+            Ok(())
+        } else {
+            Err(crate::Error::TransactionStatus(status))
+        }
     }
 
     /**
      * Set a savepoint in a transaction.
      */
-    pub fn set_save_point(&self, name: &str) -> crate::Result {
+    pub fn savepoint(&self, name: &str) -> crate::Result {
         let query = format!("savepoint {}", name);
 
         self.exec(&query)
     }
 
+    /**
+     * Alias of [`savepoint`].
+     *
+     * [`savepoint`]: Self::savepoint
+     */
+    pub fn set_save_point(&self, name: &str) -> crate::Result {
+        self.savepoint(name)
+    }
+
+    /**
+     * Rollback to the savepoint `name`, which stays defined.
+     */
+    pub fn rollback_to(&self, name: &str) -> crate::Result<Outcome> {
+        self.roolback(Some(name))
+    }
+
     /**
      * Drop a savepoint.
      */
@@ -134,6 +313,24 @@ impl<'c> Transaction<'c> {
         self.exec(&query)
     }
 
+    /**
+     * Takes an advisory lock, released at the end of the transaction. Waits
+     * until it’s available.
+     */
+    pub fn advisory_lock(&self, key: i64) -> crate::Result {
+        self.connection
+            .query_one::<()>("select pg_advisory_xact_lock($1)", &[&key])
+    }
+
+    /**
+     * Takes an advisory lock released at the end of the transaction, if it’s
+     * available.
+     */
+    pub fn try_advisory_lock(&self, key: i64) -> crate::Result<bool> {
+        self.connection
+            .query_one("select pg_try_advisory_xact_lock($1)", &[&key])
+    }
+
     /**
      * Tell if a transaction is open or not.
      */
@@ -219,7 +416,216 @@ impl<'c> Transaction<'c> {
         self.exec(&query)
     }
 
+    /**
+     * Exports the snapshot of the transaction, for other connections to see
+     * the same data with [`Connection::set_transaction_snapshot`]. The
+     * identifier is valid until the end of this transaction.
+     *
+     * See <https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-SNAPSHOT-SYNCHRONIZATION>
+     *
+     * [`Connection::set_transaction_snapshot`]: crate::Connection::set_transaction_snapshot
+     */
+    pub fn export_snapshot(&self) -> crate::Result<String> {
+        self.connection
+            .query_one("select pg_export_snapshot()", &[])
+    }
+
     fn exec(&self, query: &str) -> crate::Result {
         self.connection.execute(query).map(|_| ())
     }
 }
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn commit() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let transaction = conn.transaction();
+
+        transaction.start()?;
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn commit_aborted() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let transaction = conn.transaction();
+
+        transaction.start()?;
+        assert!(conn.execute("select 1 / 0").is_err());
+        assert!(matches!(
+            conn.query::<i32>("select $1", &[&1]),
+            Err(crate::Error::TransactionAborted)
+        ));
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::RolledBack
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn commit_failed() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table commit_failed (id int unique deferrable initially deferred)",
+        )?;
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        conn.execute("insert into commit_failed values (1), (1)")?;
+        assert!(matches!(transaction.commit(), Err(crate::Error::Sql(_))));
+        assert!(!transaction.is_in_transaction()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rollback_savepoint() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let transaction = conn.transaction();
+
+        transaction.start()?;
+        transaction.set_save_point("savepoint")?;
+        assert_eq!(
+            transaction.roolback(Some("savepoint"))?,
+            crate::transaction::Outcome::RolledBack
+        );
+        assert!(transaction.roolback(Some("unknow")).is_err());
+        assert_eq!(
+            transaction.roolback(None)?,
+            crate::transaction::Outcome::RolledBack
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn savepoint() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table savepoint (id int)")?;
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        conn.execute("insert into savepoint values (1)")?;
+        transaction.savepoint("first")?;
+        conn.execute("insert into savepoint values (2)")?;
+        assert_eq!(
+            transaction.rollback_to("first")?,
+            crate::transaction::Outcome::RolledBack
+        );
+        conn.execute("insert into savepoint values (3)")?;
+        transaction.release_savepoint("first")?;
+        assert!(transaction.release_savepoint("first").is_err());
+        assert_eq!(
+            transaction.roolback(None)?,
+            crate::transaction::Outcome::RolledBack
+        );
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        conn.execute("insert into savepoint values (1)")?;
+        transaction.savepoint("first")?;
+        conn.execute("insert into savepoint values (2)")?;
+        assert_eq!(
+            transaction.rollback_to("first")?,
+            crate::transaction::Outcome::RolledBack
+        );
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+        assert_eq!(
+            conn.query::<i32>("select id from savepoint", &[])?
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot() -> crate::Result {
+        use crate::transaction::IsolationLevel;
+
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "drop table if exists transaction_snapshot;
+            create table transaction_snapshot (id int);
+            insert into transaction_snapshot values (1);",
+        )?;
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        transaction.set_isolation_level(IsolationLevel::RepeatableRead)?;
+        let id = transaction.export_snapshot()?;
+
+        let other = crate::test::new_conn()?;
+        other.execute("insert into transaction_snapshot values (2)")?;
+
+        let worker = crate::test::new_conn()?;
+        let worker_transaction = worker.transaction();
+        worker_transaction.start()?;
+        worker_transaction.set_isolation_level(IsolationLevel::RepeatableRead)?;
+        worker.set_transaction_snapshot(&id)?;
+        assert_eq!(
+            worker.query_one::<i64>("select count(*) from transaction_snapshot", &[])?,
+            1
+        );
+        assert_eq!(
+            worker_transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+
+        conn.execute("drop table transaction_snapshot")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute("create temporary table nested (id int)")?;
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        assert!(!transaction.is_nested());
+        conn.execute("insert into nested values (1)")?;
+
+        let nested = conn.transaction();
+        nested.start()?;
+        assert!(nested.is_nested());
+        conn.execute("insert into nested values (2)")?;
+        assert_eq!(
+            nested.roolback(None)?,
+            crate::transaction::Outcome::RolledBack
+        );
+
+        let nested = conn.transaction();
+        nested.start()?;
+        assert!(conn.execute("select 1 / 0").is_err());
+        assert_eq!(nested.commit()?, crate::transaction::Outcome::RolledBack);
+
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+        assert_eq!(
+            conn.query_one::<i64>("select count(*) from nested", &[])?,
+            1
+        );
+
+        Ok(())
+    }
+}
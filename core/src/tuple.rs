@@ -4,12 +4,92 @@
 #[derive(Clone, Debug)]
 pub struct Tuple<'a> {
     result: &'a libpq::Result,
+    types: Option<&'a std::sync::Arc<crate::pq::Registry>>,
     index: usize,
+    prefix: Option<String>,
 }
 
 impl<'a> Tuple<'a> {
-    pub(crate) fn from(result: &'a libpq::Result, index: usize) -> Self {
-        Self { result, index }
+    pub(crate) fn from(
+        result: &'a libpq::Result,
+        types: Option<&'a std::sync::Arc<crate::pq::Registry>>,
+        index: usize,
+    ) -> Self {
+        Self {
+            result,
+            types,
+            index,
+            prefix: None,
+        }
+    }
+
+    /**
+     * View of the tuple where the field `name` is read from the
+     * `prefix.name` column, as generated by [`Projection::prefix`]. Prefixes
+     * are cumulated: `tuple.prefixed("a").prefixed("b")` reads `a.b.name`.
+     *
+     * Only the name lookups are affected, not the positional ones.
+     *
+     * [`Projection::prefix`]: crate::Projection::prefix
+     */
+    pub fn prefixed(&self, prefix: &str) -> Self {
+        let prefix = match &self.prefix {
+            Some(parent) => format!("{}.{}", parent, prefix),
+            None => prefix.to_string(),
+        };
+
+        Self {
+            prefix: Some(prefix),
+            ..self.clone()
+        }
+    }
+
+    /**
+     * Hydrate an entity from the fields prefixed by `prefix`, to compose
+     * several entities from a JOIN query.
+     *
+     * ```no_run
+     * # #[derive(elephantry::Entity)]
+     * # struct Order { id: i32 }
+     * # #[derive(elephantry::Entity)]
+     * # struct Customer { id: i32 }
+     * # let connection = elephantry::Connection::new("")?;
+     * # let query = "";
+     * let mut rows: Vec<(Order, Customer)> = Vec::new();
+     *
+     * for tuple in &connection.execute(query)? {
+     *     rows.push((tuple.try_entity("order")?, tuple.try_entity("customer")?));
+     * }
+     * # Ok::<(), elephantry::Error>(())
+     * ```
+     */
+    pub fn try_entity<E: crate::Entity>(&self, prefix: &str) -> crate::Result<E> {
+        E::try_from_tuple(&self.prefixed(prefix))
+    }
+
+    /**
+     * Like [`try_entity`], but returns `None` when all the fields prefixed by
+     * `prefix` are null, for the entities of an outer JOIN without match.
+     *
+     * [`try_entity`]: Self::try_entity
+     */
+    pub fn try_optional_entity<E: crate::Entity>(&self, prefix: &str) -> crate::Result<Option<E>> {
+        let tuple = self.prefixed(prefix);
+        let prefix = format!("{}.", tuple.prefix.as_deref().unwrap_or_default());
+
+        let fields = (0..self.len())
+            .filter(|n| {
+                self.field_name(*n)
+                    .map(|x| x.starts_with(&prefix))
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        if !fields.is_empty() && fields.iter().all(|n| self.raw(*n).2.is_none()) {
+            return Ok(None);
+        }
+
+        E::try_from_tuple(&tuple).map(Some)
     }
 
     /**
@@ -28,19 +108,60 @@ impl<'a> Tuple<'a> {
     }
 
     /**
-     * Retreive the value of field `name` of the tuple, or `None` if `n` is
-     * greater than or equal to the length of the tuple.
+     * Retreive the value of field `name` of the tuple.
+     *
+     * Returns [`Error::MissingField`] if the tuple doesn’t have this field,
+     * or the conversion error.
+     *
+     * [`Error::MissingField`]: crate::Error::MissingField
      */
     pub fn try_get<T>(&self, name: &str) -> crate::Result<T>
     where
         T: crate::FromSql,
     {
-        let n = match self.result.field_number(name) {
+        let name = match &self.prefix {
+            Some(prefix) => std::borrow::Cow::Owned(format!("{}.{}", prefix, name)),
+            None => std::borrow::Cow::Borrowed(name),
+        };
+
+        let n = match self.result.field_number(&name) {
             Some(n) => n,
-            None => return Err(crate::Error::MissingField(name.to_string())),
+            None => return Err(crate::Error::MissingField(name.into_owned())),
         };
 
-        self.try_nth(n)
+        self.try_get_by_index(n)
+    }
+
+    /**
+     * Retreive the value of the field at position `n`.
+     *
+     * Returns [`Error::MissingField`] if `n` is greater than or equal to the
+     * length of the tuple, or the conversion error.
+     *
+     * [`Error::MissingField`]: crate::Error::MissingField
+     */
+    pub fn try_get_by_index<T>(&self, n: usize) -> crate::Result<T>
+    where
+        T: crate::FromSql,
+    {
+        if n >= self.len() {
+            return Err(crate::Error::MissingField(format!("#{}", n)));
+        }
+
+        let (ty, format, value) = self.raw(n);
+
+        crate::pq::registry::with(self.types, || crate::FromSql::from_sql(&ty, format, value))
+    }
+
+    /**
+     * Type, format and raw value of the field at position `n`.
+     */
+    pub(crate) fn raw(&self, n: usize) -> (crate::pq::Type, crate::pq::Format, Option<&'a [u8]>) {
+        (
+            self.field_type(n),
+            self.result.field_format(n),
+            self.result.value(self.index, n),
+        )
     }
 
     /**
@@ -59,18 +180,15 @@ impl<'a> Tuple<'a> {
     }
 
     /**
-     * Retreive the nth field, or `None` if `n` is greater than or equal to the
-     * length of the tuple.
+     * Retreive the nth field, same as [`try_get_by_index`].
+     *
+     * [`try_get_by_index`]: Self::try_get_by_index
      */
     pub fn try_nth<T>(&self, n: usize) -> crate::Result<T>
     where
         T: crate::FromSql,
     {
-        let ty = self.field_type(n);
-        let format = self.result.field_format(n);
-        let value = self.result.value(self.index, n);
-
-        crate::FromSql::from_sql(&ty, format, value)
+        self.try_get_by_index(n)
     }
 
     /**
@@ -95,19 +213,38 @@ impl<'a> Tuple<'a> {
         self.result.field_name(n)
     }
 
-    fn field_type(&self, n: usize) -> crate::pq::Type {
-        use std::convert::TryFrom;
-
+    pub(crate) fn field_type(&self, n: usize) -> crate::pq::Type {
         let oid = self.result.field_type(n);
 
-        match crate::pq::Type::try_from(oid) {
-            Ok(ty) => ty,
-            Err(_) => crate::pq::Type {
-                oid,
-                name: "unknow",
-                descr: "Unknow type",
-                kind: libpq::types::Kind::Composite,
-            },
-        }
+        crate::pq::registry::with(self.types, || crate::pq::registry::lookup(oid))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn try_get() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        let results = conn.execute("select 1::int4 as id, null::text as name")?;
+        let tuple = results.get(0);
+
+        assert_eq!(tuple.try_get::<i32>("id")?, 1);
+        assert_eq!(tuple.try_get_by_index::<i32>(0)?, 1);
+        assert_eq!(tuple.try_get_by_index::<Option<String>>(1)?, None);
+
+        assert!(matches!(
+            tuple.try_get::<i32>("unknow"),
+            Err(crate::Error::MissingField(field)) if field == "unknow"
+        ));
+        assert!(matches!(
+            tuple.try_get_by_index::<i32>(2),
+            Err(crate::Error::MissingField(_))
+        ));
+        assert!(matches!(
+            tuple.try_get::<String>("name"),
+            Err(crate::Error::NotNull)
+        ));
+
+        Ok(())
     }
 }
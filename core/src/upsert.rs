@@ -0,0 +1,292 @@
+/**
+ * Conflict target of [`OnConflict`].
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConflictTarget<'a> {
+    /**
+     * The primary key of the relation.
+     */
+    PrimaryKey,
+    /**
+     * Columns, or expressions, of an unique index. Set `where` to the
+     * predicate of a partial unique index.
+     */
+    Columns {
+        columns: &'a [&'a str],
+        r#where: Option<&'a str>,
+    },
+    /**
+     * Unique or exclusion constraint `name`, quoted as an identifier.
+     */
+    Constraint(&'a str),
+}
+
+impl<'a> ConflictTarget<'a> {
+    fn to_sql<'m, M>(&self) -> String
+    where
+        M: crate::Model<'m>,
+    {
+        use crate::Structure;
+
+        match self {
+            Self::PrimaryKey => format!("({})", M::Structure::primary_key().join(", ")),
+            Self::Columns { columns, r#where } => {
+                let mut sql = format!("({})", columns.join(", "));
+
+                if let Some(r#where) = r#where {
+                    sql.push_str(&format!(" where {}", r#where));
+                }
+
+                sql
+            }
+            Self::Constraint(name) => {
+                format!("on constraint {}", crate::identifier::quote(name))
+            }
+        }
+    }
+
+    /**
+     * Columns not updated by [`OnConflict::DoUpdateOn`].
+     */
+    fn columns<'m, M>(&self) -> &'a [&'a str]
+    where
+        M: crate::Model<'m>,
+    {
+        use crate::Structure;
+
+        match self {
+            Self::Columns { columns, .. } => columns,
+            Self::PrimaryKey | Self::Constraint(_) => M::Structure::primary_key(),
+        }
+    }
+}
+
+/**
+ * Conflict handling of [`Connection::upsert_many`].
+ *
+ * See [ON CONFLICT clause](https://www.postgresql.org/docs/current/sql-insert.html#SQL-ON-CONFLICT).
+ *
+ * [`Connection::upsert_many`]: crate::Connection::upsert_many
+ */
+pub enum OnConflict<'a> {
+    /**
+     * Skips conflicting rows.
+     */
+    DoNothing,
+    /**
+     * Updates conflicting rows on the primary key with the proposed values.
+     */
+    DoUpdate,
+    /**
+     * Alternative `action` on constraint violation on `target`.
+     */
+    Custom { target: &'a str, action: &'a str },
+    /**
+     * Skips rows conflicting on `target`.
+     */
+    DoNothingOn(ConflictTarget<'a>),
+    /**
+     * Updates rows conflicting on `target` with the proposed values, except
+     * the target columns (the primary key for a constraint). Set `where` to
+     * only update the existing rows matching this condition.
+     */
+    DoUpdateOn {
+        target: ConflictTarget<'a>,
+        r#where: Option<&'a str>,
+    },
+}
+
+impl<'a> OnConflict<'a> {
+    pub(crate) fn to_sql<'m, M>(&self, columns: &[&str]) -> String
+    where
+        M: crate::Model<'m>,
+    {
+        match self {
+            Self::DoNothing => "on conflict do nothing".to_string(),
+            Self::DoUpdate => Self::DoUpdateOn {
+                target: ConflictTarget::PrimaryKey,
+                r#where: None,
+            }
+            .to_sql::<M>(columns),
+            Self::Custom { target, action } => format!("on conflict {} do {}", target, action),
+            Self::DoNothingOn(target) => {
+                format!("on conflict {} do nothing", target.to_sql::<M>())
+            }
+            Self::DoUpdateOn { target, r#where } => {
+                let excluded = target.columns::<M>();
+
+                let mut set = columns
+                    .iter()
+                    .filter(|x| !excluded.contains(x))
+                    .collect::<Vec<_>>();
+
+                // Updates nothing but returns the row
+                if set.is_empty() {
+                    set = excluded.iter().collect();
+                }
+
+                let set = set
+                    .iter()
+                    .map(|x| format!("{} = excluded.{}", x, x))
+                    .collect::<Vec<_>>();
+
+                let mut sql = format!(
+                    "on conflict {} do update set {}",
+                    target.to_sql::<M>(),
+                    set.join(", ")
+                );
+
+                if let Some(r#where) = r#where {
+                    sql.push_str(&format!(" where {}", r#where));
+                }
+
+                sql
+            }
+        }
+    }
+}
+
+/**
+ * Result of [`Connection::upsert_many`] for each row.
+ *
+ * [`Connection::upsert_many`]: crate::Connection::upsert_many
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum Upsert<E> {
+    Inserted(E),
+    Updated(E),
+}
+
+impl<E> Upsert<E> {
+    /**
+     * Returns `true` if the row was inserted.
+     */
+    pub fn is_inserted(&self) -> bool {
+        matches!(self, Self::Inserted(_))
+    }
+
+    /**
+     * Returns `true` if the row was updated.
+     */
+    pub fn is_updated(&self) -> bool {
+        matches!(self, Self::Updated(_))
+    }
+
+    /**
+     * Returns the entity with values from database.
+     */
+    pub fn into_inner(self) -> E {
+        match self {
+            Self::Inserted(entity) | Self::Updated(entity) => entity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn do_update() {
+        let on_conflict = crate::OnConflict::DoUpdate;
+
+        assert_eq!(
+            on_conflict.to_sql::<crate::test::Model<'_>>(&["employee_id", "first_name"]),
+            "on conflict (employee_id) do update set first_name = excluded.first_name"
+        );
+    }
+
+    #[test]
+    fn do_nothing_on() {
+        let on_conflict = crate::OnConflict::DoNothingOn(crate::ConflictTarget::Constraint(
+            "entity_\"name\"_key",
+        ));
+
+        assert_eq!(
+            on_conflict.to_sql::<crate::test::Model<'_>>(&["employee_id", "first_name"]),
+            "on conflict on constraint \"entity_\"\"name\"\"_key\" do nothing"
+        );
+    }
+
+    #[test]
+    fn do_update_on() {
+        let on_conflict = crate::OnConflict::DoUpdateOn {
+            target: crate::ConflictTarget::Columns {
+                columns: &["first_name"],
+                r#where: Some("last_name is not null"),
+            },
+            r#where: Some("entity.last_name <> excluded.last_name"),
+        };
+
+        assert_eq!(
+            on_conflict.to_sql::<crate::test::Model<'_>>(&["employee_id", "first_name", "last_name"]),
+            "on conflict (first_name) where last_name is not null do update set employee_id = excluded.employee_id, last_name = excluded.last_name where entity.last_name <> excluded.last_name"
+        );
+    }
+
+    #[test]
+    fn upsert_partial_index() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, false)?;
+        conn.execute("create unique index on entity (first_name) where last_name is not null")?;
+
+        let entity = |id, last_name: &str| crate::test::Entity {
+            id,
+            first_name: "Ada".to_string(),
+            last_name: last_name.to_string(),
+        };
+
+        let on_conflict = || crate::OnConflict::DoUpdateOn {
+            target: crate::ConflictTarget::Columns {
+                columns: &["first_name"],
+                r#where: Some("last_name is not null"),
+            },
+            r#where: None,
+        };
+
+        let upserted =
+            conn.upsert_many::<crate::test::Model<'_>>(&[entity(1, "Byron")], on_conflict())?;
+        assert!(upserted[0].is_inserted());
+
+        let upserted =
+            conn.upsert_many::<crate::test::Model<'_>>(&[entity(2, "Lovelace")], on_conflict())?;
+        let upserted = upserted.into_iter().next().unwrap();
+        assert!(upserted.is_updated());
+        assert_eq!(upserted.into_inner().last_name, "Lovelace");
+
+        Ok(())
+    }
+
+    #[test]
+    fn upsert_chunks() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        crate::test::create_entity_table(&conn, true)?;
+
+        let entity = |id| crate::test::Entity {
+            id,
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+        };
+
+        conn.upsert_many::<crate::test::Model<'_>>(&[entity(1)], crate::OnConflict::DoNothing)?;
+
+        // Three parameters per row: more than the 65535 parameters of a query
+        let entities = (1..=30_000).map(entity).collect::<Vec<_>>();
+        let upserted =
+            conn.upsert_many::<crate::test::Model<'_>>(&entities, crate::OnConflict::DoUpdate)?;
+
+        assert_eq!(upserted.len(), 30_000);
+        assert!(upserted[0].is_updated());
+        assert!(upserted[1..].iter().all(crate::Upsert::is_inserted));
+
+        Ok(())
+    }
+
+    #[test]
+    fn do_update_pk_only() {
+        let on_conflict = crate::OnConflict::DoUpdate;
+
+        assert_eq!(
+            on_conflict.to_sql::<crate::test::Model<'_>>(&["employee_id"]),
+            "on conflict (employee_id) do update set employee_id = excluded.employee_id"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+/**
+ * Field value of any type, decoded from its SQL type.
+ *
+ * `numeric`, date and time values are exposed as strings, in the postgresql
+ * output format. Values of unsupported types are kept as bytes.
+ */
+#[derive(Clone, Debug)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U32(u32),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+}
+
+impl crate::FromSql for Value {
+    fn from_text(ty: &crate::pq::Type, raw: Option<&str>) -> crate::Result<Self> {
+        Self::from_sql(ty, crate::pq::Format::Text, raw.map(str::as_bytes))
+    }
+
+    fn from_binary(ty: &crate::pq::Type, raw: Option<&[u8]>) -> crate::Result<Self> {
+        Self::from_sql(ty, crate::pq::Format::Binary, raw)
+    }
+
+    fn from_sql(
+        ty: &crate::pq::Type,
+        format: crate::pq::Format,
+        raw: Option<&[u8]>,
+    ) -> crate::Result<Self> {
+        use crate::pq::types;
+
+        fn decode<T: crate::FromSql>(
+            ty: &crate::pq::Type,
+            format: crate::pq::Format,
+            raw: Option<&[u8]>,
+        ) -> crate::Result<T> {
+            T::from_sql(ty, format, raw)
+        }
+
+        if raw.is_none() {
+            return Ok(Self::Null);
+        }
+
+        let value = match ty.oid {
+            oid if oid == types::BOOL.oid => Self::Bool(decode(ty, format, raw)?),
+            oid if oid == types::INT2.oid => Self::I16(decode(ty, format, raw)?),
+            oid if oid == types::INT4.oid => Self::I32(decode(ty, format, raw)?),
+            oid if oid == types::INT8.oid => Self::I64(decode(ty, format, raw)?),
+            oid if oid == types::OID.oid => Self::U32(decode(ty, format, raw)?),
+            oid if oid == types::FLOAT4.oid => Self::F32(decode(ty, format, raw)?),
+            oid if oid == types::FLOAT8.oid => Self::F64(decode(ty, format, raw)?),
+            oid if oid == types::BYTEA.oid => {
+                Self::Bytes(decode::<crate::Bytea>(ty, format, raw)?.to_vec())
+            }
+            #[cfg(feature = "numeric")]
+            oid if oid == types::NUMERIC.oid => {
+                Self::String(decode::<bigdecimal::BigDecimal>(ty, format, raw)?.to_string())
+            }
+            #[cfg(feature = "date")]
+            oid if oid == types::DATE.oid => Self::String(
+                decode::<chrono::NaiveDate>(ty, format, raw)?
+                    .format("%F")
+                    .to_string(),
+            ),
+            #[cfg(feature = "date")]
+            oid if oid == types::TIMESTAMP.oid => Self::String(
+                decode::<chrono::NaiveDateTime>(ty, format, raw)?
+                    .format("%FT%T%.f")
+                    .to_string(),
+            ),
+            #[cfg(feature = "date")]
+            oid if oid == types::TIMESTAMPTZ.oid => {
+                Self::String(decode::<chrono::DateTime<chrono::Utc>>(ty, format, raw)?.to_rfc3339())
+            }
+            #[cfg(feature = "uuid")]
+            oid if oid == types::UUID.oid => {
+                Self::String(decode::<uuid::Uuid>(ty, format, raw)?.to_string())
+            }
+            oid if oid == types::JSON.oid || oid == types::JSONB.oid => {
+                let mut raw = raw;
+
+                // Binary jsonb is prefixed by its version
+                if oid == types::JSONB.oid && format == crate::pq::Format::Binary {
+                    raw = raw.map(|x| x.strip_prefix(&[1]).unwrap_or(x));
+                }
+
+                #[cfg(feature = "json")]
+                let value = Self::Json(decode(ty, format, raw)?);
+                #[cfg(not(feature = "json"))]
+                let value = Self::String(decode(ty, format, raw)?);
+
+                value
+            }
+            // Text arrays aren’t parsed, they are kept as string
+            _ => match (&ty.kind, format) {
+                (_, crate::pq::Format::Text) | (libpq::types::Kind::String, _) => {
+                    Self::String(decode(ty, format, raw)?)
+                }
+                (libpq::types::Kind::Array(_), _) => Self::Array(decode(ty, format, raw)?),
+                // Unknown types, like enums, are text if they look like text
+                _ => match decode::<String>(ty, format, raw) {
+                    Ok(s) if !s.contains(|c: char| c.is_control() && !c.is_whitespace()) => {
+                        Self::String(s)
+                    }
+                    _ => Self::Bytes(crate::not_null(raw)?.to_vec()),
+                },
+            },
+        };
+
+        Ok(value)
+    }
+}
+
+impl Value {
+    /**
+     * Text representation of the value, `None` for null.
+     *
+     * Arrays use the postgresql array literal, bytes the hex format of
+     * `bytea`.
+     */
+    pub fn to_text(&self) -> crate::Result<Option<String>> {
+        let text = match self {
+            Self::Null => return Ok(None),
+            Self::Bool(v) => v.to_string(),
+            Self::I16(v) => v.to_string(),
+            Self::I32(v) => v.to_string(),
+            Self::I64(v) => v.to_string(),
+            Self::U32(v) => v.to_string(),
+            Self::F32(v) => v.to_string(),
+            Self::F64(v) => v.to_string(),
+            Self::String(v) => v.clone(),
+            Self::Bytes(v) => return crate::ToSql::to_text(&crate::Bytea::from(v.clone())),
+            Self::Array(v) => {
+                let mut elements = Vec::new();
+
+                for x in v {
+                    let element = match (x, x.to_text()?) {
+                        (_, None) => "NULL".to_string(),
+                        (Self::String(_), Some(s)) | (Self::Bytes(_), Some(s)) => {
+                            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+                        }
+                        (_, Some(s)) => s,
+                    };
+
+                    elements.push(element);
+                }
+
+                format!("{{{}}}", elements.join(","))
+            }
+            #[cfg(feature = "json")]
+            Self::Json(v) => v.to_string(),
+        };
+
+        Ok(Some(text))
+    }
+
+    /**
+     * Json representation of the value, bytes are exported as their text
+     * representation.
+     */
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> crate::Result<serde_json::Value> {
+        use serde_json::Value as Json;
+
+        let json = match self {
+            Self::Null => Json::Null,
+            Self::Bool(v) => Json::from(*v),
+            Self::I16(v) => Json::from(*v),
+            Self::I32(v) => Json::from(*v),
+            Self::I64(v) => Json::from(*v),
+            Self::U32(v) => Json::from(*v),
+            Self::F32(v) => Json::from(*v),
+            Self::F64(v) => Json::from(*v),
+            Self::String(v) => Json::from(v.as_str()),
+            Self::Bytes(_) => self.to_text()?.map(Json::from).unwrap_or_default(),
+            Self::Array(v) => v
+                .iter()
+                .map(Self::to_json)
+                .collect::<crate::Result<Vec<_>>>()?
+                .into(),
+            Self::Json(v) => v.clone(),
+        };
+
+        Ok(json)
+    }
+}
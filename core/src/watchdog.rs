@@ -0,0 +1,233 @@
+/**
+ * What the [`TransactionWatchdog`] does when a threshold is exceeded.
+ */
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WatchdogAction {
+    /** Logs a warning, once per transaction for its duration. */
+    #[default]
+    Warn,
+    /**
+     * Rollbacks the transaction and returns [`Error::LongTransaction`] or
+     * [`Error::IdleInTransaction`] instead of executing the query.
+     *
+     * [`Error::LongTransaction`]: crate::Error::LongTransaction
+     * [`Error::IdleInTransaction`]: crate::Error::IdleInTransaction
+     */
+    Abort,
+}
+
+/**
+ * Watchdog of the transactions kept open too long, which hold locks and
+ * prevent vacuum from cleaning dead rows.
+ *
+ * Set by [`Connection::set_transaction_watchdog`]. It’s checked before each
+ * query: a transaction forgotten without any query isn’t noticed, use the
+ * server side `idle_in_transaction_session_timeout` setting for this case.
+ *
+ * [`Connection::set_transaction_watchdog`]: crate::Connection::set_transaction_watchdog
+ */
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionWatchdog {
+    /** Maximum duration of a transaction. */
+    pub max_duration: Option<std::time::Duration>,
+    /** Maximum time between two queries of a transaction. */
+    pub max_idle: Option<std::time::Duration>,
+    pub action: WatchdogAction,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Watch {
+    policy: Option<TransactionWatchdog>,
+    started: Option<std::time::Instant>,
+    last: Option<std::time::Instant>,
+    warned: bool,
+    aborted: bool,
+}
+
+impl Watch {
+    pub(crate) fn set_policy(&mut self, policy: Option<TransactionWatchdog>) {
+        self.policy = policy;
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.policy.is_some()
+    }
+
+    /**
+     * Has the last transaction been rollbacked by the watchdog?
+     */
+    pub(crate) fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /**
+     * Records the start of a new transaction.
+     */
+    pub(crate) fn begin(&mut self) {
+        self.aborted = false;
+    }
+
+    /**
+     * Records the end of a query.
+     */
+    pub(crate) fn touch(&mut self, in_transaction: bool) {
+        let now = std::time::Instant::now();
+
+        if in_transaction {
+            self.started.get_or_insert(now);
+            self.last = Some(now);
+        } else {
+            self.started = None;
+            self.last = None;
+            self.warned = false;
+        }
+    }
+
+    /**
+     * Checks the current transaction before a query, returns the error to
+     * abort it with.
+     */
+    pub(crate) fn check(&mut self) -> Option<crate::Error> {
+        let policy = self.policy.as_ref()?;
+        let now = std::time::Instant::now();
+
+        let idle = now - self.last?;
+        let duration = now - self.started?;
+
+        let error = match (policy.max_idle, policy.max_duration) {
+            (Some(max), _) if idle > max => {
+                if policy.action == WatchdogAction::Warn {
+                    log::warn!("Transaction idle for {:?} (max {:?})", idle, max);
+                }
+
+                crate::Error::IdleInTransaction(idle)
+            }
+            (_, Some(max)) if duration > max => {
+                if policy.action == WatchdogAction::Warn && !self.warned {
+                    log::warn!("Transaction open for {:?} (max {:?})", duration, max);
+                    self.warned = true;
+                }
+
+                crate::Error::LongTransaction(duration)
+            }
+            _ => return None,
+        };
+
+        match policy.action {
+            WatchdogAction::Warn => None,
+            WatchdogAction::Abort => {
+                self.aborted = true;
+
+                Some(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn check() {
+        let mut watch = super::Watch::default();
+        watch.touch(true);
+        assert!(watch.check().is_none());
+
+        watch.set_policy(Some(crate::TransactionWatchdog {
+            max_duration: Some(std::time::Duration::from_millis(50)),
+            max_idle: None,
+            action: crate::WatchdogAction::Abort,
+        }));
+        assert!(watch.check().is_none());
+
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        assert!(matches!(
+            watch.check(),
+            Some(crate::Error::LongTransaction(_))
+        ));
+
+        watch.touch(false);
+        assert!(watch.check().is_none());
+        assert!(watch.is_aborted());
+
+        watch.begin();
+        assert!(!watch.is_aborted());
+    }
+
+    #[test]
+    fn check_warn() {
+        let mut watch = super::Watch::default();
+        watch.set_policy(Some(crate::TransactionWatchdog {
+            max_duration: Some(std::time::Duration::from_millis(10)),
+            ..Default::default()
+        }));
+        watch.touch(true);
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert!(watch.check().is_none());
+        assert!(watch.warned);
+        assert!(!watch.is_aborted());
+
+        watch.touch(false);
+        assert!(!watch.warned);
+    }
+
+    #[test]
+    fn abort() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.set_transaction_watchdog(Some(crate::TransactionWatchdog {
+            max_duration: None,
+            max_idle: Some(std::time::Duration::from_millis(50)),
+            action: crate::WatchdogAction::Abort,
+        }))?;
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        let nested = conn.transaction();
+        nested.start()?;
+        conn.execute("select 1")?;
+
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        assert!(matches!(
+            conn.execute("select 1"),
+            Err(crate::Error::IdleInTransaction(_))
+        ));
+        assert!(!transaction.is_in_transaction()?);
+
+        assert_eq!(nested.commit()?, crate::transaction::Outcome::RolledBack);
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::RolledBack
+        );
+
+        conn.execute("select 1")?;
+
+        transaction.start()?;
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn warn() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.set_transaction_watchdog(Some(crate::TransactionWatchdog {
+            max_duration: Some(std::time::Duration::from_millis(10)),
+            ..Default::default()
+        }))?;
+
+        let transaction = conn.transaction();
+        transaction.start()?;
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        conn.execute("select 1")?;
+        assert!(transaction.is_in_transaction()?);
+        assert_eq!(
+            transaction.commit()?,
+            crate::transaction::Outcome::Committed
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+/**
+ * Suggested HTTP response of an [`Error`], whatever the web framework.
+ *
+ * The message is generic and safe to send to clients, unlike the error
+ * itself which can contain values or the schema.
+ *
+ * ```no_run
+ * # let connection = elephantry::Connection::new("")?;
+ * if let Err(error) = connection.execute("insert into employee (id) values (1)") {
+ *     let response = elephantry::web::HttpError::from(&error);
+ *
+ *     eprintln!("{}", error);
+ *     // respond with response.status and response.message
+ * }
+ * # Ok::<(), elephantry::Error>(())
+ * ```
+ *
+ * [`Error`]: crate::Error
+ */
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HttpError {
+    pub status: u16,
+    pub message: &'static str,
+    /** Delay to send in a `Retry-After` header, for transient errors. */
+    pub retry_after: Option<std::time::Duration>,
+}
+
+impl HttpError {
+    fn new(status: u16, message: &'static str) -> Self {
+        Self {
+            status,
+            message,
+            retry_after: None,
+        }
+    }
+
+    fn retry(status: u16, message: &'static str) -> Self {
+        Self {
+            status,
+            message,
+            retry_after: Some(std::time::Duration::from_secs(1)),
+        }
+    }
+
+    fn from_state(state: &crate::pq::State) -> Self {
+        use crate::pq::state;
+
+        match state.code {
+            code if code == state::UNIQUE_VIOLATION.code
+                || code == state::EXCLUSION_VIOLATION.code =>
+            {
+                Self::new(409, "Resource already exists")
+            }
+            code if code == state::FOREIGN_KEY_VIOLATION.code => Self::new(
+                409,
+                "Resource is referenced or references a missing resource",
+            ),
+            code if code == state::INSUFFICIENT_PRIVILEGE.code => Self::new(403, "Forbidden"),
+            code if code == state::T_R_SERIALIZATION_FAILURE.code
+                || code == state::T_R_DEADLOCK_DETECTED.code
+                || code == state::LOCK_NOT_AVAILABLE.code =>
+            {
+                Self::retry(503, "Conflict with a concurrent request, try again")
+            }
+            code if code == state::QUERY_CANCELED.code => {
+                Self::retry(503, "Request took too long, try again")
+            }
+            // Integrity constraint violation and data exception
+            code if code.starts_with("23") || code.starts_with("22") => {
+                Self::new(422, "Invalid value")
+            }
+            // Connection exception, insufficient resources, operator intervention
+            code if code.starts_with("08") || code.starts_with("53") || code.starts_with("57") => {
+                Self::retry(503, "Service unavailable, try again")
+            }
+            _ => Self::internal(),
+        }
+    }
+
+    fn internal() -> Self {
+        Self::new(500, "Internal server error")
+    }
+}
+
+impl From<&crate::Error> for HttpError {
+    fn from(error: &crate::Error) -> Self {
+        use crate::Error::*;
+
+        match error {
+            Sql(result) => match result.state() {
+                Some(state) => Self::from_state(&state),
+                None => Self::internal(),
+            },
+            Validation { .. } => Self::new(422, "Invalid value"),
+            Busy | Connect { .. } | Ping(_) | IdleInTransaction(_) | LongTransaction(_) => {
+                Self::retry(503, "Service unavailable, try again")
+            }
+            Timeout(_) => Self::retry(503, "Request took too long, try again"),
+            _ => Self::internal(),
+        }
+    }
+}
+
+impl From<crate::Error> for HttpError {
+    fn from(error: crate::Error) -> Self {
+        Self::from(&error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HttpError;
+
+    #[test]
+    fn sql() -> crate::Result {
+        let conn = crate::test::new_conn()?;
+        conn.execute(
+            "create temporary table web (id int primary key, value int check (value > 0))",
+        )?;
+        conn.execute("insert into web values (1, 1)")?;
+
+        let status = |query: &str| {
+            let error = conn.execute(query).unwrap_err();
+            let http = HttpError::from(&error);
+
+            (http.status, http.retry_after.is_some())
+        };
+
+        assert_eq!(status("insert into web values (1, 1)"), (409, false));
+        assert_eq!(status("insert into web values (2, -1)"), (422, false));
+        assert_eq!(status("select 1 / 0"), (422, false));
+        assert_eq!(status("select * from unknown_table"), (500, false));
+        assert_eq!(
+            status("do $$ begin raise sqlstate '40001'; end $$"),
+            (503, true)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn error() {
+        let http = HttpError::from(crate::Error::Validation {
+            field: "email".to_string(),
+            message: "invalid".to_string(),
+        });
+        assert_eq!(http.status, 422);
+
+        let http = HttpError::from(crate::Error::Timeout(std::time::Duration::from_secs(1)));
+        assert_eq!(http.status, 503);
+        assert!(http.retry_after.is_some());
+
+        assert_eq!(HttpError::from(crate::Error::NotNull).status, 500);
+    }
+}
@@ -21,6 +21,23 @@ impl<'a> Builder<'a> {
         self
     }
 
+    pub fn between(
+        mut self,
+        element: &str,
+        start: &'a dyn crate::ToSql,
+        end: &'a dyn crate::ToSql,
+    ) -> Self {
+        self.0 = crate::Where::new_between(element, start, end);
+
+        self
+    }
+
+    pub fn in_values(mut self, element: &str, values: &'a dyn crate::ToSql) -> Self {
+        self.0 = crate::Where::new_in_values(element, values);
+
+        self
+    }
+
     pub fn group_condition(
         mut self,
         element: &str,
@@ -78,13 +95,13 @@ mod test {
         assert_eq!(
             w.params()
                 .iter()
-                .map(|x| x.to_sql().unwrap())
+                .map(|x| x.to_text().unwrap())
                 .collect::<Vec<_>>(),
             vec![
-                Some(vec![b'1', 0]),
-                Some(vec![b'2', 0]),
-                Some(vec![b'3', 0]),
-                Some(vec![b'4', 0])
+                Some("1".to_string()),
+                Some("2".to_string()),
+                Some("3".to_string()),
+                Some("4".to_string())
             ],
         );
     }
@@ -6,6 +6,23 @@ use builder::Builder;
  * This struct represents a WHERE clause of a SQL statement. It deals with AND &
  * OR operator you can add using handy methods. This allows you to build
  * queries dynamically.
+ *
+ * ```no_run
+ * # mod entity {
+ * # #[derive(elephantry::Entity)]
+ * # #[elephantry(model = "Model", structure = "Structure")]
+ * # pub struct Entity { name: String, age: i32 }
+ * # }
+ * # let connection = elephantry::Connection::new("")?;
+ * let ids = vec![1, 2, 3];
+ * let clause = elephantry::Where::from("name = $*", vec![&"alice"])
+ *     .and(elephantry::Where::new_between("age", &18, &65))
+ *     .or(elephantry::Where::new_in_values("id", &ids));
+ *
+ * let rows = connection.find_where::<entity::Model<'_>>(&clause.to_string(), &clause.params(), None)?;
+ * let count = connection.count_where::<entity::Model<'_>>(&clause.to_string(), &clause.params())?;
+ * # Ok::<(), elephantry::Error>(())
+ * ```
  */
 #[derive(Clone, Default)]
 pub struct Where<'a> {
@@ -47,6 +64,25 @@ impl<'a> Where<'a> {
         Self::new_group_condition(element, "not in", params)
     }
 
+    /**
+     * Create a BETWEEN clause.
+     */
+    pub fn new_between(
+        element: &str,
+        start: &'a dyn crate::ToSql,
+        end: &'a dyn crate::ToSql,
+    ) -> Self {
+        Self::from(&format!("{} between $* and $*", element), vec![start, end])
+    }
+
+    /**
+     * Create an IN clause with a single array parameter, the query text
+     * doesn’t depend on the number of values.
+     */
+    pub fn new_in_values(element: &str, values: &'a dyn crate::ToSql) -> Self {
+        Self::from(&format!("{} = any($*)", element), vec![values])
+    }
+
     pub fn new_group_condition(
         element: &str,
         operation: &str,
@@ -81,6 +117,24 @@ impl<'a> Where<'a> {
         self.add_where(element, params, "or");
     }
 
+    /**
+     * Combines two clauses with the AND operator.
+     */
+    pub fn and(mut self, rhs: Self) -> Self {
+        self &= rhs;
+
+        self
+    }
+
+    /**
+     * Combines two clauses with the OR operator.
+     */
+    pub fn or(mut self, rhs: Self) -> Self {
+        self |= rhs;
+
+        self
+    }
+
     /**
      * You can add a new WHERE clause with your own operator.
      */
@@ -214,6 +268,32 @@ mod test {
         assert_eq!(w.to_string(), "b not in ($*, $*)");
     }
 
+    #[test]
+    fn create_between() {
+        let w = crate::Where::new_between("b", &1, &2);
+        assert_eq!(w.to_string(), "b between $* and $*");
+        assert_eq!(w.params().len(), 2);
+    }
+
+    #[test]
+    fn create_in_values() -> crate::Result {
+        let values = vec![1, 3];
+        let w = crate::Where::new_in_values("x", &values);
+        assert_eq!(w.to_string(), "x = any($*)");
+
+        let conn = crate::test::new_conn()?;
+        let count = conn.query_one::<i64>(
+            &format!(
+                "select count(*) from generate_series(1, 5) x where {}",
+                w.to_string()
+            ),
+            &w.params(),
+        )?;
+        assert_eq!(count, 2);
+
+        Ok(())
+    }
+
     #[test]
     fn empty() {
         let mut w = crate::Where::new();
@@ -239,6 +319,16 @@ mod test {
         assert_eq!(b.params().len(), 3);
     }
 
+    #[test]
+    fn and_or() {
+        let w = crate::Where::from("a", vec![&1])
+            .and(crate::Where::from("b", vec![&2]))
+            .or(crate::Where::from("c", Vec::new()));
+
+        assert_eq!(w.to_string(), "((a and b) or c)");
+        assert_eq!(w.params().len(), 2);
+    }
+
     #[test]
     fn or_where() {
         let w = crate::Where::from("a", vec![&1]);
@@ -255,12 +345,12 @@ mod test {
         assert_eq!(
             b.params()
                 .iter()
-                .map(|x| x.to_sql().unwrap())
+                .map(|x| x.to_text().unwrap())
                 .collect::<Vec<_>>(),
             vec![
-                Some(vec![b'1', 0]),
-                Some(vec![b'2', 0]),
-                Some(vec![b'3', 0])
+                Some("1".to_string()),
+                Some("2".to_string()),
+                Some("3".to_string())
             ],
         );
     }
@@ -277,13 +367,13 @@ mod test {
         assert_eq!(
             w.params()
                 .iter()
-                .map(|x| x.to_sql().unwrap())
+                .map(|x| x.to_text().unwrap())
                 .collect::<Vec<_>>(),
             vec![
-                Some(vec![b'1', 0]),
-                Some(vec![b'2', 0]),
-                Some(vec![b'3', 0]),
-                Some(vec![b'4', 0])
+                Some("1".to_string()),
+                Some("2".to_string()),
+                Some("3".to_string()),
+                Some("4".to_string())
             ],
         );
     }
@@ -111,6 +111,7 @@ fn insert_one<'a, M>(
 ) -> elephantry::Result<M::Entity>
 where
     M: elephantry::Model<'a>,
+    M::Structure: elephantry::Writable,
     M::Entity: std::fmt::Debug,
 {
     let new_entity = connection.insert_one::<M>(&entity)?;
@@ -127,6 +128,7 @@ fn update_one<'a, M>(
 ) -> elephantry::Result<M::Entity>
 where
     M: elephantry::Model<'a>,
+    M::Structure: elephantry::Writable,
     M::Entity: std::fmt::Debug,
 {
     let new_entity = connection.update_one::<M>(pk, entity)?;
@@ -157,3 +157,5 @@ impl elephantry::Structure for EventStructure {
         &["uuid", "name", "visitor_id", "properties", "browser"]
     }
 }
+
+impl elephantry::Writable for EventStructure {}
@@ -79,3 +79,24 @@ impl<'a> elephantry::Model<'a> for EventExtraModel {
         Self::default_projection().add_field("os", "%:browser:% ->> 'os'")
     }
 }
+
+// The derive checks the feature of the date and time types
+#[cfg(feature = "time")]
+#[derive(Debug, elephantry::Entity)]
+#[allow(dead_code)]
+struct TimeEvent {
+    at: time::OffsetDateTime,
+    date: time::Date,
+    duration: time::Duration,
+    local: time::PrimitiveDateTime,
+}
+
+#[cfg(feature = "jiff")]
+#[derive(Debug, elephantry::Entity)]
+#[allow(dead_code)]
+struct JiffEvent {
+    at: jiff::Timestamp,
+    zoned: jiff::Zoned,
+    date: jiff::civil::Date,
+    local: jiff::civil::DateTime,
+}
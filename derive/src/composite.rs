@@ -80,6 +80,33 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::Tok
                 Ok(Box::new(s))
             }
         }
+
+        #[automatically_derived]
+        impl #impl_generics #elephantry::ToSql for #name #ty_generics #where_clause {
+            fn ty(&self) -> #elephantry::pq::Type {
+                #elephantry::pq::Type {
+                    oid: 0,
+                    descr: <Self as #elephantry::Composite>::name(),
+                    name: <Self as #elephantry::Composite>::name(),
+                    kind: #elephantry::pq::types::Kind::Composite,
+                }
+            }
+
+            fn to_sql(&self) -> #elephantry::Result<Option<Vec<u8>>> {
+                <Self as #elephantry::Composite>::to_sql(self)
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #elephantry::FromSql for #name #ty_generics #where_clause {
+            fn from_text(ty: &#elephantry::pq::Type, raw: Option<&str>) -> #elephantry::Result<Self> {
+                <Self as #elephantry::Composite>::from_text(ty, raw).map(|x| *x)
+            }
+
+            fn from_binary(ty: &#elephantry::pq::Type, raw: Option<&[u8]>) -> #elephantry::Result<Self> {
+                <Self as #elephantry::Composite>::from_binary(ty, raw).map(|x| *x)
+            }
+        }
     };
 
     Ok(gen)
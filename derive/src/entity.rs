@@ -17,7 +17,7 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::Tok
         proc_macro2::TokenStream::new()
     };
 
-    let entity = entity_impl(ast, &elephantry)?;
+    let entity = entity_impl(ast, &params, &elephantry)?;
     let structure = structure_impl(ast, &params, &elephantry, &public)?;
     let model = model_impl(ast, &params, &elephantry, &public)?;
 
@@ -30,8 +30,88 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::Tok
     Ok(gen)
 }
 
+pub(crate) fn impl_model_macro(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut params = crate::params::Entity::from_ast(ast)?;
+
+    let elephantry = if params.internal {
+        quote::quote! {
+            crate
+        }
+    } else {
+        quote::quote! {
+            elephantry
+        }
+    };
+
+    let public = if is_public(ast) {
+        quote::quote!(pub)
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    if params.model.is_none() {
+        let model = quote::format_ident!("{}Model", ast.ident);
+        params.model = Some(quote::quote!(#model));
+    }
+
+    if params.structure.is_none() {
+        let structure = quote::format_ident!("{}Structure", ast.ident);
+        params.structure = Some(quote::quote!(#structure));
+    }
+
+    let structure = structure_impl(ast, &params, &elephantry, &public)?;
+    let model = model_impl(ast, &params, &elephantry, &public)?;
+
+    let gen = quote::quote! {
+        #structure
+        #model
+    };
+
+    Ok(gen)
+}
+
+/**
+ * Checks the columns of the `pk` container attribute exist.
+ */
+fn check_pk(ast: &syn::DeriveInput, params: &crate::params::Entity) -> syn::Result<()> {
+    let fields = match ast.data {
+        syn::Data::Struct(ref s) => &s.fields,
+        _ => {
+            return crate::error(
+                ast,
+                "this derive macro only works on structs with named fields",
+            )
+        }
+    };
+
+    let mut columns = Vec::new();
+
+    for field in fields {
+        let field_params = crate::params::Field::from_ast(field)?;
+
+        if field_params.flatten {
+            continue;
+        }
+
+        columns.push(
+            field_params
+                .column
+                .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string()),
+        );
+    }
+
+    for pk in &params.pk {
+        if !columns.contains(pk) {
+            return crate::error(ast, &format!("Unknow primary key column '{}'", pk));
+        }
+    }
+
+    Ok(())
+}
+
 fn entity_impl(
     ast: &syn::DeriveInput,
+    params: &crate::params::Entity,
     elephantry: &proc_macro2::TokenStream,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let fields = match ast.data {
@@ -45,19 +125,65 @@ fn entity_impl(
     };
 
     let mut from_body = Vec::new();
+    let mut validate_body = Vec::new();
     let mut get_body = Vec::new();
+    let mut set_body = Vec::new();
+    let mut columns = Vec::new();
+    let mut column_fields = Vec::new();
+    let mut primary_key = Vec::new();
+    let mut projection_body = Vec::new();
+    let mut flatten = Vec::new();
 
     for field in fields {
         let field_params = crate::params::Field::from_ast(field)?;
 
         let name = &field.ident;
+
+        if field_params.flatten {
+            let ty = &field.ty;
+
+            // Prefixed entities come from a JOIN, they are only read
+            if let Some(prefix) = field_params.prefix {
+                // All the fields are null without match in an outer JOIN
+                if is_option(ty) {
+                    from_body.push(quote::quote! {
+                        #name: tuple.try_optional_entity(#prefix)?
+                    });
+
+                    continue;
+                }
+
+                from_body.push(quote::quote! {
+                    #name: <#ty as #elephantry::Entity>::try_from_tuple(&tuple.prefixed(#prefix))?
+                });
+            } else {
+                from_body.push(quote::quote! {
+                    #name: <#ty as #elephantry::Entity>::try_from_tuple(tuple)?
+                });
+                flatten.push((name, ty));
+            }
+
+            continue;
+        }
+
         let column = field_params
             .column
             .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string());
         let ty = &field.ty;
         crate::check_type(ty)?;
 
-        let from_part = if field_params.default {
+        let from_part = if field_params.encrypted {
+            quote::quote! {
+                #name: tuple.try_get::<#elephantry::Encrypted<#ty>>(#column)?.into_inner()
+            }
+        } else if field_params.optional_column {
+            quote::quote! {
+                #name: match tuple.try_get(#column) {
+                    Err(#elephantry::Error::MissingField(_)) => Default::default(),
+                    value => value?,
+                }
+            }
+        } else if field_params.default {
             quote::quote! {
                 #name: tuple.try_get(#column).unwrap_or_default()
             }
@@ -67,13 +193,28 @@ fn entity_impl(
             }
         } else {
             quote::quote! {
-                #name: tuple.get(#column)
+                #name: tuple.try_get(#column)?
             }
         };
 
         from_body.push(from_part);
 
-        let get_part = if is_option(ty) {
+        if let Some(validate) = field_params.validate {
+            let validate_part = quote::quote! {
+                #validate(&entity.#name).map_err(|err| #elephantry::Error::Validation {
+                    field: #column.to_string(),
+                    message: err.to_string(),
+                })?;
+            };
+
+            validate_body.push(validate_part);
+        }
+
+        let get_part = if field_params.encrypted {
+            quote::quote! {
+                #column => Some(#elephantry::Encrypted::from_ref(&self.#name))
+            }
+        } else if is_option(ty) {
             quote::quote! {
                 #column => match self.#name {
                     Some(ref value) => Some(value),
@@ -87,8 +228,100 @@ fn entity_impl(
         };
 
         get_body.push(get_part);
+
+        let set_part = quote::quote! {
+            #column => {
+                self.#name = *value.downcast::<#ty>().map_err(|_| #elephantry::Error::FieldType {
+                    field: #column.to_string(),
+                    rust_type: stringify!(#ty).to_string(),
+                })?;
+            }
+        };
+
+        set_body.push(set_part);
+
+        if field_params.pk || params.pk.contains(&column) {
+            primary_key.push(column.clone());
+        }
+
+        if let Some(projection) = field_params.projection {
+            projection_body.push(quote::quote! {
+                .add_field(stringify!(#name), #projection)
+            });
+        }
+
+        if !field_params.r#virtual {
+            column_fields.push(column.clone());
+        }
+
+        columns.push(column);
     }
 
+    let get_flatten = flatten.iter().map(|(name, _)| {
+        quote::quote! {
+            .or_else(|| #elephantry::Entity::get(&self.#name, field))
+        }
+    });
+
+    let set_flatten = flatten.iter().map(|(name, ty)| {
+        quote::quote! {
+            _ if <#ty as #elephantry::Entity>::fields().contains(&field) => {
+                return #elephantry::Entity::set_dyn(&mut self.#name, field, value);
+            }
+        }
+    });
+
+    let flatten_type = flatten.iter().map(|(_, ty)| *ty).collect::<Vec<_>>();
+    let fields = names(
+        elephantry,
+        "fields",
+        &columns,
+        &flatten_type,
+        quote::quote!(fields),
+    );
+    let column_fields = names(
+        elephantry,
+        "columns",
+        &column_fields,
+        &flatten_type,
+        quote::quote!(column_fields),
+    );
+    let primary_key_fields = names(
+        elephantry,
+        "primary_key",
+        &primary_key,
+        &flatten_type,
+        quote::quote!(primary_key_fields),
+    );
+
+    // `Any` requires 'static types, this can't be guaranteed for generic entities
+    let set_dyn = if !ast.generics.params.is_empty() {
+        proc_macro2::TokenStream::new()
+    } else if set_body.is_empty() {
+        // Without own columns, all the arms return
+        quote::quote! {
+            #[allow(unused_variables)]
+            fn set_dyn(&mut self, field: &str, value: Box<dyn std::any::Any>) -> #elephantry::Result<()> {
+                match field {
+                    #(#set_flatten)*
+                    _ => Err(#elephantry::Error::MissingField(field.to_string())),
+                }
+            }
+        }
+    } else {
+        quote::quote! {
+            fn set_dyn(&mut self, field: &str, value: Box<dyn std::any::Any>) -> #elephantry::Result<()> {
+                match field {
+                    #(#set_body, )*
+                    #(#set_flatten)*
+                    _ => return Err(#elephantry::Error::MissingField(field.to_string())),
+                }
+
+                Ok(())
+            }
+        }
+    };
+
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
@@ -98,17 +331,48 @@ fn entity_impl(
         {
             fn from(tuple: &#elephantry::Tuple<'_>) -> Self
             {
-                Self {
+                <Self as #elephantry::Entity>::try_from_tuple(tuple)
+                    .unwrap_or_else(|err| panic!("Unable to retrieve entity: {}", err))
+            }
+
+            fn try_from_tuple(tuple: &#elephantry::Tuple<'_>) -> #elephantry::Result<Self>
+            {
+                let entity = Self {
                     #(#from_body, )*
-                }
+                };
+
+                #(#validate_body)*
+
+                Ok(entity)
             }
 
             fn get(&self, field: &str) -> Option<&dyn #elephantry::ToSql> {
                 match field {
                     #(#get_body, )*
-                    _ => None,
+                    _ => None #(#get_flatten)*,
                 }
             }
+
+            fn fields() -> &'static [&'static str] {
+                #fields
+            }
+
+            fn column_fields() -> &'static [&'static str] {
+                #column_fields
+            }
+
+            fn primary_key_fields() -> &'static [&'static str] {
+                #primary_key_fields
+            }
+
+            fn add_projection(projection: #elephantry::Projection) -> #elephantry::Projection {
+                let projection = projection #(#projection_body)*;
+                #(let projection = <#flatten_type as #elephantry::Entity>::add_projection(projection);)*
+
+                projection
+            }
+
+            #set_dyn
         }
     };
 
@@ -126,6 +390,8 @@ fn structure_impl(
         None => return Ok(proc_macro2::TokenStream::new()),
     };
 
+    check_pk(ast, params)?;
+
     let fields = match ast.data {
         syn::Data::Struct(ref s) => &s.fields,
         _ => unreachable!(),
@@ -135,28 +401,87 @@ fn structure_impl(
         .relation
         .clone()
         .unwrap_or_else(|| ast.ident.to_string().to_lowercase());
+    let relation = match &params.schema {
+        Some(schema) => format!("{}.{}", schema, relation),
+        None => relation,
+    };
 
     let mut primary_key = Vec::new();
+    let mut unique_keys = Vec::new();
     let mut columns = Vec::new();
+    let mut flatten = Vec::new();
 
     for field in fields {
         let field_params = crate::params::Field::from_ast(field)?;
+
+        if field_params.flatten {
+            if field_params.prefix.is_none() {
+                flatten.push(&field.ty);
+            }
+            continue;
+        }
+
         let column = field_params
             .column
             .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string());
 
-        if field_params.pk {
+        if field_params.pk || params.pk.contains(&column) {
             primary_key.push(column.clone());
         }
 
+        if field_params.unique {
+            unique_keys.push(column.clone());
+        }
+
         if !field_params.r#virtual {
             columns.push(column);
         }
     }
 
+    let columns = names(
+        elephantry,
+        "columns",
+        &columns,
+        &flatten,
+        quote::quote!(column_fields),
+    );
+    let primary_key = names(
+        elephantry,
+        "primary_key",
+        &primary_key,
+        &flatten,
+        quote::quote!(primary_key_fields),
+    );
+
+    let kind_impl = if params.materialized_view {
+        quote::quote! {
+            #[automatically_derived]
+            impl #elephantry::View for #name {
+            }
+
+            #[automatically_derived]
+            impl #elephantry::MaterializedView for #name {
+            }
+        }
+    } else if params.view {
+        quote::quote! {
+            #[automatically_derived]
+            impl #elephantry::View for #name {
+            }
+        }
+    } else {
+        quote::quote! {
+            #[automatically_derived]
+            impl #elephantry::Writable for #name {
+            }
+        }
+    };
+
     let structure_impl = quote::quote! {
         #public struct #name;
 
+        #kind_impl
+
         #[automatically_derived]
         impl #elephantry::Structure for #name {
             fn relation() -> &'static str {
@@ -164,14 +489,16 @@ fn structure_impl(
             }
 
             fn primary_key() -> &'static [&'static str] {
-                &[
-                    #(#primary_key, )*
-                ]
+                #primary_key
             }
 
             fn columns() -> &'static [&'static str] {
+                #columns
+            }
+
+            fn unique_keys() -> &'static [&'static [&'static str]] {
                 &[
-                    #(#columns, )*
+                    #(&[#unique_keys], )*
                 ]
             }
         }
@@ -204,10 +531,28 @@ fn model_impl(
     let entity = &ast.ident;
 
     let mut projection_body = Vec::new();
+    let mut primary_key = Vec::new();
+    let mut flatten = Vec::new();
 
     for field in fields {
         let field_params = crate::params::Field::from_ast(field)?;
 
+        if field_params.flatten {
+            if field_params.prefix.is_none() {
+                flatten.push(&field.ty);
+            }
+            continue;
+        }
+
+        let column = field_params
+            .column
+            .clone()
+            .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string());
+
+        if field_params.pk || params.pk.contains(&column) {
+            primary_key.push((column, &field.ty));
+        }
+
         if let Some(projection) = field_params.projection {
             let name = &field.ident;
             let projection_part = quote::quote!(
@@ -218,24 +563,69 @@ fn model_impl(
         }
     }
 
-    let create_projection = if projection_body.is_empty() {
+    let create_projection = if projection_body.is_empty() && flatten.is_empty() {
         proc_macro2::TokenStream::new()
     } else {
         quote::quote! {
             fn create_projection() -> #elephantry::Projection {
-                Self::default_projection()
-                    #(#projection_body)*
+                let projection = Self::default_projection()
+                    #(#projection_body)*;
+                #(let projection = <#flatten as #elephantry::Entity>::add_projection(projection);)*
+
+                projection
             }
         }
     };
 
+    let primary_key_type = primary_key.iter().map(|(_, ty)| ty);
+    let primary_key_values = primary_key.iter().enumerate().map(|(n, (column, _))| {
+        let value = if primary_key.len() == 1 {
+            quote::quote!(pk)
+        } else {
+            let n = syn::Index::from(n);
+            quote::quote!(&pk.#n)
+        };
+
+        quote::quote! {
+            values.insert(#column, #value as &dyn #elephantry::ToSql);
+        }
+    });
+
+    let primary_key_type = if primary_key.len() == 1 {
+        quote::quote!(#(#primary_key_type)*)
+    } else {
+        quote::quote!((#(#primary_key_type, )*))
+    };
+
+    let primary_key_impl = if primary_key.is_empty() {
+        proc_macro2::TokenStream::new()
+    } else {
+        quote::quote! {
+            #[automatically_derived]
+            impl<'a> #elephantry::HasPrimaryKey<'a> for #name<'a> {
+                type PrimaryKey = #primary_key_type;
+
+                fn primary_key_values(
+                    pk: &Self::PrimaryKey,
+                ) -> #elephantry::Result<std::collections::HashMap<&'static str, &dyn #elephantry::ToSql>> {
+                    let mut values = std::collections::HashMap::new();
+                    #(#primary_key_values)*
+
+                    Ok(values)
+                }
+            }
+        }
+    };
+
+    let associations = associations_impl(params, name, entity, elephantry);
+
     let model_impl = quote::quote! {
         #public struct #name<'a> {
             connection: &'a #elephantry::Connection,
         }
 
         #[automatically_derived]
-        impl<'a> #elephantry::Model<'a> for Model<'a> {
+        impl<'a> #elephantry::Model<'a> for #name<'a> {
             type Entity = #entity;
             type Structure = #structure;
 
@@ -247,11 +637,127 @@ fn model_impl(
 
             #create_projection
         }
+
+        #primary_key_impl
+
+        #associations
     };
 
     Ok(model_impl)
 }
 
+/**
+ * Implements [`HasMany`] and [`BelongsTo`] for the declared associations,
+ * with their `{name}_for` helper.
+ */
+fn associations_impl(
+    params: &crate::params::Entity,
+    model: &proc_macro2::TokenStream,
+    entity: &syn::Ident,
+    elephantry: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut associations = Vec::new();
+
+    for association in &params.has_many {
+        let other = &association.model;
+        let fk = &association.fk;
+        let method = quote::format_ident!("{}_for", association.name);
+        let key = association.key.as_ref().map(|key| {
+            quote::quote! {
+                fn key() -> &'static [&'static str] {
+                    &[#(#key, )*]
+                }
+            }
+        });
+
+        associations.push(quote::quote! {
+            #[automatically_derived]
+            impl<'a> #elephantry::HasMany<'a, #other<'a>> for #model<'a> {
+                fn foreign_key() -> &'static [&'static str] {
+                    &[#(#fk, )*]
+                }
+
+                #key
+            }
+
+            impl<'a> #model<'a> {
+                pub fn #method(
+                    &self,
+                    entity: &#entity,
+                ) -> #elephantry::Result<#elephantry::Rows<<#other<'a> as #elephantry::Model<'a>>::Entity>> {
+                    self.connection.find_has_many::<Self, #other<'a>>(entity)
+                }
+            }
+        });
+    }
+
+    for association in &params.belongs_to {
+        let other = &association.model;
+        let fk = &association.fk;
+        let method = quote::format_ident!("{}_for", association.name);
+        let key = association.key.as_ref().map(|key| {
+            quote::quote! {
+                fn key() -> &'static [&'static str] {
+                    &[#(#key, )*]
+                }
+            }
+        });
+
+        associations.push(quote::quote! {
+            #[automatically_derived]
+            impl<'a> #elephantry::BelongsTo<'a, #other<'a>> for #model<'a> {
+                fn foreign_key() -> &'static [&'static str] {
+                    &[#(#fk, )*]
+                }
+
+                #key
+            }
+
+            impl<'a> #model<'a> {
+                pub fn #method(
+                    &self,
+                    entity: &#entity,
+                ) -> #elephantry::Result<Option<<#other<'a> as #elephantry::Model<'a>>::Entity>> {
+                    self.connection.find_belongs_to::<Self, #other<'a>>(entity)
+                }
+            }
+        });
+    }
+
+    quote::quote! {
+        #(#associations)*
+    }
+}
+
+/**
+ * Static list of `own` names, extended at runtime with the names returned by
+ * `method` of the embedded entities.
+ */
+fn names(
+    elephantry: &proc_macro2::TokenStream,
+    kind: &str,
+    own: &[String],
+    flatten: &[&syn::Type],
+    method: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if flatten.is_empty() {
+        return quote::quote! {
+            &[
+                #(#own, )*
+            ]
+        };
+    }
+
+    quote::quote! {
+        #elephantry::names_of::<Self>(#kind, || {
+            let mut names = vec![#(#own, )*];
+            #(names.extend_from_slice(<#flatten as #elephantry::Entity>::#method());)*
+
+            names
+        })
+    }
+}
+
 fn is_public(ast: &syn::DeriveInput) -> bool {
     matches!(ast.vis, syn::Visibility::Public(_))
 }
@@ -259,7 +765,9 @@ fn is_public(ast: &syn::DeriveInput) -> bool {
 fn is_option(ty: &syn::Type) -> bool {
     let typepath = match ty {
         syn::Type::Path(typepath) => typepath,
-        _ => unimplemented!(),
+        // Types from a `macro_rules` fragment
+        syn::Type::Group(group) => return is_option(&group.elem),
+        _ => return false,
     };
 
     typepath.path.leading_colon.is_none()
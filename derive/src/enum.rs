@@ -21,7 +21,23 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::Tok
         let name = &variant.ident;
 
         quote::quote! {
-            stringify!(#name) => Self::#name
+            stringify!(#name) => Some(Self::#name)
+        }
+    });
+
+    let variants_body = variants.iter().map(|variant| {
+        let name = &variant.ident;
+
+        quote::quote! {
+            stringify!(#name)
+        }
+    });
+
+    let ordinal_body = variants.iter().enumerate().map(|(ordinal, variant)| {
+        let name = &variant.ident;
+
+        quote::quote! {
+            Self::#name => #ordinal
         }
     });
 
@@ -35,12 +51,69 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::Tok
             }
 
             fn from_text(value: &str) -> #elephantry::Result<Box<Self>> {
-                let v = match value {
+                match <Self as #elephantry::Enum>::try_from_text(value) {
+                    Some(v) => Ok(Box::new(v)),
+                    None => Err(#elephantry::Error::FromSql {
+                        pg_type: #elephantry::pq::Type {
+                            oid: 0,
+                            descr: <Self as #elephantry::Enum>::name(),
+                            name: <Self as #elephantry::Enum>::name(),
+                            kind: #elephantry::pq::types::Kind::Enum,
+                        },
+                        rust_type: stringify!(#name).to_string(),
+                        value: value.to_string(),
+                    }),
+                }
+            }
+
+            fn variants() -> &'static [&'static str] {
+                &[
+                    #(#variants_body, )*
+                ]
+            }
+
+            fn ordinal(&self) -> usize {
+                match self {
+                    #(#ordinal_body, )*
+                }
+            }
+
+            fn try_from_text(value: &str) -> Option<Self> {
+                match value {
                     #(#from_text_body, )*
-                    _ => unreachable!(),
-                };
+                    _ => None,
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #elephantry::ToSql for #name #ty_generics #where_clause {
+            fn ty(&self) -> #elephantry::pq::Type {
+                #elephantry::pq::Type {
+                    oid: 0,
+                    descr: <Self as #elephantry::Enum>::name(),
+                    name: <Self as #elephantry::Enum>::name(),
+                    kind: #elephantry::pq::types::Kind::Enum,
+                }
+            }
+
+            fn to_sql(&self) -> #elephantry::Result<Option<Vec<u8>>> {
+                #elephantry::ToSql::to_sql(&format!("{:?}", self))
+            }
+        }
+
+        #[automatically_derived]
+        impl #impl_generics #elephantry::FromSql for #name #ty_generics #where_clause {
+            fn from_text(_: &#elephantry::pq::Type, raw: Option<&str>) -> #elephantry::Result<Self> {
+                let value = raw.ok_or(#elephantry::Error::NotNull)?;
+
+                <Self as #elephantry::Enum>::from_text(value).map(|x| *x)
+            }
+
+            fn from_binary(ty: &#elephantry::pq::Type, raw: Option<&[u8]>) -> #elephantry::Result<Self> {
+                let value = <String as #elephantry::FromSql>::from_binary(ty, raw)?;
 
-                Ok(Box::new(v))
+                <Self as #elephantry::Enum>::from_text(&value).map(|x| *x)
             }
         }
     };
@@ -23,7 +23,47 @@ pub fn composite_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 /**
  * Impl [`Entity`] trait.
  *
+ * A field marked with `#[elephantry(flatten)]` embeds another entity, its
+ * fields are read from the same columns:
+ *
+ * ```ignore
+ * #[derive(elephantry::Entity)]
+ * struct Audit {
+ *     created_by: String,
+ *     updated_at: chrono::NaiveDateTime,
+ * }
+ *
+ * #[derive(elephantry::Entity)]
+ * #[elephantry(model = "Model", structure = "Structure", relation = "page")]
+ * struct Page {
+ *     #[elephantry(pk)]
+ *     id: i32,
+ *     title: String,
+ *     #[elephantry(flatten)]
+ *     audit: Audit,
+ * }
+ * ```
+ *
+ * With a `prefix`, the embedded entity is read from the `prefix.field`
+ * columns, as selected by a [`Projection`] with the same prefix. This
+ * composes several entities from a JOIN query; these fields are read only
+ * and aren’t part of the entity fields:
+ *
+ * ```ignore
+ * #[derive(elephantry::Entity)]
+ * struct OrderWithCustomer {
+ *     #[elephantry(flatten, prefix = "order")]
+ *     order: Order,
+ *     #[elephantry(flatten, prefix = "customer")]
+ *     customer: Customer,
+ * }
+ * ```
+ *
+ * An `Option` prefixed entity is `None` when all its fields are null, as
+ * returned by an outer JOIN without match.
+ *
  * [`Entity`]: trait.Entity.html
+ * [`Projection`]: struct.Projection.html
  */
 #[proc_macro_derive(Entity, attributes(elephantry))]
 pub fn entity_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -34,6 +74,51 @@ pub fn entity_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
         .into()
 }
 
+/**
+ * Generates the [`Structure`] and [`Model`] of an entity, named after the
+ * `structure` and `model` attributes or `{Entity}Structure` and
+ * `{Entity}Model` by default.
+ *
+ * `#[derive(Entity)]` already generates them when both attributes are set:
+ * combine it with `#[derive(Model)]` only without them.
+ *
+ * Relations declared with the `view` or `materialized_view` attribute are
+ * read-only: the write helpers of `Connection` are not available for their
+ * models.
+ *
+ * The primary key columns are listed with `pk = "uuid, name"` or
+ * `pk(uuid, name)`.
+ *
+ * Associations with other models are declared with
+ * `has_many(name = "events", model = "EventModel", fk = "visitor_id")` and
+ * `belongs_to(name = "visitor", model = "VisitorModel", fk = "visitor_id")`,
+ * with an optional `key` when the referenced columns aren’t the primary key.
+ * They implement [`HasMany`] and [`BelongsTo`] and add a `{name}_for`
+ * method to the model.
+ *
+ * ```ignore
+ * #[derive(elephantry::Entity, elephantry::Model)]
+ * #[elephantry(relation = "public.event", pk = "uuid")]
+ * struct Event {
+ *     uuid: uuid::Uuid,
+ *     name: String,
+ * }
+ * ```
+ *
+ * [`BelongsTo`]: trait.BelongsTo.html
+ * [`HasMany`]: trait.HasMany.html
+ * [`Model`]: trait.Model.html
+ * [`Structure`]: trait.Structure.html
+ */
+#[proc_macro_derive(Model, attributes(elephantry))]
+pub fn model_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    entity::impl_model_macro(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /**
  * Impl [`Enum`] trait.
  *
@@ -49,21 +134,35 @@ pub fn enum_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 }
 
 pub(crate) fn check_type(ty: &syn::Type) -> syn::Result<()> {
+    if let syn::Type::Group(group) = ty {
+        return check_type(&group.elem);
+    }
+
     let features = vec![
         #[cfg(feature = "bit")]
         "bit",
+        #[cfg(feature = "citext")]
+        "citext",
         #[cfg(feature = "date")]
         "date",
         #[cfg(feature = "geo")]
         "geo",
+        #[cfg(feature = "hstore")]
+        "hstore",
+        #[cfg(feature = "jiff")]
+        "jiff",
         #[cfg(feature = "json")]
         "json",
+        #[cfg(feature = "ltree")]
+        "ltree",
         #[cfg(feature = "net")]
         "net",
         #[cfg(feature = "numeric")]
         "numeric",
         #[cfg(feature = "time")]
         "time",
+        #[cfg(feature = "url")]
+        "url",
         #[cfg(feature = "uuid")]
         "uuid",
         #[cfg(feature = "xml")]
@@ -72,8 +171,10 @@ pub(crate) fn check_type(ty: &syn::Type) -> syn::Result<()> {
 
     let types = [
         ("bit", "bit_vec::BitVec"),
-        ("bit", "u8"),
+        ("bit", "elephantry::Bit"),
+        ("citext", "elephantry::Citext"),
         ("date", "chrono::DateTime"),
+        ("date", "chrono::Duration"),
         ("date", "chrono::NaiveDate"),
         ("date", "chrono::NaiveDateTime"),
         ("geo", "elephantry::Box"),
@@ -83,7 +184,14 @@ pub(crate) fn check_type(ty: &syn::Type) -> syn::Result<()> {
         ("geo", "elephantry::Point"),
         ("geo", "elephantry::Polygon"),
         ("geo", "elephantry::Segment"),
+        ("hstore", "elephantry::Hstore"),
+        ("jiff", "jiff::Timestamp"),
+        ("jiff", "jiff::Zoned"),
+        ("jiff", "jiff::civil::Date"),
+        ("jiff", "jiff::civil::DateTime"),
         ("json", "serde_json::value::Value"),
+        ("ltree", "elephantry::Lquery"),
+        ("ltree", "elephantry::Ltree"),
         ("net", "ipnetwork::IpNetwork"),
         ("net", "macaddr::MacAddr6"),
         ("net", "macaddr::MacAddr8"),
@@ -91,6 +199,11 @@ pub(crate) fn check_type(ty: &syn::Type) -> syn::Result<()> {
         ("numeric", "bigdecimal::BigDecimal"),
         ("time", "elephantry::Time"),
         ("time", "elephantry::TimeTz"),
+        ("time", "time::Date"),
+        ("time", "time::Duration"),
+        ("time", "time::OffsetDateTime"),
+        ("time", "time::PrimitiveDateTime"),
+        ("url", "url::Url"),
         ("uuid", "uuid::Uuid"),
         ("xml", "xmltree::Element"),
     ];
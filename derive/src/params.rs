@@ -26,12 +26,79 @@ impl Container {
     }
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct Association {
+    pub fk: Vec<String>,
+    pub key: Option<Vec<String>>,
+    pub model: proc_macro2::TokenStream,
+    pub name: syn::Ident,
+}
+
+impl Association {
+    fn from_list(list: &syn::MetaList) -> syn::Result<Self> {
+        let mut fk = None;
+        let mut key = None;
+        let mut model = None;
+        let mut name = None;
+
+        for item in &list.nested {
+            match item {
+                // Parse fk = ""
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m)) if m.path == crate::symbol::FK => {
+                    fk = Some(get_columns(crate::symbol::FK, &m.lit)?);
+                }
+                // Parse key = ""
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m)) if m.path == crate::symbol::KEY => {
+                    key = Some(get_columns(crate::symbol::KEY, &m.lit)?);
+                }
+                // Parse model = ""
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m))
+                    if m.path == crate::symbol::MODEL =>
+                {
+                    model = Some(get_lit(crate::symbol::MODEL, &m.lit)?);
+                }
+                // Parse name = ""
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m)) if m.path == crate::symbol::NAME => {
+                    let lit = get_lit_str(crate::symbol::NAME, &m.lit)?;
+                    name = Some(syn::Ident::new(&lit, m.lit.span()));
+                }
+                _ => return crate::error(item, "Unknow elephantry association attribute"),
+            }
+        }
+
+        match (fk, model, name) {
+            (Some(fk), Some(model), Some(name)) => Ok(Self {
+                fk,
+                key,
+                model,
+                name,
+            }),
+            _ => crate::error(
+                list,
+                &format!(
+                    "expected `{}(name = \"...\", model = \"...\", fk = \"...\")`",
+                    list.path
+                        .get_ident()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                ),
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 pub(crate) struct Entity {
+    pub belongs_to: Vec<Association>,
+    pub has_many: Vec<Association>,
     pub internal: bool,
+    pub materialized_view: bool,
     pub model: Option<proc_macro2::TokenStream>,
+    pub pk: Vec<String>,
     pub relation: Option<String>,
+    pub schema: Option<String>,
     pub structure: Option<proc_macro2::TokenStream>,
+    pub view: bool,
 }
 
 impl Entity {
@@ -40,10 +107,33 @@ impl Entity {
 
         for item in flat_map(&ast.attrs)? {
             match &item {
+                // Parse #[elephantry(belongs_to(name = "", model = "", fk = ""))]
+                syn::NestedMeta::Meta(syn::Meta::List(list))
+                    if list.path == crate::symbol::BELONGS_TO =>
+                {
+                    param.belongs_to.push(Association::from_list(list)?);
+                }
+                // Parse #[elephantry(has_many(name = "", model = "", fk = ""))]
+                syn::NestedMeta::Meta(syn::Meta::List(list))
+                    if list.path == crate::symbol::HAS_MANY =>
+                {
+                    param.has_many.push(Association::from_list(list)?);
+                }
                 // Parse #[elephantry(internal)]
                 syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::INTERNAL => {
                     param.internal = true;
                 }
+                // Parse #[elephantry(materialized_view)]
+                syn::NestedMeta::Meta(syn::Meta::Path(w))
+                    if w == crate::symbol::MATERIALIZED_VIEW =>
+                {
+                    param.materialized_view = true;
+                    param.view = true;
+                }
+                // Parse #[elephantry(view)]
+                syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::VIEW => {
+                    param.view = true;
+                }
                 // Parse #[elephantry(model = "")]
                 syn::NestedMeta::Meta(syn::Meta::NameValue(m))
                     if m.path == crate::symbol::MODEL =>
@@ -51,6 +141,25 @@ impl Entity {
                     let model = get_lit(crate::symbol::MODEL, &m.lit).unwrap();
                     param.model = Some(model);
                 }
+                // Parse #[elephantry(pk = "")]
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m)) if m.path == crate::symbol::PK => {
+                    param.pk = get_columns(crate::symbol::PK, &m.lit)?;
+                }
+                // Parse #[elephantry(pk(uuid, name))]
+                syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path == crate::symbol::PK => {
+                    param.pk = list
+                        .nested
+                        .iter()
+                        .map(|x| match x {
+                            syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                                path.get_ident().map(ToString::to_string).ok_or_else(|| {
+                                    syn::Error::new_spanned(path, "Expected a column name")
+                                })
+                            }
+                            _ => crate::error(x, "Expected a column name"),
+                        })
+                        .collect::<syn::Result<_>>()?;
+                }
                 // Parse #[elephantry(relation = "")]
                 syn::NestedMeta::Meta(syn::Meta::NameValue(m))
                     if m.path == crate::symbol::RELATION =>
@@ -58,6 +167,13 @@ impl Entity {
                     let relation = get_lit_str(crate::symbol::STRUCTURE, &m.lit)?;
                     param.relation = Some(relation);
                 }
+                // Parse #[elephantry(schema = "")]
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m))
+                    if m.path == crate::symbol::SCHEMA =>
+                {
+                    let schema = get_lit_str(crate::symbol::SCHEMA, &m.lit)?;
+                    param.schema = Some(schema);
+                }
                 // Parse #[elephantry(structure = "")]
                 syn::NestedMeta::Meta(syn::Meta::NameValue(m))
                     if m.path == crate::symbol::STRUCTURE =>
@@ -86,6 +202,15 @@ fn get_lit(
     syn::parse_str(&lit)
 }
 
+/*
+ * Parses a `"a, b"` list of columns.
+ */
+fn get_columns(attr_name: crate::symbol::Symbol, lit: &syn::Lit) -> syn::Result<Vec<String>> {
+    let columns = get_lit_str(attr_name, lit)?;
+
+    Ok(columns.split(',').map(|x| x.trim().to_string()).collect())
+}
+
 fn get_lit_str(attr_name: crate::symbol::Symbol, lit: &syn::Lit) -> syn::Result<String> {
     if let syn::Lit::Str(lit) = lit {
         Ok(lit.value())
@@ -104,8 +229,14 @@ fn get_lit_str(attr_name: crate::symbol::Symbol, lit: &syn::Lit) -> syn::Result<
 pub(crate) struct Field {
     pub column: Option<String>,
     pub default: bool,
+    pub encrypted: bool,
+    pub flatten: bool,
+    pub optional_column: bool,
     pub pk: bool,
+    pub prefix: Option<String>,
     pub projection: Option<String>,
+    pub unique: bool,
+    pub validate: Option<proc_macro2::TokenStream>,
     pub r#virtual: bool,
 }
 
@@ -119,10 +250,28 @@ impl Field {
                 syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::DEFAULT => {
                     param.default = true;
                 }
+                // Parse #[elephantry(encrypted)]
+                syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::ENCRYPTED => {
+                    param.encrypted = true;
+                }
+                // Parse #[elephantry(flatten)]
+                syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::FLATTEN => {
+                    param.flatten = true;
+                }
+                // Parse #[elephantry(optional_column)]
+                syn::NestedMeta::Meta(syn::Meta::Path(w))
+                    if w == crate::symbol::OPTIONAL_COLUMN =>
+                {
+                    param.optional_column = true;
+                }
                 // Parse #[elephantry(pk)]
                 syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::PK => {
                     param.pk = true;
                 }
+                // Parse #[elephantry(unique)]
+                syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::UNIQUE => {
+                    param.unique = true;
+                }
                 // Parse #[elephantry(column = "")]
                 syn::NestedMeta::Meta(syn::Meta::NameValue(m))
                     if m.path == crate::symbol::COLUMN =>
@@ -130,6 +279,20 @@ impl Field {
                     let column = get_lit_str(crate::symbol::COLUMN, &m.lit)?;
                     param.column = Some(column);
                 }
+                // Parse #[elephantry(prefix = "")]
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m))
+                    if m.path == crate::symbol::PREFIX =>
+                {
+                    let prefix = get_lit_str(crate::symbol::PREFIX, &m.lit)?;
+                    param.prefix = Some(prefix);
+                }
+                // Parse #[elephantry(validate = "")]
+                syn::NestedMeta::Meta(syn::Meta::NameValue(m))
+                    if m.path == crate::symbol::VALIDATE =>
+                {
+                    let validate = get_lit(crate::symbol::VALIDATE, &m.lit)?;
+                    param.validate = Some(validate);
+                }
                 // Parse #[elephantry(virtual)]
                 syn::NestedMeta::Meta(syn::Meta::Path(w)) if w == crate::symbol::VIRTUAL => {
                     param.r#virtual = true;
@@ -151,6 +314,10 @@ impl Field {
             }
         }
 
+        if param.prefix.is_some() && !param.flatten {
+            return crate::error(field, "prefix attribute requires flatten");
+        }
+
         Ok(param)
     }
 }
@@ -1,13 +1,27 @@
 pub(crate) struct Symbol(&'static str);
 
+pub(crate) const BELONGS_TO: Symbol = Symbol("belongs_to");
 pub(crate) const COLUMN: Symbol = Symbol("column");
 pub(crate) const DEFAULT: Symbol = Symbol("default");
 pub(crate) const ELEPHANTRY: Symbol = Symbol("elephantry");
+pub(crate) const ENCRYPTED: Symbol = Symbol("encrypted");
+pub(crate) const FK: Symbol = Symbol("fk");
+pub(crate) const FLATTEN: Symbol = Symbol("flatten");
+pub(crate) const HAS_MANY: Symbol = Symbol("has_many");
 pub(crate) const INTERNAL: Symbol = Symbol("internal");
+pub(crate) const KEY: Symbol = Symbol("key");
+pub(crate) const MATERIALIZED_VIEW: Symbol = Symbol("materialized_view");
 pub(crate) const MODEL: Symbol = Symbol("model");
+pub(crate) const NAME: Symbol = Symbol("name");
+pub(crate) const OPTIONAL_COLUMN: Symbol = Symbol("optional_column");
 pub(crate) const PK: Symbol = Symbol("pk");
+pub(crate) const PREFIX: Symbol = Symbol("prefix");
 pub(crate) const RELATION: Symbol = Symbol("relation");
+pub(crate) const SCHEMA: Symbol = Symbol("schema");
 pub(crate) const STRUCTURE: Symbol = Symbol("structure");
+pub(crate) const UNIQUE: Symbol = Symbol("unique");
+pub(crate) const VALIDATE: Symbol = Symbol("validate");
+pub(crate) const VIEW: Symbol = Symbol("view");
 pub(crate) const VIRTUAL: Symbol = Symbol("virtual");
 
 impl PartialEq<Symbol> for syn::Path {